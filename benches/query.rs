@@ -0,0 +1,53 @@
+//! Benchmarks of query iteration.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use edict::{component::Component, world::World};
+
+#[derive(Clone, Copy)]
+struct Pos(f32);
+
+impl Component for Pos {}
+
+#[derive(Clone, Copy)]
+struct Vel(f32);
+
+impl Component for Vel {}
+
+fn world(count: usize) -> World {
+    let mut world = World::new();
+    for i in 0..count {
+        world.spawn((Pos(i as f32), Vel(1.0)));
+    }
+    world
+}
+
+fn for_each(c: &mut Criterion) {
+    let mut world = world(100_000);
+
+    c.bench_function("for_each", |b| {
+        b.iter(|| {
+            world
+                .query::<(&mut Pos, &Vel)>()
+                .for_each(|(pos, vel)| pos.0 += vel.0);
+        })
+    });
+
+    // Disabled component forces per-item visiting checks.
+    let e = world.spawn((Pos(0.0), Vel(1.0)));
+    world.set_enabled::<Vel>(e, false).unwrap();
+
+    c.bench_function("for_each_disabled", |b| {
+        b.iter(|| {
+            world
+                .query::<(&mut Pos, &Vel)>()
+                .for_each(|(pos, vel)| pos.0 += vel.0);
+        })
+    });
+
+    c.bench_function("fold", |b| {
+        b.iter(|| black_box(world.query::<&Pos>().fold(0.0, |acc, pos| acc + pos.0)))
+    });
+}
+
+criterion_group!(benches, for_each);
+criterion_main!(benches);
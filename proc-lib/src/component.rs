@@ -1,7 +1,9 @@
 use proc_easy::EasyAttributes;
 use syn::spanned::Spanned;
 
-use crate::{merge_where_clauses, Borrow, Name, OnDrop, OnReplace, WhereClause};
+use crate::{
+    merge_where_clauses, Borrow, Name, OnDrop, OnMove, OnReplace, StableName, WhereClause,
+};
 
 proc_easy::easy_attributes! {
     @(edict)
@@ -10,6 +12,8 @@ proc_easy::easy_attributes! {
         borrow: Option<Borrow>,
         on_drop: Option<OnDrop>,
         on_replace: Option<OnReplace>,
+        on_move: Option<OnMove>,
+        stable_name: Option<StableName>,
         where_clauses: Vec<WhereClause>,
     }
 }
@@ -58,6 +62,22 @@ pub fn derive(
         }
     );
 
+    let on_move = attributes.on_move.map(|on_move| {
+        let on_move = &on_move.function;
+        quote::quote! {
+            const ON_MOVE: core::option::Option<fn(&mut Self, *const Self)> =
+                core::option::Option::Some(#on_move);
+        }
+    });
+
+    let stable_name = attributes.stable_name.map(|stable_name| {
+        let stable_name = stable_name.literal;
+        quote::quote! {
+            const STABLE_NAME: core::option::Option<&'static str> =
+                core::option::Option::Some(#stable_name);
+        }
+    });
+
     let insert_borrows = match attributes.borrow {
         None => None,
         Some(borrow) => {
@@ -263,6 +283,10 @@ pub fn derive(
 
             #on_replace
 
+            #on_move
+
+            #stable_name
+
             fn borrows() -> #edict_path::private::Vec<#edict_path::component::ComponentBorrow> {
                 let mut output = Vec::new();
                 output.push(#edict_path::component::ComponentBorrow::auto::<Self>());
@@ -9,9 +9,15 @@ mod kw {
     proc_easy::easy_token!(on_drop);
     proc_easy::easy_token!(on_target_drop);
     proc_easy::easy_token!(on_replace);
+    proc_easy::easy_token!(on_move);
+    proc_easy::easy_token!(stable_name);
     proc_easy::easy_token!(exclusive);
     proc_easy::easy_token!(symmetric);
     proc_easy::easy_token!(owned);
+    proc_easy::easy_token!(cascade);
+    proc_easy::easy_token!(acyclic);
+    proc_easy::easy_token!(exclusive_target);
+    proc_easy::easy_token!(max_targets);
 }
 
 proc_easy::easy_argument_value! {
@@ -21,6 +27,20 @@ proc_easy::easy_argument_value! {
     }
 }
 
+proc_easy::easy_argument_value! {
+    struct StableName {
+        kw: kw::stable_name,
+        literal: syn::LitStr,
+    }
+}
+
+proc_easy::easy_argument_value! {
+    struct MaxTargets {
+        kw: kw::max_targets,
+        limit: syn::Expr,
+    }
+}
+
 proc_easy::easy_argument! {
     struct Borrow {
         kw: kw::borrow,
@@ -44,6 +64,14 @@ proc_easy::easy_argument! {
     }
 }
 
+proc_easy::easy_argument! {
+    struct OnMove {
+        kw: kw::on_move,
+        eq: syn::Token![=],
+        function: syn::Expr,
+    }
+}
+
 proc_easy::easy_argument! {
     struct OnTargetDrop {
         kw: kw::on_target_drop,
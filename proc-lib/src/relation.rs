@@ -1,7 +1,9 @@
 use proc_easy::EasyAttributes;
 use syn::spanned::Spanned;
 
-use crate::{kw, merge_where_clauses, Name, OnDrop, OnReplace, OnTargetDrop, WhereClause};
+use crate::{
+    kw, merge_where_clauses, MaxTargets, Name, OnDrop, OnReplace, OnTargetDrop, WhereClause,
+};
 
 proc_easy::easy_attributes! {
     @(edict)
@@ -10,6 +12,10 @@ proc_easy::easy_attributes! {
         exclusive: Option<kw::exclusive>,
         symmetric: Option<kw::symmetric>,
         owned: Option<kw::owned>,
+        cascade: Option<kw::cascade>,
+        acyclic: Option<kw::acyclic>,
+        exclusive_target: Option<kw::exclusive_target>,
+        max_targets: Option<MaxTargets>,
         on_drop: Option<OnDrop>,
         on_replace: Option<OnReplace>,
         on_target_drop: Option<OnTargetDrop>,
@@ -36,10 +42,34 @@ pub fn derive(
         .symmetric
         .map(|_| quote::quote! { const SYMMETRIC: bool = true; });
 
+    // `cascade` is the despawn policy spelled from the target side.
+    // Both generate the same flag, so only one of them may be specified.
+    if attributes.owned.is_some() && attributes.cascade.is_some() {
+        return Err(syn::Error::new(
+            input.span(),
+            "`cascade` and `owned` are the same despawn policy, specify only one",
+        ));
+    }
+
     let owned = attributes
         .owned
+        .map(|_| ())
+        .or(attributes.cascade.map(|_| ()))
         .map(|_| quote::quote! { const OWNED: bool = true; });
 
+    let acyclic = attributes
+        .acyclic
+        .map(|_| quote::quote! { const ACYCLIC: bool = true; });
+
+    let exclusive_target = attributes
+        .exclusive_target
+        .map(|_| quote::quote! { const EXCLUSIVE_TARGET: bool = true; });
+
+    let max_targets = attributes.max_targets.map(|max_targets| {
+        let limit = &max_targets.limit;
+        quote::quote! { const MAX_TARGETS: usize = #limit; }
+    });
+
     let fn_name = attributes.name.map(|name| {
         let name = name.literal;
         Some(quote::quote! {
@@ -94,6 +124,12 @@ pub fn derive(
 
             #owned
 
+            #acyclic
+
+            #exclusive_target
+
+            #max_targets
+
             #fn_name
 
             #on_drop
@@ -55,6 +55,7 @@ impl ActionBuffer {
             fun.call(world, self);
         }
 
+        world.check_flushed_invariants();
         true
     }
 }
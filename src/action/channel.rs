@@ -15,7 +15,7 @@ use crate::{
     world::{iter_reserve_hint, World},
 };
 
-use super::{ActionBuffer, ActionEncoder, ActionFn};
+use super::{logged, ActionBuffer, ActionEncoder, ActionFn};
 
 struct Shared {
     queue: Mutex<VecDeque<ActionFn<'static>>>,
@@ -90,7 +90,7 @@ impl ActionSender {
     where
         B: DynamicComponentBundle + Send + 'static,
     {
-        self.push_fn(move |world, _| {
+        self.push_fn("ActionSender::spawn", move |world, _| {
             let _ = world.spawn(bundle);
         });
     }
@@ -101,7 +101,7 @@ impl ActionSender {
     where
         B: DynamicBundle + Send + 'static,
     {
-        self.push_fn(move |world, _| {
+        self.push_fn("ActionSender::spawn_external", move |world, _| {
             let _ = world.spawn_external(bundle);
         });
     }
@@ -114,7 +114,7 @@ impl ActionSender {
         I: IntoIterator,
         I::Item: ComponentBundle + Send + 'static,
     {
-        self.push_fn(|world, _| {
+        self.push_fn("ActionSender::spawn_batch", |world, _| {
             world.ensure_bundle_registered::<I::Item>();
         });
 
@@ -141,7 +141,7 @@ impl ActionSender {
     /// Encodes an action to despawn specified entity.
     #[inline]
     pub fn despawn(&self, id: EntityId) {
-        self.push_fn(move |world, buffer| {
+        self.push_fn("ActionSender::despawn", move |world, buffer| {
             let _ = world.despawn_with_buffer(id, buffer);
         })
     }
@@ -152,7 +152,7 @@ impl ActionSender {
     where
         T: Component + Send,
     {
-        self.push_fn(move |world, buffer| {
+        self.push_fn("ActionSender::insert", move |world, buffer| {
             let _ = world.insert_with_buffer(id, component, buffer);
         });
     }
@@ -163,7 +163,7 @@ impl ActionSender {
     where
         T: Send + 'static,
     {
-        self.push_fn(move |world, buffer| {
+        self.push_fn("ActionSender::insert_external", move |world, buffer| {
             let _ = world.insert_external_with_buffer(id, component, buffer);
         });
     }
@@ -174,7 +174,7 @@ impl ActionSender {
     where
         B: DynamicComponentBundle + Send + 'static,
     {
-        self.push_fn(move |world, buffer| {
+        self.push_fn("ActionSender::insert_bundle", move |world, buffer| {
             let _ = world.insert_bundle_with_buffer(id, bundle, buffer);
         });
     }
@@ -185,9 +185,12 @@ impl ActionSender {
     where
         B: DynamicBundle + Send + 'static,
     {
-        self.push_fn(move |world, buffer| {
-            let _ = world.insert_external_bundle_with_buffer(id, bundle, buffer);
-        });
+        self.push_fn(
+            "ActionSender::insert_external_bundle",
+            move |world, buffer| {
+                let _ = world.insert_external_bundle_with_buffer(id, bundle, buffer);
+            },
+        );
     }
 
     /// Encodes an action to drop component from specified entity.
@@ -202,7 +205,7 @@ impl ActionSender {
     /// Encodes an action to drop component from specified entity.
     #[inline]
     pub fn drop_erased(&self, id: EntityId, ty: TypeId) {
-        self.push_fn(move |world, buffer| {
+        self.push_fn("ActionSender::drop_erased", move |world, buffer| {
             let _ = world.drop_erased_with_buffer(id, ty, buffer);
         })
     }
@@ -213,7 +216,7 @@ impl ActionSender {
     where
         B: Bundle,
     {
-        self.push_fn(move |world, buffer| {
+        self.push_fn("ActionSender::drop_bundle", move |world, buffer| {
             let _ = world.drop_bundle_with_buffer::<B>(id, buffer);
         });
     }
@@ -224,7 +227,7 @@ impl ActionSender {
     where
         R: Relation,
     {
-        self.push_fn(move |world, buffer| {
+        self.push_fn("ActionSender::add_relation", move |world, buffer| {
             let _ = world.add_relation_with_buffer(origin, relation, target, buffer);
         });
     }
@@ -235,7 +238,7 @@ impl ActionSender {
     where
         R: Relation,
     {
-        self.push_fn(move |world, buffer| {
+        self.push_fn("ActionSender::drop_relation", move |world, buffer| {
             let _ = world.remove_relation_with_buffer::<R>(origin, target, buffer);
         });
     }
@@ -245,14 +248,14 @@ impl ActionSender {
     where
         T: Send + 'static,
     {
-        self.push_fn(move |world, _| {
+        self.push_fn("ActionSender::insert_resource", move |world, _| {
             world.insert_resource(resource);
         });
     }
 
     /// Encodes an action to drop resource instance.
     pub fn drop_resource<T: 'static>(&self) {
-        self.push_fn(move |world, _| {
+        self.push_fn("ActionSender::drop_resource", move |world, _| {
             world.remove_resource::<T>();
         });
     }
@@ -260,14 +263,16 @@ impl ActionSender {
     /// Encodes a custom action with a closure that takes mutable reference to `World`.
     #[inline]
     pub fn closure(&self, fun: impl FnOnce(&mut World) + Send + 'static) {
-        self.push_fn(move |world, buffer| world.with_buffer(buffer, fun))
+        self.push_fn("ActionSender::closure", move |world, buffer| {
+            world.with_buffer(buffer, fun)
+        })
     }
 
     /// Encodes a custom action with a closure that takes reference to `World`
     /// and [`ActionEncoder`] that can be used to record new actions.
     #[inline]
     pub fn closure_with_encoder(&self, fun: impl FnOnce(&World, ActionEncoder) + Send + 'static) {
-        self.push_fn(|world, buffer| {
+        self.push_fn("ActionSender::closure_with_encoder", |world, buffer| {
             let encoder = ActionEncoder::new(buffer, world.entity_set());
             fun(world, encoder);
         });
@@ -275,8 +280,12 @@ impl ActionSender {
 
     /// Encodes an action to remove component from specified entity.
     #[inline]
-    fn push_fn(&self, fun: impl FnOnce(&mut World, &mut ActionBuffer) + Send + 'static) {
-        let action = ActionFn::new(fun);
+    fn push_fn(
+        &self,
+        name: &'static str,
+        fun: impl FnOnce(&mut World, &mut ActionBuffer) + Send + 'static,
+    ) {
+        let action = ActionFn::new(logged(name, fun));
         self.shared.queue.lock().push_back(action);
         self.shared.non_empty.store(true, Ordering::Relaxed);
     }
@@ -331,9 +340,10 @@ where
         F: FnMut(T, ()) -> T,
     {
         let additional = iter_reserve_hint(&self.bundles);
-        self.sender.push_fn(move |world, _| {
-            world.spawn_reserve::<B>(additional);
-        });
+        self.sender
+            .push_fn("ActionSender::spawn_batch", move |world, _| {
+                world.spawn_reserve::<B>(additional);
+            });
 
         self.bundles.fold(init, |acc, bundle| {
             f(acc, self.sender.spawn_external(bundle))
@@ -351,9 +361,10 @@ where
         // Hence we should reserve space in archetype here.
 
         let additional = iter_reserve_hint(&self.bundles);
-        self.sender.push_fn(move |world, _| {
-            world.spawn_reserve::<B>(additional);
-        });
+        self.sender
+            .push_fn("ActionSender::spawn_batch", move |world, _| {
+                world.spawn_reserve::<B>(additional);
+            });
 
         FromIterator::from_iter(self)
     }
@@ -395,9 +406,10 @@ where
         F: FnMut(T, ()) -> T,
     {
         let additional = iter_reserve_hint(&self.bundles);
-        self.sender.push_fn(move |world, _| {
-            world.spawn_reserve::<B>(additional);
-        });
+        self.sender
+            .push_fn("ActionSender::spawn_batch", move |world, _| {
+                world.spawn_reserve::<B>(additional);
+            });
 
         self.bundles.rfold(init, |acc, bundle| {
             f(acc, self.sender.spawn_external(bundle))
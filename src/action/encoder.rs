@@ -10,7 +10,7 @@ use crate::{
     world::{iter_reserve_hint, World},
 };
 
-use super::{ActionBuffer, ActionFn};
+use super::{logged, ActionBuffer, ActionFn};
 
 /// Encoder for actions that require mutable access to [`World`],
 /// like spawning/despawning entities and inserting/removing/dropping components and relations.
@@ -78,7 +78,7 @@ impl<'a> ActionEncoder<'a> {
         I: IntoIterator,
         I::Item: ComponentBundle + Send + 'static,
     {
-        self.push_fn(|world, _| {
+        self.push_fn("ActionEncoder::spawn_batch", |world, _| {
             world.ensure_bundle_registered::<I::Item>();
         });
 
@@ -105,7 +105,7 @@ impl<'a> ActionEncoder<'a> {
     /// Encodes an action to despawn specified entity.
     #[inline]
     pub fn despawn(&mut self, id: EntityId) {
-        self.push_fn(move |world, buffer| {
+        self.push_fn("ActionEncoder::despawn", move |world, buffer| {
             let _ = world.despawn_with_buffer(id, buffer);
         })
     }
@@ -116,7 +116,7 @@ impl<'a> ActionEncoder<'a> {
     where
         T: Component + Send,
     {
-        self.push_fn(move |world, buffer| {
+        self.push_fn("ActionEncoder::insert", move |world, buffer| {
             let _ = world.insert_with_buffer(id, component, buffer);
         });
     }
@@ -127,7 +127,7 @@ impl<'a> ActionEncoder<'a> {
     where
         T: Send + 'static,
     {
-        self.push_fn(move |world, buffer| {
+        self.push_fn("ActionEncoder::insert_external", move |world, buffer| {
             let _ = world.insert_external_with_buffer(id, component, buffer);
         });
     }
@@ -138,7 +138,7 @@ impl<'a> ActionEncoder<'a> {
     where
         B: DynamicComponentBundle + Send + 'static,
     {
-        self.push_fn(move |world, buffer| {
+        self.push_fn("ActionEncoder::insert_bundle", move |world, buffer| {
             let _ = world.insert_bundle_with_buffer(id, bundle, buffer);
         });
     }
@@ -149,9 +149,12 @@ impl<'a> ActionEncoder<'a> {
     where
         B: DynamicBundle + Send + 'static,
     {
-        self.push_fn(move |world, buffer| {
-            let _ = world.insert_external_bundle_with_buffer(id, bundle, buffer);
-        });
+        self.push_fn(
+            "ActionEncoder::insert_external_bundle",
+            move |world, buffer| {
+                let _ = world.insert_external_bundle_with_buffer(id, bundle, buffer);
+            },
+        );
     }
 
     /// Encodes an action to drop component from specified entity.
@@ -166,7 +169,7 @@ impl<'a> ActionEncoder<'a> {
     /// Encodes an action to drop component from specified entity.
     #[inline]
     pub fn drop_erased(&mut self, id: EntityId, ty: TypeId) {
-        self.push_fn(move |world, buffer| {
+        self.push_fn("ActionEncoder::drop_erased", move |world, buffer| {
             let _ = world.drop_erased_with_buffer(id, ty, buffer);
         })
     }
@@ -177,7 +180,7 @@ impl<'a> ActionEncoder<'a> {
     where
         B: Bundle,
     {
-        self.push_fn(move |world, buffer| {
+        self.push_fn("ActionEncoder::drop_bundle", move |world, buffer| {
             let _ = world.drop_bundle_with_buffer::<B>(id, buffer);
         });
     }
@@ -188,7 +191,7 @@ impl<'a> ActionEncoder<'a> {
     where
         R: Relation,
     {
-        self.push_fn(move |world, buffer| {
+        self.push_fn("ActionEncoder::add_relation", move |world, buffer| {
             let _ = world.add_relation_with_buffer(origin, relation, target, buffer);
         });
     }
@@ -199,7 +202,7 @@ impl<'a> ActionEncoder<'a> {
     where
         R: Relation,
     {
-        self.push_fn(move |world, buffer| {
+        self.push_fn("ActionEncoder::drop_relation", move |world, buffer| {
             let _ = world.remove_relation_with_buffer::<R>(origin, target, buffer);
         });
     }
@@ -216,7 +219,7 @@ impl<'a> ActionEncoder<'a> {
     where
         T: Send + 'static,
     {
-        self.push_fn(move |world, _| {
+        self.push_fn("ActionEncoder::insert_resource", move |world, _| {
             world.insert_resource(resource);
         });
     }
@@ -224,7 +227,7 @@ impl<'a> ActionEncoder<'a> {
     /// Encodes an action to drop resource instance.
     #[inline]
     pub fn drop_resource<T: 'static>(&mut self) {
-        self.push_fn(move |world, _| {
+        self.push_fn("ActionEncoder::drop_resource", move |world, _| {
             world.remove_resource::<T>();
         });
     }
@@ -232,7 +235,9 @@ impl<'a> ActionEncoder<'a> {
     /// Encodes a custom action with a closure that takes mutable reference to `World`.
     #[inline]
     pub fn closure(&mut self, fun: impl FnOnce(&mut World) + Send + 'static) {
-        self.push_fn(move |world, buffer| world.with_buffer(buffer, fun))
+        self.push_fn("ActionEncoder::closure", move |world, buffer| {
+            world.with_buffer(buffer, fun)
+        })
     }
 
     /// Encodes a custom action with a closure that takes reference to `World`
@@ -242,7 +247,7 @@ impl<'a> ActionEncoder<'a> {
         &mut self,
         fun: impl FnOnce(&World, ActionEncoder) + Send + 'static,
     ) {
-        self.push_fn(|world, buffer| {
+        self.push_fn("ActionEncoder::closure_with_encoder", |world, buffer| {
             let encoder = ActionEncoder::new(buffer, world.entity_set());
             fun(world, encoder);
         });
@@ -260,8 +265,12 @@ impl<'a> ActionEncoder<'a> {
 
     /// Encodes an action to remove component from specified entity.
     #[inline]
-    fn push_fn(&mut self, fun: impl FnOnce(&mut World, &mut ActionBuffer) + Send + 'static) {
-        self.actions.push_back(ActionFn::new(fun));
+    fn push_fn(
+        &mut self,
+        name: &'static str,
+        fun: impl FnOnce(&mut World, &mut ActionBuffer) + Send + 'static,
+    ) {
+        self.actions.push_back(ActionFn::new(logged(name, fun)));
     }
 }
 
@@ -314,9 +323,10 @@ where
         F: FnMut(T, EntityId) -> T,
     {
         let additional = iter_reserve_hint(&self.bundles);
-        self.encoder.push_fn(move |world, _| {
-            world.spawn_reserve::<B>(additional);
-        });
+        self.encoder
+            .push_fn("ActionEncoder::spawn_batch", move |world, _| {
+                world.spawn_reserve::<B>(additional);
+            });
 
         self.bundles.fold(init, |acc, bundle| {
             f(acc, self.encoder.spawn_external(bundle))
@@ -334,9 +344,10 @@ where
         // Hence we should reserve space in archetype here.
 
         let additional = iter_reserve_hint(&self.bundles);
-        self.encoder.push_fn(move |world, _| {
-            world.spawn_reserve::<B>(additional);
-        });
+        self.encoder
+            .push_fn("ActionEncoder::spawn_batch", move |world, _| {
+                world.spawn_reserve::<B>(additional);
+            });
 
         FromIterator::from_iter(self)
     }
@@ -378,9 +389,10 @@ where
         F: FnMut(T, EntityId) -> T,
     {
         let additional = iter_reserve_hint(&self.bundles);
-        self.encoder.push_fn(move |world, _| {
-            world.spawn_reserve::<B>(additional);
-        });
+        self.encoder
+            .push_fn("ActionEncoder::spawn_batch", move |world, _| {
+                world.spawn_reserve::<B>(additional);
+            });
 
         self.bundles.rfold(init, |acc, bundle| {
             f(acc, self.encoder.spawn_external(bundle))
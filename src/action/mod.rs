@@ -23,3 +23,20 @@ pub use self::{
 };
 
 pub(crate) use self::channel::ActionChannel;
+
+/// Wraps action to log its execution under specified operation name for invariant checks.
+/// Logging happens only in debug builds when invariants are registered.
+///
+/// See [`World::add_invariant`].
+#[inline]
+fn logged(
+    name: &'static str,
+    fun: impl FnOnce(&mut World, &mut ActionBuffer) + Send + 'static,
+) -> impl FnOnce(&mut World, &mut ActionBuffer) + Send + 'static {
+    move |world: &mut World, buffer: &mut ActionBuffer| {
+        if world.log_actions() {
+            world.log_action(name);
+        }
+        fun(world, buffer);
+    }
+}
@@ -4,40 +4,607 @@ use core::{
     alloc::Layout,
     any::TypeId,
     cell::UnsafeCell,
+    fmt,
+    hash::Hasher,
     hint::unreachable_unchecked,
     intrinsics::copy_nonoverlapping,
     iter::FromIterator,
-    mem::{self, size_of, MaybeUninit},
-    ops::Deref,
+    mem::{self, size_of, ManuallyDrop, MaybeUninit},
+    ops::{Deref, DerefMut, Range},
     ptr::{self, NonNull},
+    slice,
+    sync::atomic::{AtomicBool, AtomicIsize, AtomicU8, Ordering},
+    task::Waker,
 };
 
 use alloc::{
     alloc::{alloc, dealloc},
     boxed::Box,
+    sync::Arc,
     vec::Vec,
 };
 use atomicell::borrow::{
     new_lock, release_borrow, release_borrow_mut, try_borrow, try_borrow_mut, Lock,
 };
 use hashbrown::HashMap;
+use parking_lot::Mutex;
 
 use crate::{
-    action::ActionEncoder, bundle::DynamicBundle, component::ComponentInfo, entity::EntityId,
-    epoch::EpochId, hash::NoOpHasherBuilder, idx::MAX_IDX_USIZE, query::Access,
+    action::ActionEncoder,
+    bundle::{DynamicBundle, ReplaceBundle},
+    component::{ComponentBorrow, ComponentInfo},
+    entity::EntityId,
+    epoch::{AtomicEpochId, EpochId},
+    hash::NoOpHasherBuilder,
+    idx::MAX_IDX_USIZE,
+    query::Access,
 };
 
+#[cfg(feature = "column-guard")]
+use crate::guard::{alloc_column, dealloc_column, realloc_column};
+#[cfg(not(feature = "column-guard"))]
+use crate::pool::{alloc_column, dealloc_column, realloc_column};
+
+/// Allocator of component columns.
+///
+/// By default small columns are allocated from the world's pool
+/// and larger ones from the global allocator.
+/// Custom allocator configured with [`WorldBuilder::column_allocator`]
+/// may place columns into memory shared with other processes,
+/// so that an out-of-process editor or inspector can read world state live,
+/// locating columns with [`World::layout_manifest`].
+///
+/// Only component columns are allocated with this allocator.
+/// Entity ids, epochs and other bookkeeping stay in the global allocator.
+///
+/// [`WorldBuilder::column_allocator`]: crate::world::WorldBuilder::column_allocator
+/// [`World::layout_manifest`]: crate::world::World::layout_manifest
+///
+/// # Safety
+///
+/// [`ColumnAllocator::alloc`] must return pointer to a block of memory
+/// that fits the layout and is not used by anything else
+/// until it is passed to [`ColumnAllocator::dealloc`].
+pub unsafe trait ColumnAllocator: Send + Sync + 'static {
+    /// Allocates memory block for a column.
+    ///
+    /// Layout size is never zero.
+    /// Must not return if allocation fails.
+    fn alloc(&self, layout: Layout) -> NonNull<u8>;
+
+    /// Deallocates memory block of a column.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be allocated by this allocator with the same `layout`.
+    unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout);
+
+    /// Changes size of a column's memory block.
+    /// Contents are preserved up to the smaller of the sizes.
+    ///
+    /// Default implementation allocates new block and copies contents.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be allocated by this allocator with the `layout`.
+    /// `new_size` must be non-zero.
+    unsafe fn realloc(&self, ptr: NonNull<u8>, layout: Layout, new_size: usize) -> NonNull<u8> {
+        let new_layout = unsafe { Layout::from_size_align_unchecked(new_size, layout.align()) };
+        let new_ptr = self.alloc(new_layout);
+        unsafe {
+            copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr(), layout.size().min(new_size));
+            self.dealloc(ptr, layout);
+        }
+        new_ptr
+    }
+
+    /// Returns offset of the pointer within memory region shared with other processes.
+    ///
+    /// Reported in [`ColumnLayout::offset`] so that readers can locate columns
+    /// in their own mapping of the region.
+    /// Returns `None` by default.
+    ///
+    /// [`ColumnLayout::offset`]: crate::world::ColumnLayout::offset
+    #[inline]
+    fn shared_offset(&self, ptr: NonNull<u8>) -> Option<usize> {
+        let _ = ptr;
+        None
+    }
+}
+
+/// Allocates memory for a column with custom allocator if provided.
+///
+/// # Safety
+///
+/// Same as for [`alloc_column`].
+#[inline]
+unsafe fn alloc_column_in(allocator: Option<&dyn ColumnAllocator>, layout: Layout) -> NonNull<u8> {
+    match allocator {
+        None => unsafe { alloc_column(layout) },
+        Some(allocator) => allocator.alloc(layout),
+    }
+}
+
+/// Deallocates memory of a column allocated with [`alloc_column_in`].
+///
+/// # Safety
+///
+/// Same as for [`dealloc_column`], allocator must be the same.
+#[inline]
+unsafe fn dealloc_column_in(
+    allocator: Option<&dyn ColumnAllocator>,
+    ptr: NonNull<u8>,
+    layout: Layout,
+) {
+    match allocator {
+        None => unsafe { dealloc_column(ptr, layout) },
+        Some(allocator) => unsafe { allocator.dealloc(ptr, layout) },
+    }
+}
+
+/// Changes size of a column's memory allocated with [`alloc_column_in`].
+///
+/// # Safety
+///
+/// Same as for [`realloc_column`], allocator must be the same.
+#[inline]
+unsafe fn realloc_column_in(
+    allocator: Option<&dyn ColumnAllocator>,
+    ptr: NonNull<u8>,
+    layout: Layout,
+    new_size: usize,
+) -> NonNull<u8> {
+    match allocator {
+        None => unsafe { realloc_column(ptr, layout, new_size) },
+        Some(allocator) => unsafe { allocator.realloc(ptr, layout, new_size) },
+    }
+}
+
+/// Storage of hibernated component columns.
+///
+/// Columns of cold archetypes hibernated with
+/// [`World::hibernate_cold_archetypes_with`] are moved into the store,
+/// for example to disk, and loaded back on first access.
+///
+/// Columns of components with move hooks are never stored,
+/// since they must stay at a valid address while hooks run.
+/// They are moved into global allocator instead.
+///
+/// [`World::hibernate_cold_archetypes_with`]: crate::world::World::hibernate_cold_archetypes_with
+///
+/// # Safety
+///
+/// [`ColumnStore::load`] must fill the buffer with exactly the bytes
+/// passed to [`ColumnStore::store`] call that returned the key.
+pub unsafe trait ColumnStore: Send + Sync + 'static {
+    /// Stores bytes of a column and returns key to load them back.
+    ///
+    /// Bytes are never empty.
+    fn store(&self, bytes: &[MaybeUninit<u8>]) -> u64;
+
+    /// Loads bytes stored under the key into the buffer.
+    ///
+    /// Buffer length is equal to number of bytes stored.
+    /// Each key is loaded exactly once.
+    fn load(&self, key: u64, bytes: &mut [MaybeUninit<u8>]);
+}
+
+/// Returns layout of column with `cap` components.
+///
+/// # Safety
+///
+/// Column of this size must fit into `isize`,
+/// which holds for any allocated column.
+#[inline]
+unsafe fn column_layout(info: &ComponentInfo, cap: usize) -> Layout {
+    unsafe { Layout::from_size_align_unchecked(info.layout().size() * cap, info.layout().align()) }
+}
+
+/// Column storage that is not owned by the archetype.
+enum Detached {
+    /// Column moved out of allocator memory by hibernation.
+    Hibernated(Hibernated),
+
+    /// Column shared with forks of the world.
+    Shared(Shared),
+}
+
+/// State of column storage stored in [`ArchetypeComponent::storage`].
+const OWNED: u8 = 0;
+const HIBERNATED: u8 = 1;
+const SHARED: u8 = 2;
+
+/// Column moved out of allocator memory by hibernation.
+struct Hibernated {
+    bytes: HibernatedBytes,
+    len: usize,
+    cap: usize,
+    allocator: Option<Arc<dyn ColumnAllocator>>,
+}
+
+enum HibernatedBytes {
+    /// Components moved into global allocator block that fits them exactly.
+    Heap(NonNull<u8>),
+
+    /// Component bytes are kept by the store under the key.
+    Store(Arc<dyn ColumnStore>, u64),
+}
+
+/// Column shared by archetypes of forked worlds.
+/// Each archetype copies the column before modifying components.
+struct Shared {
+    column: Arc<SharedColumn>,
+
+    /// Capacity of the archetype sharing the column.
+    cap: usize,
+
+    /// Allocator of the archetype sharing the column.
+    allocator: Option<Arc<dyn ColumnAllocator>>,
+}
+
+/// Component column owned by all archetypes that share it.
+/// Components are dropped when last archetype releases the column.
+struct SharedColumn {
+    info: ComponentInfo,
+    ptr: NonNull<u8>,
+    len: usize,
+    cap: usize,
+    allocator: Option<Arc<dyn ColumnAllocator>>,
+}
+
+// Safety: components of shared column are only read and dropped,
+// same as components of archetypes in `World` that is `Sync`.
+unsafe impl Send for SharedColumn {}
+unsafe impl Sync for SharedColumn {}
+
+impl SharedColumn {
+    fn layout(&self) -> Layout {
+        // Safety: layout of existing allocation.
+        unsafe { column_layout(&self.info, self.cap) }
+    }
+
+    /// Gives up ownership of the column and returns pointer to it.
+    fn into_ptr(self) -> NonNull<u8> {
+        let column = ManuallyDrop::new(self);
+
+        // Safety: fields are read once and column is never dropped.
+        unsafe {
+            drop(ptr::read(&column.info));
+            drop(ptr::read(&column.allocator));
+        }
+        column.ptr
+    }
+
+    /// Frees the column without dropping components
+    /// that were moved out of it.
+    fn free(mut self) {
+        self.len = 0;
+    }
+}
+
+impl Drop for SharedColumn {
+    fn drop(&mut self) {
+        self.info.final_drop(self.ptr, self.len);
+
+        // Safety: shared columns are never empty.
+        unsafe {
+            dealloc_column_in(self.allocator.as_deref(), self.ptr, self.layout());
+        }
+    }
+}
+
 pub(crate) struct ComponentData {
     pub ptr: NonNull<u8>,
-    pub epoch: EpochId,
-    pub entity_epochs: Box<[EpochId]>,
-    pub chunk_epochs: Box<[EpochId]>,
+    pub epoch: AtomicEpochId,
+    pub entity_epochs: EpochColumn,
+    pub chunk_epochs: EpochColumn,
+
+    /// Bitset of entity indices with disabled component.
+    /// Empty if no component in the column was ever disabled.
+    pub disabled: Vec<u64>,
+}
+
+/// Boxed slice of epochs.
+///
+/// Pointer returned by [`EpochColumn::as_mut_ptr`] may be used to write epochs
+/// through shared [`ComponentData`] reference.
+/// Fetches of queries that borrow disjoint ranges of chunks
+/// are created concurrently this way, each writing epochs of its own chunks only.
+pub(crate) struct EpochColumn {
+    ptr: NonNull<[EpochId]>,
+}
+
+// Safety: owns the epochs like `Box<[EpochId]>` does.
+unsafe impl Send for EpochColumn {}
+unsafe impl Sync for EpochColumn {}
+
+impl Default for EpochColumn {
+    #[inline]
+    fn default() -> Self {
+        EpochColumn::from_box(Box::new([]))
+    }
+}
+
+impl Drop for EpochColumn {
+    #[inline]
+    fn drop(&mut self) {
+        // Safety: pointer is obtained from `Box::into_raw`.
+        let _ = unsafe { Box::from_raw(self.ptr.as_ptr()) };
+    }
+}
+
+impl Deref for EpochColumn {
+    type Target = [EpochId];
+
+    #[inline]
+    fn deref(&self) -> &[EpochId] {
+        unsafe { &*self.ptr.as_ptr() }
+    }
+}
+
+impl DerefMut for EpochColumn {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [EpochId] {
+        unsafe { &mut *self.ptr.as_ptr() }
+    }
+}
+
+impl EpochColumn {
+    #[inline]
+    fn from_box(epochs: Box<[EpochId]>) -> Self {
+        EpochColumn {
+            // Safety: `Box::into_raw` never returns null.
+            ptr: unsafe { NonNull::new_unchecked(Box::into_raw(epochs)) },
+        }
+    }
+
+    /// Returns pointer to the first epoch.
+    ///
+    /// Writes through the pointer must not race with each other
+    /// or with references returned by `Deref` and `DerefMut`.
+    #[inline]
+    pub fn as_mut_ptr(&self) -> *mut EpochId {
+        self.ptr.as_ptr().cast()
+    }
+
+    /// Resizes column to `len` epochs, filling new ones with starting epoch.
+    fn resize(&mut self, len: usize) {
+        // Safety: pointer is obtained from `Box::into_raw` and ownership is transferred.
+        let epochs = unsafe { Box::from_raw(ManuallyDrop::new(mem::take(self)).ptr.as_ptr()) };
+        let mut epochs = epochs.into_vec();
+        epochs.reserve_exact(len.saturating_sub(epochs.len()));
+        epochs.resize(len, EpochId::start());
+        *self = EpochColumn::from_box(epochs.into_boxed_slice());
+    }
+}
+
+impl ComponentData {
+    /// Returns `true` if component of the entity with specified index is enabled.
+    #[inline]
+    pub fn is_enabled(&self, idx: usize) -> bool {
+        is_enabled(&self.disabled, idx)
+    }
+
+    /// Enables or disables component of the entity with specified index.
+    #[inline]
+    pub fn set_enabled(&mut self, idx: usize, enabled: bool) {
+        let word = idx / 64;
+        let bit = 1 << (idx % 64);
+
+        if enabled {
+            if let Some(w) = self.disabled.get_mut(word) {
+                *w &= !bit;
+            }
+        } else {
+            if self.disabled.len() <= word {
+                self.disabled.resize(word + 1, 0);
+            }
+            self.disabled[word] |= bit;
+        }
+    }
+
+    /// Swaps enabled bits of two entities.
+    #[inline]
+    fn swap_enabled(&mut self, a: usize, b: usize) {
+        if !self.disabled.is_empty() {
+            let a_enabled = self.is_enabled(a);
+            let b_enabled = self.is_enabled(b);
+            self.set_enabled(a, b_enabled);
+            self.set_enabled(b, a_enabled);
+        }
+    }
+
+    /// Moves enabled bit of the last entity into removed entity slot.
+    #[inline]
+    fn swap_remove_enabled(&mut self, idx: usize, last_idx: usize) {
+        if !self.disabled.is_empty() {
+            let enabled = self.is_enabled(last_idx);
+            self.set_enabled(idx, enabled);
+            self.set_enabled(last_idx, true);
+        }
+    }
 }
 
 pub(crate) struct ArchetypeComponent {
     info: ComponentInfo,
     lock: Lock,
+
+    /// Locks of individual chunks.
+    /// Taken in addition to shared `lock` by queries that visit a range of chunks.
+    chunk_locks: Box<[Lock]>,
+
+    /// Number of whole-column readers if positive,
+    /// or negated number of chunk writers if negative.
+    /// Whole-column readers and chunk writers can't both hold shared `lock`.
+    chunk_mode: AtomicIsize,
+
+    waiters: LockWaiters,
     data: UnsafeCell<ComponentData>,
+
+    /// Dropped components waiting for [`Archetype::flush_drops`].
+    deferred: DeferredDrops,
+
+    /// One of `OWNED`, `HIBERNATED` or `SHARED`.
+    /// Hibernated column is loaded back on first access to component data.
+    /// Shared column is copied on first access to modify components.
+    storage: AtomicU8,
+    detached: Mutex<Option<Detached>>,
+}
+
+/// Queue of components which drop is deferred.
+struct DeferredDrops {
+    ptr: NonNull<u8>,
+    len: usize,
+    cap: usize,
+}
+
+impl DeferredDrops {
+    const fn new() -> Self {
+        DeferredDrops {
+            ptr: NonNull::dangling(),
+            len: 0,
+            cap: 0,
+        }
+    }
+
+    /// Drops the component or moves it into the queue
+    /// if drop is deferred for the component type.
+    /// Drop hook is executed immediately.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to initialized component described by `info`.
+    /// Component must not be used after this call.
+    unsafe fn drop_one(
+        &mut self,
+        info: &ComponentInfo,
+        ptr: NonNull<u8>,
+        id: EntityId,
+        encoder: ActionEncoder,
+    ) {
+        if !info.is_drop_deferred() {
+            info.drop_one(ptr, id, encoder);
+            return;
+        }
+
+        info.drop_hook(ptr, id, encoder);
+
+        let size = info.layout().size();
+        if size != 0 && self.len == self.cap {
+            let new_cap = (self.cap * 2).max(4);
+            let new_layout =
+                Layout::from_size_align(size.checked_mul(new_cap).unwrap(), info.layout().align())
+                    .unwrap();
+
+            // Safety: size and new_cap are non-zero.
+            let Some(new_ptr) = NonNull::new(unsafe { alloc(new_layout) }) else {
+                alloc::alloc::handle_alloc_error(new_layout);
+            };
+
+            if self.len != 0 {
+                unsafe {
+                    copy_nonoverlapping(self.ptr.as_ptr(), new_ptr.as_ptr(), self.len * size);
+                    info.on_move(new_ptr, self.ptr.as_ptr(), self.len);
+                }
+            }
+
+            if self.cap != 0 {
+                // Safety: layout of existing allocation.
+                unsafe {
+                    dealloc(
+                        self.ptr.as_ptr(),
+                        Layout::from_size_align_unchecked(size * self.cap, info.layout().align()),
+                    );
+                }
+            }
+
+            self.ptr = new_ptr;
+            self.cap = new_cap;
+        }
+
+        unsafe {
+            let dst = NonNull::new_unchecked(self.ptr.as_ptr().add(self.len * size));
+            copy_nonoverlapping(ptr.as_ptr(), dst.as_ptr(), size);
+            info.on_move(dst, ptr.as_ptr(), 1);
+        }
+        self.len += 1;
+    }
+
+    /// Drops all components in the queue.
+    ///
+    /// # Safety
+    ///
+    /// `info` must describe components in the queue.
+    unsafe fn flush(&mut self, info: &ComponentInfo) {
+        let len = mem::replace(&mut self.len, 0);
+        if len != 0 {
+            info.final_drop(self.ptr, len);
+        }
+    }
+
+    /// Drops all components in the queue and frees the queue.
+    ///
+    /// # Safety
+    ///
+    /// `info` must describe components in the queue.
+    unsafe fn free(&mut self, info: &ComponentInfo) {
+        unsafe {
+            self.flush(info);
+        }
+
+        let size = info.layout().size();
+        if size != 0 && self.cap != 0 {
+            // Safety: layout of existing allocation.
+            unsafe {
+                dealloc(
+                    self.ptr.as_ptr(),
+                    Layout::from_size_align_unchecked(size * self.cap, info.layout().align()),
+                );
+            }
+        }
+        *self = DeferredDrops::new();
+    }
+}
+
+/// List of wakers of tasks waiting for component lock to be released.
+struct LockWaiters {
+    pending: AtomicBool,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl LockWaiters {
+    fn new() -> Self {
+        LockWaiters {
+            pending: AtomicBool::new(false),
+            wakers: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn register(&self, waker: &Waker) {
+        let mut wakers = self.wakers.lock();
+        if !wakers.iter().any(|w| w.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+        self.pending.store(true, Ordering::SeqCst);
+    }
+
+    #[inline]
+    fn wake(&self) {
+        // Fast path for locks nobody waits for.
+        if !self.pending.load(Ordering::SeqCst) {
+            return;
+        }
+
+        let wakers = {
+            let mut wakers = self.wakers.lock();
+            self.pending.store(false, Ordering::SeqCst);
+            mem::take(&mut *wakers)
+        };
+
+        for waker in wakers {
+            waker.wake();
+        }
+    }
 }
 
 impl Deref for ArchetypeComponent {
@@ -52,7 +619,16 @@ impl ArchetypeComponent {
     #[inline]
     pub unsafe fn borrow(&self, access: Access) -> bool {
         match access {
-            Access::Read => try_borrow(&self.lock),
+            Access::Read => {
+                if !try_borrow(&self.lock) {
+                    return false;
+                }
+                if !self.enter_mode(1) {
+                    release_borrow(&self.lock);
+                    return false;
+                }
+                true
+            }
             Access::Write => try_borrow_mut(&self.lock),
         }
     }
@@ -60,20 +636,261 @@ impl ArchetypeComponent {
     #[inline]
     pub unsafe fn release(&self, access: Access) {
         match access {
-            Access::Read => release_borrow(&self.lock),
+            Access::Read => {
+                self.leave_mode(1);
+                release_borrow(&self.lock);
+            }
             Access::Write => release_borrow_mut(&self.lock),
         }
+        self.waiters.wake();
+    }
+
+    /// Borrows range of chunks of the component.
+    ///
+    /// Disjoint ranges of chunks can be borrowed for writing concurrently.
+    /// Falls back to [`ArchetypeComponent::borrow`] if range covers all chunks.
+    pub unsafe fn borrow_chunks(&self, access: Access, chunks: Range<usize>) -> bool {
+        let chunks = self.clamp_chunks(chunks);
+        if chunks.start == 0 && chunks.end == self.chunk_locks.len() {
+            return self.borrow(access);
+        }
+
+        if !try_borrow(&self.lock) {
+            return false;
+        }
+
+        // Empty range writes nothing and need not exclude whole-column readers.
+        let writer = access == Access::Write && !chunks.is_empty();
+        if writer && !self.enter_mode(-1) {
+            release_borrow(&self.lock);
+            return false;
+        }
+
+        for idx in chunks.clone() {
+            let lock = &self.chunk_locks[idx];
+            let success = match access {
+                Access::Read => try_borrow(lock),
+                Access::Write => try_borrow_mut(lock),
+            };
+
+            if !success {
+                self.release_chunk_locks(access, chunks.start..idx);
+                if writer {
+                    self.leave_mode(-1);
+                }
+                release_borrow(&self.lock);
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Releases range of chunks borrowed with [`ArchetypeComponent::borrow_chunks`].
+    pub unsafe fn release_chunks(&self, access: Access, chunks: Range<usize>) {
+        let chunks = self.clamp_chunks(chunks);
+        if chunks.start == 0 && chunks.end == self.chunk_locks.len() {
+            self.release(access);
+            return;
+        }
+
+        let writer = access == Access::Write && !chunks.is_empty();
+        self.release_chunk_locks(access, chunks);
+        if writer {
+            self.leave_mode(-1);
+        }
+        release_borrow(&self.lock);
+        self.waiters.wake();
+    }
+
+    #[inline]
+    fn clamp_chunks(&self, chunks: Range<usize>) -> Range<usize> {
+        let len = self.chunk_locks.len();
+        chunks.start.min(len)..chunks.end.min(len)
+    }
+
+    #[inline]
+    unsafe fn release_chunk_locks(&self, access: Access, chunks: Range<usize>) {
+        for idx in chunks {
+            let lock = &self.chunk_locks[idx];
+            match access {
+                Access::Read => release_borrow(lock),
+                Access::Write => release_borrow_mut(lock),
+            }
+        }
+    }
+
+    /// Joins group of whole-column readers if `delta` is positive
+    /// or group of chunk writers if `delta` is negative.
+    /// Fails if other group is not empty.
+    #[inline]
+    fn enter_mode(&self, delta: isize) -> bool {
+        self.chunk_mode
+            .fetch_update(Ordering::Acquire, Ordering::Relaxed, |mode| {
+                if mode == 0 || (mode > 0) == (delta > 0) {
+                    Some(mode + delta)
+                } else {
+                    None
+                }
+            })
+            .is_ok()
+    }
+
+    #[inline]
+    fn leave_mode(&self, delta: isize) {
+        self.chunk_mode.fetch_sub(delta, Ordering::Release);
+    }
+
+    /// Registers waker to be woken when lock of this component is released.
+    ///
+    /// Caller should attempt to borrow the component again after registering,
+    /// as lock could be released before waker was registered.
+    #[inline]
+    pub fn wait(&self, waker: &Waker) {
+        self.waiters.register(waker);
     }
 
     #[inline]
     pub unsafe fn data(&self) -> &ComponentData {
+        self.rehydrate();
+        unsafe { &*self.data.get() }
+    }
+
+    /// Returns component data, copying column shared with forks of the world.
+    /// Must be used to access components that are going to be modified.
+    #[inline]
+    pub unsafe fn data_unique(&self) -> &ComponentData {
+        self.make_unique();
         unsafe { &*self.data.get() }
     }
 
     #[inline]
     pub unsafe fn data_mut(&self) -> &mut ComponentData {
+        self.make_unique();
         unsafe { &mut *self.data.get() }
     }
+
+    /// Loads hibernated column back into allocator memory.
+    #[inline]
+    fn rehydrate(&self) {
+        if self.storage.load(Ordering::Acquire) == HIBERNATED {
+            self.attach();
+        }
+    }
+
+    /// Makes column owned by this archetype,
+    /// loading hibernated column or copying shared one.
+    #[inline]
+    fn make_unique(&self) {
+        if self.storage.load(Ordering::Acquire) != OWNED {
+            self.attach();
+        }
+    }
+
+    #[cold]
+    fn attach(&self) {
+        let mut detached = self.detached.lock();
+
+        // Another thread may have attached the column already.
+        let ptr = match detached.take() {
+            None => return,
+            Some(Detached::Hibernated(hibernated)) => self.rehydrate_column(hibernated),
+            Some(Detached::Shared(shared)) => self.unshare_column(shared),
+        };
+
+        // Safety: components of detached column are not referenced
+        // until storage is set to owned.
+        unsafe {
+            ptr::addr_of_mut!((*self.data.get()).ptr).write(ptr);
+        }
+        self.storage.store(OWNED, Ordering::Release);
+    }
+
+    fn rehydrate_column(&self, hibernated: Hibernated) -> NonNull<u8> {
+        let size = self.info.layout().size();
+        let align = self.info.layout().align();
+
+        // Safety: layout of the column before hibernation.
+        let layout = unsafe { Layout::from_size_align_unchecked(size * hibernated.cap, align) };
+
+        // Safety: hibernated columns are never empty.
+        let ptr = unsafe { alloc_column_in(hibernated.allocator.as_deref(), layout) };
+
+        let bytes = size * hibernated.len;
+        match hibernated.bytes {
+            HibernatedBytes::Heap(buffer) => unsafe {
+                copy_nonoverlapping(buffer.as_ptr(), ptr.as_ptr(), bytes);
+                self.info.on_move(ptr, buffer.as_ptr(), hibernated.len);
+                dealloc(
+                    buffer.as_ptr(),
+                    Layout::from_size_align_unchecked(bytes, align),
+                );
+            },
+            HibernatedBytes::Store(store, key) => {
+                let column = unsafe {
+                    slice::from_raw_parts_mut(ptr.as_ptr().cast::<MaybeUninit<u8>>(), bytes)
+                };
+                store.load(key, column);
+            }
+        }
+
+        ptr
+    }
+
+    fn unshare_column(&self, shared: Shared) -> NonNull<u8> {
+        let column = match Arc::try_unwrap(shared.column) {
+            Ok(column) => column,
+            Err(column) => {
+                // Safety: layout of the archetype's column, shared columns are never empty.
+                let ptr = unsafe {
+                    alloc_column_in(
+                        shared.allocator.as_deref(),
+                        column_layout(&self.info, shared.cap),
+                    )
+                };
+                let size = self.info.layout().size();
+
+                for idx in 0..column.len {
+                    // Safety: shared components are cloneable.
+                    unsafe {
+                        self.info.clone_one(
+                            NonNull::new_unchecked(column.ptr.as_ptr().add(idx * size)),
+                            NonNull::new_unchecked(ptr.as_ptr().add(idx * size)),
+                        );
+                    }
+                }
+                return ptr;
+            }
+        };
+
+        let same_allocator = match (&column.allocator, &shared.allocator) {
+            (None, None) => true,
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        };
+
+        // Last archetype sharing the column takes it back.
+        if same_allocator && column.cap == shared.cap {
+            return column.into_ptr();
+        }
+
+        let ptr = unsafe {
+            alloc_column_in(
+                shared.allocator.as_deref(),
+                column_layout(&self.info, shared.cap),
+            )
+        };
+        unsafe {
+            copy_nonoverlapping(
+                column.ptr.as_ptr(),
+                ptr.as_ptr(),
+                column.len * self.info.layout().size(),
+            );
+            self.info.on_move(ptr, column.ptr.as_ptr(), column.len);
+        }
+        column.free();
+        ptr
+    }
 }
 
 impl ArchetypeComponent {
@@ -81,16 +898,34 @@ impl ArchetypeComponent {
         ArchetypeComponent {
             data: UnsafeCell::new(ComponentData {
                 ptr: NonNull::dangling(),
-                epoch: EpochId::start(),
-                chunk_epochs: Box::new([]),
-                entity_epochs: Box::new([]),
+                epoch: AtomicEpochId::start(),
+                chunk_epochs: EpochColumn::default(),
+                entity_epochs: EpochColumn::default(),
+                disabled: Vec::new(),
             }),
             lock: new_lock(),
+            chunk_locks: Box::new([]),
+            chunk_mode: AtomicIsize::new(0),
+            waiters: LockWaiters::new(),
             info: info.clone(),
+            deferred: DeferredDrops::new(),
+            storage: AtomicU8::new(OWNED),
+            detached: Mutex::new(None),
         }
     }
 
-    unsafe fn drop(&mut self, cap: usize, len: usize) {
+    unsafe fn drop(&mut self, cap: usize, len: usize, allocator: Option<&dyn ColumnAllocator>) {
+        unsafe {
+            self.deferred.free(&self.info);
+        }
+
+        // Shared column is dropped by the last archetype that releases it.
+        if *self.storage.get_mut() == SHARED {
+            *self.detached.get_mut() = None;
+            return;
+        }
+
+        self.rehydrate();
         let data = self.data.get_mut();
 
         self.info.final_drop(data.ptr, len);
@@ -105,12 +940,19 @@ impl ArchetypeComponent {
             };
 
             unsafe {
-                dealloc(data.ptr.as_ptr(), layout);
+                dealloc_column_in(allocator, data.ptr, layout);
             }
         }
     }
 
-    unsafe fn grow(&mut self, len: usize, old_cap: usize, new_cap: usize) {
+    unsafe fn grow(
+        &mut self,
+        len: usize,
+        old_cap: usize,
+        new_cap: usize,
+        allocator: Option<&dyn ColumnAllocator>,
+    ) {
+        self.make_unique();
         let data = self.data.get_mut();
 
         debug_assert!(len <= old_cap);
@@ -124,10 +966,8 @@ impl ArchetypeComponent {
             .unwrap();
 
             // # Safety: component size is non-zero, new_cap is non-zero.
-            // Thus new_layout size is non-zero.
-            let Some(mut ptr) = NonNull::new(unsafe { alloc(new_layout) }) else {
-                alloc::alloc::handle_alloc_error(new_layout);
-            };
+            // Thus new_layout size is non-zero.
+            let mut ptr = unsafe { alloc_column_in(allocator, new_layout) };
 
             if len != 0 {
                 unsafe {
@@ -135,7 +975,8 @@ impl ArchetypeComponent {
                         data.ptr.as_ptr(),
                         ptr.as_ptr(),
                         len * self.info.layout().size(),
-                    )
+                    );
+                    self.info.on_move(ptr, data.ptr.as_ptr(), len);
                 };
             }
 
@@ -151,22 +992,306 @@ impl ArchetypeComponent {
                 mem::swap(&mut data.ptr, &mut ptr);
 
                 unsafe {
-                    dealloc(ptr.as_ptr(), old_layout);
+                    dealloc_column_in(allocator, ptr, old_layout);
                 }
             } else {
                 data.ptr = ptr;
             }
         }
 
-        let mut entity_epochs = core::mem::take(&mut data.entity_epochs).into_vec();
-        entity_epochs.reserve_exact(new_cap - old_cap);
-        entity_epochs.resize(new_cap, EpochId::start());
-        data.entity_epochs = entity_epochs.into_boxed_slice();
+        self.resize_epochs(new_cap);
+    }
+
+    unsafe fn shrink(
+        &mut self,
+        len: usize,
+        old_cap: usize,
+        new_cap: usize,
+        allocator: Option<&dyn ColumnAllocator>,
+    ) {
+        self.make_unique();
+        let data = self.data.get_mut();
+
+        debug_assert!(len <= new_cap);
+        debug_assert!(new_cap < old_cap);
+
+        if self.info.layout().size() != 0 {
+            // Safety: layout of existing allocation.
+            let old_layout = unsafe {
+                Layout::from_size_align_unchecked(
+                    self.info.layout().size() * old_cap,
+                    self.info.layout().align(),
+                )
+            };
+
+            if new_cap == 0 {
+                unsafe {
+                    dealloc_column_in(allocator, data.ptr, old_layout);
+                }
+                data.ptr = NonNull::dangling();
+            } else {
+                // Safety: size fits into existing allocation size.
+                let new_layout = unsafe {
+                    Layout::from_size_align_unchecked(
+                        self.info.layout().size() * new_cap,
+                        self.info.layout().align(),
+                    )
+                };
+
+                if self.info.has_on_move() {
+                    // Move hook must run while old storage is still allocated.
+                    // Safety: new layout size is non-zero.
+                    let ptr = unsafe { alloc_column_in(allocator, new_layout) };
+
+                    unsafe {
+                        copy_nonoverlapping(
+                            data.ptr.as_ptr(),
+                            ptr.as_ptr(),
+                            len * self.info.layout().size(),
+                        );
+                        self.info.on_move(ptr, data.ptr.as_ptr(), len);
+                        dealloc_column_in(allocator, data.ptr, old_layout);
+                    }
+                    data.ptr = ptr;
+                } else {
+                    // Safety: old layout is layout of existing allocation,
+                    // new size is non-zero and smaller than old size.
+                    data.ptr = unsafe {
+                        realloc_column_in(allocator, data.ptr, old_layout, new_layout.size())
+                    };
+                }
+            }
+        }
+
+        self.resize_epochs(new_cap);
+    }
+
+    /// Resizes epochs and chunk locks to fit column with `cap` components.
+    fn resize_epochs(&mut self, cap: usize) {
+        let data = self.data.get_mut();
+        data.entity_epochs.resize(cap);
+        data.chunk_epochs.resize(chunks_count(cap));
+
+        let mut chunk_locks = core::mem::take(&mut self.chunk_locks).into_vec();
+        chunk_locks.resize_with(chunks_count(cap), new_lock);
+        self.chunk_locks = chunk_locks.into_boxed_slice();
+    }
+
+    /// Moves components out of allocator memory and frees the column.
+    /// Components are passed to `store` if provided and have no move hook,
+    /// otherwise they are moved into global allocator block that fits them exactly.
+    ///
+    /// Returns `false` if column is empty, already hibernated or shared.
+    unsafe fn hibernate(
+        &mut self,
+        len: usize,
+        cap: usize,
+        allocator: Option<&Arc<dyn ColumnAllocator>>,
+        store: Option<&Arc<dyn ColumnStore>>,
+    ) -> bool {
+        let size = self.info.layout().size();
+        if size == 0 || len == 0 || *self.storage.get_mut() != OWNED {
+            return false;
+        }
+
+        debug_assert!(len <= cap);
+
+        let align = self.info.layout().align();
+        let data = self.data.get_mut();
+        let bytes = size * len;
+
+        let hibernated = match store {
+            Some(store) if !self.info.has_on_move() => {
+                let column = unsafe {
+                    slice::from_raw_parts(data.ptr.as_ptr().cast::<MaybeUninit<u8>>(), bytes)
+                };
+                HibernatedBytes::Store(store.clone(), store.store(column))
+            }
+            _ => {
+                // Safety: size is non-zero and fits into existing allocation size.
+                let layout = unsafe { Layout::from_size_align_unchecked(bytes, align) };
+                let Some(buffer) = NonNull::new(unsafe { alloc(layout) }) else {
+                    alloc::alloc::handle_alloc_error(layout);
+                };
+
+                unsafe {
+                    copy_nonoverlapping(data.ptr.as_ptr(), buffer.as_ptr(), bytes);
+                    self.info.on_move(buffer, data.ptr.as_ptr(), len);
+                }
+                HibernatedBytes::Heap(buffer)
+            }
+        };
+
+        // Safety: layout of existing allocation.
+        unsafe {
+            dealloc_column_in(
+                allocator.map(|a| &**a),
+                data.ptr,
+                Layout::from_size_align_unchecked(size * cap, align),
+            );
+        }
+        data.ptr = NonNull::dangling();
+
+        *self.detached.get_mut() = Some(Detached::Hibernated(Hibernated {
+            bytes: hibernated,
+            len,
+            cap,
+            allocator: allocator.cloned(),
+        }));
+        *self.storage.get_mut() = HIBERNATED;
+        true
+    }
+
+    /// Shares column with component of forked archetype,
+    /// copying epochs and enabled state of components.
+    /// Zero-sized components are cloned instead.
+    ///
+    /// # Safety
+    ///
+    /// `fork` must be new component of the same type in archetype
+    /// with capacity `fork_cap` that is not smaller than `len`.
+    /// Components must be cloneable.
+    unsafe fn share(
+        &mut self,
+        len: usize,
+        cap: usize,
+        allocator: Option<&Arc<dyn ColumnAllocator>>,
+        fork: &mut ArchetypeComponent,
+        fork_cap: usize,
+        fork_allocator: Option<&Arc<dyn ColumnAllocator>>,
+    ) {
+        debug_assert!(len <= cap && len <= fork_cap);
+
+        self.rehydrate();
+        fork.resize_epochs(fork_cap);
+
+        let src = self.data.get_mut();
+        let dst = fork.data.get_mut();
+
+        if self.info.layout().size() == 0 {
+            for _ in 0..len {
+                unsafe {
+                    self.info.clone_one(src.ptr, dst.ptr);
+                }
+            }
+        } else if len != 0 {
+            let column = match self.detached.get_mut() {
+                Some(Detached::Shared(shared)) => shared.column.clone(),
+                _ => {
+                    let column = Arc::new(SharedColumn {
+                        info: self.info.clone(),
+                        ptr: src.ptr,
+                        len,
+                        cap,
+                        allocator: allocator.cloned(),
+                    });
+
+                    *self.detached.get_mut() = Some(Detached::Shared(Shared {
+                        column: column.clone(),
+                        cap,
+                        allocator: allocator.cloned(),
+                    }));
+                    *self.storage.get_mut() = SHARED;
+                    column
+                }
+            };
+
+            dst.ptr = src.ptr;
+            *fork.detached.get_mut() = Some(Detached::Shared(Shared {
+                column,
+                cap: fork_cap,
+                allocator: fork_allocator.cloned(),
+            }));
+            *fork.storage.get_mut() = SHARED;
+        }
+
+        dst.epoch.update(src.epoch.get());
+        dst.entity_epochs[..len].copy_from_slice(&src.entity_epochs[..len]);
+        let chunks = src.chunk_epochs.len().min(dst.chunk_epochs.len());
+        dst.chunk_epochs[..chunks].copy_from_slice(&src.chunk_epochs[..chunks]);
+        dst.disabled.clone_from(&src.disabled);
+    }
+}
+
+/// Borrow lock of the whole component column.
+///
+/// Returned by [`Archetype::raw_column`] and releases the lock on drop.
+/// While lock is alive, queries that access the component in conflicting way
+/// fail to lock the column.
+#[must_use = "Column is unlocked when guard is dropped"]
+pub struct ColumnLock<'a> {
+    component: &'a ArchetypeComponent,
+    access: Access,
+}
+
+// Safety: lock release is thread-safe.
+unsafe impl Send for ColumnLock<'_> {}
+unsafe impl Sync for ColumnLock<'_> {}
+
+impl ColumnLock<'_> {
+    /// Returns access held by the lock.
+    #[inline]
+    pub fn access(&self) -> Access {
+        self.access
+    }
+}
+
+impl fmt::Debug for ColumnLock<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ColumnLock")
+            .field("component", &self.component.name())
+            .field("access", &self.access)
+            .finish()
+    }
+}
+
+impl Drop for ColumnLock<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            self.component.release(self.access);
+        }
+    }
+}
+
+/// Policy of archetype storage growth.
+///
+/// Archetype grows storage of entities and all component columns together
+/// when there is no room for new entities.
+///
+/// Configured with [`WorldBuilder::growth_policy`], [`World::set_growth_policy`]
+/// and [`World::set_archetype_growth_policy`].
+///
+/// [`WorldBuilder::growth_policy`]: crate::world::WorldBuilder::growth_policy
+/// [`World::set_growth_policy`]: crate::world::World::set_growth_policy
+/// [`World::set_archetype_growth_policy`]: crate::world::World::set_archetype_growth_policy
+#[derive(Clone, Copy, Debug, Default)]
+pub enum GrowthPolicy {
+    /// Capacity is at least doubled on each growth.
+    /// Amortizes cost of growth at expense of unused memory.
+    #[default]
+    Doubling,
+
+    /// Capacity grows exactly to the required number of entities.
+    /// Minimizes memory usage, but every spawn into full archetype reallocates storage.
+    Exact,
+
+    /// Function that receives current and required capacities
+    /// and returns new capacity.
+    /// Returned values smaller than required capacity are raised to it.
+    Custom(fn(usize, usize) -> usize),
+}
 
-        let mut chunk_epochs = core::mem::take(&mut data.chunk_epochs).into_vec();
-        chunk_epochs.reserve_exact(chunks_count(new_cap) - chunks_count(old_cap));
-        chunk_epochs.resize(chunks_count(new_cap), EpochId::start());
-        data.chunk_epochs = chunk_epochs.into_boxed_slice();
+impl GrowthPolicy {
+    /// Returns capacity to grow to from `cap`
+    /// when at least `required` entities must fit.
+    #[inline]
+    pub fn new_capacity(&self, cap: usize, required: usize) -> usize {
+        match self {
+            GrowthPolicy::Doubling => cap.saturating_mul(2).max(required).max(4),
+            GrowthPolicy::Exact => required,
+            GrowthPolicy::Custom(f) => f(cap, required).max(required),
+        }
     }
 }
 
@@ -176,16 +1301,31 @@ impl ArchetypeComponent {
 /// This type is exposed for `Query` implementations.
 pub struct Archetype {
     entities: Vec<EntityId>,
+    spawn_epochs: Vec<EpochId>,
+    spawn_epoch: EpochId,
+    fixed_capacity: bool,
+    growth: GrowthPolicy,
+
+    /// Custom allocator of component columns.
+    allocator: Option<Arc<dyn ColumnAllocator>>,
     components: HashMap<TypeId, ArchetypeComponent, NoOpHasherBuilder>,
     borrows: HashMap<TypeId, Vec<(TypeId, usize)>, NoOpHasherBuilder>,
     borrows_mut: HashMap<TypeId, Vec<(TypeId, usize)>, NoOpHasherBuilder>,
 }
 
+// Safety: component data is accessed through shared reference
+// only under borrow locks, same as with `World` that exposes archetypes.
+unsafe impl Sync for Archetype {}
+
 impl Drop for Archetype {
     fn drop(&mut self) {
         for (_, c) in &mut self.components {
             unsafe {
-                c.drop(self.entities.capacity(), self.entities.len());
+                c.drop(
+                    self.entities.capacity(),
+                    self.entities.len(),
+                    self.allocator.as_deref(),
+                );
             }
         }
     }
@@ -199,33 +1339,142 @@ impl Archetype {
             (c.id(), c)
         }));
 
-        let mut borrows = HashMap::with_hasher(NoOpHasherBuilder);
-        let mut borrows_mut = HashMap::with_hasher(NoOpHasherBuilder);
-
-        for (&id, c) in &components {
-            for (idx, cb) in c.borrows().iter().enumerate() {
-                borrows
-                    .entry(cb.target())
-                    .or_insert_with(Vec::new)
-                    .push((id, idx));
-
-                if cb.has_borrow_mut() {
-                    borrows_mut
-                        .entry(cb.target())
-                        .or_insert_with(Vec::new)
-                        .push((id, idx));
-                }
-            }
-        }
+        let (borrows, borrows_mut) = collect_borrows(&components);
 
         Archetype {
             entities: Vec::new(),
+            spawn_epochs: Vec::new(),
+            spawn_epoch: EpochId::start(),
+            fixed_capacity: false,
+            growth: GrowthPolicy::Doubling,
+            allocator: None,
             components,
             borrows,
             borrows_mut,
         }
     }
 
+    /// Returns copy of the archetype that shares component columns with this one.
+    /// Shared column is copied by each archetype on first modification,
+    /// allocating the copy with archetype's allocator.
+    /// Entities keep their indices, epochs and enabled state of components.
+    ///
+    /// # Panics
+    ///
+    /// Panics if archetype is not empty and contains component
+    /// that is not registered as cloneable.
+    pub(crate) fn fork(&mut self, allocator: Option<Arc<dyn ColumnAllocator>>) -> Archetype {
+        let mut fork = Archetype::new(self.infos());
+        let len = self.entities.len();
+
+        fork.growth = self.growth;
+        fork.allocator = allocator;
+        fork.fixed_capacity = self.fixed_capacity;
+
+        if len == 0 {
+            return fork;
+        }
+
+        let cap = self.entities.capacity();
+        fork.entities.reserve_exact(cap);
+        fork.spawn_epochs.reserve_exact(cap);
+        let fork_cap = fork.entities.capacity();
+
+        for (id, component) in &mut self.components {
+            assert!(
+                component.is_cloneable(),
+                "Component `{}` is not cloneable",
+                component.name()
+            );
+
+            let dst = fork.components.get_mut(id).unwrap();
+            unsafe {
+                component.share(
+                    len,
+                    cap,
+                    self.allocator.as_ref(),
+                    dst,
+                    fork_cap,
+                    fork.allocator.as_ref(),
+                );
+            }
+        }
+
+        fork.entities.extend_from_slice(&self.entities);
+        fork.spawn_epochs.extend_from_slice(&self.spawn_epochs);
+        fork.spawn_epoch = self.spawn_epoch;
+        fork
+    }
+
+    /// Replaces column of `from` component with column of `to` component.
+    /// Each component is moved out of the old column by `migrate`
+    /// that writes migrated value into the new column.
+    /// Epochs and enabled state of components are preserved.
+    ///
+    /// Returns `false` if archetype does not contain `from` component.
+    ///
+    /// # Panics
+    ///
+    /// Panics if archetype already contains `to` component.
+    ///
+    /// # Safety
+    ///
+    /// `migrate` must move component of type `from` out of the first pointer
+    /// and write component of type `to` into the second pointer.
+    pub(crate) unsafe fn migrate_component(
+        &mut self,
+        from: TypeId,
+        to: &ComponentInfo,
+        migrate: &mut dyn FnMut(NonNull<u8>, NonNull<u8>),
+    ) -> bool {
+        let Some(mut old) = self.components.remove(&from) else {
+            return false;
+        };
+
+        assert!(
+            !self.components.contains_key(&to.id()),
+            "Component `{}` is already present in the archetype",
+            to.name()
+        );
+
+        let len = self.entities.len();
+        let cap = self.entities.capacity();
+
+        let mut new = ArchetypeComponent::new(to);
+        if cap != 0 {
+            unsafe {
+                new.grow(0, 0, cap, self.allocator.as_deref());
+            }
+        }
+
+        old.make_unique();
+        let old_data = old.data.get_mut();
+        new.make_unique();
+        let new_data = new.data.get_mut();
+
+        for idx in 0..len {
+            unsafe {
+                let src = old_data.ptr.as_ptr().add(idx * old.info.layout().size());
+                let dst = new_data.ptr.as_ptr().add(idx * to.layout().size());
+                migrate(NonNull::new_unchecked(src), NonNull::new_unchecked(dst));
+            }
+        }
+
+        new_data.epoch.update(old_data.epoch.get());
+        new_data.entity_epochs = mem::take(&mut old_data.entity_epochs);
+        new_data.chunk_epochs = mem::take(&mut old_data.chunk_epochs);
+        new_data.disabled = mem::take(&mut old_data.disabled);
+
+        // Components were moved out, free the column only.
+        unsafe {
+            old.drop(cap, 0, self.allocator.as_deref());
+        }
+
+        self.components.insert(to.id(), new);
+        (self.borrows, self.borrows_mut) = collect_borrows(&self.components);
+        true
+    }
+
     /// Returns `true` if archetype contains compoment with specified id.
     #[inline]
     pub fn has_component(&self, type_id: TypeId) -> bool {
@@ -258,6 +1507,33 @@ impl Archetype {
         self.borrows_mut.get(&type_id).map(|v| &v[..])
     }
 
+    /// Appends borrows to the component with specified id
+    /// and registers them in the borrow maps.
+    /// Does nothing if archetype does not contain the component.
+    pub(crate) fn extend_borrows(&mut self, id: TypeId, borrows: &[ComponentBorrow]) {
+        let component = match self.components.get_mut(&id) {
+            None => return,
+            Some(component) => component,
+        };
+
+        let start = component.info.borrows().len();
+        component.info.extend_borrows(borrows);
+
+        for (idx, cb) in component.info.borrows().iter().enumerate().skip(start) {
+            self.borrows
+                .entry(cb.target())
+                .or_insert_with(Vec::new)
+                .push((id, idx));
+
+            if cb.has_borrow_mut() {
+                self.borrows_mut
+                    .entry(cb.target())
+                    .or_insert_with(Vec::new)
+                    .push((id, idx));
+            }
+        }
+    }
+
     /// Returns `true` if archetype matches components set specified.
     #[inline]
     pub fn matches(&self, mut type_ids: impl Iterator<Item = TypeId>) -> bool {
@@ -288,6 +1564,64 @@ impl Archetype {
         self.components.iter().map(|(_, c)| &c.info)
     }
 
+    /// Locks column of component `T` and returns raw pointer to its values
+    /// together with number of entities in the archetype.
+    ///
+    /// This is intended for integrations that need raw access to component data,
+    /// like GPU uploads or SIMD kernels, while the crate keeps track of borrows.
+    /// Pointer may be used to read values while lock is alive,
+    /// and to write them if lock holds [`Access::Write`].
+    /// Writes through the pointer are not tracked,
+    /// use [`World::touch`] to mark written components as modified.
+    ///
+    /// Returns `None` if archetype does not contain component `T`.
+    ///
+    /// [`World::touch`]: crate::world::World::touch
+    ///
+    /// # Panics
+    ///
+    /// Panics if column is already borrowed in conflicting way.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, query::Access, world::World};
+    /// #[derive(Component)]
+    /// struct Pos(f32);
+    ///
+    /// let mut world = World::new();
+    /// let e = world.spawn((Pos(1.0),));
+    ///
+    /// let archetype = &world.archetypes()[world.locate(e).unwrap().archetype as usize];
+    /// let (lock, ptr, len) = archetype.raw_column::<Pos>(Access::Write).unwrap();
+    /// for i in 0..len {
+    ///     unsafe { (*ptr.add(i)).0 *= 2.0 };
+    /// }
+    /// drop(lock);
+    ///
+    /// assert_eq!(world.query_one_mut::<&Pos>(e).unwrap().0, 2.0);
+    /// ```
+    pub fn raw_column<T>(&self, access: Access) -> Option<(ColumnLock<'_>, *mut T, usize)>
+    where
+        T: 'static,
+    {
+        let component = self.components.get(&TypeId::of::<T>())?;
+
+        unsafe {
+            if !component.borrow(access) {
+                panic!("Failed to lock `{}` from archetype", component.name());
+            }
+
+            let data = match access {
+                Access::Read => component.data(),
+                Access::Write => component.data_unique(),
+            };
+            let ptr = data.ptr.as_ptr().cast::<T>();
+            let lock = ColumnLock { component, access };
+            Some((lock, ptr, self.entities.len()))
+        }
+    }
+
     /// Spawns new entity in the archetype.
     ///
     /// Returns index of the newly created entity in the archetype.
@@ -308,6 +1642,8 @@ impl Archetype {
         }
 
         self.entities.push(id);
+        self.spawn_epochs.push(epoch);
+        self.spawn_epoch.update(epoch);
         entity_idx as u32
     }
 
@@ -341,6 +1677,7 @@ impl Archetype {
         let last_entity_idx = self.entities.len() - 1;
 
         for component in self.components.values_mut() {
+            component.make_unique();
             let data = component.data.get_mut();
             let size = component.info.layout().size();
 
@@ -348,7 +1685,11 @@ impl Archetype {
             // Or dangling if size is 0, but than result equals `data.ptr`
             let ptr = unsafe { NonNull::new_unchecked(data.ptr.as_ptr().add(entity_idx * size)) };
 
-            component.info.drop_one(ptr, id, encoder.reborrow());
+            unsafe {
+                component
+                    .deferred
+                    .drop_one(&component.info, ptr, id, encoder.reborrow());
+            }
 
             if entity_idx != last_entity_idx {
                 let chunk_idx = chunk_idx(entity_idx);
@@ -364,9 +1705,12 @@ impl Archetype {
                 let last_ptr = unsafe { data.ptr.as_ptr().add(last_entity_idx * size) };
                 unsafe {
                     ptr::copy_nonoverlapping(last_ptr, ptr.as_ptr(), size);
+                    component.info.on_move(ptr, last_ptr, 1);
                 }
             }
 
+            data.swap_remove_enabled(entity_idx, last_entity_idx);
+
             #[cfg(debug_assertions)]
             unsafe {
                 *data.entity_epochs.get_unchecked_mut(last_entity_idx) = EpochId::start();
@@ -374,6 +1718,7 @@ impl Archetype {
         }
 
         self.entities.swap_remove(entity_idx);
+        self.spawn_epochs.swap_remove(entity_idx);
         if entity_idx != last_entity_idx {
             Some(self.entities[entity_idx])
         } else {
@@ -381,6 +1726,97 @@ impl Archetype {
         }
     }
 
+    /// Swaps two rows of the archetype with all their components,
+    /// epochs and enabled bits.
+    ///
+    /// Locations of swapped entities must be updated by the caller.
+    ///
+    /// # Safety
+    ///
+    /// `a` and `b` must be different indices in bounds of the archetype entities array.
+    /// Columns must not be borrowed.
+    pub(crate) unsafe fn swap_rows(&mut self, a: usize, b: usize) {
+        debug_assert_ne!(a, b);
+        debug_assert!(a < self.entities.len());
+        debug_assert!(b < self.entities.len());
+
+        for component in self.components.values_mut() {
+            component.make_unique();
+            let data = component.data.get_mut();
+            let size = component.info.layout().size();
+
+            let a_ptr = unsafe { data.ptr.as_ptr().add(a * size) };
+            let b_ptr = unsafe { data.ptr.as_ptr().add(b * size) };
+
+            unsafe {
+                ptr::swap_nonoverlapping(a_ptr, b_ptr, size);
+                component
+                    .info
+                    .on_move(NonNull::new_unchecked(a_ptr), b_ptr, 1);
+                component
+                    .info
+                    .on_move(NonNull::new_unchecked(b_ptr), a_ptr, 1);
+            }
+
+            data.entity_epochs.swap(a, b);
+
+            let a_epoch = data.entity_epochs[a];
+            let b_epoch = data.entity_epochs[b];
+            data.chunk_epochs[chunk_idx(a)].update(a_epoch);
+            data.chunk_epochs[chunk_idx(b)].update(b_epoch);
+
+            data.swap_enabled(a, b);
+        }
+
+        self.entities.swap(a, b);
+        self.spawn_epochs.swap(a, b);
+    }
+
+    /// Sorts rows by values of component `T` using insertion sort.
+    /// Runs in linear time if rows are already mostly sorted.
+    ///
+    /// Returns index of the first row that was moved, if any.
+    ///
+    /// # Safety
+    ///
+    /// Archetype must contain component `T`.
+    /// Columns must not be borrowed.
+    pub(crate) unsafe fn insertion_sort_by<T>(
+        &mut self,
+        mut cmp: impl FnMut(&T, &T) -> core::cmp::Ordering,
+    ) -> Option<usize>
+    where
+        T: 'static,
+    {
+        let ptr = {
+            let component = unsafe {
+                self.components
+                    .get_mut(&TypeId::of::<T>())
+                    .unwrap_unchecked()
+            };
+            component.make_unique();
+            component.data.get_mut().ptr.cast::<T>()
+        };
+
+        let mut first_moved = None;
+        for idx in 1..self.entities.len() {
+            let mut row = idx;
+            while row > 0 {
+                // Swapping rows does not reallocate the column.
+                let (prev, cur) = unsafe { (&*ptr.as_ptr().add(row - 1), &*ptr.as_ptr().add(row)) };
+                if cmp(prev, cur) != core::cmp::Ordering::Greater {
+                    break;
+                }
+                unsafe { self.swap_rows(row - 1, row) };
+                row -= 1;
+            }
+            if row != idx {
+                first_moved = Some(first_moved.map_or(row, |first: usize| first.min(row)));
+            }
+        }
+        first_moved
+    }
+
     /// Set components from bundle to the entity.
     ///
     /// # Safety
@@ -397,13 +1833,66 @@ impl Archetype {
         B: DynamicBundle,
     {
         let entity_idx = idx as usize;
-        debug_assert!(
-            bundle.with_ids(|ids| ids.iter().all(|&id| self.components.contains_key(&id)))
-        );
+        debug_assert!(
+            bundle.with_ids(|ids| ids.iter().all(|&id| self.components.contains_key(&id)))
+        );
+        debug_assert!(entity_idx < self.entities.len());
+
+        unsafe {
+            self.write_bundle(id, entity_idx, bundle, epoch, Some(encoder), |_| true);
+        }
+    }
+
+    /// Set components from bundle to the entity without dropping old values.
+    ///
+    /// # Safety
+    ///
+    /// Bundle must not contain components that are absent in this archetype.
+    /// Old values must be moved out before this call.
+    pub(crate) unsafe fn overwrite_bundle<B>(
+        &mut self,
+        id: EntityId,
+        idx: u32,
+        bundle: B,
+        epoch: EpochId,
+    ) where
+        B: DynamicBundle,
+    {
+        let entity_idx = idx as usize;
+        debug_assert!(
+            bundle.with_ids(|ids| ids.iter().all(|&id| self.components.contains_key(&id)))
+        );
+        debug_assert!(entity_idx < self.entities.len());
+
+        unsafe {
+            self.write_bundle(id, entity_idx, bundle, epoch, None, |_| false);
+        }
+    }
+
+    /// Moves values of bundle components out of the entity.
+    /// Components absent in this archetype are returned as `None`.
+    ///
+    /// # Safety
+    ///
+    /// `idx` must be in bounds of this archetype.
+    /// Taken values must be overwritten before entity is accessed again.
+    pub(crate) unsafe fn take_bundle<B>(&mut self, idx: u32) -> B::Replaced
+    where
+        B: ReplaceBundle,
+    {
+        let entity_idx = idx as usize;
         debug_assert!(entity_idx < self.entities.len());
 
         unsafe {
-            self.write_bundle(id, entity_idx, bundle, epoch, Some(encoder), |_| true);
+            B::take_replaced(|tid| {
+                let component = self.components.get_mut(&tid)?;
+                let size = component.layout().size();
+                component.make_unique();
+                let data = component.data.get_mut();
+                Some(NonNull::new_unchecked(
+                    data.ptr.as_ptr().add(entity_idx * size),
+                ))
+            })
         }
     }
 
@@ -487,6 +1976,7 @@ impl Archetype {
                 .get_mut(&TypeId::of::<T>())
                 .unwrap_unchecked()
         };
+        component.make_unique();
         let data = component.data.get_mut();
         let ptr = unsafe { data.ptr.as_ptr().cast::<T>().add(entity_idx) };
 
@@ -501,6 +1991,56 @@ impl Archetype {
         unsafe { &mut *ptr }
     }
 
+    /// Swaps whole column of the component with values from the slice.
+    /// Marks all components in the column as modified at `epoch`.
+    /// Returns `false` if archetype does not contain the component.
+    ///
+    /// # Safety
+    ///
+    /// `values` length must be equal to number of entities in the archetype.
+    /// `epoch` must be advanced before this call.
+    pub(crate) unsafe fn swap_column<T>(&mut self, values: &mut [T], epoch: EpochId) -> bool
+    where
+        T: 'static,
+    {
+        let len = self.entities.len();
+        debug_assert_eq!(values.len(), len);
+
+        let Some(component) = self.components.get_mut(&TypeId::of::<T>()) else {
+            return false;
+        };
+        component.make_unique();
+        let data = component.data.get_mut();
+
+        if len == 0 {
+            return true;
+        }
+
+        let column = data.ptr.cast::<T>();
+        let buffer = values.as_mut_ptr();
+
+        unsafe {
+            ptr::swap_nonoverlapping(column.as_ptr(), buffer, len);
+            component.info.on_move(data.ptr, buffer.cast(), len);
+            component.info.on_move(
+                NonNull::new_unchecked(buffer.cast()),
+                column.as_ptr().cast(),
+                len,
+            );
+        }
+
+        // `epoch` must be advanced in `World` before this call.
+        data.epoch.bump(epoch);
+        for chunk_epoch in &mut data.chunk_epochs[..chunks_count(len)] {
+            chunk_epoch.bump(epoch);
+        }
+        for entity_epoch in &mut data.entity_epochs[..len] {
+            entity_epoch.bump(epoch);
+        }
+
+        true
+    }
+
     /// Add components from bundle to the entity, moving entity to new archetype.
     ///
     /// # Safety
@@ -517,6 +2057,45 @@ impl Archetype {
         epoch: EpochId,
         encoder: ActionEncoder,
     ) -> (u32, Option<EntityId>)
+    where
+        B: DynamicBundle,
+    {
+        unsafe { self.insert_bundle_impl(id, dst, src_idx, bundle, epoch, Some(encoder)) }
+    }
+
+    /// Add components from bundle to the entity, moving entity to new archetype,
+    /// without dropping old values of components the entity already had.
+    ///
+    /// # Safety
+    ///
+    /// Same as for [`Archetype::insert_bundle`].
+    /// Old values must be moved out before this call.
+    pub(crate) unsafe fn insert_overwrite_bundle<B>(
+        &mut self,
+        id: EntityId,
+        dst: &mut Archetype,
+        src_idx: u32,
+        bundle: B,
+        epoch: EpochId,
+    ) -> (u32, Option<EntityId>)
+    where
+        B: DynamicBundle,
+    {
+        unsafe { self.insert_bundle_impl(id, dst, src_idx, bundle, epoch, None) }
+    }
+
+    /// Implementation of bundle insertion.
+    /// Old values are replaced through the encoder if one is provided
+    /// and overwritten otherwise.
+    unsafe fn insert_bundle_impl<B>(
+        &mut self,
+        id: EntityId,
+        dst: &mut Archetype,
+        src_idx: u32,
+        bundle: B,
+        epoch: EpochId,
+        encoder: Option<ActionEncoder>,
+    ) -> (u32, Option<EntityId>)
     where
         B: DynamicBundle,
     {
@@ -543,14 +2122,15 @@ impl Archetype {
 
         debug_assert_ne!(dst.entities.len(), dst.entities.capacity());
         unsafe {
-            self.relocate_components(src_entity_idx, dst, dst_entity_idx, |_, _| {
+            self.relocate_components(src_entity_idx, dst, dst_entity_idx, |_, _, _| {
                 unreachable_unchecked()
             });
         }
 
+        let replace = encoder.is_some();
         unsafe {
-            dst.write_bundle(id, dst_entity_idx, bundle, epoch, Some(encoder), |id| {
-                if self.components.contains_key(&id) {
+            dst.write_bundle(id, dst_entity_idx, bundle, epoch, encoder, |id| {
+                if replace && self.components.contains_key(&id) {
                     true
                 } else {
                     false
@@ -560,6 +2140,7 @@ impl Archetype {
 
         let entity = self.entities.swap_remove(src_entity_idx);
         dst.entities.push(entity);
+        self.relocate_spawn_epoch(src_entity_idx, dst);
 
         if src_entity_idx != self.entities.len() {
             (dst_entity_idx as u32, Some(self.entities[src_entity_idx]))
@@ -601,7 +2182,7 @@ impl Archetype {
 
         debug_assert_ne!(dst.entities.len(), dst.entities.capacity());
         unsafe {
-            self.relocate_components(src_entity_idx, dst, dst_entity_idx, |_, _| {
+            self.relocate_components(src_entity_idx, dst, dst_entity_idx, |_, _, _| {
                 unreachable_unchecked()
             });
         }
@@ -612,6 +2193,7 @@ impl Archetype {
 
         let entity = self.entities.swap_remove(src_entity_idx);
         dst.entities.push(entity);
+        self.relocate_spawn_epoch(src_entity_idx, dst);
 
         if src_entity_idx != self.entities.len() {
             (dst_entity_idx as u32, Some(self.entities[src_entity_idx]))
@@ -654,7 +2236,7 @@ impl Archetype {
 
         debug_assert_ne!(dst.entities.len(), dst.entities.capacity());
         unsafe {
-            self.relocate_components(src_entity_idx, dst, dst_entity_idx, |info, ptr| {
+            self.relocate_components(src_entity_idx, dst, dst_entity_idx, |info, _, ptr| {
                 if info.id() != TypeId::of::<T>() {
                     unreachable_unchecked()
                 }
@@ -665,6 +2247,7 @@ impl Archetype {
 
         let entity = self.entities.swap_remove(src_entity_idx);
         dst.entities.push(entity);
+        self.relocate_spawn_epoch(src_entity_idx, dst);
 
         if src_entity_idx != self.entities.len() {
             (
@@ -705,13 +2288,19 @@ impl Archetype {
         debug_assert_ne!(dst.entities.len(), dst.entities.capacity());
 
         unsafe {
-            self.relocate_components(src_entity_idx, dst, dst_entity_idx, |info, ptr| {
-                info.drop_one(ptr, id, encoder.reborrow());
-            });
+            self.relocate_components(
+                src_entity_idx,
+                dst,
+                dst_entity_idx,
+                |info, deferred, ptr| {
+                    deferred.drop_one(info, ptr, id, encoder.reborrow());
+                },
+            );
         }
 
         let entity = self.entities.swap_remove(src_entity_idx);
         dst.entities.push(entity);
+        self.relocate_spawn_epoch(src_entity_idx, dst);
 
         if src_entity_idx != self.entities.len() {
             (dst_entity_idx as u32, Some(self.entities[src_entity_idx]))
@@ -736,6 +2325,334 @@ impl Archetype {
         self.entities.len()
     }
 
+    #[inline]
+    pub(crate) fn capacity(&self) -> usize {
+        self.entities.capacity()
+    }
+
+    /// Checks canaries around all columns of the archetype.
+    ///
+    /// # Panics
+    ///
+    /// Panics if canary of any column is corrupted.
+    #[cfg(feature = "column-guard")]
+    #[track_caller]
+    pub(crate) fn check_column_guards(&self) {
+        // Custom allocators do not place canaries.
+        if self.allocator.is_some() {
+            return;
+        }
+
+        let cap = self.entities.capacity();
+        for component in self.components.values() {
+            let size = component.info.layout().size();
+            // Hibernated columns are not allocated
+            // and shared ones are checked by the archetype that allocated them.
+            if size == 0 || cap == 0 || component.storage.load(Ordering::Acquire) != OWNED {
+                continue;
+            }
+
+            // Safety: layout of existing allocation.
+            let layout = unsafe {
+                Layout::from_size_align_unchecked(size * cap, component.info.layout().align())
+            };
+
+            // Safety: world is borrowed mutably, no fetches may be alive.
+            let ptr = unsafe { component.data().ptr };
+            assert!(
+                unsafe { crate::guard::check_column(ptr, layout) },
+                "Canary of `{}` column is corrupted",
+                component.info.name()
+            );
+        }
+    }
+
+    /// Feeds whole column of the component into the hasher.
+    /// Returns `false` if archetype does not contain the component.
+    ///
+    /// # Panics
+    ///
+    /// Panics if component is not hashable or column is borrowed mutably.
+    pub(crate) fn hash_column(&self, id: TypeId, state: &mut dyn Hasher) -> bool {
+        let component = match self.components.get(&id) {
+            None => return false,
+            Some(component) => component,
+        };
+
+        assert!(
+            component.is_hashable(),
+            "Component `{}` is not registered as hashable",
+            component.name()
+        );
+
+        unsafe {
+            if !component.borrow(Access::Read) {
+                panic!("Component `{}` is borrowed mutably", component.name());
+            }
+            component.hash_slice(component.data().ptr, self.entities.len(), state);
+            component.release(Access::Read);
+        }
+        true
+    }
+
+    /// Drops components which drop was deferred.
+    pub(crate) fn flush_drops(&mut self) {
+        for component in self.components.values_mut() {
+            unsafe {
+                component.deferred.flush(&component.info);
+            }
+        }
+    }
+
+    /// Returns epochs at which entities of the archetype were spawned.
+    /// Indexed the same way as entities.
+    #[inline]
+    pub fn spawn_epochs(&self) -> &[EpochId] {
+        &self.spawn_epochs
+    }
+
+    /// Returns latest epoch at which any entity of the archetype was spawned.
+    #[inline]
+    pub fn spawn_epoch(&self) -> EpochId {
+        self.spawn_epoch
+    }
+
+    /// Moves all entities from `src` archetype to the end of this archetype.
+    /// Components are copied column by column.
+    ///
+    /// Moved entities get ids from `ids` and are marked as spawned and modified at `epoch`.
+    /// Remap hooks of components are executed with `remap` function.
+    ///
+    /// Returns index of the first moved entity in this archetype.
+    ///
+    /// # Safety
+    ///
+    /// `src` must have the same set of components as this archetype.
+    /// `ids` must have the same length as `src` entities.
+    pub(crate) unsafe fn append(
+        &mut self,
+        src: &mut Archetype,
+        ids: &[EntityId],
+        epoch: EpochId,
+        remap: &dyn Fn(EntityId) -> EntityId,
+    ) -> u32 {
+        debug_assert!(self.matches(src.ids()));
+        debug_assert_eq!(ids.len(), src.entities.len());
+
+        let start = self.entities.len();
+        let count = ids.len();
+
+        if count == 0 {
+            return start as u32;
+        }
+
+        self.reserve(count);
+
+        for (type_id, src_component) in &mut src.components {
+            let dst_component = unsafe { self.components.get_mut(type_id).unwrap_unchecked() };
+            src_component.make_unique();
+            let src_data = src_component.data.get_mut();
+            dst_component.make_unique();
+            let dst_data = dst_component.data.get_mut();
+            let size = dst_component.info.layout().size();
+
+            let dst_ptr =
+                unsafe { NonNull::new_unchecked(dst_data.ptr.as_ptr().add(start * size)) };
+            unsafe {
+                ptr::copy_nonoverlapping(src_data.ptr.as_ptr(), dst_ptr.as_ptr(), count * size);
+                dst_component
+                    .info
+                    .on_move(dst_ptr, src_data.ptr.as_ptr(), count);
+                dst_component.info.on_remap(dst_ptr, count, remap);
+            }
+
+            dst_data.epoch.update(epoch);
+            for entity_epoch in &mut dst_data.entity_epochs[start..start + count] {
+                *entity_epoch = epoch;
+            }
+            for chunk_epoch in
+                &mut dst_data.chunk_epochs[chunk_idx(start)..=chunk_idx(start + count - 1)]
+            {
+                chunk_epoch.update(epoch);
+            }
+
+            for idx in 0..count {
+                if !src_data.is_enabled(idx) {
+                    dst_data.set_enabled(start + idx, false);
+                }
+            }
+            src_data.disabled.clear();
+        }
+
+        self.entities.extend_from_slice(ids);
+        self.spawn_epochs
+            .extend(core::iter::repeat(epoch).take(count));
+        self.spawn_epoch.update(epoch);
+
+        // Components are moved out.
+        src.entities.clear();
+        src.spawn_epochs.clear();
+
+        start as u32
+    }
+
+    /// Moves spawn epoch of the entity to the end of `dst` archetype.
+    #[inline]
+    fn relocate_spawn_epoch(&mut self, src_entity_idx: usize, dst: &mut Archetype) {
+        let spawned = self.spawn_epochs.swap_remove(src_entity_idx);
+        dst.spawn_epochs.push(spawned);
+        dst.spawn_epoch.update(spawned);
+    }
+
+    /// Allocates storage for at least `capacity` entities
+    /// and forbids any further growth.
+    ///
+    /// Returns `false` if archetype already contains more entities,
+    /// or if it already has fixed smaller capacity.
+    pub(crate) fn fix_capacity(&mut self, capacity: usize) -> bool {
+        let len = self.entities.len();
+        if capacity < len {
+            return false;
+        }
+
+        if self.fixed_capacity {
+            return capacity <= self.entities.capacity();
+        }
+
+        let old_cap = self.entities.capacity();
+        if capacity > old_cap {
+            self.entities.reserve_exact(capacity - len);
+            self.spawn_epochs.reserve_exact(capacity - len);
+
+            for component in self.components.values_mut() {
+                unsafe {
+                    component.grow(
+                        len,
+                        old_cap,
+                        self.entities.capacity(),
+                        self.allocator.as_deref(),
+                    );
+                }
+            }
+        }
+
+        self.fixed_capacity = true;
+        true
+    }
+
+    /// Returns growth policy of the archetype.
+    #[inline]
+    pub fn growth_policy(&self) -> GrowthPolicy {
+        self.growth
+    }
+
+    /// Sets growth policy of the archetype.
+    /// Takes effect on next growth.
+    #[inline]
+    pub(crate) fn set_growth_policy(&mut self, policy: GrowthPolicy) {
+        self.growth = policy;
+    }
+
+    /// Sets allocator of component columns.
+    ///
+    /// # Panics
+    ///
+    /// Panics if columns are already allocated.
+    #[inline]
+    pub(crate) fn set_column_allocator(&mut self, allocator: Option<Arc<dyn ColumnAllocator>>) {
+        assert_eq!(
+            self.entities.capacity(),
+            0,
+            "Cannot change allocator of allocated columns"
+        );
+        self.allocator = allocator;
+    }
+
+    /// Returns custom allocator of component columns.
+    #[inline]
+    pub(crate) fn column_allocator(&self) -> Option<&dyn ColumnAllocator> {
+        self.allocator.as_deref()
+    }
+
+    /// Returns `true` if archetype can fit `additional` entities without growing
+    /// or if it is allowed to grow.
+    #[inline]
+    pub(crate) fn has_room(&self, additional: usize) -> bool {
+        !self.fixed_capacity || self.entities.capacity() - self.entities.len() >= additional
+    }
+
+    /// Returns latest epoch at which any component of the archetype was modified
+    /// or any entity was spawned.
+    pub(crate) fn last_modified(&mut self) -> EpochId {
+        let mut epoch = self.spawn_epoch;
+        for component in self.components.values_mut() {
+            epoch.update(component.data.get_mut().epoch.get());
+        }
+        epoch
+    }
+
+    /// Releases unused storage of the archetype.
+    ///
+    /// Returns `true` if any memory was released.
+    /// Archetypes with fixed capacity are never shrunk.
+    pub(crate) fn shrink_to_fit(&mut self) -> bool {
+        if self.fixed_capacity {
+            return false;
+        }
+
+        let len = self.entities.len();
+        let old_cap = self.entities.capacity();
+
+        if len == old_cap {
+            return false;
+        }
+
+        self.entities.shrink_to_fit();
+        self.spawn_epochs.shrink_to_fit();
+
+        let new_cap = self.entities.capacity();
+        if new_cap == old_cap {
+            return false;
+        }
+
+        for component in self.components.values_mut() {
+            unsafe {
+                component.shrink(len, old_cap, new_cap, self.allocator.as_deref());
+            }
+        }
+
+        true
+    }
+
+    /// Releases unused storage of the archetype and moves component columns
+    /// out of allocator memory until they are accessed again.
+    /// See [`ColumnStore`] for where columns are moved.
+    ///
+    /// Returns `true` if any column was hibernated.
+    /// Archetypes with fixed capacity or sharing columns
+    /// with forks of the world are never hibernated.
+    pub(crate) fn hibernate(&mut self, store: Option<&Arc<dyn ColumnStore>>) -> bool {
+        if self.fixed_capacity
+            || self
+                .components
+                .values_mut()
+                .any(|c| *c.storage.get_mut() == SHARED)
+        {
+            return false;
+        }
+
+        self.shrink_to_fit();
+
+        let len = self.entities.len();
+        let cap = self.entities.capacity();
+
+        let mut hibernated = false;
+        for component in self.components.values_mut() {
+            hibernated |= unsafe { component.hibernate(len, cap, self.allocator.as_ref(), store) };
+        }
+        hibernated
+    }
+
     #[inline]
     pub(crate) fn is_empty(&self) -> bool {
         self.entities.is_empty()
@@ -752,12 +2669,24 @@ impl Archetype {
 
         // Needs to grow.
 
-        self.entities.reserve(additional);
+        if self.fixed_capacity {
+            panic!("Archetype with fixed capacity {} cannot grow", old_cap);
+        }
+
+        let required = len.checked_add(additional).expect("Capacity overflow");
+        let new_cap = self.growth.new_capacity(old_cap, required);
+        self.entities.reserve_exact(new_cap - len);
+        self.spawn_epochs.reserve_exact(new_cap - len);
         debug_assert_ne!(old_cap, self.entities.capacity(),);
 
         for component in self.components.values_mut() {
             unsafe {
-                component.grow(len, old_cap, self.entities.capacity());
+                component.grow(
+                    len,
+                    old_cap,
+                    self.entities.capacity(),
+                    self.allocator.as_deref(),
+                );
             }
         }
     }
@@ -779,6 +2708,7 @@ impl Archetype {
 
         bundle.put(|src, tid, size| {
             let component = unsafe { self.components.get_mut(&tid).unwrap_unchecked() };
+            component.make_unique();
             let data = component.data.get_mut();
             let chunk_epoch = unsafe { data.chunk_epochs.get_unchecked_mut(chunk_idx) };
             let entity_epoch = unsafe { data.entity_epochs.get_unchecked_mut(entity_idx) };
@@ -816,6 +2746,7 @@ impl Archetype {
                 .get_mut(&TypeId::of::<T>())
                 .unwrap_unchecked()
         };
+        component.make_unique();
         let data = component.data.get_mut();
         let chunk_epoch = unsafe { data.chunk_epochs.get_unchecked_mut(chunk_idx) };
         let entity_epoch = unsafe { data.entity_epochs.get_unchecked_mut(entity_idx) };
@@ -828,7 +2759,9 @@ impl Archetype {
             unsafe { NonNull::new_unchecked(data.ptr.as_ptr().add(entity_idx * size_of::<T>())) };
 
         if let Some(encoder) = occupied {
-            component.set_one(dst, NonNull::from(&value).cast(), id, encoder)
+            // Value is moved into the storage by `set_one`.
+            let value = ManuallyDrop::new(value);
+            component.set_one(dst, NonNull::from(&*value).cast(), id, encoder)
         } else {
             unsafe {
                 ptr::write(dst.as_ptr().cast(), value);
@@ -844,18 +2777,20 @@ impl Archetype {
         dst_entity_idx: usize,
         mut missing: F,
     ) where
-        F: FnMut(&ComponentInfo, NonNull<u8>),
+        F: FnMut(&ComponentInfo, &mut DeferredDrops, NonNull<u8>),
     {
         let dst_chunk_idx = chunk_idx(dst_entity_idx);
 
         let last_entity_idx = self.entities.len() - 1;
 
         for (type_id, src_component) in &mut self.components {
+            src_component.make_unique();
             let src_data = src_component.data.get_mut();
             let size = src_component.info.layout().size();
             let src_ptr = unsafe { src_data.ptr.as_ptr().add(src_entity_idx * size) };
 
             if let Some(dst_component) = dst.components.get_mut(type_id) {
+                dst_component.make_unique();
                 let dst_data = dst_component.data.get_mut();
 
                 let epoch = unsafe { *src_data.entity_epochs.get_unchecked(src_entity_idx) };
@@ -870,16 +2805,23 @@ impl Archetype {
                 debug_assert_eq!(*dst_entity_epoch, EpochId::start());
                 *dst_entity_epoch = epoch;
 
+                if !src_data.is_enabled(src_entity_idx) {
+                    dst_data.set_enabled(dst_entity_idx, false);
+                }
+
                 let dst_ptr = unsafe { dst_data.ptr.as_ptr().add(dst_entity_idx * size) };
 
                 unsafe {
                     ptr::copy_nonoverlapping(src_ptr, dst_ptr, size);
+                    src_component
+                        .info
+                        .on_move(NonNull::new_unchecked(dst_ptr), src_ptr, 1);
                 }
             } else {
                 let src_ptr = unsafe {
                     NonNull::new_unchecked(src_data.ptr.as_ptr().add(src_entity_idx * size))
                 };
-                missing(&src_component.info, src_ptr);
+                missing(&src_component.info, &mut src_component.deferred, src_ptr);
             }
 
             if src_entity_idx != last_entity_idx {
@@ -897,9 +2839,14 @@ impl Archetype {
                 let last_ptr = unsafe { src_data.ptr.as_ptr().add(last_entity_idx * size) };
                 unsafe {
                     ptr::copy_nonoverlapping(last_ptr, src_ptr, size);
+                    src_component
+                        .info
+                        .on_move(NonNull::new_unchecked(src_ptr), last_ptr, 1);
                 }
             }
 
+            src_data.swap_remove_enabled(src_entity_idx, last_entity_idx);
+
             #[cfg(debug_assertions)]
             unsafe {
                 *src_data.entity_epochs.get_unchecked_mut(last_entity_idx) = EpochId::start();
@@ -910,6 +2857,59 @@ impl Archetype {
 
 pub(crate) const CHUNK_LEN_USIZE: usize = 0x100;
 
+/// Map from borrow target to component ids and borrow indices.
+type BorrowMap = HashMap<TypeId, Vec<(TypeId, usize)>, NoOpHasherBuilder>;
+
+/// Collects borrows supported by components.
+/// Returns maps of all borrows and mutable borrows.
+fn collect_borrows(
+    components: &HashMap<TypeId, ArchetypeComponent, NoOpHasherBuilder>,
+) -> (BorrowMap, BorrowMap) {
+    let mut borrows = HashMap::with_hasher(NoOpHasherBuilder);
+    let mut borrows_mut = HashMap::with_hasher(NoOpHasherBuilder);
+
+    for (&id, c) in components {
+        for (idx, cb) in c.borrows().iter().enumerate() {
+            borrows
+                .entry(cb.target())
+                .or_insert_with(Vec::new)
+                .push((id, idx));
+
+            if cb.has_borrow_mut() {
+                borrows_mut
+                    .entry(cb.target())
+                    .or_insert_with(Vec::new)
+                    .push((id, idx));
+            }
+        }
+    }
+
+    (borrows, borrows_mut)
+}
+
+/// Checks bit of the entity index in bitset of disabled components.
+#[inline]
+pub(crate) fn is_enabled(disabled: &[u64], idx: usize) -> bool {
+    match disabled.get(idx / 64) {
+        None => true,
+        Some(word) => word & (1 << (idx % 64)) == 0,
+    }
+}
+
+/// Hints processor to load memory at `ptr` into the cache.
+/// Pointer is not dereferenced and may be dangling.
+#[inline(always)]
+pub(crate) fn prefetch<T>(ptr: *const T) {
+    #[cfg(target_arch = "x86_64")]
+    // Safety: prefetch never faults.
+    unsafe {
+        core::arch::x86_64::_mm_prefetch(ptr.cast::<i8>(), core::arch::x86_64::_MM_HINT_T0);
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    let _ = ptr;
+}
+
 #[inline]
 pub(crate) const fn chunk_idx(idx: usize) -> usize {
     idx >> 8
@@ -917,9 +2917,10 @@ pub(crate) const fn chunk_idx(idx: usize) -> usize {
 
 #[inline]
 pub(crate) const fn chunks_count(entities: usize) -> usize {
-    entities + (CHUNK_LEN_USIZE - 1) / CHUNK_LEN_USIZE
+    (entities + CHUNK_LEN_USIZE - 1) / CHUNK_LEN_USIZE
 }
 
+#[cfg(feature = "std")]
 #[inline]
 pub(crate) const fn first_of_chunk(idx: usize) -> Option<usize> {
     if idx % CHUNK_LEN_USIZE == 0 {
@@ -928,3 +2929,105 @@ pub(crate) const fn first_of_chunk(idx: usize) -> Option<usize> {
         None
     }
 }
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{
+        test::{Str, U32},
+        world::World,
+    };
+
+    #[test]
+    fn growth_policy() {
+        use crate::archetype::GrowthPolicy;
+
+        let mut world = World::builder().growth_policy(GrowthPolicy::Exact).build();
+        let e = world.spawn((U32(0),));
+        let archetype = world.locate(e).unwrap().archetype as usize;
+
+        for i in 1..5 {
+            world.spawn((U32(i),));
+            assert_eq!(world.archetypes()[archetype].capacity(), i as usize + 1);
+        }
+
+        world.set_archetype_growth_policy::<(U32,)>(GrowthPolicy::Custom(|_, required| {
+            required + 10
+        }));
+        world.spawn((U32(5),));
+        assert_eq!(world.archetypes()[archetype].capacity(), 16);
+
+        world.set_growth_policy(GrowthPolicy::Doubling);
+        for i in 6..17 {
+            world.spawn((U32(i),));
+        }
+        assert_eq!(world.archetypes()[archetype].capacity(), 32);
+    }
+
+    #[test]
+    fn column_allocator() {
+        use core::{
+            alloc::Layout,
+            ptr::NonNull,
+            sync::atomic::{AtomicUsize, Ordering},
+        };
+
+        use alloc::sync::Arc;
+
+        use crate::archetype::ColumnAllocator;
+
+        struct Counting {
+            live: Arc<AtomicUsize>,
+        }
+
+        unsafe impl ColumnAllocator for Counting {
+            fn alloc(&self, layout: Layout) -> NonNull<u8> {
+                self.live.fetch_add(1, Ordering::Relaxed);
+                NonNull::new(unsafe { alloc::alloc::alloc(layout) }).unwrap()
+            }
+
+            unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
+                self.live.fetch_sub(1, Ordering::Relaxed);
+                unsafe { alloc::alloc::dealloc(ptr.as_ptr(), layout) }
+            }
+
+            fn shared_offset(&self, ptr: NonNull<u8>) -> Option<usize> {
+                Some(ptr.as_ptr() as usize)
+            }
+        }
+
+        let live = Arc::new(AtomicUsize::new(0));
+        let mut world = World::builder()
+            .column_allocator(Counting { live: live.clone() })
+            .build();
+
+        let e = world.spawn((U32(7), Str("a")));
+        for i in 0..100 {
+            world.spawn((U32(i),));
+        }
+        assert_eq!(live.load(Ordering::Relaxed), 3);
+
+        let manifest = world.layout_manifest();
+        assert_eq!(manifest.archetypes.len(), 2);
+
+        let archetype = manifest
+            .archetypes
+            .iter()
+            .find(|archetype| archetype.entities == [e])
+            .unwrap();
+        assert_eq!(archetype.columns.len(), 2);
+        for column in &archetype.columns {
+            assert_eq!(column.offset, Some(column.address));
+        }
+
+        let column = archetype
+            .columns
+            .iter()
+            .find(|column| column.size == core::mem::size_of::<U32>())
+            .unwrap();
+        assert_eq!(unsafe { *(column.address as *const U32) }, U32(7));
+
+        drop(world);
+        assert_eq!(live.load(Ordering::Relaxed), 0);
+    }
+}
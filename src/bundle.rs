@@ -1,7 +1,7 @@
-//! This module defines [`Bundle`], [`ComponentBundle`], [`DynamicBundle`] and [`DynamicComponentBundle`] traits.
+//! This module defines [`Bundle`], [`ComponentBundle`], [`DynamicBundle`], [`DynamicComponentBundle`] and [`ReplaceBundle`] traits.
 //!
 //! Tuples of up to 26 elements implement [`Bundle`] and [`DynamicBundle`] if all elements are `'static`.
-//! They additionally implement [`ComponentBundle`] and [`DynamicComponentBundle`] if all elements implement [`Component`].
+//! They additionally implement [`ComponentBundle`], [`DynamicComponentBundle`] and [`ReplaceBundle`] if all elements implement [`Component`].
 //!
 //! Bundles can be used to spawn entities with a set of components or insert multiple components at once.
 //! This is more efficient than spawning an entity and then inserting components one by one.
@@ -11,7 +11,7 @@ use core::{
     any::TypeId,
     fmt,
     marker::PhantomData,
-    mem::{align_of, replace, size_of, ManuallyDrop},
+    mem::{align_of, replace, size_of, take, ManuallyDrop},
     ptr::{self, NonNull},
 };
 
@@ -97,6 +97,26 @@ pub unsafe trait ComponentBundle: Bundle + DynamicComponentBundle {
     fn static_with_components<R>(f: impl FnOnce(&[ComponentInfo]) -> R) -> R;
 }
 
+/// Static collection of components that can hand back
+/// values it replaces when inserted into an entity.
+///
+/// Used by [`World::replace_bundle`](crate::world::World::replace_bundle).
+pub trait ReplaceBundle: ComponentBundle {
+    /// Tuple of optional components, one for each component in the bundle.
+    type Replaced;
+
+    /// Takes values of components out of an entity.
+    ///
+    /// Calls provided closure with type id of each component in the bundle.
+    /// Closure returns pointer to the value of that type or `None` if entity doesn't have it.
+    ///
+    /// # Safety
+    ///
+    /// Returned pointers must point to valid values of corresponding types.
+    /// Values are moved out and must not be used or dropped afterwards.
+    unsafe fn take_replaced(f: impl FnMut(TypeId) -> Option<NonNull<u8>>) -> Self::Replaced;
+}
+
 macro_rules! impl_bundle {
     () => {
         unsafe impl DynamicBundle for () {
@@ -154,6 +174,13 @@ macro_rules! impl_bundle {
                 f(&[])
             }
         }
+
+        impl ReplaceBundle for () {
+            type Replaced = ();
+
+            #[inline]
+            unsafe fn take_replaced(_f: impl FnMut(TypeId) -> Option<NonNull<u8>>) {}
+        }
     };
 
     ($($a:ident)+) => {
@@ -244,6 +271,19 @@ macro_rules! impl_bundle {
                 f(&[$(ComponentInfo::of::<$a>(),)+])
             }
         }
+
+        impl<$($a),+> ReplaceBundle for ($($a,)+)
+        where $($a: Component,)+
+        {
+            type Replaced = ($(Option<$a>,)+);
+
+            #[inline]
+            unsafe fn take_replaced(mut f: impl FnMut(TypeId) -> Option<NonNull<u8>>) -> Self::Replaced {
+                ($(
+                    f(TypeId::of::<$a>()).map(|ptr| unsafe { ptr::read(ptr.as_ptr().cast::<$a>()) }),
+                )+)
+            }
+        }
     };
 }
 
@@ -273,6 +313,10 @@ impl Drop for EntityBuilder {
             let ptr = unsafe { NonNull::new_unchecked(self.ptr.as_ptr().add(offset)) };
             info.final_drop(ptr, 1);
         }
+
+        if self.layout.size() != 0 {
+            unsafe { alloc::alloc::dealloc(self.ptr.as_ptr(), self.layout) }
+        }
     }
 }
 
@@ -323,12 +367,28 @@ impl EntityBuilder {
             return self;
         }
 
+        let value_offset = self.alloc_slot(Layout::new::<T>());
+
+        unsafe {
+            ptr::write(self.ptr.as_ptr().add(value_offset).cast(), value);
+        }
+
+        self.ids.push(TypeId::of::<T>());
+        self.infos.push(ComponentInfo::of::<T>());
+        self.offsets.push(value_offset);
+
+        self
+    }
+
+    /// Allocates space for a value with specified layout
+    /// and returns its offset.
+    /// Length is advanced past the value.
+    fn alloc_slot(&mut self, layout: Layout) -> usize {
         debug_assert!(self.len <= self.layout.size());
         let value_layout = Layout::from_size_align(self.len, self.layout.align()).unwrap();
 
-        let (new_value_layout, value_offset) = value_layout
-            .extend(Layout::new::<T>())
-            .expect("EntityBuilder overflow");
+        let (new_value_layout, value_offset) =
+            value_layout.extend(layout).expect("EntityBuilder overflow");
 
         self.ids.reserve(1);
         self.infos.reserve(1);
@@ -366,24 +426,57 @@ impl EntityBuilder {
                 let old_ptr = replace(&mut self.ptr, new_ptr);
                 let old_layout = replace(&mut self.layout, new_layout);
 
-                alloc::alloc::dealloc(old_ptr.as_ptr(), old_layout);
+                // Initial dangling pointer is not allocated.
+                if old_layout.size() != 0 {
+                    alloc::alloc::dealloc(old_ptr.as_ptr(), old_layout);
+                }
             }
         }
 
-        unsafe {
-            debug_assert!(self.len <= self.layout.size());
-            debug_assert!(self.len <= value_offset);
-            debug_assert!(value_offset + size_of::<T>() <= self.layout.size());
+        debug_assert!(self.len <= self.layout.size());
+        debug_assert!(self.len <= value_offset);
+        debug_assert!(value_offset + layout.size() <= self.layout.size());
 
-            ptr::write(self.ptr.as_ptr().add(value_offset).cast(), value);
-            self.len = value_offset + size_of::<T>();
+        self.len = value_offset + layout.size();
+        value_offset
+    }
+
+    /// Adds clone of the component value to the builder.
+    ///
+    /// # Safety
+    ///
+    /// Component must be cloneable, `src` must point to its valid value
+    /// and builder must not contain this component.
+    #[cfg(feature = "undo")]
+    pub(crate) unsafe fn add_cloned(&mut self, info: &ComponentInfo, src: NonNull<u8>) {
+        debug_assert!(!self.ids.contains(&info.id()));
+
+        let offset = self.alloc_slot(info.layout());
+
+        unsafe {
+            info.clone_one(src, NonNull::new_unchecked(self.ptr.as_ptr().add(offset)));
         }
 
-        self.ids.push(TypeId::of::<T>());
-        self.infos.push(ComponentInfo::of::<T>());
-        self.offsets.push(value_offset);
+        self.ids.push(info.id());
+        self.infos.push(info.clone());
+        self.offsets.push(offset);
+    }
 
-        self
+    /// Returns builder with clones of all components.
+    ///
+    /// # Safety
+    ///
+    /// All components must be cloneable.
+    #[cfg(feature = "undo")]
+    pub(crate) unsafe fn clone_cloneable(&self) -> EntityBuilder {
+        let mut builder = EntityBuilder::new();
+        for (info, &offset) in self.infos.iter().zip(&self.offsets) {
+            unsafe {
+                let ptr = NonNull::new_unchecked(self.ptr.as_ptr().add(offset));
+                builder.add_cloned(info, ptr);
+            }
+        }
+        builder
     }
 
     /// Returns reference to component from builder.
@@ -439,10 +532,12 @@ unsafe impl DynamicBundle for EntityBuilder {
     }
 
     #[inline]
-    fn put(self, mut f: impl FnMut(NonNull<u8>, TypeId, usize)) {
-        let me = ManuallyDrop::new(self);
-        for (info, &offset) in me.infos.iter().zip(&me.offsets) {
-            let ptr = unsafe { NonNull::new_unchecked(me.ptr.as_ptr().add(offset)) };
+    fn put(mut self, mut f: impl FnMut(NonNull<u8>, TypeId, usize)) {
+        // Values are moved out, builder releases only its storage.
+        let infos = take(&mut self.infos);
+        let offsets = take(&mut self.offsets);
+        for (info, &offset) in infos.iter().zip(&offsets) {
+            let ptr = unsafe { NonNull::new_unchecked(self.ptr.as_ptr().add(offset)) };
             f(ptr, info.id(), info.layout().size());
         }
     }
@@ -519,3 +614,38 @@ where
         B::static_with_components(f)
     }
 }
+
+/// Index of the first element of a tuple bundle.
+/// Used by [`ContainsComponent`] to locate component in the bundle.
+#[derive(Clone, Copy, Debug)]
+pub enum Here {}
+
+/// Index of the element after one at index `I`.
+/// Used by [`ContainsComponent`] to locate component in the bundle.
+#[derive(Clone, Copy, Debug)]
+pub struct There<I>(PhantomData<I>);
+
+/// Static proof that bundle contains component `T`.
+///
+/// `I` is a type-level index of the component in the bundle.
+/// It is always inferred and never named explicitly.
+/// Implemented for tuples that contain `T`.
+pub trait ContainsComponent<T, I>: Bundle {}
+
+macro_rules! impl_contains {
+    (@ [$($all:ident)*] $idx:ty;) => {};
+    (@ [$($all:ident)*] $idx:ty; $head:ident $($tail:ident)*) => {
+        impl<$($all),+> ContainsComponent<$head, $idx> for ($($all,)+)
+        where
+            $($all: 'static,)+
+        {
+        }
+
+        impl_contains!(@ [$($all)*] There<$idx>; $($tail)*);
+    };
+    ($($all:ident)*) => {
+        impl_contains!(@ [$($all)*] Here; $($all)*);
+    };
+}
+
+for_tuple!(impl_contains);
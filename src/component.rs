@@ -5,6 +5,7 @@ use core::{
     alloc::Layout,
     any::{type_name, Any, TypeId},
     borrow::{Borrow, BorrowMut},
+    hash::{Hash, Hasher},
     marker::PhantomData,
     mem::{transmute, ManuallyDrop},
     ptr::{self, drop_in_place, slice_from_raw_parts_mut, NonNull},
@@ -12,7 +13,11 @@ use core::{
 
 use hashbrown::hash_map::{Entry, HashMap};
 
-use crate::{action::ActionEncoder, entity::EntityId, hash::NoOpHasherBuilder};
+use crate::{
+    action::ActionEncoder,
+    entity::{EntityId, WeakEntity},
+    hash::{MulHasherBuilder, NoOpHasherBuilder},
+};
 
 pub use edict_proc::Component;
 
@@ -162,6 +167,7 @@ impl ComponentBorrow {
 /// They are called "external", must be registered manually and require usage of
 /// [`World::spawn_external`], [`World::insert_external`], and [`World::insert_external_bundle`] methods that would panic
 /// when component type is not yet registered. Even if the type happens to implement [`Component`] trait.
+/// Alternatively such types can be wrapped into [`External`] that implements this trait.
 ///
 /// [`World::spawn_external`]: edict::world::World::spawn_external
 /// [`World::insert_external`]: edict::world::World::insert_external
@@ -190,6 +196,41 @@ pub trait Component: Sized + 'static {
         true
     }
 
+    /// Hook that is executed after component is moved to a new address
+    /// by the archetype storage.
+    /// Receives component at the new address and the address it was moved from.
+    /// Previous address must not be dereferenced.
+    ///
+    /// Components are moved when archetype storage grows or shrinks,
+    /// when entity changes its archetype and when entity takes place of despawned one.
+    /// This allows storing self-referential data or data which address
+    /// was registered elsewhere, e.g. through FFI.
+    ///
+    /// Can be overridden at registration with [`ComponentInfoRef::on_move`].
+    const ON_MOVE: Option<fn(&mut Self, *const Self)> = None;
+
+    /// Hook that is executed for components moved into another world with [`World::merge`].
+    /// Receives function that maps ids of entities from the merged world to their new ids.
+    /// Components that store ids of other entities should remap them.
+    ///
+    /// [`World::merge`]: edict::world::World::merge
+    const ON_REMAP: Option<fn(&mut Self, &dyn Fn(EntityId) -> EntityId)> = None;
+
+    /// Hook that visits [`WeakEntity`] references stored in the component.
+    /// Weak references to despawned entities are nulled
+    /// during [`World::maintenance`].
+    ///
+    /// [`World::maintenance`]: edict::world::World::maintenance
+    const VISIT_WEAK: Option<fn(&mut Self, &mut dyn FnMut(&mut WeakEntity))> = None;
+
+    /// Stable identifier of the component type.
+    ///
+    /// Unlike [`TypeId`] and type name it does not change between builds,
+    /// so it can be used to identify components in serialized worlds
+    /// and network protocols.
+    /// Stable names must be unique among registered components.
+    const STABLE_NAME: Option<&'static str> = None;
+
     /// Returns array of component borrows supported by the type.
     #[inline]
     fn borrows() -> Vec<ComponentBorrow> {
@@ -197,6 +238,76 @@ pub trait Component: Sized + 'static {
     }
 }
 
+/// Wrapper that makes any `'static` type a [`Component`].
+///
+/// Types from other crates can't implement [`Component`]
+/// and otherwise have to be registered as external components.
+/// Wrapped value is used with regular methods like [`World::spawn`]
+/// and self-registers with default configuration.
+/// Wrapper can be borrowed as the inner type by borrow queries.
+///
+/// [`World::spawn`]: edict::world::World::spawn
+///
+/// # Example
+///
+/// ```
+/// # use edict::{component::External, world::World};
+/// let mut world = World::new();
+/// let e = world.spawn((External(42u32),));
+///
+/// assert_eq!(world.query_one_mut::<&External<u32>>(e).unwrap().0, 42);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct External<T>(pub T);
+
+impl<T> External<T> {
+    /// Returns wrapped value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for External<T> {
+    #[inline]
+    fn from(value: T) -> Self {
+        External(value)
+    }
+}
+
+impl<T> core::ops::Deref for External<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> core::ops::DerefMut for External<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T> Component for External<T>
+where
+    T: 'static,
+{
+    #[inline]
+    fn borrows() -> Vec<ComponentBorrow> {
+        vec![
+            ComponentBorrow::auto::<Self>(),
+            ComponentBorrow::make::<T>(
+                |ptr, PhantomData| unsafe { &ptr.cast::<External<T>>().as_ref().0 },
+                Some(|ptr, PhantomData| unsafe { &mut ptr.cast::<External<T>>().as_mut().0 }),
+            ),
+        ]
+    }
+}
+
 /// Type information required for components.
 #[derive(Clone)]
 pub struct ComponentInfo {
@@ -213,9 +324,19 @@ pub struct ComponentInfo {
     /// Supports custom hooks.
     drop_one: DropOneFn,
 
+    /// Function that calls drop hook for a component
+    /// without dropping it.
+    drop_hook: DropOneFn,
+
     /// Context for `drop_one` command when component is dropped.
     on_drop: Arc<dyn Any + Send + Sync>,
 
+    /// Set if components are dropped with [`World::flush_drops`]
+    /// instead of being dropped on despawn.
+    ///
+    /// [`World::flush_drops`]: edict::world::World::flush_drops
+    deferred_drop: bool,
+
     /// Function that replaces component at target location.
     /// Supports custom hooks.
     set_one: SetOneFn,
@@ -229,6 +350,33 @@ pub struct ComponentInfo {
 
     /// An array of possible component borrows.
     borrows: Arc<[ComponentBorrow]>,
+
+    /// Function that feeds slice of components into hasher.
+    /// Set only for components registered as hashable.
+    hash_slice: Option<HashSliceFn>,
+
+    /// Function that compares two components for equality.
+    /// Set only for components registered as comparable.
+    eq_one: Option<EqOneFn>,
+
+    /// Function that clones component into uninitialized location.
+    /// Set only for components registered as cloneable.
+    clone_one: Option<CloneOneFn>,
+
+    /// Function that calls move hook for components.
+    /// Set only for components with move hook.
+    on_move: Option<OnMoveFn>,
+
+    /// Function that calls remap hook for components.
+    /// Set only for components with remap hook.
+    on_remap: Option<OnRemapFn>,
+
+    /// Function that calls weak references visiting hook for components.
+    /// Set only for components with the hook.
+    visit_weak: Option<VisitWeakFn>,
+
+    /// Stable identifier of the component.
+    stable_name: Option<&'static str>,
 }
 
 impl ComponentInfo {
@@ -243,11 +391,29 @@ impl ComponentInfo {
             layout: Layout::new::<T>(),
             name: T::name(),
             drop_one: drop_one::<T, DefaultDropHook>,
+            drop_hook: drop_hook::<T, DefaultDropHook>,
             on_drop: Arc::new(DefaultDropHook),
+            deferred_drop: false,
             set_one: set_one::<T, DefaultSetHook, DefaultDropHook>,
             on_replace: Arc::new(DefaultSetHook),
             final_drop: final_drop::<T>,
             borrows: Arc::from(T::borrows()),
+            hash_slice: None,
+            eq_one: None,
+            clone_one: None,
+            on_move: match T::ON_MOVE {
+                None => None,
+                Some(hook) => Some(on_move::<T, _>(hook)),
+            },
+            on_remap: match T::ON_REMAP {
+                None => None,
+                Some(_) => Some(on_remap::<T>),
+            },
+            visit_weak: match T::VISIT_WEAK {
+                None => None,
+                Some(_) => Some(visit_weak::<T>),
+            },
+            stable_name: T::STABLE_NAME,
         }
     }
 
@@ -262,11 +428,54 @@ impl ComponentInfo {
             layout: Layout::new::<T>(),
             name: type_name::<T>(),
             drop_one: drop_one::<T, ExternalDropHook>,
+            drop_hook: drop_hook::<T, ExternalDropHook>,
             on_drop: Arc::new(ExternalDropHook),
+            deferred_drop: false,
             set_one: set_one::<T, ExternalSetHook, ExternalDropHook>,
             on_replace: Arc::new(ExternalSetHook),
             final_drop: final_drop::<T>,
             borrows: Arc::new([]),
+            hash_slice: None,
+            eq_one: None,
+            clone_one: None,
+            on_move: None,
+            on_remap: None,
+            visit_weak: None,
+            stable_name: None,
+        }
+    }
+
+    /// Returns component information for component type defined outside of Rust.
+    ///
+    /// `M` is a marker type that provides unique [`TypeId`] for the component.
+    /// `drop` is called for values that are dropped. Hooks are not supported.
+    #[cfg(feature = "ffi")]
+    pub(crate) fn foreign<M>(layout: Layout, name: &'static str, drop: FinalDrop) -> Self
+    where
+        M: 'static,
+    {
+        ComponentInfo {
+            id: TypeId::of::<M>(),
+            layout,
+            name,
+            drop_one: drop_one_foreign,
+            drop_hook: drop_hook_foreign,
+            deferred_drop: false,
+            on_drop: Arc::new(ForeignDrop {
+                drop,
+                size: layout.size(),
+            }),
+            set_one: set_one_foreign,
+            on_replace: Arc::new(()),
+            final_drop: drop,
+            borrows: Arc::new([]),
+            hash_slice: None,
+            eq_one: None,
+            clone_one: None,
+            on_move: None,
+            on_remap: None,
+            visit_weak: None,
+            stable_name: None,
         }
     }
 
@@ -287,6 +496,20 @@ impl ComponentInfo {
         }
     }
 
+    /// Calls drop hook for a component without dropping it.
+    #[inline(always)]
+    pub(crate) fn drop_hook(&self, ptr: NonNull<u8>, id: EntityId, encoder: ActionEncoder) {
+        unsafe {
+            (self.drop_hook)(NonNull::from(&*self.on_drop).cast(), ptr, id, encoder);
+        }
+    }
+
+    /// Returns `true` if dropping of despawned components is deferred.
+    #[inline(always)]
+    pub(crate) fn is_drop_deferred(&self) -> bool {
+        self.deferred_drop
+    }
+
     #[inline(always)]
     pub(crate) fn set_one(
         &self,
@@ -319,10 +542,148 @@ impl ComponentInfo {
         self.name
     }
 
+    #[inline(always)]
+    pub(crate) fn stable_name(&self) -> Option<&'static str> {
+        self.stable_name
+    }
+
     #[inline]
     pub(crate) fn borrows(&self) -> &[ComponentBorrow] {
         &self.borrows
     }
+
+    /// Returns `true` if components of this type have move hook.
+    #[inline(always)]
+    pub(crate) fn has_on_move(&self) -> bool {
+        self.on_move.is_some()
+    }
+
+    /// Calls move hook for `count` components moved from `src` to `dst` address.
+    ///
+    /// # Safety
+    ///
+    /// `dst` must point to `count` initialized components of this type.
+    /// Memory at `src` must still be allocated.
+    #[inline(always)]
+    pub(crate) unsafe fn on_move(&self, dst: NonNull<u8>, src: *const u8, count: usize) {
+        if let Some(on_move) = &self.on_move {
+            on_move(dst, src, count);
+        }
+    }
+
+    /// Calls remap hook for `count` components starting at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to `count` initialized components of this type.
+    #[inline(always)]
+    pub(crate) unsafe fn on_remap(
+        &self,
+        ptr: NonNull<u8>,
+        count: usize,
+        f: &dyn Fn(EntityId) -> EntityId,
+    ) {
+        if let Some(on_remap) = self.on_remap {
+            unsafe {
+                on_remap(ptr, count, f);
+            }
+        }
+    }
+
+    /// Returns `true` if component stores weak references to entities.
+    #[inline(always)]
+    pub(crate) fn has_weak(&self) -> bool {
+        self.visit_weak.is_some()
+    }
+
+    /// Calls weak references visiting hook for component at `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to initialized component of this type.
+    #[inline(always)]
+    pub(crate) unsafe fn visit_weak(&self, ptr: NonNull<u8>, f: &mut dyn FnMut(&mut WeakEntity)) {
+        if let Some(visit_weak) = self.visit_weak {
+            unsafe {
+                visit_weak(ptr, f);
+            }
+        }
+    }
+
+    /// Returns `true` if component was registered as hashable.
+    #[inline(always)]
+    pub(crate) fn is_hashable(&self) -> bool {
+        self.hash_slice.is_some()
+    }
+
+    /// Feeds `count` components starting at `ptr` into the hasher.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to `count` initialized components of this type.
+    /// Component must be hashable.
+    #[inline(always)]
+    pub(crate) unsafe fn hash_slice(&self, ptr: NonNull<u8>, count: usize, state: &mut dyn Hasher) {
+        debug_assert!(self.hash_slice.is_some());
+        unsafe {
+            (self.hash_slice.unwrap_unchecked())(ptr, count, state);
+        }
+    }
+
+    /// Returns `true` if component was registered as cloneable.
+    #[inline(always)]
+    pub(crate) fn is_cloneable(&self) -> bool {
+        self.clone_one.is_some()
+    }
+
+    /// Returns `true` if component was registered as comparable.
+    #[inline(always)]
+    pub(crate) fn is_comparable(&self) -> bool {
+        self.eq_one.is_some()
+    }
+
+    /// Returns `true` if component was registered as comparable and cloneable.
+    #[inline(always)]
+    pub(crate) fn is_watchable(&self) -> bool {
+        self.eq_one.is_some() && self.clone_one.is_some()
+    }
+
+    /// Compares two components for equality.
+    ///
+    /// # Safety
+    ///
+    /// `lhs` and `rhs` must point to initialized components of this type.
+    /// Component must be comparable.
+    #[inline(always)]
+    pub(crate) unsafe fn eq_one(&self, lhs: NonNull<u8>, rhs: NonNull<u8>) -> bool {
+        debug_assert!(self.eq_one.is_some());
+        unsafe { (self.eq_one.unwrap_unchecked())(lhs, rhs) }
+    }
+
+    /// Clones component from `src` into uninitialized `dst`.
+    ///
+    /// # Safety
+    ///
+    /// `src` must point to initialized component of this type.
+    /// `dst` must be valid for writes of this type.
+    /// Component must be cloneable.
+    #[inline(always)]
+    pub(crate) unsafe fn clone_one(&self, src: NonNull<u8>, dst: NonNull<u8>) {
+        debug_assert!(self.clone_one.is_some());
+        unsafe { (self.clone_one.unwrap_unchecked())(src, dst) }
+    }
+
+    /// Appends borrows to the list of borrows supported by the component.
+    /// Borrows with targets already supported by the component are ignored.
+    pub(crate) fn extend_borrows(&mut self, borrows: &[ComponentBorrow]) {
+        let mut all = self.borrows.to_vec();
+        for borrow in borrows {
+            if all.iter().all(|b| b.target() != borrow.target()) {
+                all.push(*borrow);
+            }
+        }
+        self.borrows = Arc::from(all);
+    }
 }
 
 /// Trait to be implemented by custom drop hooks.
@@ -437,6 +798,7 @@ pub struct ComponentInfoRef<
     S: SetHook<T> = DefaultSetHook,
 > {
     info: Option<&'a mut ComponentInfo>,
+    names: &'a mut NameIndex,
     phantom: PhantomData<T>,
     drop: ManuallyDrop<D>,
     set: ManuallyDrop<S>,
@@ -465,12 +827,14 @@ where
     fn drop_impl(&mut self) {
         let info = self.info.as_mut().unwrap();
         info.drop_one = drop_one::<T, D>;
+        info.drop_hook = drop_hook::<T, D>;
         info.on_drop = Arc::new(unsafe { ManuallyDrop::take(&mut self.drop) });
         info.set_one = set_one::<T, S, D>;
         info.on_replace = Arc::new(unsafe { ManuallyDrop::take(&mut self.set) });
         if let Some(name) = self.name {
             info.name = name;
         }
+        self.names.add(info);
     }
 
     /// Finishes component registration.
@@ -493,6 +857,7 @@ where
 
         ComponentInfoRef {
             info: unsafe { ptr::read(&me.info) },
+            names: unsafe { ptr::read(&me.names) },
             phantom: me.phantom,
             drop: ManuallyDrop::new(hook),
             set: unsafe { ptr::read(&me.set) },
@@ -523,6 +888,7 @@ where
 
         ComponentInfoRef {
             info: unsafe { ptr::read(&me.info) },
+            names: unsafe { ptr::read(&me.names) },
             phantom: me.phantom,
             drop: unsafe { ptr::read(&me.drop) },
             set: ManuallyDrop::new(hook),
@@ -541,22 +907,147 @@ where
         self.on_replace(hook)
     }
 
+    /// Defers dropping of components of despawned entities
+    /// and components dropped when entity changes archetype.
+    ///
+    /// Drop hook is still executed immediately,
+    /// but component value is moved into a queue
+    /// and dropped with [`World::flush_drops`].
+    /// This keeps dropping of large components out of the hot path.
+    ///
+    /// [`World::flush_drops`]: edict::world::World::flush_drops
+    pub fn deferred_drop(mut self) -> Self {
+        self.info.as_mut().unwrap().deferred_drop = true;
+        self
+    }
+
     /// Overrides default component type name.
     pub fn name(mut self, name: &'static str) -> Self {
         self.name = Some(name);
         self
     }
+
+    /// Sets stable identifier of the component.
+    /// Overrides [`Component::STABLE_NAME`].
+    ///
+    /// # Panics
+    ///
+    /// Finishing registration panics if another component
+    /// is already registered with the same stable name.
+    pub fn stable_name(mut self, name: &'static str) -> Self {
+        self.info.as_mut().unwrap().stable_name = Some(name);
+        self
+    }
+
+    /// Configures move hook for this component.
+    /// Overrides [`Component::ON_MOVE`].
+    ///
+    /// Unlike [`Component::ON_MOVE`] this works for external components
+    /// and the hook may capture state, like a handle to the system
+    /// that tracks components by address.
+    pub fn on_move<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&mut T, *const T) + Send + Sync + 'static,
+    {
+        self.info.as_mut().unwrap().on_move = Some(on_move::<T, F>(hook));
+        self
+    }
+
+    /// Registers [`Hash`] implementation of the component,
+    /// allowing it to be included into [`World::checksum`].
+    ///
+    /// [`World::checksum`]: edict::world::World::checksum
+    pub fn hashable(mut self) -> Self
+    where
+        T: Hash,
+    {
+        self.info.as_mut().unwrap().hash_slice = Some(hash_slice::<T>);
+        self
+    }
+
+    /// Registers [`PartialEq`] implementation of the component,
+    /// allowing changes of its value to be detected by [`World::watch`]
+    /// and values to be compared by [`World::diff_entities`].
+    ///
+    /// [`World::watch`]: edict::world::World::watch
+    /// [`World::diff_entities`]: edict::world::World::diff_entities
+    pub fn comparable(mut self) -> Self
+    where
+        T: PartialEq,
+    {
+        self.info.as_mut().unwrap().eq_one = Some(eq_one::<T>);
+        self
+    }
+
+    /// Registers [`Clone`] implementation of the component,
+    /// allowing its value to be snapshotted by [`World::watch`].
+    ///
+    /// [`World::watch`]: edict::world::World::watch
+    pub fn cloneable(mut self) -> Self
+    where
+        T: Clone,
+    {
+        self.info.as_mut().unwrap().clone_one = Some(clone_one::<T>);
+        self
+    }
+}
+
+/// Maps component names and stable names to their ids.
+struct NameIndex {
+    names: HashMap<&'static str, TypeId, MulHasherBuilder>,
+    stable: HashMap<&'static str, TypeId, MulHasherBuilder>,
+}
+
+impl NameIndex {
+    const fn new() -> Self {
+        NameIndex {
+            names: HashMap::with_hasher(MulHasherBuilder),
+            stable: HashMap::with_hasher(MulHasherBuilder),
+        }
+    }
+
+    fn remove(&mut self, info: &ComponentInfo) {
+        if self.names.get(info.name) == Some(&info.id) {
+            self.names.remove(info.name);
+        }
+
+        if let Some(stable_name) = info.stable_name {
+            if self.stable.get(stable_name) == Some(&info.id) {
+                self.stable.remove(stable_name);
+            }
+        }
+    }
+
+    fn add(&mut self, info: &ComponentInfo) {
+        self.names.entry(info.name).or_insert(info.id);
+
+        if let Some(stable_name) = info.stable_name {
+            let id = *self.stable.entry(stable_name).or_insert(info.id);
+            assert!(
+                id == info.id,
+                "Stable name `{}` of component `{}` is already used by another component",
+                stable_name,
+                info.name,
+            );
+        }
+    }
 }
 
 /// Container for [`ComponentInfo`]s.
 pub(crate) struct ComponentRegistry {
     components: HashMap<TypeId, ComponentInfo, NoOpHasherBuilder>,
+    names: NameIndex,
+
+    /// Set when component with weak references visiting hook is registered.
+    has_weak: bool,
 }
 
 impl ComponentRegistry {
     pub const fn new() -> Self {
         Self {
             components: HashMap::with_hasher(NoOpHasherBuilder),
+            names: NameIndex::new(),
+            has_weak: false,
         }
     }
 
@@ -564,24 +1055,78 @@ impl ComponentRegistry {
     where
         T: Component,
     {
-        self.components
-            .entry(TypeId::of::<T>())
-            .or_insert_with(ComponentInfo::of::<T>)
+        match self.components.entry(TypeId::of::<T>()) {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => {
+                let info = e.insert(ComponentInfo::of::<T>());
+                self.names.add(info);
+                self.has_weak |= info.has_weak();
+                info
+            }
+        }
     }
 
     pub fn get_or_register_raw(&mut self, info: ComponentInfo) -> &ComponentInfo {
-        self.components.entry(info.id()).or_insert(info)
+        match self.components.entry(info.id()) {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => {
+                self.names.add(&info);
+                self.has_weak |= info.has_weak();
+                e.insert(info)
+            }
+        }
     }
 
     pub fn register_raw(&mut self, info: ComponentInfo) {
         match self.components.entry(info.id()) {
             Entry::Occupied(_) => panic!("Component already registered"),
             Entry::Vacant(e) => {
+                self.names.add(&info);
+                self.has_weak |= info.has_weak();
                 e.insert(info);
             }
         }
     }
 
+    /// Returns registry with the same registered components.
+    pub fn fork(&self) -> Self {
+        let mut registry = ComponentRegistry::new();
+        for info in self.components.values() {
+            registry.register_raw(info.clone());
+        }
+        registry
+    }
+
+    /// Removes registration of the component.
+    /// Returns removed component information.
+    pub fn unregister(&mut self, id: TypeId) -> Option<ComponentInfo> {
+        let info = self.components.remove(&id)?;
+        self.names.remove(&info);
+        Some(info)
+    }
+
+    /// Returns `true` if any component with weak references
+    /// visiting hook was ever registered.
+    #[inline]
+    pub fn has_weak(&self) -> bool {
+        self.has_weak
+    }
+
+    /// Returns id of the component registered with specified name.
+    pub fn id_by_name(&self, name: &str) -> Option<TypeId> {
+        self.names.names.get(name).copied()
+    }
+
+    /// Returns id of the component registered with specified stable name.
+    pub fn id_by_stable_name(&self, stable_name: &str) -> Option<TypeId> {
+        self.names.stable.get(stable_name).copied()
+    }
+
+    /// Returns stable names of all registered components that have one.
+    pub fn stable_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.names.stable.keys().copied()
+    }
+
     pub fn ensure_component_registered<T>(&mut self)
     where
         T: Component,
@@ -607,6 +1152,7 @@ impl ComponentRegistry {
 
         ComponentInfoRef {
             info: Some(info),
+            names: &mut self.names,
             phantom: PhantomData,
             drop: ManuallyDrop::new(DefaultDropHook),
             set: ManuallyDrop::new(DefaultSetHook),
@@ -627,6 +1173,7 @@ impl ComponentRegistry {
 
         ComponentInfoRef {
             info: Some(info),
+            names: &mut self.names,
             phantom: PhantomData,
             drop: ManuallyDrop::new(ExternalDropHook),
             set: ManuallyDrop::new(ExternalSetHook),
@@ -638,6 +1185,10 @@ impl ComponentRegistry {
         self.components.get(&id)
     }
 
+    pub fn get_info_mut(&mut self, id: TypeId) -> Option<&mut ComponentInfo> {
+        self.components.get_mut(&id)
+    }
+
     pub fn iter_info(&self) -> impl Iterator<Item = &ComponentInfo> {
         self.components.values()
     }
@@ -649,6 +1200,12 @@ type DropOneFn = unsafe fn(NonNull<Opaque>, NonNull<u8>, EntityId, ActionEncoder
 type SetOneFn =
     unsafe fn(NonNull<Opaque>, NonNull<Opaque>, NonNull<u8>, NonNull<u8>, EntityId, ActionEncoder);
 type FinalDrop = unsafe fn(NonNull<u8>, usize);
+type HashSliceFn = unsafe fn(NonNull<u8>, usize, &mut dyn Hasher);
+type EqOneFn = unsafe fn(NonNull<u8>, NonNull<u8>) -> bool;
+type CloneOneFn = unsafe fn(NonNull<u8>, NonNull<u8>);
+type OnMoveFn = Arc<dyn Fn(NonNull<u8>, *const u8, usize) + Send + Sync>;
+type OnRemapFn = unsafe fn(NonNull<u8>, usize, &dyn Fn(EntityId) -> EntityId);
+type VisitWeakFn = unsafe fn(NonNull<u8>, &mut dyn FnMut(&mut WeakEntity));
 
 unsafe fn drop_one<T, D>(
     hook: NonNull<Opaque>,
@@ -668,6 +1225,19 @@ unsafe fn drop_one<T, D>(
     }
 }
 
+unsafe fn drop_hook<T, D>(
+    hook: NonNull<Opaque>,
+    ptr: NonNull<u8>,
+    id: EntityId,
+    encoder: ActionEncoder,
+) where
+    T: 'static,
+    D: DropHook<T>,
+{
+    let hook = unsafe { hook.cast::<D>().as_ref() };
+    hook.on_drop(unsafe { ptr.cast::<T>().as_mut() }, id, encoder);
+}
+
 unsafe fn set_one<T, S, D>(
     on_replace: NonNull<Opaque>,
     on_drop: NonNull<Opaque>,
@@ -692,6 +1262,49 @@ unsafe fn set_one<T, S, D>(
     }
 }
 
+/// Drop function and size of component type defined outside of Rust.
+#[cfg(feature = "ffi")]
+struct ForeignDrop {
+    drop: FinalDrop,
+    size: usize,
+}
+
+#[cfg(feature = "ffi")]
+unsafe fn drop_one_foreign(
+    on_drop: NonNull<Opaque>,
+    ptr: NonNull<u8>,
+    _id: EntityId,
+    _encoder: ActionEncoder,
+) {
+    let on_drop = unsafe { on_drop.cast::<ForeignDrop>().as_ref() };
+    unsafe { (on_drop.drop)(ptr, 1) }
+}
+
+#[cfg(feature = "ffi")]
+unsafe fn drop_hook_foreign(
+    _on_drop: NonNull<Opaque>,
+    _ptr: NonNull<u8>,
+    _id: EntityId,
+    _encoder: ActionEncoder,
+) {
+}
+
+#[cfg(feature = "ffi")]
+unsafe fn set_one_foreign(
+    _on_replace: NonNull<Opaque>,
+    on_drop: NonNull<Opaque>,
+    dst: NonNull<u8>,
+    src: NonNull<u8>,
+    _id: EntityId,
+    _encoder: ActionEncoder,
+) {
+    let on_drop = unsafe { on_drop.cast::<ForeignDrop>().as_ref() };
+    unsafe {
+        (on_drop.drop)(dst, 1);
+        ptr::copy_nonoverlapping(src.as_ptr(), dst.as_ptr(), on_drop.size);
+    }
+}
+
 /// This drop is always called for all components when `Archetype` is dropped.
 /// Does not invoke any hooks.
 unsafe fn final_drop<T>(ptr: NonNull<u8>, count: usize) {
@@ -700,3 +1313,189 @@ unsafe fn final_drop<T>(ptr: NonNull<u8>, count: usize) {
         drop_in_place(slice);
     }
 }
+
+/// Wraps move hook to be called for `count` components moved from `src` to `dst`.
+/// `dst` must point to `count` initialized components of type `T`.
+fn on_move<T, F>(hook: F) -> OnMoveFn
+where
+    T: 'static,
+    F: Fn(&mut T, *const T) + Send + Sync + 'static,
+{
+    Arc::new(move |dst: NonNull<u8>, src: *const u8, count: usize| {
+        for idx in 0..count {
+            let component = unsafe { &mut *dst.cast::<T>().as_ptr().add(idx) };
+
+            // Value at old address is moved out, so it must not be read.
+            hook(component, src.cast::<T>().wrapping_add(idx));
+        }
+    })
+}
+
+unsafe fn on_remap<T>(ptr: NonNull<u8>, count: usize, f: &dyn Fn(EntityId) -> EntityId)
+where
+    T: Component,
+{
+    let hook = unsafe { T::ON_REMAP.unwrap_unchecked() };
+
+    for idx in 0..count {
+        let component = unsafe { &mut *ptr.cast::<T>().as_ptr().add(idx) };
+        hook(component, f);
+    }
+}
+
+unsafe fn visit_weak<T>(ptr: NonNull<u8>, f: &mut dyn FnMut(&mut WeakEntity))
+where
+    T: Component,
+{
+    let hook = unsafe { T::VISIT_WEAK.unwrap_unchecked() };
+    hook(unsafe { &mut *ptr.cast::<T>().as_ptr() }, f);
+}
+
+unsafe fn hash_slice<T>(ptr: NonNull<u8>, count: usize, mut state: &mut dyn Hasher)
+where
+    T: Hash,
+{
+    let slice = unsafe { core::slice::from_raw_parts(ptr.cast::<T>().as_ptr(), count) };
+    T::hash_slice(slice, &mut state);
+}
+
+unsafe fn eq_one<T>(lhs: NonNull<u8>, rhs: NonNull<u8>) -> bool
+where
+    T: PartialEq,
+{
+    unsafe { *lhs.cast::<T>().as_ref() == *rhs.cast::<T>().as_ref() }
+}
+
+unsafe fn clone_one<T>(src: NonNull<u8>, dst: NonNull<u8>)
+where
+    T: Clone,
+{
+    unsafe {
+        dst.cast::<T>()
+            .as_ptr()
+            .write(src.cast::<T>().as_ref().clone())
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{component::Component, test::U32, world::World};
+
+    /// Tests that move hook is executed whenever storage relocates component.
+    #[test]
+    fn on_move_hook() {
+        struct SelfAddr(usize);
+
+        impl Component for SelfAddr {
+            const ON_MOVE: Option<fn(&mut Self, *const Self)> = Some(|component, _| {
+                let addr = &*component as *const Self as usize;
+                component.0 = addr;
+            });
+        }
+
+        fn fix(world: &mut World, e: crate::entity::EntityId) {
+            let component = world.query_one_mut::<&mut SelfAddr>(e).unwrap();
+            component.0 = &*component as *const SelfAddr as usize;
+        }
+
+        fn check(world: &mut World, e: crate::entity::EntityId) {
+            let component = world.query_one_mut::<&SelfAddr>(e).unwrap();
+            assert_eq!(component.0, component as *const SelfAddr as usize);
+        }
+
+        let mut world = World::new();
+
+        let other = world.spawn((SelfAddr(0), U32(0)));
+        let e = world.spawn((SelfAddr(0),));
+        fix(&mut world, e);
+
+        // Grow archetype storage.
+        for _ in 0..1000 {
+            world.spawn((SelfAddr(0),));
+        }
+        check(&mut world, e);
+
+        // Move to another archetype.
+        world.insert(e, U32(1)).unwrap();
+        check(&mut world, e);
+
+        // Take place of despawned entity.
+        world.despawn(other).unwrap();
+        check(&mut world, e);
+    }
+
+    /// Tests that move hook configured at registration tracks address of external component.
+    #[test]
+    fn on_move_registered() {
+        use alloc::sync::Arc;
+        use parking_lot::Mutex;
+
+        struct Body(#[allow(dead_code)] u64);
+
+        let addr = Arc::new(Mutex::new(0usize));
+
+        let mut builder = World::builder();
+        builder.register_component::<U32>();
+        builder.register_external::<Body>().on_move({
+            let addr = addr.clone();
+            move |new, old| {
+                let mut addr = addr.lock();
+                if *addr == old as usize {
+                    *addr = new as *const Body as usize;
+                }
+            }
+        });
+        let mut world = builder.build();
+
+        fn check(world: &mut World, e: crate::entity::EntityId, addr: &Mutex<usize>) {
+            let body = world.query_one_mut::<&Body>(e).unwrap();
+            assert_eq!(*addr.lock(), body as *const Body as usize);
+        }
+
+        let other = world.spawn_external((Body(0), U32(0)));
+        let e = world.spawn_external((Body(1),));
+        *addr.lock() = world.query_one_mut::<&Body>(e).unwrap() as *const Body as usize;
+
+        // Grow archetype storage.
+        for _ in 0..1000 {
+            world.spawn_external((Body(2),));
+        }
+        check(&mut world, e, &addr);
+
+        // Move to another archetype.
+        world.insert(e, U32(1)).unwrap();
+        check(&mut world, e, &addr);
+
+        // Take place of despawned entity.
+        world.despawn(other).unwrap();
+        check(&mut world, e, &addr);
+    }
+
+    /// Tests that components are indexed by stable names.
+    #[test]
+    fn stable_names() {
+        use core::any::TypeId;
+
+        #[derive(Component)]
+        #[edict(stable_name = "test::Health")]
+        struct Health;
+
+        #[derive(Component)]
+        struct Unnamed;
+
+        let mut world = World::new();
+        world.spawn((Health, Unnamed));
+
+        assert_eq!(
+            world.component_id_by_stable_name("test::Health"),
+            Some(TypeId::of::<Health>())
+        );
+        assert_eq!(world.export_components(), ["test::Health"]);
+
+        let err = world
+            .import_components(&["test::Health", "test::Mana"])
+            .unwrap_err();
+        assert_eq!(err.stable_name(), "test::Mana");
+    }
+}
@@ -87,6 +87,27 @@ impl IdRange {
     }
 }
 
+/// Point in sequence of entity IDs allocated by the [`World`]
+/// in deterministic mode.
+///
+/// Created with [`World::id_checkpoint`] and restored with [`World::reset_ids`].
+///
+/// [`World`]: edict::world::World
+/// [`World::id_checkpoint`]: edict::world::World::id_checkpoint
+/// [`World::reset_ids`]: edict::world::World::reset_ids
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct IdCheckpoint {
+    next: NonZeroU64,
+}
+
+impl IdCheckpoint {
+    /// Returns raw value of the first ID allocated after this checkpoint.
+    #[inline]
+    pub fn next_id(&self) -> u64 {
+        self.next.get()
+    }
+}
+
 pub(super) struct IdAllocator {
     current: IdRange,
     next: IdRange,
@@ -123,6 +144,34 @@ impl IdAllocator {
         }
     }
 
+    /// Returns allocator that continues allocation from the current state.
+    /// Copy does not allocate new ranges after current ones are exhausted.
+    pub fn fork(&self) -> Self {
+        IdAllocator {
+            current: self.current,
+            next: self.next,
+            range_alloc: Box::new(DummyAllocator),
+        }
+    }
+
+    /// Returns checkpoint of allocator without external ID ranges.
+    pub fn checkpoint(&self) -> IdCheckpoint {
+        debug_assert!(self.next.is_empty());
+        IdCheckpoint {
+            next: self.current.start,
+        }
+    }
+
+    /// Resets allocator without external ID ranges to the checkpoint.
+    /// IDs allocated after the checkpoint will be allocated again.
+    pub fn reset(&mut self, checkpoint: IdCheckpoint) {
+        debug_assert!(self.next.is_empty());
+        self.current = IdRange {
+            start: checkpoint.next,
+            end: END,
+        };
+    }
+
     /// Returns next ID from the range.
     /// If the range is exhausted, allocates new range from the allocator.
     /// If allocator is exhausted, returns `None`.
@@ -235,3 +284,43 @@ unsafe impl IdRangeAllocator for OneRangeAllocator {
         range
     }
 }
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::vec::Vec;
+
+    use crate::{test::U32, world::World};
+
+    /// Tests that deterministic ID allocator may be reset to a checkpoint.
+    #[test]
+    fn deterministic_ids() {
+        let mut a = World::builder().deterministic_ids().build();
+        let mut b = World::builder().deterministic_ids().build();
+
+        let ids_a = (0..3).map(|_| a.spawn((U32(0),))).collect::<Vec<_>>();
+        let ids_b = (0..3).map(|_| b.spawn((U32(0),))).collect::<Vec<_>>();
+        assert_eq!(ids_a, ids_b);
+
+        let checkpoint = a.id_checkpoint();
+        let reserved = a.allocate();
+        let spawned = a.spawn((U32(1),));
+
+        a.despawn(reserved).unwrap();
+        a.despawn(spawned).unwrap();
+        a.reset_ids(checkpoint);
+
+        assert_eq!(a.spawn((U32(2),)), reserved);
+        assert_eq!(a.allocate(), spawned);
+    }
+
+    /// Tests that allocator can't be reset while entities allocated after checkpoint are alive.
+    #[test]
+    #[should_panic]
+    fn deterministic_ids_reset_alive() {
+        let mut world = World::builder().deterministic_ids().build();
+        let checkpoint = world.id_checkpoint();
+        world.spawn((U32(0),));
+        world.reset_ids(checkpoint);
+    }
+}
@@ -3,18 +3,45 @@ use core::{
     sync::atomic::{AtomicU64, Ordering},
 };
 
-use alloc::boxed::Box;
+use alloc::{boxed::Box, vec::Vec};
 
 use hashbrown::{hash_map::Entry, HashMap};
 
-use crate::world::NoSuchEntity;
+use crate::world::{NoSuchEntity, WorldId};
 
 use super::{
-    allocator::{IdAllocator, IdRangeAllocator},
+    allocator::{IdAllocator, IdCheckpoint, IdRangeAllocator},
+    pin::{Pins, RowPin},
     EntityId,
 };
 
+/// Location of the entity in the [`World`].
+///
+/// Location stays the same until the entity is moved.
+/// Entity is moved when component set of the entity changes,
+/// when the entity is despawned, and when another entity
+/// in the same archetype is removed from it and the last entity
+/// of the archetype takes its place.
+/// Location is not changed by modification of component values.
+///
+/// Locations may be cached and compared to detect moves cheaply.
+/// Location of a despawned entity may be taken by another entity.
+/// Use [`World::pin_entity_row`] to get notified about moves instead of polling.
+///
+/// [`World`]: edict::world::World
+/// [`World::pin_entity_row`]: edict::world::World::pin_entity_row
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Location {
+    /// Archetype index.
+    /// `u32::MAX` for entities that are allocated but not yet spawned.
+    pub archetype: u32,
+
+    /// Index within archetype.
+    pub idx: u32,
+}
+
 /// Stores entity information in the World
+#[derive(Clone, Copy)]
 struct EntityData {
     /// Archetype index.
     archetype: u32,
@@ -36,6 +63,30 @@ pub(crate) struct EntitySet {
     map: HashMap<u64, EntityData>,
     id_allocator: IdAllocator,
     reserve_counter: AtomicU64,
+    pins: Pins,
+
+    /// Log of entities that changed archetype, with new archetype index.
+    /// `u32::MAX` for despawned entities.
+    /// Recorded only when enabled.
+    journal: Option<Vec<(EntityId, u32)>>,
+
+    /// Number of times entities were spawned, despawned or changed archetype.
+    moves: u64,
+
+    /// Set if any entity was despawned since last call to `take_despawned`.
+    despawned: bool,
+
+    /// Set if IDs are allocated sequentially without external ID ranges
+    /// and allocator may be reset to a checkpoint.
+    deterministic: bool,
+
+    /// Identifier of the world that owns the set.
+    world: WorldId,
+
+    /// Identifiers of worlds this one was forked from.
+    /// Ids stamped by them stay valid in the fork.
+    #[cfg(feature = "world-id")]
+    ancestors: Vec<WorldId>,
 }
 
 impl fmt::Debug for EntitySet {
@@ -52,6 +103,21 @@ impl EntitySet {
             map: HashMap::new(),
             id_allocator: IdAllocator::new(),
             reserve_counter: AtomicU64::new(0),
+            pins: Pins::new(),
+            journal: None,
+            moves: 0,
+            despawned: false,
+            deterministic: false,
+            world: WorldId::new(),
+            #[cfg(feature = "world-id")]
+            ancestors: Vec::new(),
+        }
+    }
+
+    pub fn deterministic() -> Self {
+        EntitySet {
+            deterministic: true,
+            ..EntitySet::new()
         }
     }
 
@@ -60,15 +126,78 @@ impl EntitySet {
             map: HashMap::new(),
             id_allocator: IdAllocator::with_range_allocator(id_allocator),
             reserve_counter: AtomicU64::new(0),
+            pins: Pins::new(),
+            journal: None,
+            moves: 0,
+            despawned: false,
+            deterministic: false,
+            world: WorldId::new(),
+            #[cfg(feature = "world-id")]
+            ancestors: Vec::new(),
         }
     }
 
+    /// Returns copy of the set with the same entities and locations.
+    /// Copy allocates IDs that would be allocated next by this set.
+    /// Copy belongs to new world, but accepts ids stamped by this one.
+    pub fn fork(&self) -> Self {
+        debug_assert_eq!(self.reserve_counter.load(Ordering::Relaxed), 0);
+        EntitySet {
+            map: self.map.clone(),
+            id_allocator: self.id_allocator.fork(),
+            reserve_counter: AtomicU64::new(0),
+            pins: Pins::new(),
+            journal: None,
+            moves: 0,
+            despawned: false,
+            deterministic: self.deterministic,
+            world: WorldId::new(),
+            #[cfg(feature = "world-id")]
+            ancestors: {
+                let mut ancestors = self.ancestors.clone();
+                ancestors.push(self.world);
+                ancestors
+            },
+        }
+    }
+
+    /// Returns identifier of the world that owns the set.
+    #[inline]
+    pub fn world(&self) -> WorldId {
+        self.world
+    }
+
+    /// Panics if entity id is stamped by another world
+    /// that is not an ancestor of this one.
+    #[cfg(feature = "world-id")]
+    #[inline]
+    fn check_world(&self, id: EntityId) {
+        if let Some(world) = id.world() {
+            assert!(
+                world == self.world || self.ancestors.contains(&world),
+                "Entity {:?} belongs to {}, but used with {}",
+                id,
+                world,
+                self.world
+            );
+        }
+    }
+
+    #[cfg(not(feature = "world-id"))]
+    #[inline(always)]
+    fn check_world(&self, _id: EntityId) {}
+
+    /// Allocates new id.
+    /// Skips ids occupied by entities spawned at specific ids.
     pub fn alloc_mut(&mut self) -> EntityId {
-        match self.id_allocator.next() {
-            None => {
-                panic!("Entity id allocator is exhausted");
+        loop {
+            match self.id_allocator.next() {
+                None => {
+                    panic!("Entity id allocator is exhausted");
+                }
+                Some(id) if self.map.contains_key(&id.get()) => continue,
+                Some(id) => return EntityId::new(id).stamp(self.world),
             }
-            Some(id) => EntityId::new(id),
         }
     }
 
@@ -87,6 +216,14 @@ impl EntitySet {
             },
         );
         debug_assert!(old.is_none());
+        self.log(id, 0);
+    }
+
+    /// Returns `true` if entity with the id is spawned.
+    /// Unlike [`EntitySet::get_location`] does not check world stamp of the id.
+    #[inline]
+    pub fn is_occupied(&self, id: EntityId) -> bool {
+        self.map.contains_key(&id.bits())
     }
 
     pub fn spawn_if_missing(&mut self, id: EntityId) -> bool {
@@ -97,52 +234,172 @@ impl EntitySet {
                     archetype: 0,
                     idx: 0,
                 });
+                self.log(id, 0);
                 true
             }
         }
     }
 
+    /// Reserves new id.
+    /// Skips ids occupied by entities spawned at specific ids.
     pub fn alloc(&self) -> EntityId {
-        let idx = self.reserve_counter.fetch_add(1, Ordering::Relaxed);
+        loop {
+            let idx = self.reserve_counter.fetch_add(1, Ordering::Relaxed);
 
-        match self.id_allocator.reserve(idx) {
-            None => {
-                self.reserve_counter.fetch_sub(1, Ordering::Relaxed);
-                panic!("Too much entity ids reserved");
+            match self.id_allocator.reserve(idx) {
+                None => {
+                    self.reserve_counter.fetch_sub(1, Ordering::Relaxed);
+                    panic!("Too much entity ids reserved");
+                }
+                Some(id) if self.map.contains_key(&id.get()) => continue,
+                Some(id) => return EntityId::new(id).stamp(self.world),
             }
-            Some(id) => EntityId::new(id),
         }
     }
 
     pub fn spawn_allocated(&mut self, mut f: impl FnMut(EntityId) -> u32) {
+        let world = self.world;
         let reserved = core::mem::replace(self.reserve_counter.get_mut(), 0);
         unsafe {
             self.id_allocator.flush_reserved(reserved, |id| {
-                self.map.insert(
-                    id.get(),
-                    EntityData {
-                        archetype: 0,
-                        idx: f(EntityId::new(id)),
-                    },
-                );
+                // Id skipped by `alloc` as occupied.
+                let Entry::Vacant(entry) = self.map.entry(id.get()) else {
+                    return;
+                };
+                entry.insert(EntityData {
+                    archetype: 0,
+                    idx: f(EntityId::new(id).stamp(world)),
+                });
+                self.moves += 1;
+                if let Some(journal) = &mut self.journal {
+                    journal.push((EntityId::new(id).stamp(world), 0));
+                }
             });
         }
     }
 
+    /// Returns checkpoint of ID allocator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if IDs are not allocated in deterministic mode.
+    pub fn checkpoint(&self) -> IdCheckpoint {
+        assert!(
+            self.deterministic,
+            "Entity ID checkpoints require deterministic ID allocation"
+        );
+        debug_assert_eq!(self.reserve_counter.load(Ordering::Relaxed), 0);
+        self.id_allocator.checkpoint()
+    }
+
+    /// Resets ID allocator to the checkpoint.
+    ///
+    /// # Panics
+    ///
+    /// Panics if IDs are not allocated in deterministic mode
+    /// or if entity with ID allocated after the checkpoint is alive.
+    pub fn reset_allocator(&mut self, checkpoint: IdCheckpoint) {
+        assert!(
+            self.deterministic,
+            "Entity ID checkpoints require deterministic ID allocation"
+        );
+        debug_assert_eq!(*self.reserve_counter.get_mut(), 0);
+        assert!(
+            self.map.keys().all(|&bits| bits < checkpoint.next_id()),
+            "Entity allocated after the checkpoint is alive"
+        );
+        self.id_allocator.reset(checkpoint);
+    }
+
+    /// Discards allocated entity ids without spawning them.
+    /// Discarded ids are never reused.
+    pub fn discard_allocated(&mut self) {
+        let reserved = core::mem::replace(self.reserve_counter.get_mut(), 0);
+        unsafe {
+            self.id_allocator.flush_reserved(reserved, |_| {});
+        }
+    }
+
     pub fn despawn(&mut self, id: EntityId) -> Result<(u32, u32), NoSuchEntity> {
+        self.check_world(id);
         match self.map.remove(&id.bits()) {
             None => Err(NoSuchEntity),
-            Some(data) => Ok((data.archetype, data.idx)),
+            Some(data) => {
+                self.pins.invalidate(id);
+                self.log(id, u32::MAX);
+                self.despawned = true;
+                Ok((data.archetype, data.idx))
+            }
         }
     }
 
     pub fn set_location(&mut self, id: EntityId, archetype: u32, idx: u32) {
         let data = self.map.get_mut(&id.bits()).expect("Invalid entity id");
-        data.archetype = archetype;
-        data.idx = idx;
+        if data.archetype != archetype || data.idx != idx {
+            let moved = data.archetype != archetype;
+            data.archetype = archetype;
+            data.idx = idx;
+            self.pins.invalidate(id);
+            if moved {
+                self.log(id, archetype);
+            }
+        }
+    }
+
+    /// Enables recording of entities that change archetype.
+    pub fn enable_journal(&mut self) {
+        if self.journal.is_none() {
+            self.journal = Some(Vec::new());
+        }
+    }
+
+    /// Disables recording of entities that change archetype.
+    pub fn disable_journal(&mut self) {
+        self.journal = None;
+    }
+
+    /// Returns entities that changed archetype since last call,
+    /// with new archetype index or `u32::MAX` for despawned entities.
+    pub fn take_journal(&mut self) -> Vec<(EntityId, u32)> {
+        match &mut self.journal {
+            None => Vec::new(),
+            Some(journal) => core::mem::take(journal),
+        }
+    }
+
+    /// Returns `true` if any entity was despawned since last call.
+    #[inline]
+    pub fn take_despawned(&mut self) -> bool {
+        core::mem::take(&mut self.despawned)
+    }
+
+    /// Returns number of times entities were spawned, despawned or changed archetype.
+    #[inline]
+    pub fn moves(&self) -> u64 {
+        self.moves
+    }
+
+    #[inline]
+    fn log(&mut self, id: EntityId, archetype: u32) {
+        self.moves += 1;
+        if let Some(journal) = &mut self.journal {
+            journal.push((id, archetype));
+        }
+    }
+
+    /// Pins current location of spawned entity.
+    pub fn pin(
+        &mut self,
+        id: EntityId,
+        on_invalidate: Box<dyn FnOnce(EntityId) + Send>,
+    ) -> Result<RowPin, NoSuchEntity> {
+        self.check_world(id);
+        let data = self.map.get(&id.bits()).ok_or(NoSuchEntity)?;
+        Ok(self.pins.pin(id, data.archetype, data.idx, on_invalidate))
     }
 
     pub fn get_location(&self, id: EntityId) -> Option<(u32, u32)> {
+        self.check_world(id);
         match self.map.get(&id.bits()) {
             None => {
                 let bits = id.bits();
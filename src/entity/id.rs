@@ -1,13 +1,43 @@
-use core::{cmp::Ordering, fmt, num::NonZeroU64};
+use core::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    num::NonZeroU64,
+};
+
+use crate::world::WorldId;
 
 /// Unique identifier of an entity.
 /// The identifier is unique within the world and
 /// can be made unique across multiple worlds by
 /// specifying custom id allocator.
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
-#[repr(transparent)]
+///
+/// With `"world-id"` feature enabled, id also carries [`WorldId`]
+/// of the world that allocated it.
+/// The stamp is not part of the id value and is ignored in comparisons.
+#[derive(Clone, Copy)]
+#[cfg_attr(not(feature = "world-id"), repr(transparent))]
 pub struct EntityId {
     value: NonZeroU64,
+
+    #[cfg(feature = "world-id")]
+    world: Option<WorldId>,
+}
+
+impl PartialEq for EntityId {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl Eq for EntityId {}
+
+impl Hash for EntityId {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
 }
 
 impl PartialOrd for EntityId {
@@ -27,7 +57,39 @@ impl Ord for EntityId {
 impl EntityId {
     #[inline]
     pub(crate) fn new(value: NonZeroU64) -> Self {
-        EntityId { value }
+        EntityId {
+            value,
+            #[cfg(feature = "world-id")]
+            world: None,
+        }
+    }
+
+    /// Stamps id with the world that allocated it.
+    #[inline]
+    pub(crate) fn stamp(self, world: WorldId) -> Self {
+        #[cfg(feature = "world-id")]
+        {
+            EntityId {
+                world: Some(world),
+                ..self
+            }
+        }
+
+        #[cfg(not(feature = "world-id"))]
+        {
+            let _ = world;
+            self
+        }
+    }
+
+    /// Returns id of the world that allocated this entity id.
+    ///
+    /// Returns `None` for ids created with [`EntityId::from_bits`]
+    /// and [`EntityId::dangling`].
+    #[cfg(feature = "world-id")]
+    #[inline]
+    pub fn world(&self) -> Option<WorldId> {
+        self.world
     }
 
     /// Returns expired entity id.
@@ -44,10 +106,7 @@ impl EntityId {
     /// ```
     #[inline]
     pub fn dangling() -> Self {
-        EntityId {
-            // Safety: 1 is not 0.
-            value: NonZeroU64::new(1).unwrap(),
-        }
+        EntityId::new(NonZeroU64::new(1).unwrap())
     }
 
     /// Gets 64-bit integer that can be converted back to equal `EntityId`.
@@ -61,7 +120,7 @@ impl EntityId {
     #[inline]
     pub fn from_bits(bits: u64) -> Option<Self> {
         let value = NonZeroU64::new(bits)?;
-        Some(EntityId { value })
+        Some(EntityId::new(value))
     }
 
     // /// Returns generation part of the entity id.
@@ -85,9 +144,13 @@ impl EntityId {
 impl fmt::Debug for EntityId {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("EntityId")
-            .field("value", &self.value.get())
-            .finish()
+        let mut f = f.debug_struct("EntityId");
+        f.field("value", &self.value.get());
+        #[cfg(feature = "world-id")]
+        if let Some(world) = self.world {
+            f.field("world", &world.get());
+        }
+        f.finish()
     }
 }
 
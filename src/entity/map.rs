@@ -0,0 +1,440 @@
+use core::{fmt, iter::FusedIterator, ops::Index};
+
+use alloc::{boxed::Box, vec::Vec};
+
+use crate::world::World;
+
+use super::EntityId;
+
+/// Number of ids covered by one page of the sparse index.
+const PAGE_BITS: u32 = 8;
+const PAGE_LEN: usize = 1 << PAGE_BITS;
+
+/// Marks vacant slot of the sparse index.
+const VACANT: u32 = u32::MAX;
+
+type Page = [u32; PAGE_LEN];
+
+#[inline]
+fn split(id: EntityId) -> (u64, usize) {
+    let bits = id.bits();
+    (bits >> PAGE_BITS, (bits as usize) & (PAGE_LEN - 1))
+}
+
+/// Map from [`EntityId`] to values of type `V`.
+///
+/// Intended for per-entity side data that does not belong in components.
+/// Values are stored densely and iteration does not visit vacant slots.
+/// Lookup goes through paged index by entity id and never hashes.
+///
+/// Memory of the index is proportional to the range of stored ids,
+/// which is compact for ids allocated by default allocator
+/// or by a single [`IdRange`](super::IdRange).
+///
+/// The map is not updated when entities are despawned.
+/// Use [`EntityMap::retain_alive`] to drop values of despawned entities.
+///
+/// # Example
+///
+/// ```
+/// # use edict::{entity::EntityMap, world::World};
+/// let mut world = World::new();
+/// let a = world.spawn(());
+/// let b = world.spawn(());
+///
+/// let mut names = EntityMap::new();
+/// names.insert(a, "a");
+/// names.insert(b, "b");
+/// assert_eq!(names[a], "a");
+///
+/// world.despawn(a).unwrap();
+/// names.retain_alive(&world);
+/// assert_eq!(names.get(a), None);
+/// assert_eq!(names.len(), 1);
+/// ```
+pub struct EntityMap<V> {
+    /// Page number of the first page in `pages`.
+    base: u64,
+    pages: Vec<Option<Box<Page>>>,
+    ids: Vec<EntityId>,
+    values: Vec<V>,
+}
+
+impl<V> Default for EntityMap<V> {
+    #[inline]
+    fn default() -> Self {
+        EntityMap::new()
+    }
+}
+
+impl<V> fmt::Debug for EntityMap<V>
+where
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<V> Clone for EntityMap<V>
+where
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        EntityMap {
+            base: self.base,
+            pages: self.pages.clone(),
+            ids: self.ids.clone(),
+            values: self.values.clone(),
+        }
+    }
+}
+
+impl<V> EntityMap<V> {
+    /// Returns new empty map.
+    #[inline]
+    pub const fn new() -> Self {
+        EntityMap {
+            base: 0,
+            pages: Vec::new(),
+            ids: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Returns new empty map with space for at least `capacity` values.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        EntityMap {
+            base: 0,
+            pages: Vec::new(),
+            ids: Vec::with_capacity(capacity),
+            values: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns number of values in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Returns `true` if the map contains no values.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Returns index of the value for the entity.
+    #[inline]
+    fn slot(&self, id: EntityId) -> Option<usize> {
+        let (page, offset) = split(id);
+        let page = page.checked_sub(self.base)?;
+        let page = self.pages.get(usize::try_from(page).ok()?)?.as_ref()?;
+
+        let idx = page[offset];
+        if idx == VACANT {
+            return None;
+        }
+        debug_assert_eq!(self.ids[idx as usize], id);
+        Some(idx as usize)
+    }
+
+    /// Returns mutable slot of the sparse index for the entity.
+    /// Allocates page if needed.
+    fn slot_mut(&mut self, id: EntityId) -> &mut u32 {
+        let (page, offset) = split(id);
+
+        if self.pages.is_empty() {
+            self.base = page;
+        } else if page < self.base {
+            let prepend = usize::try_from(self.base - page).expect("Entity ids are too sparse");
+            self.pages
+                .splice(0..0, core::iter::repeat_with(|| None).take(prepend));
+            self.base = page;
+        }
+
+        let page = usize::try_from(page - self.base).expect("Entity ids are too sparse");
+        if page >= self.pages.len() {
+            self.pages.resize_with(page + 1, || None);
+        }
+
+        let page = self.pages[page].get_or_insert_with(|| Box::new([VACANT; PAGE_LEN]));
+        &mut page[offset]
+    }
+
+    /// Returns `true` if the map contains value for the entity.
+    #[inline]
+    pub fn contains_key(&self, id: EntityId) -> bool {
+        self.slot(id).is_some()
+    }
+
+    /// Returns reference to the value for the entity.
+    #[inline]
+    pub fn get(&self, id: EntityId) -> Option<&V> {
+        let idx = self.slot(id)?;
+        Some(&self.values[idx])
+    }
+
+    /// Returns mutable reference to the value for the entity.
+    #[inline]
+    pub fn get_mut(&mut self, id: EntityId) -> Option<&mut V> {
+        let idx = self.slot(id)?;
+        Some(&mut self.values[idx])
+    }
+
+    /// Inserts value for the entity.
+    /// Returns old value if there was one.
+    pub fn insert(&mut self, id: EntityId, value: V) -> Option<V> {
+        let len = self.ids.len();
+        let slot = self.slot_mut(id);
+
+        if *slot != VACANT {
+            let idx = *slot as usize;
+            return Some(core::mem::replace(&mut self.values[idx], value));
+        }
+
+        *slot = u32::try_from(len)
+            .ok()
+            .filter(|&idx| idx != VACANT)
+            .expect("Too many values in EntityMap");
+        self.ids.push(id);
+        self.values.push(value);
+        None
+    }
+
+    /// Removes value for the entity and returns it.
+    pub fn remove(&mut self, id: EntityId) -> Option<V> {
+        let idx = self.slot(id)?;
+        *self.slot_mut(id) = VACANT;
+
+        self.ids.swap_remove(idx);
+        let value = self.values.swap_remove(idx);
+
+        if idx < self.ids.len() {
+            let moved = self.ids[idx];
+            *self.slot_mut(moved) = idx as u32;
+        }
+
+        Some(value)
+    }
+
+    /// Removes all values.
+    /// Keeps allocated memory.
+    pub fn clear(&mut self) {
+        self.ids.clear();
+        self.values.clear();
+        for page in self.pages.iter_mut().flatten() {
+            page.fill(VACANT);
+        }
+    }
+
+    /// Retains only values for which predicate returns `true`.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(EntityId, &mut V) -> bool,
+    {
+        let mut idx = 0;
+        while idx < self.ids.len() {
+            if f(self.ids[idx], &mut self.values[idx]) {
+                idx += 1;
+            } else {
+                self.remove(self.ids[idx]);
+            }
+        }
+    }
+
+    /// Removes values of entities that are not alive in the world.
+    #[inline]
+    pub fn retain_alive(&mut self, world: &World) {
+        self.retain(|id, _| world.is_alive(id));
+    }
+
+    /// Returns iterator over entities and values.
+    /// Order of iteration is unspecified.
+    #[inline]
+    pub fn iter(&self) -> EntityMapIter<'_, V> {
+        EntityMapIter {
+            ids: self.ids.iter(),
+            values: self.values.iter(),
+        }
+    }
+
+    /// Returns iterator over entities and mutable values.
+    /// Order of iteration is unspecified.
+    #[inline]
+    pub fn iter_mut(&mut self) -> EntityMapIterMut<'_, V> {
+        EntityMapIterMut {
+            ids: self.ids.iter(),
+            values: self.values.iter_mut(),
+        }
+    }
+
+    /// Returns entities of the map as a slice.
+    /// Order matches [`EntityMap::values`].
+    #[inline]
+    pub fn keys(&self) -> &[EntityId] {
+        &self.ids
+    }
+
+    /// Returns values of the map as a slice.
+    /// Order matches [`EntityMap::keys`].
+    #[inline]
+    pub fn values(&self) -> &[V] {
+        &self.values
+    }
+
+    /// Returns values of the map as a mutable slice.
+    /// Order matches [`EntityMap::keys`].
+    #[inline]
+    pub fn values_mut(&mut self) -> &mut [V] {
+        &mut self.values
+    }
+}
+
+impl<V> Index<EntityId> for EntityMap<V> {
+    type Output = V;
+
+    #[inline]
+    #[track_caller]
+    fn index(&self, id: EntityId) -> &V {
+        match self.get(id) {
+            Some(value) => value,
+            None => panic!("No value for entity {}", id),
+        }
+    }
+}
+
+impl<V> Extend<(EntityId, V)> for EntityMap<V> {
+    fn extend<I: IntoIterator<Item = (EntityId, V)>>(&mut self, iter: I) {
+        for (id, value) in iter {
+            self.insert(id, value);
+        }
+    }
+}
+
+impl<V> FromIterator<(EntityId, V)> for EntityMap<V> {
+    fn from_iter<I: IntoIterator<Item = (EntityId, V)>>(iter: I) -> Self {
+        let mut map = EntityMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<'a, V> IntoIterator for &'a EntityMap<V> {
+    type Item = (EntityId, &'a V);
+    type IntoIter = EntityMapIter<'a, V>;
+
+    #[inline]
+    fn into_iter(self) -> EntityMapIter<'a, V> {
+        self.iter()
+    }
+}
+
+impl<'a, V> IntoIterator for &'a mut EntityMap<V> {
+    type Item = (EntityId, &'a mut V);
+    type IntoIter = EntityMapIterMut<'a, V>;
+
+    #[inline]
+    fn into_iter(self) -> EntityMapIterMut<'a, V> {
+        self.iter_mut()
+    }
+}
+
+/// Iterator over entities and values of [`EntityMap`].
+#[derive(Clone)]
+pub struct EntityMapIter<'a, V> {
+    ids: core::slice::Iter<'a, EntityId>,
+    values: core::slice::Iter<'a, V>,
+}
+
+impl<'a, V> Iterator for EntityMapIter<'a, V> {
+    type Item = (EntityId, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<(EntityId, &'a V)> {
+        Some((*self.ids.next()?, self.values.next()?))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ids.size_hint()
+    }
+}
+
+impl<V> ExactSizeIterator for EntityMapIter<'_, V> {}
+impl<V> FusedIterator for EntityMapIter<'_, V> {}
+
+/// Iterator over entities and mutable values of [`EntityMap`].
+pub struct EntityMapIterMut<'a, V> {
+    ids: core::slice::Iter<'a, EntityId>,
+    values: core::slice::IterMut<'a, V>,
+}
+
+impl<'a, V> Iterator for EntityMapIterMut<'a, V> {
+    type Item = (EntityId, &'a mut V);
+
+    #[inline]
+    fn next(&mut self) -> Option<(EntityId, &'a mut V)> {
+        Some((*self.ids.next()?, self.values.next()?))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ids.size_hint()
+    }
+}
+
+impl<V> ExactSizeIterator for EntityMapIterMut<'_, V> {}
+impl<V> FusedIterator for EntityMapIterMut<'_, V> {}
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::vec::Vec;
+
+    use crate::world::World;
+
+    #[test]
+    fn entity_map() {
+        use crate::entity::EntityMap;
+
+        let mut world = World::new();
+        let ids: Vec<_> = (0..1000).map(|_| world.spawn(())).collect();
+
+        let mut map = EntityMap::new();
+        for (i, &id) in ids.iter().enumerate().rev() {
+            assert_eq!(map.insert(id, i), None);
+        }
+        assert_eq!(map.len(), 1000);
+        assert_eq!(map.insert(ids[10], 10), Some(10));
+
+        let missing = world.spawn(());
+        world.despawn(missing).unwrap();
+        assert!(!map.contains_key(missing));
+
+        for id in ids.iter().step_by(2) {
+            world.despawn(*id).unwrap();
+        }
+        map.retain_alive(&world);
+        assert_eq!(map.len(), 500);
+
+        for (i, &id) in ids.iter().enumerate() {
+            assert_eq!(
+                map.get(id).copied(),
+                if i % 2 == 1 { Some(i) } else { None }
+            );
+        }
+
+        *map.get_mut(ids[1]).unwrap() = 0;
+        assert_eq!(map.remove(ids[1]), Some(0));
+        assert_eq!(map.remove(ids[1]), None);
+
+        let sum: usize = map.iter().map(|(_, v)| *v).sum();
+        assert_eq!(sum, (3..1000).step_by(2).sum());
+
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.get(ids[3]), None);
+    }
+}
@@ -2,10 +2,22 @@
 //!
 //! Strong, weak and raw ids.
 
-pub use self::allocator::{IdRange, IdRangeAllocator, OneRangeAllocator};
+pub use self::{
+    allocator::{IdCheckpoint, IdRange, IdRangeAllocator, OneRangeAllocator},
+    entities::Location,
+    id::EntityId,
+    map::{EntityMap, EntityMapIter, EntityMapIterMut},
+    pin::RowPin,
+    typed::Entity,
+    weak::WeakEntity,
+};
+
 pub(crate) use self::entities::EntitySet;
-pub use self::id::EntityId;
 
 mod allocator;
 mod entities;
 mod id;
+mod map;
+mod pin;
+mod typed;
+mod weak;
@@ -0,0 +1,124 @@
+use core::{
+    fmt,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+
+use hashbrown::HashMap;
+
+use super::EntityId;
+
+/// Handle to pinned location of an entity.
+///
+/// While pin is valid, entity is guaranteed to stay
+/// in the same archetype at the same row.
+/// Pin breaks when entity is moved to another archetype or row,
+/// or is despawned.
+///
+/// Created with [`World::pin_entity_row`].
+///
+/// [`World::pin_entity_row`]: edict::world::World::pin_entity_row
+pub struct RowPin {
+    id: EntityId,
+    archetype: u32,
+    row: u32,
+    valid: Arc<AtomicBool>,
+}
+
+impl fmt::Debug for RowPin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RowPin")
+            .field("id", &self.id)
+            .field("archetype", &self.archetype)
+            .field("row", &self.row)
+            .field("valid", &self.is_valid())
+            .finish()
+    }
+}
+
+impl RowPin {
+    /// Returns id of the pinned entity.
+    #[inline]
+    pub fn id(&self) -> EntityId {
+        self.id
+    }
+
+    /// Returns index of the archetype where entity is pinned.
+    #[inline]
+    pub fn archetype(&self) -> u32 {
+        self.archetype
+    }
+
+    /// Returns row of the entity in the archetype.
+    #[inline]
+    pub fn row(&self) -> u32 {
+        self.row
+    }
+
+    /// Returns `true` if pin is still valid.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.valid.load(Ordering::Acquire)
+    }
+}
+
+struct PinState {
+    valid: Arc<AtomicBool>,
+    on_invalidate: Box<dyn FnOnce(EntityId) + Send>,
+}
+
+/// Pins of entity locations.
+pub(super) struct Pins {
+    map: HashMap<u64, Vec<PinState>>,
+}
+
+impl Pins {
+    pub fn new() -> Self {
+        Pins {
+            map: HashMap::new(),
+        }
+    }
+
+    pub fn pin(
+        &mut self,
+        id: EntityId,
+        archetype: u32,
+        row: u32,
+        on_invalidate: Box<dyn FnOnce(EntityId) + Send>,
+    ) -> RowPin {
+        let valid = Arc::new(AtomicBool::new(true));
+
+        let states = self.map.entry(id.bits()).or_insert_with(Vec::new);
+
+        // Forget pins that were dropped by the user.
+        states.retain(|state| Arc::strong_count(&state.valid) > 1);
+
+        states.push(PinState {
+            valid: valid.clone(),
+            on_invalidate,
+        });
+
+        RowPin {
+            id,
+            archetype,
+            row,
+            valid,
+        }
+    }
+
+    /// Breaks all pins of the entity.
+    #[inline]
+    pub fn invalidate(&mut self, id: EntityId) {
+        if self.map.is_empty() {
+            return;
+        }
+
+        if let Some(states) = self.map.remove(&id.bits()) {
+            for state in states {
+                state.valid.store(false, Ordering::Release);
+                (state.on_invalidate)(id);
+            }
+        }
+    }
+}
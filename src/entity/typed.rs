@@ -0,0 +1,98 @@
+use core::{fmt, hash::Hash, marker::PhantomData};
+
+use super::EntityId;
+
+/// Entity id with static knowledge of components the entity was spawned with.
+///
+/// Returned by [`World::spawn_typed`].
+/// Allows accessing components of the bundle `B`
+/// with [`World::get_typed`] and [`World::get_typed_mut`]
+/// without checking their presence at runtime.
+///
+/// [`World::spawn_typed`]: crate::world::World::spawn_typed
+/// [`World::get_typed`]: crate::world::World::get_typed
+/// [`World::get_typed_mut`]: crate::world::World::get_typed_mut
+pub struct Entity<B> {
+    id: EntityId,
+    marker: PhantomData<fn() -> B>,
+}
+
+impl<B> Entity<B> {
+    #[inline]
+    pub(crate) fn new(id: EntityId) -> Self {
+        Entity {
+            id,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns id of the entity.
+    #[inline]
+    pub fn id(&self) -> EntityId {
+        self.id
+    }
+}
+
+impl<B> Clone for Entity<B> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<B> Copy for Entity<B> {}
+
+impl<B> PartialEq for Entity<B> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<B> Eq for Entity<B> {}
+
+impl<B> Hash for Entity<B> {
+    #[inline]
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state)
+    }
+}
+
+impl<B> fmt::Debug for Entity<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.id, f)
+    }
+}
+
+impl<B> From<Entity<B>> for EntityId {
+    #[inline]
+    fn from(entity: Entity<B>) -> EntityId {
+        entity.id
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{
+        test::{Bool, Str, U32},
+        world::{NoSuchEntity, World},
+    };
+
+    /// Tests access to components through typed entity handle.
+    #[test]
+    fn typed_entity() {
+        let mut world = World::new();
+
+        let e = world.spawn_typed((U32(1), Str("e")));
+        assert_eq!(world.get_typed::<U32, _, _>(e), Ok(&U32(1)));
+
+        world.get_typed_mut::<U32, _, _>(e).unwrap().0 = 2;
+        world.insert(e.id(), Bool(true)).unwrap();
+        assert_eq!(world.get_typed::<U32, _, _>(e), Ok(&U32(2)));
+        assert_eq!(world.get_typed::<Str, _, _>(e), Ok(&Str("e")));
+
+        world.despawn(e.id()).unwrap();
+        assert_eq!(world.get_typed::<U32, _, _>(e), Err(NoSuchEntity));
+    }
+}
@@ -0,0 +1,115 @@
+use crate::epoch::EpochId;
+
+use super::EntityId;
+
+/// Weak reference to an entity that can be stored in components.
+///
+/// Created with [`World::downgrade`] and resolved with [`World::upgrade`]
+/// which returns `None` if referenced entity was despawned,
+/// even if its id was allocated again.
+///
+/// Components that declare [`Component::VISIT_WEAK`] hook
+/// have their weak references to despawned entities nulled
+/// during [`World::maintenance`].
+///
+/// [`World::downgrade`]: crate::world::World::downgrade
+/// [`World::upgrade`]: crate::world::World::upgrade
+/// [`World::maintenance`]: crate::world::World::maintenance
+/// [`Component::VISIT_WEAK`]: crate::component::Component::VISIT_WEAK
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WeakEntity {
+    id: Option<EntityId>,
+
+    /// Epoch at which entity was spawned.
+    /// Distinguishes entities with the same id.
+    spawned: EpochId,
+}
+
+impl Default for WeakEntity {
+    #[inline]
+    fn default() -> Self {
+        WeakEntity::null()
+    }
+}
+
+impl WeakEntity {
+    #[inline]
+    pub(crate) fn new(id: EntityId, spawned: EpochId) -> Self {
+        WeakEntity {
+            id: Some(id),
+            spawned,
+        }
+    }
+
+    /// Returns weak reference that refers to no entity.
+    #[inline]
+    pub const fn null() -> Self {
+        WeakEntity {
+            id: None,
+            spawned: EpochId::start(),
+        }
+    }
+
+    /// Returns `true` if this weak reference refers to no entity.
+    #[inline]
+    pub fn is_null(&self) -> bool {
+        self.id.is_none()
+    }
+
+    /// Returns id of referenced entity without checking that it is alive.
+    #[inline]
+    pub fn id(&self) -> Option<EntityId> {
+        self.id
+    }
+
+    #[inline]
+    pub(crate) fn spawned(&self) -> EpochId {
+        self.spawned
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::vec::Vec;
+
+    use crate::{component::Component, test::U32, world::World};
+
+    /// Tests that weak references to despawned entities are nulled during maintenance.
+    #[test]
+    fn weak_entity() {
+        use crate::entity::WeakEntity;
+
+        struct Target(WeakEntity);
+
+        impl Component for Target {
+            const VISIT_WEAK: Option<fn(&mut Self, &mut dyn FnMut(&mut WeakEntity))> =
+                Some(|target, f| f(&mut target.0));
+        }
+
+        let mut world = World::new();
+        let a = world.spawn((U32(0),));
+        let b = world.spawn((U32(1),));
+
+        let weak_a = world.downgrade(a).unwrap();
+        let weak_b = world.downgrade(b).unwrap();
+        let e = world.spawn((Target(weak_a),));
+        world.spawn((Target(weak_b),));
+
+        assert_eq!(world.upgrade(weak_a), Some(a));
+
+        world.despawn(a).unwrap();
+        assert_eq!(world.upgrade(weak_a), None);
+        assert_eq!(world.upgrade(weak_b), Some(b));
+
+        world.maintenance();
+        let mut targets = world
+            .query::<&Target>()
+            .iter()
+            .map(|t| t.0)
+            .collect::<Vec<_>>();
+        targets.sort_by_key(|t| t.is_null());
+        assert_eq!(targets, [weak_b, WeakEntity::null()]);
+        assert!(world.query_one_mut::<&Target>(e).unwrap().0.is_null());
+    }
+}
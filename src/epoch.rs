@@ -23,6 +23,13 @@ impl EpochCounter {
         }
     }
 
+    /// Returns epoch counter starting at specified epoch.
+    pub(crate) const fn starting_at(epoch: EpochId) -> Self {
+        EpochCounter {
+            value: AtomicU64::new(epoch.value),
+        }
+    }
+
     /// Returns current epoch id.
     pub fn current(&self) -> EpochId {
         EpochId {
@@ -133,3 +140,65 @@ impl EpochId {
         cell.set(to);
     }
 }
+
+/// Epoch identifier that can be updated through shared reference.
+///
+/// Used for epochs that may be bumped concurrently,
+/// like epochs of components borrowed by chunks.
+#[repr(transparent)]
+pub(crate) struct AtomicEpochId {
+    value: AtomicU64,
+}
+
+impl Debug for AtomicEpochId {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> core::fmt::Result {
+        <EpochId as Debug>::fmt(&self.get(), f)
+    }
+}
+
+impl AtomicEpochId {
+    /// Returns atomic epoch id of starting epoch.
+    #[inline]
+    pub const fn start() -> Self {
+        AtomicEpochId {
+            value: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns current epoch id.
+    #[inline]
+    pub fn get(&self) -> EpochId {
+        EpochId {
+            value: self.value.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns true if this epoch comes strictly after the `other`.
+    #[inline]
+    pub fn after(&self, other: EpochId) -> bool {
+        self.get().after(other)
+    }
+
+    /// Updates epoch id to later of this and the `other`.
+    #[inline]
+    pub fn update(&self, other: EpochId) {
+        self.value.fetch_max(other.value, Ordering::Relaxed);
+    }
+
+    /// Bumps epoch to specified one.
+    ///
+    /// Unlike [`EpochId::bump`] this epoch may already be bumped
+    /// to a later epoch by another thread, in which case it is kept.
+    #[inline]
+    pub fn bump(&self, to: EpochId) {
+        self.update(to);
+    }
+
+    /// Bumps epoch to specified one.
+    /// Assumes this epoch is before epoch `to` or the same.
+    #[inline]
+    pub fn bump_again(&self, to: EpochId) {
+        self.update(to);
+    }
+}
@@ -1,8 +1,26 @@
 //! Provides API to define task executors.
 
 /// Abstract scoped task executor.
+///
+/// [`Scheduler::run_with`] spawns system tasks through this trait,
+/// so systems can run on thread pool owned by the engine.
+/// Implemented for [`rayon::Scope`] with `"rayon"` feature,
+/// for [`std::thread::Scope`] with `"std"` feature,
+/// and for [`MockExecutor`] that runs tasks on the current thread.
+///
+/// Executor acts as a wait group.
+/// Scope that provides the executor must not end
+/// before all spawned tasks, including tasks spawned by other tasks, are complete.
+/// Executors that can't borrow from the scope, like `tokio` runtime,
+/// can implement this trait by blocking on completion of spawned tasks
+/// when the scope ends.
+///
+/// [`Scheduler::run_with`]: crate::scheduler::Scheduler::run_with
+/// [`rayon::Scope`]: https://docs.rs/rayon/latest/rayon/struct.Scope.html
+/// [`std::thread::Scope`]: https://doc.rust-lang.org/std/thread/struct.Scope.html
 pub trait ScopedExecutor<'scope> {
     /// Spawns a task on the scope.
+    /// Task receives the executor to spawn more tasks.
     fn spawn<F>(&self, f: F)
     where
         F: FnOnce(&Self) + Send + 'scope;
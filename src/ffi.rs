@@ -0,0 +1,500 @@
+//! C API for embedding edict worlds into engines and editors
+//! written in other languages.
+//!
+//! Components defined outside of Rust are registered with [`edict_register_component`]
+//! that returns component id used in all other functions.
+//! Component values are stored in world archetypes as is,
+//! so pointers passed to callbacks point directly into component storage.
+//!
+//! Component ids are global for the process and shared by all worlds.
+//! At most [`EDICT_MAX_COMPONENTS`] components can be registered.
+//!
+//! Functions that receive pointer to [`World`] require it to be created with
+//! [`edict_world_new`] and not yet freed.
+//! Entity ids are passed as `u64` values, see [`EntityId::bits`].
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{
+    alloc::Layout,
+    any::TypeId,
+    ffi::{c_char, c_void, CStr},
+    ptr::{self, NonNull},
+};
+
+use parking_lot::RwLock;
+
+use crate::{
+    archetype::CHUNK_LEN_USIZE, bundle::DynamicBundle, component::ComponentInfo, entity::EntityId,
+    world::World,
+};
+
+/// Maximum number of components that can be registered with [`edict_register_component`].
+pub const EDICT_MAX_COMPONENTS: u32 = 64;
+
+/// Component id returned by [`edict_register_component`] on failure.
+pub const EDICT_INVALID_COMPONENT: u32 = u32::MAX;
+
+/// Drop function of component defined outside of Rust.
+pub type EdictDropFn = unsafe extern "C" fn(component: *mut c_void);
+
+/// Callback called by [`edict_query`] for each entity.
+/// Receives pointers to components in order they were requested.
+pub type EdictQueryFn =
+    unsafe extern "C" fn(user: *mut c_void, entity: u64, components: *const *mut c_void);
+
+/// Marker type that provides unique [`TypeId`] for each component id.
+struct Foreign<const N: usize>;
+
+struct ForeignComponent {
+    info: ComponentInfo,
+    size: usize,
+    drop: Option<EdictDropFn>,
+}
+
+static COMPONENTS: RwLock<Vec<ForeignComponent>> = parking_lot::const_rwlock(Vec::new());
+
+unsafe fn foreign_drop<const N: usize>(ptr: NonNull<u8>, count: usize) {
+    let (size, drop) = {
+        let components = COMPONENTS.read();
+        (components[N].size, components[N].drop)
+    };
+
+    if let Some(drop) = drop {
+        for idx in 0..count {
+            unsafe { drop(ptr.as_ptr().add(idx * size).cast()) }
+        }
+    }
+}
+
+macro_rules! foreign_info {
+    ($($n:literal)*) => {
+        fn foreign_info(id: u32, layout: Layout, name: &'static str) -> ComponentInfo {
+            match id {
+                $($n => ComponentInfo::foreign::<Foreign<$n>>(layout, name, foreign_drop::<$n>),)*
+                _ => unreachable!(),
+            }
+        }
+    };
+}
+
+foreign_info!(
+    0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15
+    16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31
+    32 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47
+    48 49 50 51 52 53 54 55 56 57 58 59 60 61 62 63
+);
+
+/// Returns type information of registered component.
+fn component_info(id: u32) -> Option<(ComponentInfo, usize)> {
+    let components = COMPONENTS.read();
+    let component = components.get(id as usize)?;
+    Some((component.info.clone(), component.size))
+}
+
+/// Bundle of components passed through FFI as pointers to values.
+struct RawBundle<'a> {
+    ids: Vec<TypeId>,
+    sizes: Vec<usize>,
+    data: &'a [*const c_void],
+}
+
+impl<'a> RawBundle<'a> {
+    /// Registers components in the world and returns bundle.
+    /// Returns `None` if some component id is not registered.
+    fn new(world: &mut World, ids: &[u32], data: &'a [*const c_void]) -> Option<Self> {
+        let mut bundle = RawBundle {
+            ids: Vec::with_capacity(ids.len()),
+            sizes: Vec::with_capacity(ids.len()),
+            data,
+        };
+
+        for &id in ids {
+            let (info, size) = component_info(id)?;
+            bundle.ids.push(info.id());
+            bundle.sizes.push(size);
+            world.ensure_raw_registered(info);
+        }
+
+        Some(bundle)
+    }
+}
+
+unsafe impl DynamicBundle for RawBundle<'_> {
+    fn valid(&self) -> bool {
+        self.ids
+            .iter()
+            .enumerate()
+            .all(|(idx, id)| !self.ids[..idx].contains(id))
+    }
+
+    fn contains_id(&self, id: TypeId) -> bool {
+        self.ids.contains(&id)
+    }
+
+    fn with_ids<R>(&self, f: impl FnOnce(&[TypeId]) -> R) -> R {
+        f(&self.ids)
+    }
+
+    fn put(self, mut f: impl FnMut(NonNull<u8>, TypeId, usize)) {
+        for ((&id, &size), &data) in self.ids.iter().zip(&self.sizes).zip(self.data) {
+            // Components with zero size may be passed as null pointers.
+            let ptr = NonNull::new(data as *mut u8).unwrap_or(NonNull::dangling());
+            f(ptr, id, size);
+        }
+    }
+}
+
+/// Creates new empty world.
+///
+/// World must be freed with [`edict_world_free`].
+#[no_mangle]
+pub extern "C" fn edict_world_new() -> *mut World {
+    Box::into_raw(Box::new(World::new()))
+}
+
+/// Frees world created with [`edict_world_new`]
+/// dropping all entities and components.
+///
+/// # Safety
+///
+/// `world` must be created with [`edict_world_new`] and not yet freed, or be null.
+#[no_mangle]
+pub unsafe extern "C" fn edict_world_free(world: *mut World) {
+    if !world.is_null() {
+        drop(unsafe { Box::from_raw(world) });
+    }
+}
+
+/// Registers component type defined outside of Rust.
+///
+/// `name` is copied and used in debug output.
+/// `drop` is called for component values when they are dropped, may be null.
+///
+/// Returns component id or [`EDICT_INVALID_COMPONENT`] if layout is invalid
+/// or [`EDICT_MAX_COMPONENTS`] are already registered.
+///
+/// # Safety
+///
+/// `name` must be null or point to nul-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn edict_register_component(
+    size: usize,
+    align: usize,
+    name: *const c_char,
+    drop: Option<EdictDropFn>,
+) -> u32 {
+    let Ok(layout) = Layout::from_size_align(size, align) else {
+        return EDICT_INVALID_COMPONENT;
+    };
+    let layout = layout.pad_to_align();
+
+    let name: &'static str = if name.is_null() {
+        "<foreign>"
+    } else {
+        let name = unsafe { CStr::from_ptr(name) }.to_string_lossy();
+        Box::leak(name.into_owned().into_boxed_str())
+    };
+
+    let mut components = COMPONENTS.write();
+    let id = components.len() as u32;
+    if id >= EDICT_MAX_COMPONENTS {
+        return EDICT_INVALID_COMPONENT;
+    }
+
+    components.push(ForeignComponent {
+        info: foreign_info(id, layout, name),
+        size: layout.size(),
+        drop,
+    });
+    id
+}
+
+/// Spawns entity with components.
+///
+/// `ids` and `data` must contain `count` elements.
+/// Component values are moved from memory pointed by `data` into the world
+/// and must not be used or dropped by the caller afterwards.
+///
+/// Returns entity id or `0` if some component id is invalid or repeated.
+///
+/// # Safety
+///
+/// `world` must be valid pointer to world.
+/// `ids` and `data` must point to arrays of `count` elements, or may be null if `count` is `0`.
+/// Each `data` element must point to valid value of corresponding component.
+#[no_mangle]
+pub unsafe extern "C" fn edict_spawn(
+    world: *mut World,
+    count: usize,
+    ids: *const u32,
+    data: *const *const c_void,
+) -> u64 {
+    let world = unsafe { &mut *world };
+    let ids = unsafe { raw_slice(ids, count) };
+    let data = unsafe { raw_slice(data, count) };
+
+    match RawBundle::new(world, ids, data) {
+        Some(bundle) if bundle.valid() => world.spawn_external(bundle).bits(),
+        _ => 0,
+    }
+}
+
+/// Despawns entity dropping all its components.
+///
+/// Returns `false` if entity is not alive.
+///
+/// # Safety
+///
+/// `world` must be valid pointer to world.
+#[no_mangle]
+pub unsafe extern "C" fn edict_despawn(world: *mut World, entity: u64) -> bool {
+    let world = unsafe { &mut *world };
+    match EntityId::from_bits(entity) {
+        None => false,
+        Some(id) => world.despawn(id).is_ok(),
+    }
+}
+
+/// Checks if entity is alive.
+///
+/// # Safety
+///
+/// `world` must be valid pointer to world.
+#[no_mangle]
+pub unsafe extern "C" fn edict_is_alive(world: *const World, entity: u64) -> bool {
+    let world = unsafe { &*world };
+    EntityId::from_bits(entity).map_or(false, |id| world.is_alive(id))
+}
+
+/// Inserts component to the entity, replacing existing value.
+///
+/// Component value is moved from memory pointed by `data`.
+///
+/// Returns `false` if entity is not alive or component id is invalid.
+///
+/// # Safety
+///
+/// `world` must be valid pointer to world.
+/// `data` must point to valid value of the component.
+#[no_mangle]
+pub unsafe extern "C" fn edict_insert(
+    world: *mut World,
+    entity: u64,
+    component: u32,
+    data: *const c_void,
+) -> bool {
+    let world = unsafe { &mut *world };
+    let Some(id) = EntityId::from_bits(entity) else {
+        return false;
+    };
+
+    let data = [data];
+    match RawBundle::new(world, &[component], &data) {
+        None => false,
+        Some(bundle) => world.insert_external_bundle(id, bundle).is_ok(),
+    }
+}
+
+/// Removes component from the entity and drops it.
+///
+/// Returns `false` if entity is not alive or does not have the component.
+///
+/// # Safety
+///
+/// `world` must be valid pointer to world.
+#[no_mangle]
+pub unsafe extern "C" fn edict_remove(world: *mut World, entity: u64, component: u32) -> bool {
+    let world = unsafe { &mut *world };
+    let (Some(id), Some((info, _))) = (EntityId::from_bits(entity), component_info(component))
+    else {
+        return false;
+    };
+
+    world.drop_erased(id, info.id()).is_ok()
+}
+
+/// Returns pointer to component of the entity.
+/// Component is marked as modified.
+///
+/// Returns null if entity is not alive or does not have the component.
+/// Pointer is valid until next structural change of the world.
+///
+/// # Safety
+///
+/// `world` must be valid pointer to world.
+#[no_mangle]
+pub unsafe extern "C" fn edict_get(world: *mut World, entity: u64, component: u32) -> *mut c_void {
+    let world = unsafe { &mut *world };
+    let (Some(id), Some((info, size))) = (EntityId::from_bits(entity), component_info(component))
+    else {
+        return ptr::null_mut();
+    };
+
+    let Ok(location) = world.locate(id) else {
+        return ptr::null_mut();
+    };
+    if location.archetype == u32::MAX {
+        return ptr::null_mut();
+    }
+
+    let epoch = world.epoch_counter().next();
+    let archetype = &world.archetypes()[location.archetype as usize];
+    let Some(component) = archetype.component(info.id()) else {
+        return ptr::null_mut();
+    };
+
+    let idx = location.idx as usize;
+
+    // Safety: world is borrowed mutably, no other borrows exist.
+    let data = unsafe { component.data_mut() };
+    data.epoch.bump(epoch);
+    data.chunk_epochs[idx / CHUNK_LEN_USIZE].bump(epoch);
+    data.entity_epochs[idx].bump(epoch);
+
+    unsafe { data.ptr.as_ptr().add(idx * size).cast() }
+}
+
+/// Calls `callback` for each entity that has all specified components.
+/// Entities with disabled components are skipped.
+/// Components are marked as modified.
+///
+/// Callback must not access the world.
+///
+/// Returns number of visited entities or `usize::MAX` if some component id is invalid or repeated.
+///
+/// # Safety
+///
+/// `world` must be valid pointer to world.
+/// `ids` must point to array of `count` elements, or may be null if `count` is `0`.
+#[no_mangle]
+pub unsafe extern "C" fn edict_query(
+    world: *mut World,
+    count: usize,
+    ids: *const u32,
+    callback: EdictQueryFn,
+    user: *mut c_void,
+) -> usize {
+    let world = unsafe { &mut *world };
+    let ids = unsafe { raw_slice(ids, count) };
+
+    // Repeated component would be borrowed mutably twice.
+    let components = match RawBundle::new(world, ids, &[]) {
+        Some(bundle) if bundle.valid() => bundle,
+        _ => return usize::MAX,
+    };
+    let components = components
+        .ids
+        .into_iter()
+        .zip(components.sizes)
+        .collect::<Vec<_>>();
+
+    let epoch = world.epoch_counter().next();
+    let mut ptrs = Vec::with_capacity(count);
+    let mut columns = Vec::with_capacity(count);
+    let mut visited = 0;
+
+    for archetype in world.archetypes() {
+        if archetype.is_empty() {
+            continue;
+        }
+
+        columns.clear();
+        for &(id, size) in &components {
+            match archetype.component(id) {
+                None => break,
+                // Safety: world is borrowed mutably, no other borrows exist.
+                Some(component) => columns.push((unsafe { component.data_mut() }, size)),
+            }
+        }
+        if columns.len() != count {
+            continue;
+        }
+
+        for (data, _) in &mut columns {
+            data.epoch.bump(epoch);
+        }
+
+        for (idx, entity) in archetype.entities().iter().enumerate() {
+            if !columns.iter().all(|(data, _)| data.is_enabled(idx)) {
+                continue;
+            }
+
+            ptrs.clear();
+            for (data, size) in &mut columns {
+                // Chunk epoch is already bumped for earlier entity in the chunk.
+                data.chunk_epochs[idx / CHUNK_LEN_USIZE].bump_again(epoch);
+                data.entity_epochs[idx].bump(epoch);
+                ptrs.push(unsafe { data.ptr.as_ptr().add(idx * *size).cast::<c_void>() });
+            }
+
+            unsafe { callback(user, entity.bits(), ptrs.as_ptr()) };
+            visited += 1;
+        }
+    }
+
+    visited
+}
+
+/// Returns slice from raw parts, allowing null pointer for empty slice.
+unsafe fn raw_slice<'a, T>(ptr: *const T, len: usize) -> &'a [T] {
+    if len == 0 {
+        &[]
+    } else {
+        unsafe { core::slice::from_raw_parts(ptr, len) }
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    /// Tests components defined through C API.
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn ffi_components() {
+        use core::ffi::c_void;
+
+        use crate::ffi::*;
+
+        unsafe extern "C" fn sum(user: *mut c_void, _entity: u64, components: *const *mut c_void) {
+            *user.cast::<u32>() += *(*components).cast::<u32>();
+        }
+
+        unsafe {
+            let value = edict_register_component(4, 4, b"value\0".as_ptr().cast(), None);
+            assert_ne!(value, EDICT_INVALID_COMPONENT);
+
+            let world = edict_world_new();
+
+            let a: *const c_void = (&1u32 as *const u32).cast();
+            let b: *const c_void = (&2u32 as *const u32).cast();
+            let e = edict_spawn(world, 1, &value, &a);
+            edict_spawn(world, 1, &value, &b);
+            assert!(edict_is_alive(world, e));
+
+            *edict_get(world, e, value).cast::<u32>() = 5;
+
+            let mut total = 0u32;
+            let visited = edict_query(world, 1, &value, sum, (&mut total as *mut u32).cast());
+            assert_eq!(visited, 2);
+            assert_eq!(total, 7);
+
+            let repeated = [value, value];
+            let visited = edict_query(
+                world,
+                2,
+                repeated.as_ptr(),
+                sum,
+                (&mut total as *mut u32).cast(),
+            );
+            assert_eq!(visited, usize::MAX);
+            assert_eq!(total, 7);
+
+            assert!(edict_remove(world, e, value));
+            assert!(edict_get(world, e, value).is_null());
+            assert!(edict_despawn(world, e));
+            assert!(!edict_is_alive(world, e));
+
+            edict_world_free(world);
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! Guarding of component columns for debugging of unsafe fetch code.
+//!
+//! Enabled with `"column-guard"` feature.
+//! Each column is surrounded by canary bytes and freed columns are filled with poison
+//! and kept in quarantine for a while instead of being returned to the allocator.
+//! Canaries of live columns and poison of quarantined ones are checked during
+//! [`World::maintenance`], so writes past the column bounds
+//! and writes through pointers left over after relocation are reported
+//! close to the place where they happened.
+//!
+//! This makes every column allocation slower and larger and should only be used for debugging.
+//!
+//! [`World::maintenance`]: crate::world::World::maintenance
+
+use core::{alloc::Layout, ptr::NonNull};
+
+use alloc::vec::Vec;
+use parking_lot::{const_mutex, Mutex};
+
+use crate::{
+    archetype::Archetype,
+    pool::{alloc_column as alloc_raw, dealloc_column as dealloc_raw},
+};
+
+/// Byte that fills canaries around columns.
+const CANARY: u8 = 0xCA;
+
+/// Byte that fills freed and uninitialized column memory.
+const POISON: u8 = 0xDD;
+
+/// Minimal size of the canary on each side of the column.
+const GUARD_SIZE: usize = 64;
+
+/// Number of freed columns kept in quarantine.
+const QUARANTINE_LEN: usize = 64;
+
+/// Freed column waiting in quarantine.
+struct Quarantined {
+    ptr: NonNull<u8>,
+    layout: Layout,
+}
+
+// Safety: quarantine owns freed columns.
+unsafe impl Send for Quarantined {}
+
+static QUARANTINE: Mutex<Vec<Quarantined>> = const_mutex(Vec::new());
+
+/// Returns size of the front canary and layout of the whole guarded allocation.
+#[inline]
+fn guarded_layout(layout: Layout) -> (usize, Layout) {
+    // Front canary keeps column aligned.
+    let front = GUARD_SIZE.max(layout.align());
+    let size = front + layout.size() + GUARD_SIZE;
+    let guarded = Layout::from_size_align(size, layout.align()).unwrap();
+    (front, guarded)
+}
+
+#[inline]
+fn is_filled(ptr: *const u8, len: usize, byte: u8) -> bool {
+    // Safety: caller guarantees that the range is allocated.
+    unsafe { core::slice::from_raw_parts(ptr, len) }
+        .iter()
+        .all(|&b| b == byte)
+}
+
+/// Allocates memory for a column surrounded by canaries.
+///
+/// # Safety
+///
+/// `layout` must have non-zero size.
+/// Its alignment must not exceed its size.
+pub unsafe fn alloc_column(layout: Layout) -> NonNull<u8> {
+    let (front, guarded) = guarded_layout(layout);
+
+    unsafe {
+        let base = alloc_raw(guarded).as_ptr();
+        base.write_bytes(CANARY, front);
+        base.add(front).write_bytes(POISON, layout.size());
+        base.add(front + layout.size())
+            .write_bytes(CANARY, GUARD_SIZE);
+        NonNull::new_unchecked(base.add(front))
+    }
+}
+
+/// Poisons memory of a column and puts it into quarantine.
+/// Oldest quarantined column is released.
+///
+/// # Safety
+///
+/// `ptr` must be allocated with [`alloc_column`] or [`realloc_column`]
+/// with the same `layout`.
+///
+/// # Panics
+///
+/// Panics if canaries of the column are corrupted
+/// or if memory of released column was written after it was freed.
+#[track_caller]
+pub unsafe fn dealloc_column(ptr: NonNull<u8>, layout: Layout) {
+    assert!(
+        unsafe { check_column(ptr, layout) },
+        "Canary of the column at {:p} is corrupted",
+        ptr
+    );
+
+    unsafe {
+        ptr.as_ptr().write_bytes(POISON, layout.size());
+    }
+
+    let mut quarantine = QUARANTINE.lock();
+    quarantine.push(Quarantined { ptr, layout });
+
+    if quarantine.len() > QUARANTINE_LEN {
+        let released = quarantine.remove(0);
+        drop(quarantine);
+
+        assert!(
+            is_filled(released.ptr.as_ptr(), released.layout.size(), POISON),
+            "Freed column at {:p} was written after relocation",
+            released.ptr
+        );
+
+        let (front, guarded) = guarded_layout(released.layout);
+        unsafe {
+            let base = NonNull::new_unchecked(released.ptr.as_ptr().sub(front));
+            dealloc_raw(base, guarded);
+        }
+    }
+}
+
+/// Moves column into new guarded allocation.
+/// Contents are preserved up to the smaller of the sizes.
+/// Old memory is always put into quarantine,
+/// so stale pointers to it are detected.
+///
+/// # Safety
+///
+/// `ptr` must be allocated with [`alloc_column`] or [`realloc_column`]
+/// with the `layout`.
+/// Layout with `new_size` must satisfy [`alloc_column`] requirements.
+#[track_caller]
+pub unsafe fn realloc_column(ptr: NonNull<u8>, layout: Layout, new_size: usize) -> NonNull<u8> {
+    // Safety: alignment is unchanged and is valid for `layout`.
+    let new_layout = unsafe { Layout::from_size_align_unchecked(new_size, layout.align()) };
+
+    unsafe {
+        let new_ptr = alloc_column(new_layout);
+        core::ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_ptr(), layout.size().min(new_size));
+        dealloc_column(ptr, layout);
+        new_ptr
+    }
+}
+
+/// Returns `true` if canaries around the column are intact.
+///
+/// # Safety
+///
+/// `ptr` must be allocated with [`alloc_column`] or [`realloc_column`]
+/// with the `layout`.
+pub unsafe fn check_column(ptr: NonNull<u8>, layout: Layout) -> bool {
+    let (front, _) = guarded_layout(layout);
+
+    unsafe {
+        is_filled(ptr.as_ptr().sub(front), front, CANARY)
+            && is_filled(ptr.as_ptr().add(layout.size()), GUARD_SIZE, CANARY)
+    }
+}
+
+/// Checks canaries of all columns and poison of quarantined columns.
+///
+/// # Panics
+///
+/// Panics on first corruption found.
+#[track_caller]
+pub(crate) fn check_archetypes(archetypes: &[Archetype]) {
+    for archetype in archetypes {
+        archetype.check_column_guards();
+    }
+
+    for released in QUARANTINE.lock().iter() {
+        assert!(
+            is_filled(released.ptr.as_ptr(), released.layout.size(), POISON),
+            "Freed column at {:p} was written after relocation",
+            released.ptr
+        );
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{test::U32, world::World};
+
+    #[cfg(all(feature = "column-guard", feature = "std"))]
+    #[test]
+    fn column_guard() {
+        use core::any::TypeId;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+
+        let mut world = World::new();
+        world.spawn((U32(0),));
+        world.maintenance();
+
+        let archetype = world
+            .archetypes()
+            .iter()
+            .find(|a| a.has_component(TypeId::of::<U32>()))
+            .unwrap();
+        let ptr = unsafe { archetype.component(TypeId::of::<U32>()).unwrap().data().ptr };
+
+        // Write right before the column, as buggy fetch could.
+        let canary = unsafe { ptr.as_ptr().sub(1).replace(0) };
+
+        let result = catch_unwind(AssertUnwindSafe(|| world.maintenance()));
+        assert!(result.is_err());
+
+        // Restore canary so the world can be dropped.
+        unsafe {
+            ptr.as_ptr().sub(1).write(canary);
+        }
+    }
+}
@@ -86,7 +86,7 @@ impl Hasher for NoOpHasher {
     }
 }
 
-#[derive(Default)]
+#[derive(Clone, Copy, Default)]
 pub struct NoOpHasherBuilder;
 
 impl BuildHasher for NoOpHasherBuilder {
@@ -159,6 +159,39 @@ impl BuildHasher for MulHasherBuilder {
     }
 }
 
+const FNV_OFFSET_BASIS_64: u64 = 14695981039346656037;
+const FNV_PRIME_64: u64 = 1099511628211;
+
+/// FNV-1a hasher.
+/// Unlike default hasher it is not seeded,
+/// so hashes are the same across processes.
+pub struct StableHasher {
+    value: u64,
+}
+
+impl StableHasher {
+    #[inline]
+    pub fn new() -> Self {
+        StableHasher {
+            value: FNV_OFFSET_BASIS_64,
+        }
+    }
+}
+
+impl Hasher for StableHasher {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.value ^= u64::from(byte);
+            self.value = self.value.wrapping_mul(FNV_PRIME_64);
+        }
+    }
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.value
+    }
+}
+
 // #[inline]
 // pub fn no_op_hash<T>(v: &T) -> u64
 // where
@@ -367,16 +367,24 @@ pub mod executor;
 pub mod prelude;
 pub mod query;
 pub mod relation;
+pub mod script;
 pub mod system;
 pub mod world;
 
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "std")]
+pub mod plugin;
 #[cfg(feature = "std")]
 pub mod scheduler;
 #[cfg(feature = "std")]
 pub mod task;
 
+#[cfg(feature = "column-guard")]
+mod guard;
 mod hash;
 mod idx;
+mod pool;
 mod res;
 
 #[cfg(test)]
@@ -395,6 +403,9 @@ impl component::Component for ExampleComponent {}
 #[doc(inline)]
 pub use self::prelude::*;
 
+#[doc(inline)]
+pub use self::system::validate_systems;
+
 #[cold]
 #[inline(always)]
 fn cold() {}
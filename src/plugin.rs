@@ -0,0 +1,52 @@
+//! Composition of components, resources and systems into reusable units.
+//!
+//! A [`Plugin`] bundles registration of everything a feature needs
+//! into one installation hook that is applied with [`World::install`].
+
+use crate::{scheduler::Scheduler, world::World};
+
+/// Reusable unit of world and scheduler configuration.
+///
+/// Plugin may register components and relations,
+/// insert resources and add systems to the scheduler.
+///
+/// Implemented for closures that accept `&mut World` and `&mut Scheduler`.
+///
+/// # Example
+///
+/// ```
+/// # use edict::{plugin::Plugin, scheduler::Scheduler, world::World};
+/// struct Counter(u32);
+///
+/// struct CounterPlugin;
+///
+/// impl Plugin for CounterPlugin {
+///     fn install(self, world: &mut World, scheduler: &mut Scheduler) {
+///         world.insert_resource(Counter(0));
+///         scheduler.add_system(|world: &mut World| {
+///             world.expect_resource_mut::<Counter>().0 += 1;
+///         });
+///     }
+/// }
+///
+/// let mut world = World::new();
+/// let mut scheduler = Scheduler::new();
+/// world.install(&mut scheduler, CounterPlugin);
+///
+/// scheduler.run_sequential(&mut world);
+/// assert_eq!(world.expect_resource::<Counter>().0, 1);
+/// ```
+pub trait Plugin {
+    /// Installs plugin into the world and scheduler.
+    fn install(self, world: &mut World, scheduler: &mut Scheduler);
+}
+
+impl<F> Plugin for F
+where
+    F: FnOnce(&mut World, &mut Scheduler),
+{
+    #[inline]
+    fn install(self, world: &mut World, scheduler: &mut Scheduler) {
+        self(world, scheduler)
+    }
+}
@@ -0,0 +1,388 @@
+//! Pooled allocator for small component columns.
+//!
+//! Archetypes with few entities allocate tiny columns for each component.
+//! Allocating them individually fragments the heap,
+//! so columns up to [`MAX_POOLED`] bytes are carved from pages instead.
+//! Blocks are sized by power of two classes and aligned to their size.
+//! Column that grows beyond the threshold is moved into dedicated allocation
+//! by the regular grow path.
+//!
+//! Each world owns its [`ColumnPool`] shared by its archetypes.
+//! Freed blocks are reused for columns of the same size class
+//! and pages without allocated blocks are released with [`ColumnPool::trim`]
+//! during [`World::maintenance`].
+//!
+//! [`World::maintenance`]: crate::world::World::maintenance
+
+// Columns are not pooled with "column-guard" feature.
+#![cfg_attr(feature = "column-guard", allow(dead_code))]
+
+use core::{alloc::Layout, ptr::NonNull};
+
+use alloc::{
+    alloc::{alloc, dealloc, handle_alloc_error, realloc as global_realloc},
+    vec::Vec,
+};
+use hashbrown::HashMap;
+use parking_lot::Mutex;
+
+use crate::{archetype::ColumnAllocator, hash::MulHasherBuilder};
+
+/// Size of the smallest block.
+const MIN_CLASS_SHIFT: u32 = 5;
+
+/// Size of the largest block.
+const MAX_CLASS_SHIFT: u32 = 9;
+
+const CLASS_COUNT: usize = (MAX_CLASS_SHIFT - MIN_CLASS_SHIFT + 1) as usize;
+
+/// Allocations larger than this are not pooled.
+pub const MAX_POOLED: usize = 1 << MAX_CLASS_SHIFT;
+
+/// Size of pages that are split into blocks.
+/// Pages are aligned to their size, so page of a block is found by its address.
+const PAGE_SIZE: usize = 8192;
+
+const PAGE_LAYOUT: Layout = match Layout::from_size_align(PAGE_SIZE, PAGE_SIZE) {
+    Ok(layout) => layout,
+    Err(_) => panic!("Invalid page layout"),
+};
+
+/// Free block of the pool. Stores pointer to the next free block.
+struct FreeBlock {
+    next: Option<NonNull<FreeBlock>>,
+}
+
+/// Page split into blocks of one class.
+struct Page {
+    class: usize,
+
+    /// Number of blocks allocated from the page.
+    live: usize,
+}
+
+struct Pool {
+    free: [Option<NonNull<FreeBlock>>; CLASS_COUNT],
+
+    /// Pages of the pool keyed by page number.
+    pages: HashMap<usize, Page, MulHasherBuilder>,
+
+    /// Number of pages without allocated blocks.
+    empty: usize,
+}
+
+// Safety: pool owns blocks it points to.
+unsafe impl Send for Pool {}
+
+/// Returns size class for the allocation size.
+/// Returns `None` if allocation is not pooled.
+#[inline]
+fn class(size: usize) -> Option<usize> {
+    if size > MAX_POOLED {
+        return None;
+    }
+    let shift = size
+        .next_power_of_two()
+        .trailing_zeros()
+        .max(MIN_CLASS_SHIFT);
+    Some((shift - MIN_CLASS_SHIFT) as usize)
+}
+
+#[inline]
+fn page_number(ptr: NonNull<u8>) -> usize {
+    ptr.as_ptr() as usize / PAGE_SIZE
+}
+
+impl Pool {
+    fn alloc(&mut self, class: usize) -> NonNull<u8> {
+        if self.free[class].is_none() {
+            self.fill(class);
+        }
+
+        let block = self.free[class].unwrap();
+
+        // Safety: free blocks are valid and not used.
+        self.free[class] = unsafe { block.as_ref().next };
+
+        let page = self.pages.get_mut(&page_number(block.cast())).unwrap();
+        if page.live == 0 {
+            self.empty -= 1;
+        }
+        page.live += 1;
+
+        block.cast()
+    }
+
+    /// Splits new page into blocks of the class.
+    fn fill(&mut self, class: usize) {
+        let block_size = 1 << (class as u32 + MIN_CLASS_SHIFT);
+
+        // Safety: page layout has non-zero size.
+        let Some(page) = NonNull::new(unsafe { alloc(PAGE_LAYOUT) }) else {
+            handle_alloc_error(PAGE_LAYOUT);
+        };
+
+        self.pages
+            .insert(page_number(page), Page { class, live: 0 });
+        self.empty += 1;
+
+        for offset in (0..PAGE_SIZE).step_by(block_size).rev() {
+            // Safety: offset is within the page.
+            unsafe {
+                self.push_free(NonNull::new_unchecked(page.as_ptr().add(offset)), class);
+            }
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must be unused block of the class in a page of this pool.
+    unsafe fn push_free(&mut self, ptr: NonNull<u8>, class: usize) {
+        let block = ptr.cast::<FreeBlock>();
+        unsafe {
+            block.as_ptr().write(FreeBlock {
+                next: self.free[class],
+            });
+        }
+        self.free[class] = Some(block);
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must be block of the class allocated from this pool.
+    unsafe fn dealloc(&mut self, ptr: NonNull<u8>, class: usize) {
+        unsafe {
+            self.push_free(ptr, class);
+        }
+
+        let page = self.pages.get_mut(&page_number(ptr)).unwrap();
+        debug_assert_eq!(page.class, class);
+        page.live -= 1;
+        if page.live == 0 {
+            self.empty += 1;
+        }
+    }
+
+    /// Releases pages without allocated blocks.
+    fn trim(&mut self) -> usize {
+        if self.empty == 0 {
+            return 0;
+        }
+
+        let empty = self
+            .pages
+            .iter()
+            .filter(|(_, page)| page.live == 0)
+            .map(|(&number, page)| (number, page.class))
+            .collect::<Vec<_>>();
+
+        // Unlink blocks of released pages from free lists.
+        for class in 0..CLASS_COUNT {
+            if !empty.iter().any(|&(_, c)| c == class) {
+                continue;
+            }
+
+            let mut link = &mut self.free[class];
+            while let Some(block) = *link {
+                // Safety: free blocks are valid and not used.
+                let next = unsafe { &mut (*block.as_ptr()).next };
+                if self.pages[&page_number(block.cast())].live == 0 {
+                    *link = *next;
+                } else {
+                    link = next;
+                }
+            }
+        }
+
+        for &(number, _) in &empty {
+            self.pages.remove(&number);
+
+            // Safety: page was allocated with this layout and none of its blocks are used.
+            unsafe {
+                dealloc((number * PAGE_SIZE) as *mut u8, PAGE_LAYOUT);
+            }
+        }
+
+        self.empty = 0;
+        empty.len()
+    }
+}
+
+impl Drop for Pool {
+    fn drop(&mut self) {
+        for &number in self.pages.keys() {
+            // Safety: page was allocated with this layout.
+            // Pool is dropped after all columns allocated from it.
+            unsafe {
+                dealloc((number * PAGE_SIZE) as *mut u8, PAGE_LAYOUT);
+            }
+        }
+    }
+}
+
+/// Pool of small component columns owned by a world.
+pub(crate) struct ColumnPool {
+    pool: Mutex<Pool>,
+}
+
+impl ColumnPool {
+    pub fn new() -> Self {
+        ColumnPool {
+            pool: Mutex::new(Pool {
+                free: [None; CLASS_COUNT],
+                pages: HashMap::with_hasher(MulHasherBuilder),
+                empty: 0,
+            }),
+        }
+    }
+
+    /// Releases pages without allocated blocks back to the global allocator.
+    /// Returns number of released pages.
+    pub fn trim(&self) -> usize {
+        self.pool.lock().trim()
+    }
+}
+
+// Safety: blocks are not shared until returned to the pool,
+// allocations that are not pooled come from the global allocator.
+unsafe impl ColumnAllocator for ColumnPool {
+    fn alloc(&self, layout: Layout) -> NonNull<u8> {
+        debug_assert!(layout.align() <= layout.size());
+
+        match class(layout.size()) {
+            Some(class) => self.pool.lock().alloc(class),
+            None => unsafe { alloc_column(layout) },
+        }
+    }
+
+    unsafe fn dealloc(&self, ptr: NonNull<u8>, layout: Layout) {
+        match class(layout.size()) {
+            Some(class) => unsafe { self.pool.lock().dealloc(ptr, class) },
+            None => unsafe { dealloc_column(ptr, layout) },
+        }
+    }
+
+    unsafe fn realloc(&self, ptr: NonNull<u8>, layout: Layout, new_size: usize) -> NonNull<u8> {
+        match (class(layout.size()), class(new_size)) {
+            (None, None) => unsafe { realloc_column(ptr, layout, new_size) },
+            (Some(old), Some(new)) if old == new => ptr,
+            _ => unsafe {
+                // Safety: alignment is unchanged and is valid for `layout`.
+                let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+                let new_ptr = self.alloc(new_layout);
+                core::ptr::copy_nonoverlapping(
+                    ptr.as_ptr(),
+                    new_ptr.as_ptr(),
+                    layout.size().min(new_size),
+                );
+                self.dealloc(ptr, layout);
+                new_ptr
+            },
+        }
+    }
+}
+
+/// Allocates memory for a column from the global allocator.
+/// Used for columns that are not pooled.
+///
+/// # Safety
+///
+/// `layout` must have non-zero size.
+pub unsafe fn alloc_column(layout: Layout) -> NonNull<u8> {
+    let Some(ptr) = NonNull::new(unsafe { alloc(layout) }) else {
+        handle_alloc_error(layout);
+    };
+    ptr
+}
+
+/// Deallocates memory of a column.
+///
+/// # Safety
+///
+/// `ptr` must be allocated with [`alloc_column`] or [`realloc_column`]
+/// with the same `layout`.
+pub unsafe fn dealloc_column(ptr: NonNull<u8>, layout: Layout) {
+    unsafe { dealloc(ptr.as_ptr(), layout) }
+}
+
+/// Changes size of a column's memory.
+/// Contents are preserved up to the smaller of the sizes.
+///
+/// # Safety
+///
+/// `ptr` must be allocated with [`alloc_column`] or [`realloc_column`]
+/// with the `layout`.
+/// `new_size` must be non-zero.
+pub unsafe fn realloc_column(ptr: NonNull<u8>, layout: Layout, new_size: usize) -> NonNull<u8> {
+    let ptr = unsafe { global_realloc(ptr.as_ptr(), layout, new_size) };
+    let Some(ptr) = NonNull::new(ptr) else {
+        // Safety: alignment is unchanged and is valid for `layout`.
+        handle_alloc_error(unsafe { Layout::from_size_align_unchecked(new_size, layout.align()) });
+    };
+    ptr
+}
+
+mod test {
+    #![cfg(test)]
+
+    use core::alloc::Layout;
+
+    use alloc::vec::Vec;
+
+    use crate::{
+        archetype::ColumnAllocator,
+        test::{Str, U32},
+        world::World,
+    };
+
+    use super::{ColumnPool, PAGE_SIZE};
+
+    #[test]
+    fn pooled_columns_grow() {
+        let mut world = World::new();
+
+        // Columns start in the pool and move to dedicated allocations as they grow.
+        let entities = (0..1000u32)
+            .map(|i| world.spawn((U32(i), Str("x"))))
+            .collect::<Vec<_>>();
+
+        for (i, &e) in entities.iter().enumerate() {
+            assert_eq!(world.query_one_mut::<&U32>(e), Ok(&U32(i as u32)));
+        }
+
+        for &e in &entities[10..] {
+            world.despawn(e).unwrap();
+        }
+
+        for (i, &e) in entities[..10].iter().enumerate() {
+            assert_eq!(world.query_one_mut::<&U32>(e), Ok(&U32(i as u32)));
+        }
+    }
+
+    #[test]
+    fn pool_trim() {
+        let pool = ColumnPool::new();
+        let layout = Layout::from_size_align(64, 8).unwrap();
+
+        let blocks = (0..PAGE_SIZE / 64 + 1)
+            .map(|_| pool.alloc(layout))
+            .collect::<Vec<_>>();
+
+        // Pages with allocated blocks are kept.
+        assert_eq!(pool.trim(), 0);
+
+        for &block in &blocks[1..] {
+            unsafe { pool.dealloc(block, layout) };
+        }
+        assert_eq!(pool.trim(), 1);
+        assert_eq!(pool.trim(), 0);
+
+        // Free list of the remaining page is still valid.
+        let other = pool.alloc(layout);
+        unsafe {
+            pool.dealloc(other, layout);
+            pool.dealloc(blocks[0], layout);
+        }
+        assert_eq!(pool.trim(), 1);
+    }
+}
@@ -6,13 +6,14 @@ pub use crate::{
     component::Component,
     entity::EntityId,
     query::{Alt, Entities, Modified, PhantomQuery, Query, QueryIter},
-    relation::{ChildOf, Related, Relates, RelatesExclusive, RelatesTo, Relation},
+    relation::{ChildOf, Related, Relates, RelatesExclusive, RelatesTo, RelatesToAny, Relation},
     system::{IntoSystem, Res, ResMut, ResMutNoSend, ResNoSync, State, System},
     world::{EntityError, MissingComponents, NoSuchEntity, QueryOneError, QueryRef, World},
 };
 
 #[cfg(feature = "std")]
 pub use crate::{
+    plugin::Plugin,
     scheduler::Scheduler,
     task::{task_system, task_world, Task},
-};
\ No newline at end of file
+};
\ No newline at end of file
@@ -8,7 +8,7 @@ use core::{
 
 use crate::{
     archetype::{chunk_idx, Archetype},
-    epoch::EpochId,
+    epoch::{AtomicEpochId, EpochId},
 };
 
 use super::{phantom::PhantomQuery, Access, Fetch};
@@ -21,7 +21,7 @@ pub struct RefMut<'a, T: ?Sized> {
     pub(super) component: &'a mut T,
     pub(super) entity_epoch: &'a mut EpochId,
     pub(super) chunk_epoch: &'a Cell<EpochId>,
-    pub(super) archetype_epoch: &'a Cell<EpochId>,
+    pub(super) archetype_epoch: &'a AtomicEpochId,
     pub(super) epoch: EpochId,
 }
 
@@ -39,7 +39,7 @@ impl<T> DerefMut for RefMut<'_, T> {
     fn deref_mut(&mut self) -> &mut T {
         self.entity_epoch.bump_again(self.epoch);
         EpochId::bump_cell(&self.chunk_epoch, self.epoch);
-        EpochId::bump_cell(&self.archetype_epoch, self.epoch);
+        self.archetype_epoch.bump_again(self.epoch);
         self.component
     }
 }
@@ -50,7 +50,7 @@ pub struct FetchAlt<'a, T> {
     ptr: NonNull<T>,
     entity_epochs: NonNull<EpochId>,
     chunk_epochs: NonNull<Cell<EpochId>>,
-    archetype_epoch: NonNull<Cell<EpochId>>,
+    archetype_epoch: NonNull<AtomicEpochId>,
     marker: PhantomData<&'a [T]>,
 }
 
@@ -80,7 +80,7 @@ where
 
     #[inline]
     unsafe fn get_item(&mut self, idx: usize) -> RefMut<'a, T> {
-        let archetype_epoch = &mut *self.archetype_epoch.as_ptr();
+        let archetype_epoch = &*self.archetype_epoch.as_ptr();
         let chunk_epoch = &mut *self.chunk_epochs.as_ptr().add(chunk_idx(idx));
         let entity_epoch = &mut *self.entity_epochs.as_ptr().add(idx);
 
@@ -160,15 +160,14 @@ where
     unsafe fn fetch<'a>(archetype: &'a Archetype, epoch: EpochId) -> FetchAlt<'a, T> {
         let component = archetype.component(TypeId::of::<T>()).unwrap_unchecked();
         debug_assert_eq!(component.id(), TypeId::of::<T>());
-        let data = component.data_mut();
-        debug_assert!(data.epoch.before(epoch));
+        let data = component.data_unique();
 
         FetchAlt {
             epoch,
             ptr: data.ptr.cast(),
             entity_epochs: NonNull::new_unchecked(data.entity_epochs.as_mut_ptr()),
             chunk_epochs: NonNull::new_unchecked(data.chunk_epochs.as_mut_ptr()).cast(),
-            archetype_epoch: NonNull::from(&mut data.epoch).cast(),
+            archetype_epoch: NonNull::from(&data.epoch),
             marker: PhantomData,
         }
     }
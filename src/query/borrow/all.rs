@@ -1,4 +1,4 @@
-use core::{any::TypeId, marker::PhantomData, ptr::NonNull};
+use core::{any::TypeId, fmt, marker::PhantomData, ops::Deref, ptr::NonNull};
 
 use alloc::vec::Vec;
 
@@ -23,12 +23,106 @@ where
     }
 }
 
+phantom_newtype! {
+    /// [`PhantomQuery`] that borrows from components
+    /// and reports component each value is borrowed from.
+    ///
+    /// Yields [`BorrowedFrom`] for each component that can be borrowed as `T`,
+    /// so borrowed values can be told apart by their component type.
+    pub struct QueryBorrowAllFrom<T>
+}
+
+impl<T> QueryBorrowAllFrom<&T>
+where
+    T: Sync + ?Sized + 'static,
+{
+    /// Creates a new [`QueryBorrowAllFrom`] query.
+    pub fn query() -> PhantomData<fn() -> Self> {
+        PhantomQuery::query()
+    }
+}
+
+/// Reference borrowed by [`QueryBorrowAllFrom`]
+/// together with the component type it was borrowed from.
+pub struct BorrowedFrom<'a, T: ?Sized> {
+    id: TypeId,
+    name: &'static str,
+    value: &'a T,
+}
+
+impl<T: ?Sized> Clone for BorrowedFrom<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for BorrowedFrom<'_, T> {}
+
+impl<T> fmt::Debug for BorrowedFrom<'_, T>
+where
+    T: fmt::Debug + ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BorrowedFrom")
+            .field("component", &self.name)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+impl<'a, T: ?Sized> BorrowedFrom<'a, T> {
+    /// Returns [`TypeId`] of the component the value is borrowed from.
+    #[inline]
+    pub fn id(&self) -> TypeId {
+        self.id
+    }
+
+    /// Returns name of the component the value is borrowed from.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns borrowed reference.
+    #[inline]
+    pub fn get(&self) -> &'a T {
+        self.value
+    }
+}
+
+impl<T: ?Sized> Deref for BorrowedFrom<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
 struct FetchBorrowAllReadComponent<'a, T: ?Sized> {
+    id: TypeId,
+    name: &'static str,
     ptr: NonNull<u8>,
     size: usize,
     borrow_fn: unsafe fn(NonNull<u8>, PhantomData<&'a ()>) -> &'a T,
 }
 
+impl<'a, T: ?Sized> FetchBorrowAllReadComponent<'a, T> {
+    /// # Safety
+    ///
+    /// `idx` must be in bounds of the archetype this component was fetched from.
+    #[inline]
+    unsafe fn borrow(&self, idx: usize) -> &'a T {
+        unsafe {
+            (self.borrow_fn)(
+                NonNull::new_unchecked(self.ptr.as_ptr().add(idx * self.size)),
+                PhantomData::<&'a ()>,
+            )
+        }
+    }
+}
+
 /// [`Fetch`] for [`QueryBorrowAll<&T>`].
 pub struct FetchBorrowAllRead<'a, T: ?Sized> {
     components: Vec<FetchBorrowAllReadComponent<'a, T>>,
@@ -53,12 +147,7 @@ where
     unsafe fn get_item(&mut self, idx: usize) -> Vec<&'a T> {
         self.components
             .iter()
-            .map(|c| unsafe {
-                (c.borrow_fn)(
-                    NonNull::new_unchecked(c.ptr.as_ptr().add(idx * c.size)),
-                    PhantomData::<&'a ()>,
-                )
-            })
+            .map(|c| unsafe { c.borrow(idx) })
             .collect()
     }
 }
@@ -108,6 +197,8 @@ where
                 let data = unsafe { component.data() };
 
                 FetchBorrowAllReadComponent {
+                    id: component.id(),
+                    name: component.name(),
                     ptr: data.ptr,
                     size: component.layout().size(),
                     borrow_fn: component.borrows()[idx].borrow(),
@@ -123,3 +214,98 @@ where
 }
 
 unsafe impl<T> ImmutablePhantomQuery for QueryBorrowAll<&T> where T: Sync + ?Sized + 'static {}
+
+/// [`Fetch`] for [`QueryBorrowAllFrom<&T>`].
+pub struct FetchBorrowAllFromRead<'a, T: ?Sized> {
+    inner: FetchBorrowAllRead<'a, T>,
+}
+
+unsafe impl<'a, T> Fetch<'a> for FetchBorrowAllFromRead<'a, T>
+where
+    T: Sync + ?Sized + 'a,
+{
+    type Item = Vec<BorrowedFrom<'a, T>>;
+
+    #[inline]
+    fn dangling() -> Self {
+        FetchBorrowAllFromRead {
+            inner: FetchBorrowAllRead::dangling(),
+        }
+    }
+
+    #[inline]
+    unsafe fn get_item(&mut self, idx: usize) -> Vec<BorrowedFrom<'a, T>> {
+        self.inner
+            .components
+            .iter()
+            .map(|c| BorrowedFrom {
+                id: c.id,
+                name: c.name,
+                value: unsafe { c.borrow(idx) },
+            })
+            .collect()
+    }
+}
+
+unsafe impl<T> PhantomQuery for QueryBorrowAllFrom<&T>
+where
+    T: Sync + ?Sized + 'static,
+{
+    type Item<'a> = Vec<BorrowedFrom<'a, T>>;
+    type Fetch<'a> = FetchBorrowAllFromRead<'a, T>;
+
+    #[inline]
+    fn access(ty: TypeId) -> Option<Access> {
+        <QueryBorrowAll<&T> as PhantomQuery>::access(ty)
+    }
+
+    #[inline]
+    fn visit_archetype(archetype: &Archetype) -> bool {
+        <QueryBorrowAll<&T> as PhantomQuery>::visit_archetype(archetype)
+    }
+
+    #[inline]
+    unsafe fn access_archetype(archetype: &Archetype, f: &dyn Fn(TypeId, Access)) {
+        unsafe { <QueryBorrowAll<&T> as PhantomQuery>::access_archetype(archetype, f) }
+    }
+
+    #[inline]
+    unsafe fn fetch<'a>(archetype: &'a Archetype, epoch: EpochId) -> FetchBorrowAllFromRead<'a, T> {
+        FetchBorrowAllFromRead {
+            inner: unsafe { <QueryBorrowAll<&T> as PhantomQuery>::fetch(archetype, epoch) },
+        }
+    }
+}
+
+unsafe impl<T> ImmutablePhantomQuery for QueryBorrowAllFrom<&T> where T: Sync + ?Sized + 'static {}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{component::Component, world::World};
+
+    #[test]
+    fn borrow_all_origin() {
+        use core::{any::TypeId, fmt::Debug};
+
+        #[derive(Debug, Component)]
+        #[edict(borrow(dyn Debug))]
+        struct A;
+
+        #[derive(Debug, Component)]
+        #[edict(borrow(dyn Debug))]
+        struct B;
+
+        let mut world = World::new();
+        world.spawn((A, B));
+
+        let query = world.new_query().borrow_all_from::<&(dyn Debug + Sync)>();
+        let mut items = query.iter().next().unwrap();
+        items.sort_by_key(|item| item.name());
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].id(), TypeId::of::<A>());
+        assert_eq!(items[1].id(), TypeId::of::<B>());
+        assert!(items[1].name().ends_with("B"));
+    }
+}
@@ -196,7 +196,7 @@ where
         let component = archetype.component(id).unwrap_unchecked();
         debug_assert_eq!(component.borrows()[idx].target(), TypeId::of::<T>());
 
-        let data = component.data_mut();
+        let data = component.data_unique();
 
         FetchBorrowAnyWrite {
             ptr: data.ptr,
@@ -230,7 +230,7 @@ where
 
         assert!(cb.borrow_mut::<T>().is_some());
 
-        let data = component.data_mut();
+        let data = component.data_unique();
 
         data.epoch.bump(epoch);
 
@@ -0,0 +1,171 @@
+use core::{any::TypeId, ops::Range};
+
+use crate::{archetype::Archetype, epoch::EpochId};
+
+use super::{Access, Fetch, ImmutableQuery, IntoQuery, Query};
+
+/// Filter that visits only chunks with indices in the range.
+///
+/// Borrow locks are acquired only for chunks in the range,
+/// so queries over disjoint ranges of chunks may write
+/// to the same component concurrently.
+/// Useful to split work on large archetypes between jobs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChunkRange {
+    range: Range<usize>,
+}
+
+impl ChunkRange {
+    /// Creates new `ChunkRange` filter.
+    pub fn new(range: Range<usize>) -> Self {
+        ChunkRange { range }
+    }
+
+    /// Returns range of chunk indices visited by this filter.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+}
+
+/// [`Fetch`] type for the [`ChunkRange`] query.
+pub struct ChunkRangeFetch {
+    range: Range<usize>,
+}
+
+unsafe impl<'a> Fetch<'a> for ChunkRangeFetch {
+    type Item = ();
+
+    #[inline]
+    fn dangling() -> Self {
+        ChunkRangeFetch { range: 0..0 }
+    }
+
+    #[inline]
+    unsafe fn visit_chunk(&mut self, chunk_idx: usize) -> bool {
+        self.range.contains(&chunk_idx)
+    }
+
+    #[inline]
+    unsafe fn get_item(&mut self, _: usize) {}
+}
+
+impl IntoQuery for ChunkRange {
+    type Query = Self;
+
+    fn into_query(self) -> Self {
+        self
+    }
+}
+
+unsafe impl Query for ChunkRange {
+    type Item<'a> = ();
+    type Fetch<'a> = ChunkRangeFetch;
+
+    #[inline]
+    fn access(&self, _ty: TypeId) -> Option<Access> {
+        None
+    }
+
+    #[inline]
+    fn visit_archetype(&self, _archetype: &Archetype) -> bool {
+        true
+    }
+
+    #[inline]
+    unsafe fn access_archetype(&self, _archetype: &Archetype, _f: &dyn Fn(TypeId, Access)) {}
+
+    #[inline]
+    unsafe fn fetch<'a>(&mut self, _archetype: &'a Archetype, _epoch: EpochId) -> ChunkRangeFetch {
+        ChunkRangeFetch {
+            range: self.range.clone(),
+        }
+    }
+
+    #[inline]
+    fn chunk_range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+}
+
+unsafe impl ImmutableQuery for ChunkRange {}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{
+        query::{Alt, Modified},
+        test::U32,
+        world::World,
+    };
+
+    /// Tests that queries over disjoint chunk ranges can write the same component.
+    #[test]
+    fn chunk_borrows() {
+        let mut world = World::new();
+        for i in 0..1000 {
+            world.spawn((U32(i),));
+        }
+
+        let first = world.query::<&mut U32>().filter_chunks(0..2);
+        let rest = world.query::<&mut U32>().filter_chunks(2..4);
+        assert!(first.try_ensure_borrow().is_ok());
+        assert!(rest.try_ensure_borrow().is_ok());
+
+        let overlapping = world.query::<&mut U32>().filter_chunks(1..3);
+        assert!(overlapping.try_ensure_borrow().is_err());
+
+        let whole = world.query::<&U32>();
+        assert!(whole.try_ensure_borrow().is_err());
+
+        drop(first);
+        let shared = world.query::<&U32>().filter_chunks(0..2);
+        assert!(shared.try_ensure_borrow().is_ok());
+        drop((rest, shared));
+
+        assert!(whole.try_ensure_borrow().is_ok());
+        assert_eq!(whole.iter().count(), 1000);
+    }
+
+    /// Tests that empty chunk range does not exclude whole-column readers.
+    #[test]
+    fn empty_chunk_range() {
+        let mut world = World::new();
+        for i in 0..100 {
+            world.spawn((U32(i),));
+        }
+
+        let empty = world.query::<&mut U32>().filter_chunks(5..8);
+        assert!(empty.try_ensure_borrow().is_ok());
+
+        let whole = world.query::<&U32>();
+        assert!(whole.try_ensure_borrow().is_ok());
+        assert_eq!(whole.iter().count(), 100);
+    }
+
+    /// Tests that writers of disjoint chunk ranges run concurrently
+    /// and both updates of component epoch are observed.
+    #[test]
+    fn concurrent_chunk_writers() {
+        let mut world = World::new();
+        for i in 0..1000 {
+            world.spawn((U32(i),));
+        }
+        let before = world.epoch();
+
+        std::thread::scope(|scope| {
+            let world = &world;
+            for range in [0..2, 2..4] {
+                scope.spawn(move || {
+                    world
+                        .query::<Alt<U32>>()
+                        .filter_chunks(range)
+                        .for_each(|mut u| u.0 += 1);
+                });
+            }
+        });
+
+        let modified = world.query_with(Modified::<&U32>::new(before));
+        assert_eq!(modified.iter().count(), 1000);
+        assert_eq!(world.query::<&U32>().iter().map(|u| u.0).min(), Some(1));
+    }
+}
@@ -1,8 +1,8 @@
-use core::{any::TypeId, marker::PhantomData};
+use core::{any::TypeId, marker::PhantomData, ops::Range};
 
 use crate::{archetype::Archetype, entity::EntityId};
 
-use super::{Access, Fetch, ImmutablePhantomQuery, PhantomQuery};
+use super::{Access, Fetch, ImmutablePhantomQuery, PhantomQuery, SliceFetch};
 
 /// [`Fetch`] type for the [`Entities`] query.
 pub struct EntitiesFetch<'a> {
@@ -17,12 +17,26 @@ unsafe impl<'a> Fetch<'a> for EntitiesFetch<'a> {
         EntitiesFetch { entities: &[] }
     }
 
+    #[inline]
+    fn visits_all(&self) -> bool {
+        true
+    }
+
     #[inline]
     unsafe fn get_item(&mut self, idx: usize) -> EntityId {
         *self.entities.get_unchecked(idx)
     }
 }
 
+unsafe impl<'a> SliceFetch<'a> for EntitiesFetch<'a> {
+    type Slice = &'a [EntityId];
+
+    #[inline]
+    unsafe fn get_chunk_slice(&mut self, range: Range<usize>) -> &'a [EntityId] {
+        self.entities.get_unchecked(range)
+    }
+}
+
 /// Queries entity ids.
 #[derive(Clone, Copy, Debug, Default)]
 pub struct Entities;
@@ -0,0 +1,460 @@
+use core::{any::TypeId, fmt, marker::PhantomData, ptr::NonNull};
+
+use crate::{
+    archetype::{is_enabled, Archetype},
+    component::Component,
+    epoch::EpochId,
+};
+
+use super::{Access, Fetch, ImmutableQuery, IntoQuery, Query};
+
+/// Component values stored outside of the world,
+/// for example body array owned by a physics engine.
+///
+/// World stores only [`ExternalRow<Self>`] component with row index,
+/// and [`ExternalRead`] and [`ExternalWrite`] queries fetch values through this accessor.
+/// Access to the values is checked as access to the [`ExternalRow<Self>`] component,
+/// so conflicting queries are detected same way as for regular components.
+///
+/// # Safety
+///
+/// `get` must return pointer to a valid value for any row
+/// of [`ExternalRow<Self>`] attached to an entity,
+/// or panic if row is out of bounds.
+/// Pointers to values of different rows must not alias.
+/// Values must not be accessed by other means while query that fetches them is alive.
+pub unsafe trait ExternalStorage: Clone + 'static {
+    /// Type of the values in the storage.
+    type Value: 'static;
+
+    /// Returns pointer to the value in specified row.
+    fn get(&self, row: usize) -> NonNull<Self::Value>;
+}
+
+/// Component that maps entity to a row of the external storage `S`.
+pub struct ExternalRow<S> {
+    row: usize,
+    marker: PhantomData<fn() -> S>,
+}
+
+impl<S> Clone for ExternalRow<S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for ExternalRow<S> {}
+
+impl<S> PartialEq for ExternalRow<S> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.row == other.row
+    }
+}
+
+impl<S> Eq for ExternalRow<S> {}
+
+impl<S> fmt::Debug for ExternalRow<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ExternalRow").field(&self.row).finish()
+    }
+}
+
+impl<S> ExternalRow<S> {
+    /// Returns new row mapping.
+    ///
+    /// # Safety
+    ///
+    /// No other entity in the same world may be mapped to the same row.
+    #[inline]
+    pub unsafe fn new(row: usize) -> Self {
+        ExternalRow {
+            row,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns row index in the external storage.
+    #[inline]
+    pub fn row(&self) -> usize {
+        self.row
+    }
+}
+
+impl<S> Component for ExternalRow<S> where S: 'static {}
+
+/// [`Fetch`] type for the [`ExternalRead`] query.
+pub struct ExternalReadFetch<'a, S> {
+    rows: NonNull<ExternalRow<S>>,
+    disabled: &'a [u64],
+
+    /// `None` only for dangling fetch.
+    storage: Option<S>,
+}
+
+unsafe impl<'a, S> Fetch<'a> for ExternalReadFetch<'a, S>
+where
+    S: ExternalStorage,
+    S::Value: Sync,
+{
+    type Item = &'a S::Value;
+
+    #[inline]
+    fn dangling() -> Self {
+        ExternalReadFetch {
+            rows: NonNull::dangling(),
+            disabled: &[],
+            storage: None,
+        }
+    }
+
+    #[inline]
+    unsafe fn visit_item(&mut self, idx: usize) -> bool {
+        is_enabled(self.disabled, idx)
+    }
+
+    #[inline]
+    unsafe fn get_item(&mut self, idx: usize) -> &'a S::Value {
+        // Dangling fetch is never used.
+        let storage = self.storage.as_ref().unwrap_unchecked();
+        let row = (*self.rows.as_ptr().add(idx)).row;
+        &*storage.get(row).as_ptr()
+    }
+}
+
+/// Query that yields references to values of external storage `S`
+/// for entities with [`ExternalRow<S>`] component.
+///
+/// Skips entities that don't have the row component or have it disabled.
+///
+/// # Example
+///
+/// ```
+/// # use core::ptr::NonNull;
+/// # use edict::{query::{ExternalRead, ExternalRow, ExternalStorage, ExternalWrite}, world::World};
+/// /// Positions owned by physics engine.
+/// #[derive(Clone)]
+/// struct Positions {
+///     ptr: NonNull<f32>,
+///     len: usize,
+/// }
+///
+/// unsafe impl ExternalStorage for Positions {
+///     type Value = f32;
+///
+///     fn get(&self, row: usize) -> NonNull<f32> {
+///         assert!(row < self.len);
+///         unsafe { NonNull::new_unchecked(self.ptr.as_ptr().add(row)) }
+///     }
+/// }
+///
+/// let mut bodies = vec![1.0, 2.0];
+/// let positions = Positions {
+///     ptr: NonNull::new(bodies.as_mut_ptr()).unwrap(),
+///     len: bodies.len(),
+/// };
+///
+/// let mut world = World::new();
+/// world.spawn((unsafe { ExternalRow::<Positions>::new(0) },));
+/// world.spawn((unsafe { ExternalRow::<Positions>::new(1) },));
+///
+/// world
+///     .query_with(ExternalWrite::new(positions.clone()))
+///     .for_each(|pos| *pos *= 10.0);
+///
+/// let sum = world
+///     .query_with(ExternalRead::new(positions))
+///     .fold(0.0, |acc, pos| acc + *pos);
+/// assert_eq!(sum, 30.0);
+/// assert_eq!(bodies, [10.0, 20.0]);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct ExternalRead<S> {
+    storage: S,
+}
+
+impl<S> ExternalRead<S>
+where
+    S: ExternalStorage,
+    S::Value: Sync,
+{
+    /// Returns query that reads values through the storage accessor.
+    #[inline]
+    pub fn new(storage: S) -> Self {
+        ExternalRead { storage }
+    }
+}
+
+impl<S> IntoQuery for ExternalRead<S>
+where
+    S: ExternalStorage,
+    S::Value: Sync,
+{
+    type Query = Self;
+
+    #[inline]
+    fn into_query(self) -> Self {
+        self
+    }
+}
+
+unsafe impl<S> Query for ExternalRead<S>
+where
+    S: ExternalStorage,
+    S::Value: Sync,
+{
+    type Item<'a> = &'a S::Value;
+    type Fetch<'a> = ExternalReadFetch<'a, S>;
+
+    #[inline]
+    fn access(&self, ty: TypeId) -> Option<Access> {
+        if ty == TypeId::of::<ExternalRow<S>>() {
+            Some(Access::Read)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn visit_archetype(&self, archetype: &Archetype) -> bool {
+        archetype.has_component(TypeId::of::<ExternalRow<S>>())
+    }
+
+    #[inline]
+    unsafe fn access_archetype(&self, _archetype: &Archetype, f: &dyn Fn(TypeId, Access)) {
+        f(TypeId::of::<ExternalRow<S>>(), Access::Read)
+    }
+
+    #[inline]
+    unsafe fn fetch<'a>(
+        &mut self,
+        archetype: &'a Archetype,
+        _epoch: EpochId,
+    ) -> ExternalReadFetch<'a, S> {
+        let component = archetype
+            .component(TypeId::of::<ExternalRow<S>>())
+            .unwrap_unchecked();
+
+        let data = component.data();
+
+        ExternalReadFetch {
+            rows: data.ptr.cast(),
+            disabled: &data.disabled,
+            storage: Some(self.storage.clone()),
+        }
+    }
+}
+
+unsafe impl<S> ImmutableQuery for ExternalRead<S>
+where
+    S: ExternalStorage,
+    S::Value: Sync,
+{
+}
+
+/// [`Fetch`] type for the [`ExternalWrite`] query.
+pub struct ExternalWriteFetch<'a, S> {
+    rows: NonNull<ExternalRow<S>>,
+    disabled: &'a [u64],
+    entity_epochs: NonNull<EpochId>,
+    chunk_epochs: NonNull<EpochId>,
+    epoch: EpochId,
+
+    /// `None` only for dangling fetch.
+    storage: Option<S>,
+}
+
+unsafe impl<'a, S> Fetch<'a> for ExternalWriteFetch<'a, S>
+where
+    S: ExternalStorage,
+    S::Value: Send,
+{
+    type Item = &'a mut S::Value;
+
+    #[inline]
+    fn dangling() -> Self {
+        ExternalWriteFetch {
+            rows: NonNull::dangling(),
+            disabled: &[],
+            entity_epochs: NonNull::dangling(),
+            chunk_epochs: NonNull::dangling(),
+            epoch: EpochId::start(),
+            storage: None,
+        }
+    }
+
+    #[inline]
+    unsafe fn touch_chunk(&mut self, chunk_idx: usize) {
+        let chunk_epoch = &mut *self.chunk_epochs.as_ptr().add(chunk_idx);
+        chunk_epoch.bump(self.epoch);
+    }
+
+    #[inline]
+    unsafe fn visit_item(&mut self, idx: usize) -> bool {
+        is_enabled(self.disabled, idx)
+    }
+
+    #[inline]
+    unsafe fn get_item(&mut self, idx: usize) -> &'a mut S::Value {
+        let entity_epoch = &mut *self.entity_epochs.as_ptr().add(idx);
+        entity_epoch.bump(self.epoch);
+
+        // Dangling fetch is never used.
+        let storage = self.storage.as_ref().unwrap_unchecked();
+        let row = (*self.rows.as_ptr().add(idx)).row;
+        &mut *storage.get(row).as_ptr()
+    }
+}
+
+/// Query that yields mutable references to values of external storage `S`
+/// for entities with [`ExternalRow<S>`] component.
+///
+/// Skips entities that don't have the row component or have it disabled.
+/// Marks row components as modified, so [`Modified`](super::Modified)
+/// queries over `&ExternalRow<S>` observe changes of the values.
+///
+/// See [`ExternalRead`] for example.
+#[derive(Clone, Copy, Debug)]
+pub struct ExternalWrite<S> {
+    storage: S,
+}
+
+impl<S> ExternalWrite<S>
+where
+    S: ExternalStorage,
+    S::Value: Send,
+{
+    /// Returns query that modifies values through the storage accessor.
+    #[inline]
+    pub fn new(storage: S) -> Self {
+        ExternalWrite { storage }
+    }
+}
+
+impl<S> IntoQuery for ExternalWrite<S>
+where
+    S: ExternalStorage,
+    S::Value: Send,
+{
+    type Query = Self;
+
+    #[inline]
+    fn into_query(self) -> Self {
+        self
+    }
+}
+
+unsafe impl<S> Query for ExternalWrite<S>
+where
+    S: ExternalStorage,
+    S::Value: Send,
+{
+    type Item<'a> = &'a mut S::Value;
+    type Fetch<'a> = ExternalWriteFetch<'a, S>;
+
+    #[inline]
+    fn access(&self, ty: TypeId) -> Option<Access> {
+        if ty == TypeId::of::<ExternalRow<S>>() {
+            Some(Access::Write)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn visit_archetype(&self, archetype: &Archetype) -> bool {
+        archetype.has_component(TypeId::of::<ExternalRow<S>>())
+    }
+
+    #[inline]
+    unsafe fn access_archetype(&self, _archetype: &Archetype, f: &dyn Fn(TypeId, Access)) {
+        f(TypeId::of::<ExternalRow<S>>(), Access::Write)
+    }
+
+    #[inline]
+    unsafe fn fetch<'a>(
+        &mut self,
+        archetype: &'a Archetype,
+        epoch: EpochId,
+    ) -> ExternalWriteFetch<'a, S> {
+        let component = archetype
+            .component(TypeId::of::<ExternalRow<S>>())
+            .unwrap_unchecked();
+
+        let data = component.data_unique();
+        data.epoch.bump(epoch);
+
+        ExternalWriteFetch {
+            rows: data.ptr.cast(),
+            disabled: &data.disabled,
+            entity_epochs: NonNull::new_unchecked(data.entity_epochs.as_mut_ptr()),
+            chunk_epochs: NonNull::new_unchecked(data.chunk_epochs.as_mut_ptr()),
+            epoch,
+            storage: Some(self.storage.clone()),
+        }
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::{vec, vec::Vec};
+
+    use crate::{query::Entities, test::U32, world::World};
+
+    #[test]
+    fn external_storage() {
+        use core::ptr::NonNull;
+
+        use crate::query::{ExternalRead, ExternalRow, ExternalStorage, ExternalWrite, Modified};
+
+        #[derive(Clone)]
+        struct Values {
+            ptr: NonNull<u32>,
+            len: usize,
+        }
+
+        unsafe impl ExternalStorage for Values {
+            type Value = u32;
+
+            fn get(&self, row: usize) -> NonNull<u32> {
+                assert!(row < self.len);
+                unsafe { NonNull::new_unchecked(self.ptr.as_ptr().add(row)) }
+            }
+        }
+
+        let mut values = vec![1u32, 2, 3];
+        let storage = Values {
+            ptr: NonNull::new(values.as_mut_ptr()).unwrap(),
+            len: values.len(),
+        };
+
+        let mut world = World::new();
+        let a = world.spawn((unsafe { ExternalRow::<Values>::new(2) }, U32(0)));
+        let b = world.spawn((unsafe { ExternalRow::<Values>::new(0) },));
+        world.set_enabled::<ExternalRow<Values>>(b, false).unwrap();
+
+        let epoch = world.epoch();
+        world
+            .query_with(ExternalWrite::new(storage.clone()))
+            .for_each(|value| *value += 10);
+
+        let modified = world
+            .query_with((Entities, Modified::<&ExternalRow<Values>>::new(epoch)))
+            .iter()
+            .map(|(e, _)| e)
+            .collect::<Vec<_>>();
+        assert_eq!(modified, [a]);
+
+        let read = world
+            .query_with(ExternalRead::new(storage))
+            .with::<U32>()
+            .iter()
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(read, [13]);
+
+        assert_eq!(values, [1, 2, 13]);
+    }
+}
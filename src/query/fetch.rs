@@ -1,3 +1,5 @@
+use core::ops::Range;
+
 use crate::archetype::chunk_idx;
 
 /// This type can be used in [`Fetch`] implementation
@@ -213,6 +215,31 @@ pub unsafe trait Fetch<'a> {
         true
     }
 
+    /// Returns `true` if this fetch visits all chunks and items of the archetype,
+    /// i.e. [`Fetch::visit_chunk`] and [`Fetch::visit_item`] would always return `true`.
+    ///
+    /// Iteration over fetch that visits all items may skip calls to `visit_chunk`
+    /// and `visit_item`, calling only [`Fetch::touch_chunk`] and [`Fetch::get_item`].
+    /// Implementation must not return `true` otherwise.
+    #[inline]
+    #[must_use]
+    fn visits_all(&self) -> bool {
+        false
+    }
+
+    /// Hints this fetch that chunk with specified index will be accessed soon.
+    /// Implementation may prefetch memory of the chunk into the cache.
+    ///
+    /// # Safety
+    ///
+    /// Chunk index must in range `0..=chunk_count`,
+    /// where `chunk_count` is the number of chunks in the archetype
+    /// from which query produced this instance.
+    #[inline]
+    unsafe fn prefetch_chunk(&self, chunk_idx: usize) {
+        let _ = chunk_idx;
+    }
+
     /// Notifies this fetch that at least one item in the chunk will be accessed.
     /// This method is called for each chunk in the archetype that is not skipped.
     ///
@@ -246,6 +273,36 @@ pub unsafe trait Fetch<'a> {
     unsafe fn get_item(&mut self, idx: usize) -> Self::Item;
 }
 
+/// Extension of the [`Fetch`] trait for fetches that never skip items
+/// and can yield items of a chunk as a single contiguous slice.
+///
+/// Closure called over slices instead of single items
+/// gives compiler a chance to vectorize the loop.
+///
+/// # Safety
+///
+/// `Fetch::visit_item` must always return `true`,
+/// except for items with disabled components.
+/// Slices include items with disabled components.
+/// Calling `get_chunk_slice` must have the same effect
+/// as calling `Fetch::get_item` for each index in the range.
+pub unsafe trait SliceFetch<'a>: Fetch<'a> {
+    /// Slice of items this fetch yields.
+    type Slice: 'a;
+
+    /// Returns items with indices in specified range as a slice.
+    ///
+    /// # Safety
+    ///
+    /// Range must be within one chunk of the archetype
+    /// from which query produced this instance.
+    ///
+    /// `visit_chunk` must have been called for this chunk and returned `true`.
+    /// `touch_chunk` must have been called for this chunk after it.
+    #[must_use]
+    unsafe fn get_chunk_slice(&mut self, range: Range<usize>) -> Self::Slice;
+}
+
 /// Fetch type for `Query` implementations
 /// where nothing needs to be fetched.
 #[repr(transparent)]
@@ -306,3 +363,12 @@ unsafe impl<'a> Fetch<'a> for UnitFetch {
         self.verify.get_item(idx)
     }
 }
+
+unsafe impl<'a> SliceFetch<'a> for UnitFetch {
+    type Slice = ();
+
+    #[inline]
+    unsafe fn get_chunk_slice(&mut self, range: Range<usize>) {
+        let _ = range;
+    }
+}
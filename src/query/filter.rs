@@ -1,9 +1,10 @@
-use core::{any::TypeId, marker::PhantomData};
+use core::{any::TypeId, marker::PhantomData, ops::Range};
 
 use crate::{archetype::Archetype, epoch::EpochId};
 
 use super::{
-    fetch::UnitFetch, merge_access, Access, Fetch, ImmutablePhantomQuery, ImmutableQuery,
+    fetch::{SliceFetch, UnitFetch},
+    merge_access, merge_chunk_range, Access, Fetch, ImmutablePhantomQuery, ImmutableQuery,
     IntoQuery, PhantomQuery, Query,
 };
 
@@ -102,6 +103,19 @@ where
     }
 }
 
+unsafe impl<'a, F, Q> SliceFetch<'a> for FilteredFetch<F, Q>
+where
+    F: SliceFetch<'a>,
+    Q: SliceFetch<'a>,
+{
+    type Slice = Q::Slice;
+
+    #[inline]
+    unsafe fn get_chunk_slice(&mut self, range: Range<usize>) -> Q::Slice {
+        self.query.get_chunk_slice(range)
+    }
+}
+
 /// Combines query and filter.
 /// Skips using both and yields using query.
 #[derive(Clone, Copy, Debug)]
@@ -145,7 +159,10 @@ where
     }
 
     #[inline]
-    unsafe fn access_archetype(&self, _archetype: &Archetype, _f: &dyn Fn(TypeId, Access)) {}
+    unsafe fn access_archetype(&self, archetype: &Archetype, f: &dyn Fn(TypeId, Access)) {
+        // Filters only read epochs and are allowed to alias the query.
+        self.query.access_archetype(archetype, f);
+    }
 
     #[inline]
     unsafe fn fetch<'a>(
@@ -158,6 +175,11 @@ where
             query: self.query.fetch(archetype, index),
         }
     }
+
+    #[inline]
+    fn chunk_range(&self) -> Range<usize> {
+        merge_chunk_range(self.filter.chunk_range(), self.query.chunk_range())
+    }
 }
 
 unsafe impl<F, Q> ImmutableQuery for FilteredQuery<F, Q>
@@ -298,3 +320,29 @@ unsafe impl<T> ImmutablePhantomQuery for With<T> where T: 'static {}
 /// [`Filter`] that allows only archetypes without specified component.
 /// Inverse of [`With`].
 pub type Without<T> = Not<With<T>>;
+
+/// Returns filter that allows only entities with specified component.
+///
+/// Same as [`QueryRef::with`] but produces a value
+/// that can be combined with other queries in a tuple.
+///
+/// [`QueryRef::with`]: crate::world::QueryRef::with
+pub fn with<T>() -> PhantomData<fn() -> With<T>>
+where
+    T: 'static,
+{
+    PhantomData
+}
+
+/// Returns filter that allows only entities without specified component.
+///
+/// Same as [`QueryRef::without`] but produces a value
+/// that can be combined with other queries in a tuple.
+///
+/// [`QueryRef::without`]: crate::world::QueryRef::without
+pub fn without<T>() -> Not<PhantomData<fn() -> With<T>>>
+where
+    T: 'static,
+{
+    Not(PhantomData)
+}
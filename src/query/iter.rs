@@ -1,36 +1,277 @@
 use core::{ops::Range, slice};
 
 use crate::{
-    archetype::{chunk_idx, first_of_chunk, Archetype, CHUNK_LEN_USIZE},
+    archetype::{chunk_idx, Archetype, CHUNK_LEN_USIZE},
     epoch::EpochId,
 };
 
-use super::{fetch::Fetch, Query, QueryItem};
+use super::{fetch::Fetch, MetricsCounters, Query, QueryItem, DISCARDED_METRICS};
+
+/// Position of iteration over entities of one archetype.
+/// Can be advanced from both ends.
+struct Cursor<'a, F> {
+    fetch: F,
+    indices: Range<usize>,
+    metrics: &'a MetricsCounters,
+
+    /// Chunk for which `Fetch::visit_chunk` was called last and returned `true`.
+    chunk: usize,
+
+    /// Set if `Fetch::touch_chunk` was called for `chunk`.
+    touched: bool,
+}
+
+impl<'a, F> Cursor<'a, F>
+where
+    F: Fetch<'a>,
+{
+    fn dangling() -> Self {
+        Cursor {
+            fetch: F::dangling(),
+            indices: 0..0,
+            metrics: &DISCARDED_METRICS,
+            chunk: usize::MAX,
+            touched: false,
+        }
+    }
+
+    fn new(fetch: F, len: usize, metrics: &'a MetricsCounters) -> Self {
+        metrics.archetype_visited();
+        Cursor {
+            fetch,
+            indices: 0..len,
+            metrics,
+            chunk: usize::MAX,
+            touched: false,
+        }
+    }
+
+    /// Visits chunk of the entity unless it is already visited.
+    /// Returns `false` if chunk must be skipped.
+    #[inline]
+    unsafe fn enter_chunk(&mut self, idx: usize) -> bool {
+        let chunk_idx = chunk_idx(idx);
+        if chunk_idx != self.chunk {
+            if !unsafe { self.fetch.visit_chunk(chunk_idx) } {
+                self.metrics.chunk_skipped();
+                self.chunk = usize::MAX;
+                return false;
+            }
+            self.chunk = chunk_idx;
+            self.touched = false;
+        }
+        true
+    }
+
+    #[inline]
+    unsafe fn get_item(&mut self, idx: usize) -> Option<F::Item> {
+        if !unsafe { self.fetch.visit_item(idx) } {
+            return None;
+        }
+
+        if !self.touched {
+            unsafe { self.fetch.touch_chunk(self.chunk) }
+            self.touched = true;
+        }
+
+        self.metrics.items_yielded(1);
+        Some(unsafe { self.fetch.get_item(idx) })
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<F::Item> {
+        loop {
+            let idx = self.indices.next()?;
+
+            if !unsafe { self.enter_chunk(idx) } {
+                let chunk_end = (chunk_idx(idx) + 1) * CHUNK_LEN_USIZE;
+                self.indices.start = chunk_end.min(self.indices.end);
+                continue;
+            }
+
+            if let Some(item) = unsafe { self.get_item(idx) } {
+                return Some(item);
+            }
+        }
+    }
+
+    #[inline]
+    fn next_back(&mut self) -> Option<F::Item> {
+        loop {
+            let idx = self.indices.next_back()?;
+
+            if !unsafe { self.enter_chunk(idx) } {
+                let chunk_start = chunk_idx(idx) * CHUNK_LEN_USIZE;
+                self.indices.end = chunk_start.max(self.indices.start);
+                continue;
+            }
+
+            if let Some(item) = unsafe { self.get_item(idx) } {
+                return Some(item);
+            }
+        }
+    }
+
+    /// Skips up to `n` items from the front without fetching them.
+    /// Whole chunks are skipped at once.
+    /// Returns number of skipped items.
+    fn skip(&mut self, n: usize) -> usize {
+        let mut left = n;
+
+        while left > 0 && !self.indices.is_empty() {
+            let idx = self.indices.start;
+            let chunk_end = ((chunk_idx(idx) + 1) * CHUNK_LEN_USIZE).min(self.indices.end);
+
+            if !unsafe { self.enter_chunk(idx) } {
+                self.indices.start = chunk_end;
+                continue;
+            }
+
+            let fetch = &mut self.fetch;
+            let visible = (idx..chunk_end)
+                .filter(|&idx| unsafe { fetch.visit_item(idx) })
+                .count();
+
+            if visible <= left {
+                left -= visible;
+                self.indices.start = chunk_end;
+                continue;
+            }
+
+            while left > 0 {
+                let idx = self.indices.next().unwrap();
+                if unsafe { self.fetch.visit_item(idx) } {
+                    left -= 1;
+                }
+            }
+        }
+
+        n - left
+    }
+
+    /// Skips up to `n` items from the back without fetching them.
+    /// Whole chunks are skipped at once.
+    /// Returns number of skipped items.
+    fn skip_back(&mut self, n: usize) -> usize {
+        let mut left = n;
+
+        while left > 0 && !self.indices.is_empty() {
+            let idx = self.indices.end - 1;
+            let chunk_start = (chunk_idx(idx) * CHUNK_LEN_USIZE).max(self.indices.start);
+
+            if !unsafe { self.enter_chunk(idx) } {
+                self.indices.end = chunk_start;
+                continue;
+            }
+
+            let fetch = &mut self.fetch;
+            let visible = (chunk_start..self.indices.end)
+                .filter(|&idx| unsafe { fetch.visit_item(idx) })
+                .count();
+
+            if visible <= left {
+                left -= visible;
+                self.indices.end = chunk_start;
+                continue;
+            }
+
+            while left > 0 {
+                let idx = self.indices.next_back().unwrap();
+                if unsafe { self.fetch.visit_item(idx) } {
+                    left -= 1;
+                }
+            }
+        }
+
+        n - left
+    }
+
+    #[inline]
+    fn fold<B, Fun>(mut self, init: B, mut f: Fun) -> B
+    where
+        Fun: FnMut(B, F::Item) -> B,
+    {
+        let mut acc = init;
+        while let Some(item) = self.next() {
+            acc = f(acc, item);
+        }
+        acc
+    }
+}
 
 /// Iterator over entities with a query `Q`.
 /// Yields query items for every matching entity.
+///
+/// Can be iterated in reverse order.
+/// [`Iterator::nth`] and [`Iterator::skip`] skip entities without fetching them
+/// and skip whole chunks at once.
 pub struct QueryIter<'a, Q: Query> {
     query: Q,
     epoch: EpochId,
     archetypes_iter: slice::Iter<'a, Archetype>,
-    fetch: Q::Fetch<'a>,
-    indices: Range<usize>,
-    visit_chunk: bool,
+    metrics: &'a MetricsCounters,
+    front: Cursor<'a, Q::Fetch<'a>>,
+    back: Cursor<'a, Q::Fetch<'a>>,
 }
 
 impl<'a, Q> QueryIter<'a, Q>
 where
     Q: Query,
 {
-    pub(crate) fn new(query: Q, epoch: EpochId, archetypes: &'a [Archetype]) -> Self {
+    pub(crate) fn new(
+        query: Q,
+        epoch: EpochId,
+        archetypes: &'a [Archetype],
+        metrics: &'a MetricsCounters,
+    ) -> Self {
         QueryIter {
             query,
             epoch,
             archetypes_iter: archetypes.iter(),
-            fetch: <Q::Fetch<'a>>::dangling(),
-            indices: 0..0,
-            visit_chunk: false,
+            metrics,
+            front: Cursor::dangling(),
+            back: Cursor::dangling(),
+        }
+    }
+
+    fn cursor(
+        query: &mut Q,
+        epoch: EpochId,
+        archetype: &'a Archetype,
+        metrics: &'a MetricsCounters,
+    ) -> Option<Cursor<'a, Q::Fetch<'a>>> {
+        if archetype.is_empty() || !query.visit_archetype(archetype) {
+            return None;
+        }
+
+        let fetch = unsafe { query.fetch(archetype, epoch) };
+        Some(Cursor::new(fetch, archetype.len(), metrics))
+    }
+
+    /// Moves front cursor to the next archetype.
+    /// Returns `false` if there are no more archetypes.
+    fn next_archetype(&mut self) -> bool {
+        for archetype in self.archetypes_iter.by_ref() {
+            if let Some(cursor) = Self::cursor(&mut self.query, self.epoch, archetype, self.metrics)
+            {
+                self.front = cursor;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Moves back cursor to the previous archetype.
+    /// Returns `false` if there are no more archetypes.
+    fn next_archetype_back(&mut self) -> bool {
+        while let Some(archetype) = self.archetypes_iter.next_back() {
+            if let Some(cursor) = Self::cursor(&mut self.query, self.epoch, archetype, self.metrics)
+            {
+                self.back = cursor;
+                return true;
+            }
         }
+        false
     }
 }
 
@@ -42,15 +283,15 @@ where
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let upper = self
-            .archetypes_iter
-            .clone()
-            .fold(self.indices.len(), |acc, archetype| {
+        let upper = self.archetypes_iter.clone().fold(
+            self.front.indices.len() + self.back.indices.len(),
+            |acc, archetype| {
                 if !self.query.visit_archetype(archetype) {
                     return acc;
                 }
                 acc + archetype.len()
-            });
+            },
+        );
 
         (0, Some(upper))
     }
@@ -58,45 +299,28 @@ where
     #[inline]
     fn next(&mut self) -> Option<QueryItem<'a, Q>> {
         loop {
-            match self.indices.next() {
-                None => {
-                    // move to the next archetype.
-                    loop {
-                        let archetype = self.archetypes_iter.next()?;
-
-                        if archetype.is_empty() {
-                            continue;
-                        }
-
-                        if !self.query.visit_archetype(archetype) {
-                            continue;
-                        }
-
-                        self.fetch = unsafe { self.query.fetch(archetype, self.epoch) };
-                        self.indices = 0..archetype.len();
-                        break;
-                    }
-                }
-                Some(idx) => {
-                    if let Some(chunk_idx) = first_of_chunk(idx) {
-                        if !unsafe { self.fetch.visit_chunk(chunk_idx) } {
-                            self.indices.nth(CHUNK_LEN_USIZE - 1);
-                            continue;
-                        }
-                        self.visit_chunk = true;
-                    }
-
-                    if unsafe { self.fetch.visit_item(idx) } {
-                        if self.visit_chunk {
-                            unsafe { self.fetch.touch_chunk(chunk_idx(idx)) }
-                            self.visit_chunk = false;
-                        }
-
-                        let item = unsafe { self.fetch.get_item(idx) };
-
-                        return Some(item);
-                    }
+            if let Some(item) = self.front.next() {
+                return Some(item);
+            }
+            if !self.next_archetype() {
+                return self.back.next();
+            }
+        }
+    }
+
+    fn nth(&mut self, n: usize) -> Option<QueryItem<'a, Q>> {
+        let mut left = n;
+        loop {
+            left -= self.front.skip(left);
+            if left == 0 {
+                return self.next();
+            }
+            if !self.next_archetype() {
+                left -= self.back.skip(left);
+                if left == 0 {
+                    return self.back.next();
                 }
+                return None;
             }
         }
     }
@@ -106,58 +330,277 @@ where
         Self: Sized,
         Fun: FnMut(B, QueryItem<'a, Q>) -> B,
     {
-        let mut acc = init;
-        while let Some(idx) = self.indices.next() {
-            if let Some(chunk_idx) = first_of_chunk(idx) {
-                if !unsafe { self.fetch.visit_chunk(chunk_idx) } {
-                    self.indices.nth(CHUNK_LEN_USIZE - 1);
-                    continue;
-                }
-                self.visit_chunk = true;
+        let mut acc = self.front.fold(init, &mut f);
+
+        for archetype in self.archetypes_iter.by_ref() {
+            if let Some(cursor) = Self::cursor(&mut self.query, self.epoch, archetype, self.metrics)
+            {
+                acc = cursor.fold(acc, &mut f);
             }
+        }
 
-            if unsafe { self.fetch.visit_item(idx) } {
-                if self.visit_chunk {
-                    unsafe { self.fetch.touch_chunk(chunk_idx(idx)) }
-                    self.visit_chunk = false;
-                }
-                let item = unsafe { self.fetch.get_item(idx) };
+        self.back.fold(acc, f)
+    }
+}
 
-                acc = f(acc, item);
+impl<'a, Q> DoubleEndedIterator for QueryIter<'a, Q>
+where
+    Q: Query,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<QueryItem<'a, Q>> {
+        loop {
+            if let Some(item) = self.back.next_back() {
+                return Some(item);
+            }
+            if !self.next_archetype_back() {
+                return self.front.next_back();
             }
         }
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<QueryItem<'a, Q>> {
+        let mut left = n;
+        loop {
+            left -= self.back.skip_back(left);
+            if left == 0 {
+                return self.next_back();
+            }
+            if !self.next_archetype_back() {
+                left -= self.front.skip_back(left);
+                if left == 0 {
+                    return self.front.next_back();
+                }
+                return None;
+            }
+        }
+    }
+}
+
+/// Iterator over entities of single archetype with a query `Q`.
+/// Yields query items for every matching entity in the archetype.
+///
+/// Produced by [`SplitByArchetype`] iterator.
+/// Can be sent to another thread if query items can be.
+pub struct ArchetypeQueryIter<'a, Q: Query> {
+    cursor: Cursor<'a, Q::Fetch<'a>>,
+}
+
+// Safety: Fetch only accesses data of one archetype
+// that is exclusively owned by this iterator,
+// and yields items that are `Send`.
+unsafe impl<'a, Q> Send for ArchetypeQueryIter<'a, Q>
+where
+    Q: Query,
+    Q::Item<'a>: Send,
+{
+}
+
+impl<'a, Q> Iterator for ArchetypeQueryIter<'a, Q>
+where
+    Q: Query,
+{
+    type Item = QueryItem<'a, Q>;
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.cursor.indices.len()))
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<QueryItem<'a, Q>> {
+        self.cursor.next()
+    }
+
+    #[inline]
+    fn nth(&mut self, n: usize) -> Option<QueryItem<'a, Q>> {
+        if self.cursor.skip(n) < n {
+            return None;
+        }
+        self.cursor.next()
+    }
+
+    #[inline]
+    fn fold<B, Fun>(self, init: B, f: Fun) -> B
+    where
+        Self: Sized,
+        Fun: FnMut(B, QueryItem<'a, Q>) -> B,
+    {
+        self.cursor.fold(init, f)
+    }
+}
+
+impl<'a, Q> DoubleEndedIterator for ArchetypeQueryIter<'a, Q>
+where
+    Q: Query,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<QueryItem<'a, Q>> {
+        self.cursor.next_back()
+    }
+
+    #[inline]
+    fn nth_back(&mut self, n: usize) -> Option<QueryItem<'a, Q>> {
+        if self.cursor.skip_back(n) < n {
+            return None;
+        }
+        self.cursor.next_back()
+    }
+}
+
+/// Iterator over matching archetypes of a query.
+/// Yields independent [`ArchetypeQueryIter`] for each non-empty archetype
+/// matched by the query.
+///
+/// Produced by [`QueryRef::split_by_archetype`].
+///
+/// [`QueryRef::split_by_archetype`]: edict::world::QueryRef::split_by_archetype
+pub struct SplitByArchetype<'a, Q: Query> {
+    query: Q,
+    epoch: EpochId,
+    archetypes_iter: slice::Iter<'a, Archetype>,
+    metrics: &'a MetricsCounters,
+}
+
+impl<'a, Q> SplitByArchetype<'a, Q>
+where
+    Q: Query,
+{
+    pub(crate) fn new(
+        query: Q,
+        epoch: EpochId,
+        archetypes: &'a [Archetype],
+        metrics: &'a MetricsCounters,
+    ) -> Self {
+        SplitByArchetype {
+            query,
+            epoch,
+            archetypes_iter: archetypes.iter(),
+            metrics,
+        }
+    }
+}
+
+impl<'a, Q> Iterator for SplitByArchetype<'a, Q>
+where
+    Q: Query,
+{
+    type Item = ArchetypeQueryIter<'a, Q>;
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.archetypes_iter.len()))
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<ArchetypeQueryIter<'a, Q>> {
+        loop {
+            let archetype = self.archetypes_iter.next()?;
 
-        for archetype in self.archetypes_iter.by_ref() {
             if archetype.is_empty() {
                 continue;
             }
+
             if !self.query.visit_archetype(archetype) {
                 continue;
             }
-            let mut fetch = unsafe { self.query.fetch(archetype, self.epoch) };
 
-            let mut indices = 0..archetype.len();
+            let fetch = unsafe { self.query.fetch(archetype, self.epoch) };
+            return Some(ArchetypeQueryIter {
+                cursor: Cursor::new(fetch, archetype.len(), self.metrics),
+            });
+        }
+    }
+}
 
-            while let Some(idx) = indices.next() {
-                if let Some(chunk_idx) = first_of_chunk(idx) {
-                    if !unsafe { fetch.visit_chunk(chunk_idx) } {
-                        self.indices.nth(CHUNK_LEN_USIZE - 1);
-                        continue;
-                    }
-                    self.visit_chunk = true;
-                }
+mod test {
+    #![cfg(test)]
 
-                if unsafe { fetch.visit_item(idx) } {
-                    if self.visit_chunk {
-                        unsafe { fetch.touch_chunk(chunk_idx(idx)) }
-                        self.visit_chunk = false;
-                    }
-                    let item = unsafe { fetch.get_item(idx) };
+    use alloc::vec::Vec;
 
-                    acc = f(acc, item);
-                }
+    use crate::{
+        query::Entities,
+        test::{Bool, U32},
+        world::World,
+    };
+
+    /// Tests that reversed and skipping query iteration match plain iteration.
+    #[test]
+    fn query_iter_rev_skip() {
+        let mut world = World::new();
+
+        for i in 0..600 {
+            world.spawn((U32(i),));
+        }
+        for i in 600..900 {
+            world.spawn((U32(i), Bool(i % 2 == 0)));
+        }
+
+        let all = world
+            .query::<&U32>()
+            .iter()
+            .map(|v| v.0)
+            .collect::<Vec<_>>();
+        assert_eq!(all.len(), 900);
+
+        let rev = world
+            .query::<&U32>()
+            .iter()
+            .rev()
+            .map(|v| v.0)
+            .collect::<Vec<_>>();
+        assert!(rev.iter().eq(all.iter().rev()));
+
+        let pages = [(0, 10), (255, 3), (256, 350), (890, 20), (1000, 1)];
+        for (skip, take) in pages {
+            let page = world
+                .query::<&U32>()
+                .iter()
+                .skip(skip)
+                .take(take)
+                .map(|v| v.0)
+                .collect::<Vec<_>>();
+            assert!(page.iter().eq(all.iter().skip(skip).take(take)));
+
+            let page = world
+                .query::<&U32>()
+                .iter()
+                .rev()
+                .skip(skip)
+                .take(take)
+                .map(|v| v.0)
+                .collect::<Vec<_>>();
+            assert!(page.iter().eq(all.iter().rev().skip(skip).take(take)));
+        }
+
+        let query = world.query::<&U32>();
+        let mut iter = query.iter();
+        let mut front = Vec::new();
+        let mut back = Vec::new();
+        while let Some(v) = iter.nth(99) {
+            front.push(v.0);
+            match iter.nth_back(99) {
+                Some(v) => back.push(v.0),
+                None => break,
             }
         }
-        acc
+        assert_eq!(front, [99, 199, 299, 399, 499]);
+        assert_eq!(back, [800, 700, 600, 500]);
+        drop(query);
+
+        let epoch = world.epoch();
+        for v in world.query::<&mut U32>().iter_mut().skip(300).step_by(100) {
+            v.0 += 1000;
+        }
+
+        let modified = world
+            .query::<Entities>()
+            .modified::<&U32>(epoch)
+            .iter()
+            .rev()
+            .skip(1)
+            .map(|(_, v)| v.0)
+            .collect::<Vec<_>>();
+        assert_eq!(modified, [1700, 1600, 1500, 1400, 1300]);
     }
 }
@@ -0,0 +1,153 @@
+//! Per-query statistics collected with `"metrics"` feature.
+
+#[cfg(feature = "metrics")]
+use core::sync::atomic::{AtomicU64, Ordering};
+
+/// Statistics of a single [`QueryRef`] collected since it was created.
+///
+/// Archetypes, chunks and items are counted by iterators returned from the query,
+/// by [`QueryRef::for_each_slice`] and by closure-based methods
+/// built on [`QueryRef::try_fold`], like [`QueryRef::for_each`].
+/// Other methods, like [`QueryRef::get_one`] and [`QueryRef::for_each_budget`],
+/// are not counted.
+///
+/// Available with `"metrics"` feature.
+///
+/// [`QueryRef`]: crate::world::QueryRef
+/// [`QueryRef::for_each_slice`]: crate::world::QueryRef::for_each_slice
+/// [`QueryRef::try_fold`]: crate::world::QueryRef::try_fold
+/// [`QueryRef::for_each`]: crate::world::QueryRef::for_each
+/// [`QueryRef::get_one`]: crate::world::QueryRef::get_one
+/// [`QueryRef::for_each_budget`]: crate::world::QueryRef::for_each_budget
+#[cfg(feature = "metrics")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct QueryMetrics {
+    /// Number of non-empty archetypes matched by the query
+    /// which rows were visited.
+    pub archetypes_visited: u64,
+
+    /// Number of chunks skipped without visiting their rows,
+    /// e.g. because [`Modified`](super::Modified) filter found no changes in them.
+    pub chunks_skipped: u64,
+
+    /// Number of items yielded.
+    pub items_yielded: u64,
+
+    /// Number of times query failed to acquire borrow locks held by someone else.
+    /// Only asynchronous queries wait for locks, other queries panic instead.
+    pub lock_waits: u64,
+}
+
+/// Counters of [`QueryMetrics`] updated during iteration.
+///
+/// Without `"metrics"` feature this type is zero-sized
+/// and all updates are no-ops.
+pub(crate) struct MetricsCounters {
+    #[cfg(feature = "metrics")]
+    archetypes_visited: AtomicU64,
+    #[cfg(feature = "metrics")]
+    chunks_skipped: AtomicU64,
+    #[cfg(feature = "metrics")]
+    items_yielded: AtomicU64,
+    #[cfg(feature = "metrics")]
+    lock_waits: AtomicU64,
+}
+
+/// Counters for iterators that are not tied to any query.
+pub(crate) static DISCARDED_METRICS: MetricsCounters = MetricsCounters::new();
+
+impl MetricsCounters {
+    #[inline]
+    pub const fn new() -> Self {
+        MetricsCounters {
+            #[cfg(feature = "metrics")]
+            archetypes_visited: AtomicU64::new(0),
+            #[cfg(feature = "metrics")]
+            chunks_skipped: AtomicU64::new(0),
+            #[cfg(feature = "metrics")]
+            items_yielded: AtomicU64::new(0),
+            #[cfg(feature = "metrics")]
+            lock_waits: AtomicU64::new(0),
+        }
+    }
+
+    #[inline(always)]
+    pub fn archetype_visited(&self) {
+        #[cfg(feature = "metrics")]
+        self.archetypes_visited.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline(always)]
+    pub fn chunk_skipped(&self) {
+        #[cfg(feature = "metrics")]
+        self.chunks_skipped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[inline(always)]
+    pub fn items_yielded(&self, count: usize) {
+        #[cfg(feature = "metrics")]
+        self.items_yielded
+            .fetch_add(count as u64, Ordering::Relaxed);
+        #[cfg(not(feature = "metrics"))]
+        let _ = count;
+    }
+
+    #[inline(always)]
+    pub fn lock_waited(&self) {
+        #[cfg(feature = "metrics")]
+        self.lock_waits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "metrics")]
+    pub fn get(&self) -> QueryMetrics {
+        QueryMetrics {
+            archetypes_visited: self.archetypes_visited.load(Ordering::Relaxed),
+            chunks_skipped: self.chunks_skipped.load(Ordering::Relaxed),
+            items_yielded: self.items_yielded.load(Ordering::Relaxed),
+            lock_waits: self.lock_waits.load(Ordering::Relaxed),
+        }
+    }
+}
+
+mod test {
+    #![cfg(all(test, feature = "metrics"))]
+
+    use alloc::vec::Vec;
+
+    use crate::{
+        test::{Str, U32},
+        world::World,
+    };
+
+    #[test]
+    fn query_metrics() {
+        use crate::{archetype::CHUNK_LEN_USIZE, query::Modified};
+
+        let mut world = World::new();
+        let ids = (0..2 * CHUNK_LEN_USIZE)
+            .map(|i| world.spawn((U32(i as u32),)))
+            .collect::<Vec<_>>();
+        world.spawn((U32(0), Str("a")));
+
+        let epoch = world.epoch();
+        world.query_one_mut::<&mut U32>(ids[0]).unwrap().0 = 10;
+
+        let mut query = world.query_with(Modified::<&U32>::new(epoch));
+        assert_eq!(query.iter().count(), 1);
+        query.for_each(|_| {});
+
+        let metrics = query.metrics();
+        assert_eq!(metrics.archetypes_visited, 2);
+        assert_eq!(metrics.chunks_skipped, 2);
+        assert_eq!(metrics.items_yielded, 2);
+        assert_eq!(metrics.lock_waits, 0);
+        drop(query);
+
+        let mut writer = world.query::<&mut U32>();
+        let _ = writer.iter_mut();
+
+        let reader = world.query::<&U32>();
+        assert!(reader.try_ensure_borrow().is_err());
+        assert_eq!(reader.metrics().lock_waits, 1);
+    }
+}
@@ -6,10 +6,15 @@
 //!
 //! [`Query`] trait has a lot of implementations and is composable using tuples.
 
-use core::any::TypeId;
+use core::{any::TypeId, ops::Range};
 
 use crate::{archetype::Archetype, entity::EntityId, epoch::EpochId};
 
+pub(crate) use self::{
+    metrics::{MetricsCounters, DISCARDED_METRICS},
+    validate::validate_query,
+};
+
 pub use self::{
     alt::{Alt, FetchAlt},
     any_of::AnyOf,
@@ -18,43 +23,66 @@ pub use self::{
         Or, Or2, Or3, Or4, Or5, Or6, Or7, Or8, Xor, Xor2, Xor3, Xor4, Xor5, Xor6, Xor7, Xor8,
     },
     borrow::{
-        FetchBorrowAllRead, FetchBorrowAnyRead, FetchBorrowAnyWrite, FetchBorrowOneRead,
-        FetchBorrowOneWrite, QueryBorrowAll, QueryBorrowAny, QueryBorrowOne,
+        BorrowedFrom, FetchBorrowAllFromRead, FetchBorrowAllRead, FetchBorrowAnyRead,
+        FetchBorrowAnyWrite, FetchBorrowOneRead, FetchBorrowOneWrite, QueryBorrowAll,
+        QueryBorrowAllFrom, QueryBorrowAny, QueryBorrowOne,
     },
+    chunks::{ChunkRange, ChunkRangeFetch},
     copied::{copied, Copied, FetchCopied},
     entities::{Entities, EntitiesFetch, EntitiesQuery},
-    fetch::{Fetch, UnitFetch, VerifyFetch},
-    filter::{FilteredFetch, FilteredQuery, Not, With, Without},
-    iter::QueryIter,
+    external::{
+        ExternalRead, ExternalReadFetch, ExternalRow, ExternalStorage, ExternalWrite,
+        ExternalWriteFetch,
+    },
+    fetch::{Fetch, SliceFetch, UnitFetch, VerifyFetch},
+    filter::{with, without, FilteredFetch, FilteredQuery, Not, With, Without},
+    iter::{ArchetypeQueryIter, QueryIter, SplitByArchetype},
     modified::{
-        Modified, ModifiedFetchAlt, ModifiedFetchCopied, ModifiedFetchRead, ModifiedFetchWith,
-        ModifiedFetchWrite,
+        modified, Modified, ModifiedFetchAlt, ModifiedFetchCopied, ModifiedFetchRead,
+        ModifiedFetchWith, ModifiedFetchWrite, ModifiedFilter,
     },
     phantom::{ImmutablePhantomQuery, PhantomQuery},
     read::{read, FetchRead, Read},
+    spawned::{Spawned, SpawnedFetch},
+    stride::{Stride, StrideFetch},
+    tracked::{FetchMut, FetchRef, Mut, Ref, Tracked, TrackedMut},
+    validate::QueryConflict,
+    value_filter::{value_filter, ValueFilter, ValueFilterFetch},
     with_epoch::{EpochOf, FetchEpoch},
     write::{write, FetchWrite, Write},
 };
 
+#[cfg(feature = "metrics")]
+pub use self::metrics::QueryMetrics;
+
 mod alt;
 mod any_of;
 mod boolean;
 mod borrow;
+mod chunks;
 mod copied;
 mod entities;
+mod external;
 mod fetch;
 mod filter;
 mod iter;
+mod metrics;
 mod modified;
 mod option;
 mod phantom;
+pub mod prelude;
 mod read;
+mod spawned;
+mod stride;
+mod tracked;
 mod tuple;
+mod validate;
+mod value_filter;
 mod with_epoch;
 mod write;
 
 /// Specifies kind of access query performs for particular component.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Access {
     /// Cannot be aliased with any other access.
     Write,
@@ -117,6 +145,18 @@ pub unsafe trait Query: IntoQuery<Query = Self> {
     #[must_use]
     unsafe fn fetch<'a>(&mut self, archetype: &'a Archetype, epoch: EpochId) -> Self::Fetch<'a>;
 
+    /// Returns range of chunk indices this query may visit in any archetype.
+    /// Borrow locks are acquired only for these chunks.
+    ///
+    /// # Safety
+    ///
+    /// Fetch must never visit chunks outside of this range.
+    #[must_use]
+    #[inline]
+    fn chunk_range(&self) -> Range<usize> {
+        0..usize::MAX
+    }
+
     /// Returns item for reserved entity if reserved entity satisfies the query.
     /// Otherwise returns `None`.
     #[must_use]
@@ -183,6 +223,10 @@ where
     unsafe fn fetch<'a>(&mut self, archetype: &'a Archetype, epoch: EpochId) -> Self::Fetch<'a> {
         self.query.fetch(archetype, epoch)
     }
+
+    fn chunk_range(&self) -> Range<usize> {
+        self.query.chunk_range()
+    }
 }
 
 unsafe impl<T> ImmutableQuery for MutQuery<'_, T> where T: ImmutableQuery {}
@@ -198,6 +242,11 @@ pub unsafe trait ImmutableQuery: Query {}
 /// Type alias for items returned by the [`Query`] type.
 pub type QueryItem<'a, Q> = <<Q as IntoQuery>::Query as Query>::Item<'a>;
 
+/// Type alias for slices of items returned by the [`Query`] type
+/// with [`SliceFetch`] fetch.
+pub type QuerySlice<'a, Q> =
+    <<<Q as IntoQuery>::Query as Query>::Fetch<'a> as SliceFetch<'a>>::Slice;
+
 /// Merge two optional access values.
 #[inline]
 pub const fn merge_access(lhs: Option<Access>, rhs: Option<Access>) -> Option<Access> {
@@ -209,6 +258,14 @@ pub const fn merge_access(lhs: Option<Access>, rhs: Option<Access>) -> Option<Ac
     }
 }
 
+/// Intersects two chunk ranges.
+#[inline]
+pub fn merge_chunk_range(lhs: Range<usize>, rhs: Range<usize>) -> Range<usize> {
+    let start = lhs.start.max(rhs.start);
+    let end = lhs.end.min(rhs.end);
+    start..end.max(start)
+}
+
 /// Helps to assert that type implements [`Query`] in compile time.
 const fn assert_query<Q: Query>() {}
 
@@ -2,7 +2,7 @@ use core::{any::TypeId, cell::Cell, marker::PhantomData, ptr::NonNull};
 
 use crate::{
     archetype::{chunk_idx, Archetype},
-    epoch::EpochId,
+    epoch::{AtomicEpochId, EpochId},
     query::{
         alt::{Alt, RefMut},
         phantom::PhantomQuery,
@@ -21,7 +21,7 @@ pub struct ModifiedFetchAlt<'a, T> {
     ptr: NonNull<T>,
     entity_epochs: NonNull<EpochId>,
     chunk_epochs: NonNull<Cell<EpochId>>,
-    archetype_epoch: NonNull<Cell<EpochId>>,
+    archetype_epoch: NonNull<AtomicEpochId>,
     marker: PhantomData<&'a mut [T]>,
 }
 
@@ -58,7 +58,7 @@ where
 
     #[inline]
     unsafe fn get_item(&mut self, idx: usize) -> RefMut<'a, T> {
-        let archetype_epoch = &mut *self.archetype_epoch.as_ptr();
+        let archetype_epoch = &*self.archetype_epoch.as_ptr();
         let chunk_epoch = &mut *self.chunk_epochs.as_ptr().add(chunk_idx(idx));
         let entity_epoch = &mut *self.entity_epochs.as_ptr().add(idx);
 
@@ -106,7 +106,7 @@ where
                 debug_assert_eq!(<Alt<T> as PhantomQuery>::visit_archetype(archetype), true);
 
                 debug_assert_eq!(component.id(), TypeId::of::<T>());
-                let data = component.data_mut();
+                let data = component.data();
                 data.epoch.after(self.after_epoch)
             },
         }
@@ -126,10 +126,9 @@ where
         let component = archetype.component(TypeId::of::<T>()).unwrap_unchecked();
         debug_assert_eq!(component.id(), TypeId::of::<T>());
 
-        let data = component.data_mut();
+        let data = component.data_unique();
 
         debug_assert!(data.epoch.after(self.after_epoch));
-        debug_assert!(data.epoch.before(epoch));
 
         ModifiedFetchAlt {
             after_epoch: self.after_epoch,
@@ -137,7 +136,7 @@ where
             ptr: data.ptr.cast(),
             entity_epochs: NonNull::new_unchecked(data.entity_epochs.as_mut_ptr()),
             chunk_epochs: NonNull::new_unchecked(data.chunk_epochs.as_mut_ptr()).cast(),
-            archetype_epoch: NonNull::from(&mut data.epoch).cast(),
+            archetype_epoch: NonNull::from(&data.epoch),
             marker: PhantomData,
         }
     }
@@ -248,7 +247,7 @@ where
         match archetype.component(TypeId::of::<T>()) {
             None => None,
             Some(component) => {
-                let data = component.data_mut();
+                let data = component.data_unique();
 
                 debug_assert!(data.epoch.after(self.after_epoch));
 
@@ -256,11 +255,9 @@ where
                     after_epoch: self.after_epoch,
                     epoch,
                     ptr: data.ptr.cast(),
-                    entity_epochs: NonNull::new_unchecked(
-                        data.entity_epochs.as_ptr() as *mut EpochId
-                    ),
+                    entity_epochs: NonNull::new_unchecked(data.entity_epochs.as_mut_ptr()),
                     chunk_epochs: NonNull::new_unchecked(data.chunk_epochs.as_mut_ptr()).cast(),
-                    archetype_epoch: NonNull::from(&mut data.epoch).cast(),
+                    archetype_epoch: NonNull::from(&data.epoch),
                     marker: PhantomData,
                 })
             }
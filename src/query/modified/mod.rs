@@ -7,7 +7,7 @@ mod write;
 
 use core::marker::PhantomData;
 
-use crate::epoch::EpochId;
+use crate::{epoch::EpochId, query::With};
 
 pub use self::{
     alt::ModifiedFetchAlt, copied::ModifiedFetchCopied, read::ModifiedFetchRead,
@@ -44,6 +44,24 @@ impl<T> Modified<T> {
     }
 }
 
+/// Returns query over component modified after specified epoch.
+///
+/// Same as [`QueryRef::modified`] but produces a value
+/// that can be combined with other queries in a tuple.
+///
+/// [`QueryRef::modified`]: crate::world::QueryRef::modified
+pub fn modified<T>(after_epoch: EpochId) -> Modified<T> {
+    Modified::new(after_epoch)
+}
+
+/// Filter that skips entities with unmodified component `T`.
+///
+/// Yields no item, so it can be used in the filter position
+/// without fetching the component.
+///
+/// See [`QueryRef::filter_modified`](crate::world::QueryRef::filter_modified).
+pub type ModifiedFilter<T> = Modified<With<T>>;
+
 pub struct ModifiedCache<T> {
     after_epoch: EpochId,
     marker: PhantomData<fn() -> T>,
@@ -10,7 +10,7 @@ use crate::{
 
 use super::{Modified, ModifiedCache};
 
-/// [`Fetch`] type for the [`Modified<&T>`] query.
+/// [`Fetch`] type for the [`Modified<With<T>>`] query.
 pub struct ModifiedFetchWith<'a, T> {
     after_epoch: EpochId,
     entity_epochs: NonNull<EpochId>,
@@ -96,7 +96,7 @@ where
                 debug_assert_eq!(<&mut T as PhantomQuery>::visit_archetype(archetype), true);
 
                 debug_assert_eq!(component.id(), TypeId::of::<T>());
-                let data = component.data_mut();
+                let data = component.data();
                 data.epoch.after(self.after_epoch)
             },
         }
@@ -114,7 +114,7 @@ where
         epoch: EpochId,
     ) -> ModifiedFetchWrite<'a, T> {
         let component = archetype.component(TypeId::of::<T>()).unwrap_unchecked();
-        let data = component.data_mut();
+        let data = component.data_unique();
 
         debug_assert!(data.epoch.after(self.after_epoch));
         data.epoch.bump(epoch);
@@ -234,7 +234,7 @@ where
         match archetype.component(TypeId::of::<T>()) {
             None => None,
             Some(component) => {
-                let data = component.data();
+                let data = component.data_unique();
 
                 debug_assert!(data.epoch.after(self.after_epoch));
 
@@ -242,10 +242,8 @@ where
                     after_epoch: self.after_epoch,
                     epoch,
                     ptr: data.ptr.cast(),
-                    entity_epochs: NonNull::new_unchecked(
-                        data.entity_epochs.as_ptr() as *mut EpochId
-                    ),
-                    chunk_epochs: NonNull::new_unchecked(data.chunk_epochs.as_ptr() as *mut EpochId),
+                    entity_epochs: NonNull::new_unchecked(data.entity_epochs.as_mut_ptr()),
+                    chunk_epochs: NonNull::new_unchecked(data.chunk_epochs.as_mut_ptr()),
                     marker: PhantomData,
                 })
             }
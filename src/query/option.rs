@@ -1,8 +1,11 @@
 use core::any::TypeId;
 
-use crate::{archetype::Archetype, epoch::EpochId};
+use crate::{archetype::Archetype, entity::EntityId, epoch::EpochId};
 
-use super::{Access, Fetch, ImmutablePhantomQuery, PhantomQuery};
+use super::{
+    Access, DefaultQuery, Fetch, ImmutablePhantomQuery, ImmutableQuery, IntoQuery, PhantomQuery,
+    Query,
+};
 
 unsafe impl<'a, T> Fetch<'a> for Option<T>
 where
@@ -87,3 +90,119 @@ where
 }
 
 unsafe impl<T> ImmutablePhantomQuery for Option<T> where T: ImmutablePhantomQuery {}
+
+/// Implements [`Query`] for optional tuple of queries.
+/// Yields `None` for entities in archetypes that do not match the whole tuple.
+macro_rules! impl_option_tuple {
+    () => {};
+    ($($a:ident)+) => {
+        impl<$($a),+> IntoQuery for Option<($($a,)+)>
+        where
+            $($a: IntoQuery,)+
+        {
+            type Query = Option<($($a::Query,)+)>;
+
+            #[inline]
+            fn into_query(self) -> Self::Query {
+                self.map(IntoQuery::into_query)
+            }
+        }
+
+        impl<$($a),+> DefaultQuery for Option<($($a,)+)>
+        where
+            $($a: DefaultQuery,)+
+        {
+            #[inline]
+            fn default_query() -> Self::Query {
+                Some(<($($a,)+)>::default_query())
+            }
+        }
+
+        unsafe impl<$($a),+> Query for Option<($($a,)+)>
+        where
+            $($a: Query,)+
+        {
+            type Item<'a> = Option<<($($a,)+) as Query>::Item<'a>>;
+            type Fetch<'a> = Option<<($($a,)+) as Query>::Fetch<'a>>;
+
+            #[inline]
+            fn access(&self, ty: TypeId) -> Option<Access> {
+                match self {
+                    None => None,
+                    Some(query) => query.access(ty),
+                }
+            }
+
+            #[inline]
+            fn visit_archetype(&self, _: &Archetype) -> bool {
+                true
+            }
+
+            #[inline]
+            unsafe fn access_archetype(&self, archetype: &Archetype, f: &dyn Fn(TypeId, Access)) {
+                if let Some(query) = self {
+                    if query.visit_archetype(archetype) {
+                        query.access_archetype(archetype, f)
+                    }
+                }
+            }
+
+            #[inline]
+            unsafe fn fetch<'a>(
+                &mut self,
+                archetype: &'a Archetype,
+                epoch: EpochId,
+            ) -> Self::Fetch<'a> {
+                match self {
+                    Some(query) if query.visit_archetype(archetype) => {
+                        Some(query.fetch(archetype, epoch))
+                    }
+                    _ => None,
+                }
+            }
+
+            #[inline]
+            fn reserved_entity_item<'a>(&self, id: EntityId) -> Option<Self::Item<'a>> {
+                match self {
+                    None => Some(None),
+                    Some(query) => Some(query.reserved_entity_item(id)),
+                }
+            }
+        }
+
+        unsafe impl<$($a),+> ImmutableQuery for Option<($($a,)+)> where $($a: ImmutableQuery,)+ {}
+    };
+}
+
+for_tuple!(impl_option_tuple);
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{
+        query::Entities,
+        test::{Bool, Str, U32},
+        world::World,
+    };
+
+    /// Tests that optional tuple yields `None` unless whole tuple matches.
+    #[test]
+    fn option_tuple() {
+        let mut world = World::new();
+
+        let a = world.spawn((U32(1),));
+        let b = world.spawn((U32(2), Str("b")));
+        let c = world.spawn((U32(3), Str("c"), Bool(true)));
+
+        let query = world.query::<(Entities, &U32, Option<(&Str, &Bool)>)>();
+        for (e, _, opt) in query.iter() {
+            if e == c {
+                assert_eq!(opt, Some((&Str("c"), &Bool(true))));
+            } else {
+                assert!(e == a || e == b);
+                assert_eq!(opt, None);
+            }
+        }
+        assert_eq!(query.iter().count(), 3);
+    }
+}
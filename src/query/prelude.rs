@@ -0,0 +1,39 @@
+//! Query prelude. Reexports query types and functions that construct query values.
+//!
+//! Functions mirror methods of [`QueryRef`] that add sub-queries and filters.
+//! Their results can be combined in tuples,
+//! so whole query is constructed as a single expression.
+//!
+//! [`QueryRef`]: crate::world::QueryRef
+//!
+//! # Example
+//!
+//! ```
+//! # use edict::{component::Component, query::prelude::*, world::World};
+//! #[derive(Component)]
+//! struct Pos(f32);
+//!
+//! #[derive(Component)]
+//! struct Frozen;
+//!
+//! let mut world = World::new();
+//! world.spawn((Pos(0.0),));
+//! world.spawn((Pos(1.0), Frozen));
+//!
+//! let epoch = world.epoch();
+//! let query = (read::<Pos>(), without::<Frozen>());
+//! assert_eq!(world.query_with(query).iter().count(), 1);
+//!
+//! world.query_mut::<&mut Pos>().for_each(|pos| pos.0 += 1.0);
+//! let query = (modified::<&Pos>(epoch), with::<Frozen>());
+//! assert_eq!(world.query_with(query).iter().count(), 1);
+//! ```
+
+#[doc(no_inline)]
+pub use crate::{
+    query::{
+        copied, modified, read, with, without, write, Alt, Entities, ImmutableQuery, IntoQuery,
+        Modified, Not, Query, With, Without,
+    },
+    relation::{related, related_by, relates, relates_to},
+};
@@ -1,13 +1,19 @@
-use core::{any::TypeId, marker::PhantomData, ptr::NonNull};
+use core::{any::TypeId, marker::PhantomData, ops::Range, ptr::NonNull};
 
-use crate::{archetype::Archetype, epoch::EpochId};
+use crate::{
+    archetype::{is_enabled, prefetch, Archetype, CHUNK_LEN_USIZE},
+    epoch::EpochId,
+};
 
-use super::{assert_immutable_query, phantom::PhantomQuery, Access, Fetch, ImmutablePhantomQuery};
+use super::{
+    assert_immutable_query, phantom::PhantomQuery, Access, Fetch, ImmutablePhantomQuery, SliceFetch,
+};
 
 /// [`Fetch`] type for the `&T` query.
 
 pub struct FetchRead<'a, T> {
     ptr: NonNull<T>,
+    disabled: &'a [u64],
     marker: PhantomData<&'a [T]>,
 }
 
@@ -21,16 +27,44 @@ where
     fn dangling() -> Self {
         FetchRead {
             ptr: NonNull::dangling(),
+            disabled: &[],
             marker: PhantomData,
         }
     }
 
+    #[inline]
+    unsafe fn visit_item(&mut self, idx: usize) -> bool {
+        is_enabled(self.disabled, idx)
+    }
+
+    #[inline]
+    fn visits_all(&self) -> bool {
+        self.disabled.is_empty()
+    }
+
+    #[inline]
+    unsafe fn prefetch_chunk(&self, chunk_idx: usize) {
+        prefetch(self.ptr.as_ptr().wrapping_add(chunk_idx * CHUNK_LEN_USIZE));
+    }
+
     #[inline]
     unsafe fn get_item(&mut self, idx: usize) -> &'a T {
         &*self.ptr.as_ptr().add(idx)
     }
 }
 
+unsafe impl<'a, T> SliceFetch<'a> for FetchRead<'a, T>
+where
+    T: Sync + 'a,
+{
+    type Slice = &'a [T];
+
+    #[inline]
+    unsafe fn get_chunk_slice(&mut self, range: Range<usize>) -> &'a [T] {
+        core::slice::from_raw_parts(self.ptr.as_ptr().add(range.start), range.len())
+    }
+}
+
 unsafe impl<T> PhantomQuery for &T
 where
     T: Sync + 'static,
@@ -66,6 +100,7 @@ where
 
         FetchRead {
             ptr: data.ptr.cast(),
+            disabled: &data.disabled,
             marker: PhantomData,
         }
     }
@@ -0,0 +1,170 @@
+use core::{any::TypeId, marker::PhantomData, ptr::NonNull};
+
+use crate::{
+    archetype::Archetype,
+    epoch::EpochId,
+    system::{QueryArg, QueryArgCache, QueryArgGet},
+    world::World,
+};
+
+use super::{Access, Fetch, ImmutableQuery, IntoQuery, Query};
+
+/// Filter that yields only entities spawned after specified epoch.
+///
+/// This is tracking query that uses epoch lower bound to filter out entities
+/// that existed before, similar to [`Modified`](super::Modified).
+#[derive(Clone, Copy, Debug)]
+pub struct Spawned {
+    after_epoch: EpochId,
+}
+
+impl Spawned {
+    /// Creates new `Spawned` query.
+    /// Uses provided `after_epoch` id to skip entities that were spawned not after this epoch.
+    pub fn new(after_epoch: EpochId) -> Self {
+        Spawned { after_epoch }
+    }
+
+    /// Epoch id threshold for this query.
+    pub fn after_epoch(&self) -> EpochId {
+        self.after_epoch
+    }
+}
+
+/// [`Fetch`] type for the [`Spawned`] query.
+pub struct SpawnedFetch<'a> {
+    after_epoch: EpochId,
+    spawn_epochs: NonNull<EpochId>,
+    marker: PhantomData<&'a [EpochId]>,
+}
+
+unsafe impl<'a> Fetch<'a> for SpawnedFetch<'a> {
+    type Item = ();
+
+    #[inline]
+    fn dangling() -> Self {
+        SpawnedFetch {
+            after_epoch: EpochId::start(),
+            spawn_epochs: NonNull::dangling(),
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    unsafe fn visit_item(&mut self, idx: usize) -> bool {
+        let epoch = *self.spawn_epochs.as_ptr().add(idx);
+        epoch.after(self.after_epoch)
+    }
+
+    #[inline]
+    unsafe fn get_item(&mut self, _: usize) {}
+}
+
+impl IntoQuery for Spawned {
+    type Query = Self;
+
+    fn into_query(self) -> Self {
+        self
+    }
+}
+
+unsafe impl Query for Spawned {
+    type Item<'a> = ();
+    type Fetch<'a> = SpawnedFetch<'a>;
+
+    #[inline]
+    fn access(&self, _ty: TypeId) -> Option<Access> {
+        None
+    }
+
+    #[inline]
+    fn visit_archetype(&self, archetype: &Archetype) -> bool {
+        archetype.spawn_epoch().after(self.after_epoch)
+    }
+
+    #[inline]
+    unsafe fn access_archetype(&self, _archetype: &Archetype, _f: &dyn Fn(TypeId, Access)) {}
+
+    #[inline]
+    unsafe fn fetch<'a>(&mut self, archetype: &'a Archetype, _epoch: EpochId) -> SpawnedFetch<'a> {
+        debug_assert!(archetype.spawn_epoch().after(self.after_epoch));
+
+        SpawnedFetch {
+            after_epoch: self.after_epoch,
+            spawn_epochs: NonNull::new_unchecked(archetype.spawn_epochs().as_ptr() as *mut EpochId),
+            marker: PhantomData,
+        }
+    }
+}
+
+unsafe impl ImmutableQuery for Spawned {}
+
+/// Cache for [`Spawned`] query argument of function-systems.
+/// Remembers epoch of the previous run.
+pub struct SpawnedCache {
+    after_epoch: EpochId,
+}
+
+impl<'a> QueryArgGet<'a> for SpawnedCache {
+    type Arg = Spawned;
+    type Query = Spawned;
+
+    #[inline]
+    fn get(&mut self, world: &'a World) -> Spawned {
+        let after_epoch = core::mem::replace(&mut self.after_epoch, world.epoch());
+        Spawned { after_epoch }
+    }
+}
+
+impl QueryArgCache for SpawnedCache {
+    fn new() -> Self {
+        SpawnedCache {
+            after_epoch: EpochId::start(),
+        }
+    }
+
+    fn access_component(&self, _ty: TypeId) -> Option<Access> {
+        None
+    }
+
+    fn visit_archetype(&self, _archetype: &Archetype) -> bool {
+        true
+    }
+}
+
+impl QueryArg for Spawned {
+    type Cache = SpawnedCache;
+}
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::{vec, vec::Vec};
+
+    use crate::{
+        query::Entities,
+        test::{Str, U32},
+        world::World,
+    };
+
+    /// Tests that `Spawned` filter yields only entities spawned after the epoch.
+    #[test]
+    fn spawned_filter() {
+        let mut world = World::new();
+
+        let a = world.spawn((U32(1),));
+        let epoch = world.epoch();
+
+        let b = world.spawn((U32(2),));
+        world.insert(a, Str("a")).unwrap();
+        world.insert(b, Str("b")).unwrap();
+
+        let spawned = world
+            .query::<Entities>()
+            .filter_spawned(epoch)
+            .iter()
+            .collect::<Vec<_>>();
+
+        assert_eq!(spawned, vec![b]);
+    }
+}
@@ -0,0 +1,138 @@
+use core::{any::TypeId, marker::PhantomData, ptr::NonNull};
+
+use crate::{archetype::Archetype, entity::EntityId, epoch::EpochId};
+
+use super::{Access, Fetch, ImmutableQuery, IntoQuery, Query};
+
+/// Filter that yields a subset of entities, roughly one of every `n`.
+///
+/// Entities are split into `n` groups by a hash of their ids
+/// and only the group selected by `phase` is visited.
+/// Group of an entity does not depend on its position in the archetype,
+/// so rotating `phase` each frame visits every entity exactly once every `n` frames,
+/// even if entities are moved or despawned in between.
+#[derive(Clone, Copy, Debug)]
+pub struct Stride {
+    n: u64,
+    phase: u64,
+}
+
+impl Stride {
+    /// Creates new `Stride` filter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    pub fn new(n: u64, phase: u64) -> Self {
+        assert_ne!(n, 0, "Stride must not be zero");
+        Stride {
+            n,
+            phase: phase % n,
+        }
+    }
+
+    /// Returns `true` if entity is selected by this filter.
+    #[inline]
+    pub fn selects(&self, id: EntityId) -> bool {
+        mix(id.bits()) % self.n == self.phase
+    }
+}
+
+/// Bit mixer from splitmix64.
+#[inline]
+fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+/// [`Fetch`] type for the [`Stride`] query.
+pub struct StrideFetch<'a> {
+    stride: Stride,
+    entities: NonNull<EntityId>,
+    marker: PhantomData<&'a [EntityId]>,
+}
+
+unsafe impl<'a> Fetch<'a> for StrideFetch<'a> {
+    type Item = ();
+
+    #[inline]
+    fn dangling() -> Self {
+        StrideFetch {
+            stride: Stride { n: 1, phase: 0 },
+            entities: NonNull::dangling(),
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    unsafe fn visit_item(&mut self, idx: usize) -> bool {
+        let id = *self.entities.as_ptr().add(idx);
+        self.stride.selects(id)
+    }
+
+    #[inline]
+    unsafe fn get_item(&mut self, _: usize) {}
+}
+
+impl IntoQuery for Stride {
+    type Query = Self;
+
+    fn into_query(self) -> Self {
+        self
+    }
+}
+
+unsafe impl Query for Stride {
+    type Item<'a> = ();
+    type Fetch<'a> = StrideFetch<'a>;
+
+    #[inline]
+    fn access(&self, _ty: TypeId) -> Option<Access> {
+        None
+    }
+
+    #[inline]
+    fn visit_archetype(&self, _archetype: &Archetype) -> bool {
+        true
+    }
+
+    #[inline]
+    unsafe fn access_archetype(&self, _archetype: &Archetype, _f: &dyn Fn(TypeId, Access)) {}
+
+    #[inline]
+    unsafe fn fetch<'a>(&mut self, archetype: &'a Archetype, _epoch: EpochId) -> StrideFetch<'a> {
+        StrideFetch {
+            stride: *self,
+            entities: NonNull::new_unchecked(archetype.entities().as_ptr() as *mut EntityId),
+            marker: PhantomData,
+        }
+    }
+}
+
+unsafe impl ImmutableQuery for Stride {}
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::vec::Vec;
+
+    use crate::{query::Entities, test::U32, world::World};
+
+    /// Tests that stride filter visits every entity exactly once over all phases.
+    #[test]
+    fn stride_filter() {
+        let mut world = World::new();
+
+        let mut entities = (0..100).map(|i| world.spawn((U32(i),))).collect::<Vec<_>>();
+
+        let mut visited = Vec::new();
+        for phase in 0..3 {
+            visited.extend(world.query::<Entities>().filter_stride(3, phase).iter());
+        }
+
+        entities.sort_unstable_by_key(|id| id.bits());
+        visited.sort_unstable_by_key(|id| id.bits());
+        assert_eq!(entities, visited);
+    }
+}
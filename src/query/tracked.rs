@@ -0,0 +1,440 @@
+use core::{
+    any::TypeId,
+    cell::Cell,
+    fmt,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    ptr::NonNull,
+};
+
+use crate::{
+    archetype::{chunk_idx, is_enabled, Archetype},
+    epoch::{AtomicEpochId, EpochId},
+};
+
+use super::{phantom::PhantomQuery, Access, Fetch, ImmutablePhantomQuery};
+
+/// Item type that [`Ref`] yields.
+/// Wraps `&T` together with epoch of its last modification.
+pub struct Tracked<'a, T: ?Sized> {
+    component: &'a T,
+    entity_epoch: EpochId,
+}
+
+impl<T> Clone for Tracked<'_, T>
+where
+    T: ?Sized,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Tracked<'_, T> where T: ?Sized {}
+
+impl<T> fmt::Debug for Tracked<'_, T>
+where
+    T: fmt::Debug + ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tracked")
+            .field("component", &self.component)
+            .field("epoch", &self.entity_epoch)
+            .finish()
+    }
+}
+
+impl<'a, T> Tracked<'a, T>
+where
+    T: ?Sized,
+{
+    /// Returns epoch at which the component was last modified.
+    #[inline]
+    pub fn last_modified_epoch(&self) -> EpochId {
+        self.entity_epoch
+    }
+
+    /// Returns `true` if the component was modified after `epoch`.
+    #[inline]
+    pub fn is_modified_since(&self, epoch: EpochId) -> bool {
+        self.entity_epoch.after(epoch)
+    }
+
+    /// Returns wrapped reference.
+    #[inline]
+    pub fn into_inner(self) -> &'a T {
+        self.component
+    }
+}
+
+impl<T> Deref for Tracked<'_, T>
+where
+    T: ?Sized,
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        self.component
+    }
+}
+
+/// Item type that [`Mut`] yields.
+/// Wraps `&mut T` together with epoch of its last modification.
+/// Bumps component epoch on mutable dereference, same as [`RefMut`](super::alt::RefMut).
+pub struct TrackedMut<'a, T: ?Sized> {
+    component: &'a mut T,
+    entity_epoch: &'a mut EpochId,
+    chunk_epoch: &'a Cell<EpochId>,
+    archetype_epoch: &'a AtomicEpochId,
+    epoch: EpochId,
+}
+
+impl<T> fmt::Debug for TrackedMut<'_, T>
+where
+    T: fmt::Debug + ?Sized,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrackedMut")
+            .field("component", &self.component)
+            .field("epoch", &*self.entity_epoch)
+            .finish()
+    }
+}
+
+impl<T> TrackedMut<'_, T>
+where
+    T: ?Sized,
+{
+    /// Returns epoch at which the component was last modified.
+    ///
+    /// Mutable dereference of this wrapper updates the epoch.
+    #[inline]
+    pub fn last_modified_epoch(&self) -> EpochId {
+        *self.entity_epoch
+    }
+
+    /// Returns `true` if the component was modified after `epoch`.
+    #[inline]
+    pub fn is_modified_since(&self, epoch: EpochId) -> bool {
+        self.entity_epoch.after(epoch)
+    }
+}
+
+impl<T> Deref for TrackedMut<'_, T>
+where
+    T: ?Sized,
+{
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &*self.component
+    }
+}
+
+impl<T> DerefMut for TrackedMut<'_, T>
+where
+    T: ?Sized,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        self.entity_epoch.bump_again(self.epoch);
+        EpochId::bump_cell(self.chunk_epoch, self.epoch);
+        self.archetype_epoch.bump_again(self.epoch);
+        self.component
+    }
+}
+
+/// [`Fetch`] type for the [`Ref`] query.
+pub struct FetchRef<'a, T> {
+    ptr: NonNull<T>,
+    disabled: &'a [u64],
+    entity_epochs: &'a [EpochId],
+    marker: PhantomData<&'a [T]>,
+}
+
+unsafe impl<'a, T> Fetch<'a> for FetchRef<'a, T>
+where
+    T: Sync + 'a,
+{
+    type Item = Tracked<'a, T>;
+
+    #[inline]
+    fn dangling() -> Self {
+        FetchRef {
+            ptr: NonNull::dangling(),
+            disabled: &[],
+            entity_epochs: &[],
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    unsafe fn visit_item(&mut self, idx: usize) -> bool {
+        is_enabled(self.disabled, idx)
+    }
+
+    #[inline]
+    unsafe fn get_item(&mut self, idx: usize) -> Tracked<'a, T> {
+        Tracked {
+            component: &*self.ptr.as_ptr().add(idx),
+            entity_epoch: *self.entity_epochs.get_unchecked(idx),
+        }
+    }
+}
+
+phantom_newtype! {
+    /// Query that yields reference to specified component
+    /// wrapped into [`Tracked`] that exposes epoch of its last modification.
+    ///
+    /// Skips entities that don't have the component or have it disabled.
+    ///
+    /// Works as `&T` does, but allows inspecting changes
+    /// of individual items without [`Modified`](super::Modified) filtering.
+    pub struct Ref<T>
+}
+
+impl<T> Ref<T>
+where
+    T: Sync + 'static,
+{
+    /// Creates a new [`Ref`] query.
+    pub fn query() -> PhantomData<fn() -> Self> {
+        PhantomQuery::query()
+    }
+}
+
+unsafe impl<T> PhantomQuery for Ref<T>
+where
+    T: Sync + 'static,
+{
+    type Item<'a> = Tracked<'a, T>;
+    type Fetch<'a> = FetchRef<'a, T>;
+
+    #[inline]
+    fn access(ty: TypeId) -> Option<Access> {
+        if ty == TypeId::of::<T>() {
+            Some(Access::Read)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn visit_archetype(archetype: &Archetype) -> bool {
+        archetype.has_component(TypeId::of::<T>())
+    }
+
+    #[inline]
+    unsafe fn access_archetype(_archetype: &Archetype, f: &dyn Fn(TypeId, Access)) {
+        f(TypeId::of::<T>(), Access::Read)
+    }
+
+    #[inline]
+    unsafe fn fetch<'a>(archetype: &'a Archetype, _epoch: EpochId) -> FetchRef<'a, T> {
+        let component = archetype.component(TypeId::of::<T>()).unwrap_unchecked();
+        debug_assert_eq!(component.id(), TypeId::of::<T>());
+
+        let data = component.data();
+
+        FetchRef {
+            ptr: data.ptr.cast(),
+            disabled: &data.disabled,
+            entity_epochs: &data.entity_epochs,
+            marker: PhantomData,
+        }
+    }
+}
+
+unsafe impl<T> ImmutablePhantomQuery for Ref<T> where T: Sync + 'static {}
+
+/// [`Fetch`] type for the [`Mut`] query.
+pub struct FetchMut<'a, T> {
+    epoch: EpochId,
+    ptr: NonNull<T>,
+    disabled: &'a [u64],
+    entity_epochs: NonNull<EpochId>,
+    chunk_epochs: NonNull<Cell<EpochId>>,
+    archetype_epoch: NonNull<AtomicEpochId>,
+    marker: PhantomData<&'a mut [T]>,
+}
+
+unsafe impl<'a, T> Fetch<'a> for FetchMut<'a, T>
+where
+    T: Send + 'a,
+{
+    type Item = TrackedMut<'a, T>;
+
+    #[inline]
+    fn dangling() -> Self {
+        FetchMut {
+            epoch: EpochId::start(),
+            ptr: NonNull::dangling(),
+            disabled: &[],
+            entity_epochs: NonNull::dangling(),
+            chunk_epochs: NonNull::dangling(),
+            archetype_epoch: NonNull::dangling(),
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    unsafe fn visit_item(&mut self, idx: usize) -> bool {
+        is_enabled(self.disabled, idx)
+    }
+
+    #[inline]
+    unsafe fn get_item(&mut self, idx: usize) -> TrackedMut<'a, T> {
+        let archetype_epoch = &*self.archetype_epoch.as_ptr();
+        let chunk_epoch = &*self.chunk_epochs.as_ptr().add(chunk_idx(idx));
+        let entity_epoch = &mut *self.entity_epochs.as_ptr().add(idx);
+
+        TrackedMut {
+            component: &mut *self.ptr.as_ptr().add(idx),
+            entity_epoch,
+            chunk_epoch,
+            archetype_epoch,
+            epoch: self.epoch,
+        }
+    }
+}
+
+phantom_newtype! {
+    /// Query that yields mutable reference to specified component
+    /// wrapped into [`TrackedMut`] that exposes epoch of its last modification.
+    ///
+    /// Skips entities that don't have the component or have it disabled.
+    ///
+    /// Works as [`Alt`](super::Alt) does,
+    /// entity epoch is not updated unless returned wrapper is mutably dereferenced.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, query::{Mut, Ref}, world::World};
+    /// #[derive(Component)]
+    /// struct Health(u32);
+    ///
+    /// let mut world = World::new();
+    /// let e = world.spawn((Health(10),));
+    /// let epoch = world.epoch();
+    ///
+    /// world.query_mut::<Mut<Health>>().for_each(|mut health| {
+    ///     assert!(!health.is_modified_since(epoch));
+    ///     if health.0 > 5 {
+    ///         health.0 -= 5;
+    ///     }
+    ///     assert!(health.is_modified_since(epoch));
+    /// });
+    ///
+    /// let health = world.query_one_mut::<Ref<Health>>(e).unwrap();
+    /// assert_eq!(health.0, 5);
+    /// assert!(health.is_modified_since(epoch));
+    /// ```
+    pub struct Mut<T>
+}
+
+impl<T> Mut<T>
+where
+    T: Send + 'static,
+{
+    /// Creates a new [`Mut`] query.
+    pub fn query() -> PhantomData<fn() -> Self> {
+        PhantomQuery::query()
+    }
+}
+
+unsafe impl<T> PhantomQuery for Mut<T>
+where
+    T: Send + 'static,
+{
+    type Item<'a> = TrackedMut<'a, T>;
+    type Fetch<'a> = FetchMut<'a, T>;
+
+    #[inline]
+    fn access(ty: TypeId) -> Option<Access> {
+        if ty == TypeId::of::<T>() {
+            Some(Access::Write)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn visit_archetype(archetype: &Archetype) -> bool {
+        archetype.has_component(TypeId::of::<T>())
+    }
+
+    #[inline]
+    unsafe fn access_archetype(_archetype: &Archetype, f: &dyn Fn(TypeId, Access)) {
+        f(TypeId::of::<T>(), Access::Write)
+    }
+
+    #[inline]
+    unsafe fn fetch<'a>(archetype: &'a Archetype, epoch: EpochId) -> FetchMut<'a, T> {
+        let component = archetype.component(TypeId::of::<T>()).unwrap_unchecked();
+        debug_assert_eq!(component.id(), TypeId::of::<T>());
+
+        let data = component.data_unique();
+
+        FetchMut {
+            epoch,
+            ptr: data.ptr.cast(),
+            disabled: &data.disabled,
+            entity_epochs: NonNull::new_unchecked(data.entity_epochs.as_mut_ptr()),
+            chunk_epochs: NonNull::new_unchecked(data.chunk_epochs.as_mut_ptr()).cast(),
+            archetype_epoch: NonNull::from(&data.epoch),
+            marker: PhantomData,
+        }
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{test::U32, world::World};
+
+    #[test]
+    fn tracked_queries() {
+        use crate::query::{Mut, Ref};
+
+        let mut world = World::new();
+        let a = world.spawn((U32(1),));
+        let b = world.spawn((U32(2),));
+
+        let epoch = world.epoch();
+
+        // Mutable query without mutable dereference does not modify components.
+        world.query_mut::<Mut<U32>>().for_each(|u| {
+            assert!(!u.is_modified_since(epoch));
+        });
+        let modified = world
+            .query_with(crate::query::Modified::<&U32>::new(epoch))
+            .iter()
+            .count();
+        assert_eq!(modified, 0);
+
+        let mut u = world.query_one_mut::<Mut<U32>>(b).unwrap();
+        u.0 += 10;
+        assert!(u.is_modified_since(epoch));
+
+        let a_epoch = world
+            .query_one_mut::<Ref<U32>>(a)
+            .unwrap()
+            .last_modified_epoch();
+        assert!(!a_epoch.after(epoch));
+
+        let u = world.query_one_mut::<Ref<U32>>(b).unwrap();
+        assert_eq!(u.0, 12);
+        assert!(u.is_modified_since(epoch));
+        assert!(!u.is_modified_since(u.last_modified_epoch()));
+
+        world.set_enabled::<U32>(a, false).unwrap();
+        assert_eq!(world.query::<Ref<U32>>().iter().count(), 1);
+        let mut count = 0;
+        world.query_mut::<Mut<U32>>().for_each(|_| count += 1);
+        assert_eq!(count, 1);
+    }
+}
@@ -1,8 +1,11 @@
-use core::any::TypeId;
+use core::{any::TypeId, ops::Range};
 
 use crate::{archetype::Archetype, entity::EntityId, epoch::EpochId};
 
-use super::{fetch::Fetch, merge_access, Access, DefaultQuery, ImmutableQuery, IntoQuery, Query};
+use super::{
+    fetch::{Fetch, SliceFetch},
+    merge_access, merge_chunk_range, Access, DefaultQuery, ImmutableQuery, IntoQuery, Query,
+};
 
 macro_rules! impl_fetch {
     () => {
@@ -12,10 +15,22 @@ macro_rules! impl_fetch {
             #[inline]
             fn dangling() {}
 
+            #[inline]
+            fn visits_all(&self) -> bool {
+                true
+            }
+
             #[inline]
             unsafe fn get_item(&mut self, _: usize) {}
         }
 
+        unsafe impl SliceFetch<'_> for () {
+            type Slice = ();
+
+            #[inline]
+            unsafe fn get_chunk_slice(&mut self, _: Range<usize>) {}
+        }
+
         impl IntoQuery for () {
             type Query = ();
 
@@ -81,6 +96,18 @@ macro_rules! impl_fetch {
                 $($a.visit_item(idx) &&)+ true
             }
 
+            #[inline]
+            fn visits_all(&self) -> bool {
+                let ($($a,)+) = self;
+                $($a.visits_all() &&)+ true
+            }
+
+            #[inline]
+            unsafe fn prefetch_chunk(&self, chunk_idx: usize) {
+                let ($($a,)+) = self;
+                $($a.prefetch_chunk(chunk_idx);)+
+            }
+
             /// Notifies this fetch that it visits a chunk.
             #[inline]
             unsafe fn touch_chunk(&mut self, chunk_idx: usize) {
@@ -95,6 +122,20 @@ macro_rules! impl_fetch {
             }
         }
 
+        #[allow(unused_parens)]
+        #[allow(non_snake_case)]
+        unsafe impl<'a $(, $a)+> SliceFetch<'a> for ($($a,)+)
+        where $($a: SliceFetch<'a>,)+
+        {
+            type Slice = ($($a::Slice),+);
+
+            #[inline]
+            unsafe fn get_chunk_slice(&mut self, range: Range<usize>) -> ($($a::Slice),+) {
+                let ($($a,)+) = self;
+                ($( $a.get_chunk_slice(range.clone()) ),+)
+            }
+        }
+
         #[allow(non_snake_case)]
         #[allow(unused_parens)]
         unsafe impl<$($a),+> Query for ($($a,)+) where $($a: Query,)+ {
@@ -127,6 +168,14 @@ macro_rules! impl_fetch {
                 ($( <$a as Query>::fetch($a, archetype, epoch) ),+)
             }
 
+            #[inline]
+            fn chunk_range(&self) -> Range<usize> {
+                let ($($a,)+) = self;
+                let range = 0..usize::MAX;
+                $( let range = merge_chunk_range(range, $a.chunk_range()); )+
+                range
+            }
+
             #[inline]
             fn reserved_entity_item<'a>(&self, id: EntityId) -> Option<($($a::Item<'a>),+)> {
                 let ($($a,)+) = self;
@@ -0,0 +1,94 @@
+use alloc::vec::Vec;
+use core::{any::TypeId, cell::RefCell, fmt};
+
+use crate::archetype::Archetype;
+
+use super::{Access, Query};
+
+/// Error returned when query accesses the same component
+/// in conflicting ways, for example `(&mut T, &T)`.
+///
+/// Such query would fail to borrow components at runtime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct QueryConflict {
+    component: &'static str,
+    first: Access,
+    second: Access,
+}
+
+impl QueryConflict {
+    /// Returns name of the component that is accessed in conflicting ways.
+    #[inline]
+    pub fn component(&self) -> &'static str {
+        self.component
+    }
+}
+
+impl fmt::Display for QueryConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = |access| match access {
+            Access::Read => "immutably",
+            Access::Write => "mutably",
+        };
+
+        write!(
+            f,
+            "Query accesses component `{}` {} and {} at the same time",
+            self.component,
+            kind(self.first),
+            kind(self.second)
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for QueryConflict {}
+
+/// Checks that query does not access any component in conflicting ways
+/// in any of the archetypes it visits.
+pub(crate) fn validate_query(
+    query: &impl Query,
+    archetypes: &[Archetype],
+) -> Result<(), QueryConflict> {
+    let accesses = RefCell::new(Vec::<(TypeId, Access)>::new());
+    let conflict = RefCell::new(None);
+
+    for archetype in archetypes {
+        if !query.visit_archetype(archetype) {
+            continue;
+        }
+
+        accesses.borrow_mut().clear();
+
+        unsafe {
+            query.access_archetype(archetype, &|id, access| {
+                let mut accesses = accesses.borrow_mut();
+                let mut conflict = conflict.borrow_mut();
+
+                if conflict.is_some() {
+                    return;
+                }
+
+                if let Some(&(_, first)) = accesses.iter().find(|(other, first)| {
+                    *other == id
+                        && (matches!(first, Access::Write) || matches!(access, Access::Write))
+                }) {
+                    let component = archetype.component(id).unwrap_unchecked();
+                    *conflict = Some(QueryConflict {
+                        component: component.name(),
+                        first,
+                        second: access,
+                    });
+                }
+
+                accesses.push((id, access));
+            });
+        }
+
+        if let Some(conflict) = conflict.take() {
+            return Err(conflict);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,242 @@
+use core::{any::TypeId, fmt, marker::PhantomData, ptr::NonNull};
+
+use crate::{
+    archetype::{is_enabled, Archetype},
+    epoch::EpochId,
+};
+
+use super::{Access, Fetch, ImmutableQuery, IntoQuery, Query};
+
+/// [`Fetch`] type for the [`ValueFilter`] query.
+pub struct ValueFilterFetch<'a, T, P> {
+    ptr: NonNull<T>,
+    disabled: &'a [u64],
+
+    /// `None` only for dangling fetch.
+    predicate: Option<P>,
+}
+
+unsafe impl<'a, T, P> Fetch<'a> for ValueFilterFetch<'a, T, P>
+where
+    T: Sync + 'a,
+    P: Fn(&T) -> bool + 'a,
+{
+    type Item = ();
+
+    #[inline]
+    fn dangling() -> Self {
+        ValueFilterFetch {
+            ptr: NonNull::dangling(),
+            disabled: &[],
+            predicate: None,
+        }
+    }
+
+    #[inline]
+    unsafe fn visit_item(&mut self, idx: usize) -> bool {
+        // Dangling fetch is never used.
+        let predicate = self.predicate.as_ref().unwrap_unchecked();
+        is_enabled(self.disabled, idx) && predicate(&*self.ptr.as_ptr().add(idx))
+    }
+
+    #[inline]
+    unsafe fn get_item(&mut self, _: usize) {}
+}
+
+/// Filter that skips entities where value of component `T`
+/// does not satisfy the predicate.
+///
+/// Predicate is evaluated for each entity while query skips items,
+/// so rejected entities are not fetched by the rest of the query
+/// and closures receive only matching items.
+/// Entities with disabled component `T` are skipped.
+///
+/// Predicate is evaluated again each time query is iterated,
+/// chunks are not pruned based on previous results.
+///
+/// # Example
+///
+/// ```
+/// # use edict::{component::Component, query::{Entities, ValueFilter}, world::World};
+/// #[derive(Component)]
+/// struct Health(u32);
+///
+/// let mut world = World::new();
+/// let weak = world.spawn((Health(5),));
+/// world.spawn((Health(50),));
+///
+/// let low = world
+///     .query::<Entities>()
+///     .filter(ValueFilter::new(|h: &Health| h.0 < 10))
+///     .iter()
+///     .collect::<Vec<_>>();
+///
+/// assert_eq!(low, [weak]);
+/// ```
+pub struct ValueFilter<T, P> {
+    predicate: P,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T, P> Clone for ValueFilter<T, P>
+where
+    P: Clone,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        ValueFilter {
+            predicate: self.predicate.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, P> Copy for ValueFilter<T, P> where P: Copy {}
+
+impl<T, P> fmt::Debug for ValueFilter<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValueFilter")
+            .field("component", &core::any::type_name::<T>())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, P> ValueFilter<T, P>
+where
+    T: Sync + 'static,
+    P: Fn(&T) -> bool + Clone + 'static,
+{
+    /// Creates new filter with the predicate.
+    #[inline]
+    pub fn new(predicate: P) -> Self {
+        ValueFilter {
+            predicate,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Returns filter that skips entities where value of component `T`
+/// does not satisfy the predicate.
+///
+/// See [`ValueFilter`].
+#[inline]
+pub fn value_filter<T, P>(predicate: P) -> ValueFilter<T, P>
+where
+    T: Sync + 'static,
+    P: Fn(&T) -> bool + Clone + 'static,
+{
+    ValueFilter::new(predicate)
+}
+
+impl<T, P> IntoQuery for ValueFilter<T, P>
+where
+    T: Sync + 'static,
+    P: Fn(&T) -> bool + Clone + 'static,
+{
+    type Query = Self;
+
+    #[inline]
+    fn into_query(self) -> Self {
+        self
+    }
+}
+
+unsafe impl<T, P> Query for ValueFilter<T, P>
+where
+    T: Sync + 'static,
+    P: Fn(&T) -> bool + Clone + 'static,
+{
+    type Item<'a> = ();
+    type Fetch<'a> = ValueFilterFetch<'a, T, P>;
+
+    #[inline]
+    fn access(&self, ty: TypeId) -> Option<Access> {
+        if ty == TypeId::of::<T>() {
+            Some(Access::Read)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn visit_archetype(&self, archetype: &Archetype) -> bool {
+        archetype.has_component(TypeId::of::<T>())
+    }
+
+    #[inline]
+    unsafe fn access_archetype(&self, _archetype: &Archetype, f: &dyn Fn(TypeId, Access)) {
+        f(TypeId::of::<T>(), Access::Read)
+    }
+
+    #[inline]
+    unsafe fn fetch<'a>(
+        &mut self,
+        archetype: &'a Archetype,
+        _epoch: EpochId,
+    ) -> ValueFilterFetch<'a, T, P> {
+        let component = archetype.component(TypeId::of::<T>()).unwrap_unchecked();
+        debug_assert_eq!(component.id(), TypeId::of::<T>());
+
+        let data = component.data();
+
+        ValueFilterFetch {
+            ptr: data.ptr.cast(),
+            disabled: &data.disabled,
+            predicate: Some(self.predicate.clone()),
+        }
+    }
+}
+
+unsafe impl<T, P> ImmutableQuery for ValueFilter<T, P>
+where
+    T: Sync + 'static,
+    P: Fn(&T) -> bool + Clone + 'static,
+{
+}
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::vec::Vec;
+
+    use crate::{
+        query::Entities,
+        test::{Str, U32},
+        world::World,
+    };
+
+    #[test]
+    fn value_filter() {
+        use crate::query::{value_filter, ValueFilter};
+
+        let mut world = World::new();
+        let a = world.spawn((U32(1),));
+        world.spawn((U32(20), Str("b")));
+        let c = world.spawn((U32(3), Str("c")));
+
+        let mut low = world
+            .query::<Entities>()
+            .filter(ValueFilter::new(|u: &U32| u.0 < 10))
+            .iter()
+            .collect::<Vec<_>>();
+        low.sort();
+        assert_eq!(low, [a, c]);
+
+        let threshold = 10;
+        let mut names = Vec::new();
+        world
+            .query::<&Str>()
+            .filter(value_filter(move |u: &U32| u.0 > threshold))
+            .for_each(|s| names.push(s.0));
+        assert_eq!(names, ["b"]);
+
+        world.set_enabled::<U32>(a, false).unwrap();
+        let count = world
+            .query::<Entities>()
+            .filter(ValueFilter::new(|u: &U32| u.0 < 10))
+            .iter()
+            .count();
+        assert_eq!(count, 1);
+    }
+}
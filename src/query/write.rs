@@ -1,12 +1,16 @@
-use core::{any::TypeId, marker::PhantomData, ptr::NonNull};
+use core::{any::TypeId, marker::PhantomData, ops::Range, ptr::NonNull};
 
-use crate::{archetype::Archetype, epoch::EpochId};
+use crate::{
+    archetype::{is_enabled, prefetch, Archetype, CHUNK_LEN_USIZE},
+    epoch::EpochId,
+};
 
-use super::{assert_query, phantom::PhantomQuery, Access, Fetch};
+use super::{assert_query, phantom::PhantomQuery, Access, Fetch, SliceFetch};
 
 /// [`Fetch`] type for the `&mut T` query.
 pub struct FetchWrite<'a, T> {
     ptr: NonNull<T>,
+    disabled: &'a [u64],
     entity_epochs: NonNull<EpochId>,
     chunk_epochs: NonNull<EpochId>,
     epoch: EpochId,
@@ -23,6 +27,7 @@ where
     fn dangling() -> Self {
         FetchWrite {
             ptr: NonNull::dangling(),
+            disabled: &[],
             entity_epochs: NonNull::dangling(),
             chunk_epochs: NonNull::dangling(),
             epoch: EpochId::start(),
@@ -36,6 +41,21 @@ where
         chunk_epoch.bump(self.epoch);
     }
 
+    #[inline]
+    unsafe fn visit_item(&mut self, idx: usize) -> bool {
+        is_enabled(self.disabled, idx)
+    }
+
+    #[inline]
+    fn visits_all(&self) -> bool {
+        self.disabled.is_empty()
+    }
+
+    #[inline]
+    unsafe fn prefetch_chunk(&self, chunk_idx: usize) {
+        prefetch(self.ptr.as_ptr().wrapping_add(chunk_idx * CHUNK_LEN_USIZE));
+    }
+
     #[inline]
     unsafe fn get_item(&mut self, idx: usize) -> &'a mut T {
         let entity_epoch = &mut *self.entity_epochs.as_ptr().add(idx);
@@ -45,6 +65,23 @@ where
     }
 }
 
+unsafe impl<'a, T> SliceFetch<'a> for FetchWrite<'a, T>
+where
+    T: Send + 'a,
+{
+    type Slice = &'a mut [T];
+
+    #[inline]
+    unsafe fn get_chunk_slice(&mut self, range: Range<usize>) -> &'a mut [T] {
+        for idx in range.clone() {
+            let entity_epoch = &mut *self.entity_epochs.as_ptr().add(idx);
+            entity_epoch.bump(self.epoch);
+        }
+
+        core::slice::from_raw_parts_mut(self.ptr.as_ptr().add(range.start), range.len())
+    }
+}
+
 unsafe impl<T> PhantomQuery for &mut T
 where
     T: Send + 'static,
@@ -76,11 +113,12 @@ where
         let component = archetype.component(TypeId::of::<T>()).unwrap_unchecked();
         debug_assert_eq!(component.id(), TypeId::of::<T>());
 
-        let data = component.data_mut();
+        let data = component.data_unique();
         data.epoch.bump(epoch);
 
         FetchWrite {
             ptr: data.ptr.cast(),
+            disabled: &data.disabled,
             entity_epochs: NonNull::new_unchecked(data.entity_epochs.as_mut_ptr()),
             chunk_epochs: NonNull::new_unchecked(data.chunk_epochs.as_mut_ptr()),
             epoch,
@@ -12,11 +12,42 @@ use alloc::{vec, vec::Vec};
 
 use crate::{
     action::ActionEncoder,
-    borrow_dyn_trait,
     component::{Component, ComponentBorrow},
     entity::EntityId,
+    world::RelationEvent,
 };
 
+/// Derives [`Relation`] trait.
+///
+/// Behavior is configured with `#[edict(...)]` attribute:
+///
+/// * `exclusive` - sets [`Relation::EXCLUSIVE`].
+/// * `symmetric` - sets [`Relation::SYMMETRIC`].
+/// * `owned` or `cascade` - sets [`Relation::OWNED`],
+///   so origin is despawned when its last target is despawned.
+/// * `acyclic`, `exclusive_target` and `max_targets = N` - set constraints
+///   checked by [`World::validate_relation`](crate::world::World::validate_relation).
+/// * `name = "..."` - overrides [`Relation::name`].
+/// * `on_drop = f`, `on_replace = f` and `on_target_drop = f` - implement hooks
+///   by calling `f` with the same arguments.
+/// * `where ...` - adds predicates to the generated impl.
+///
+/// # Example
+///
+/// ```
+/// # use edict::{relation::Relation, world::World};
+/// #[derive(Clone, Copy, Relation)]
+/// #[edict(exclusive, cascade)]
+/// struct AttachedTo;
+///
+/// let mut world = World::new();
+/// let parent = world.spawn(());
+/// let child = world.spawn(());
+/// world.add_relation(child, AttachedTo, parent).unwrap();
+///
+/// world.despawn(parent).unwrap();
+/// assert!(!world.is_alive(child));
+/// ```
 pub use edict_proc::Relation;
 
 pub use self::{
@@ -24,9 +55,11 @@ pub use self::{
     query::{
         related, related_by, relates, relates_to, FetchFilterRelatedBy, FetchRelated,
         FetchRelatesExclusiveRead, FetchRelatesExclusiveWrite, FetchRelatesRead,
-        FetchRelatesToRead, FetchRelatesToWrite, FetchRelatesWrite, FilterFetchRelationTo,
-        FilterRelated, FilterRelatedBy, FilterRelates, FilterRelatesTo, Related, Relates,
-        RelatesExclusive, RelatesReadIter, RelatesTo, RelatesWriteIter,
+        FetchRelatesTarget, FetchRelatesToAnyRead, FetchRelatesToAnyWrite, FetchRelatesToRead,
+        FetchRelatesToWrite, FetchRelatesWrite, FilterFetchRelationTo, FilterRelated,
+        FilterRelatedBy, FilterRelates, FilterRelatesTo, ModifiedFetchRelatesRead, Related,
+        Relates, RelatesExclusive, RelatesReadIter, RelatesTarget, RelatesTo, RelatesToAny,
+        RelatesWriteIter,
     },
 };
 
@@ -46,6 +79,28 @@ pub trait Relation: Send + Sync + Copy + 'static {
     /// This means that when last target is dropped, entity is also dropped, not just relation.
     const OWNED: bool = false;
 
+    /// If `true` then relations of this type must not form cycles.
+    /// Ignored for symmetric relations.
+    ///
+    /// Checked by [`World::validate_relation`].
+    ///
+    /// [`World::validate_relation`]: crate::world::World::validate_relation
+    const ACYCLIC: bool = false;
+
+    /// Maximum number of targets of a single origin.
+    ///
+    /// Checked by [`World::validate_relation`].
+    ///
+    /// [`World::validate_relation`]: crate::world::World::validate_relation
+    const MAX_TARGETS: usize = usize::MAX;
+
+    /// If `true` then target can be related by only one origin.
+    ///
+    /// Checked by [`World::validate_relation`].
+    ///
+    /// [`World::validate_relation`]: crate::world::World::validate_relation
+    const EXCLUSIVE_TARGET: bool = false;
+
     /// Returns name of the relation type.
     #[inline]
     #[must_use]
@@ -98,12 +153,38 @@ pub trait Relation: Send + Sync + Copy + 'static {
     }
 }
 
-pub(crate) struct Origin<R> {
-    pub target: EntityId,
-    pub relation: R,
+/// Relation stored on the origin entity.
+///
+/// Returned by [`OriginComponent::origins`].
+pub struct Origin<R> {
+    pub(crate) target: EntityId,
+    pub(crate) relation: R,
+}
+
+impl<R> Origin<R> {
+    /// Returns target entity of the relation.
+    #[inline]
+    #[must_use]
+    pub fn target(&self) -> EntityId {
+        self.target
+    }
+
+    /// Returns relation value.
+    #[inline]
+    #[must_use]
+    pub fn relation(&self) -> &R {
+        &self.relation
+    }
 }
 
-pub(crate) union OriginComponent<R: Relation> {
+/// Component that is added to origin entity of the relation.
+///
+/// It is managed by the [`World`] when relations are added and removed.
+/// It can be fetched with `&OriginComponent<R>` query
+/// to implement custom relation queries.
+///
+/// [`World`]: crate::world::World
+pub union OriginComponent<R: Relation> {
     exclusive: ManuallyDrop<Origin<R>>,
     non_exclusive: ManuallyDrop<Vec<Origin<R>>>,
 }
@@ -125,7 +206,7 @@ where
     R: Relation,
 {
     #[must_use]
-    pub fn new(target: EntityId, relation: R) -> Self {
+    pub(crate) fn new(target: EntityId, relation: R) -> Self {
         match R::EXCLUSIVE {
             false => OriginComponent {
                 non_exclusive: ManuallyDrop::new(vec![Origin { target, relation }]),
@@ -136,7 +217,13 @@ where
         }
     }
 
-    pub fn add(&mut self, id: EntityId, target: EntityId, relation: R, encoder: ActionEncoder) {
+    pub(crate) fn add(
+        &mut self,
+        id: EntityId,
+        target: EntityId,
+        relation: R,
+        encoder: ActionEncoder,
+    ) {
         match R::EXCLUSIVE {
             false => {
                 let origins = unsafe { &mut *self.non_exclusive };
@@ -155,7 +242,7 @@ where
         }
     }
 
-    pub fn remove_relation(
+    pub(crate) fn remove_relation(
         &mut self,
         id: EntityId,
         target: EntityId,
@@ -167,6 +254,7 @@ where
                 for idx in 0..origins.len() {
                     if origins[idx].target == target {
                         let origin = origins.swap_remove(idx);
+                        Self::emit_removed(id, target, encoder.reborrow());
                         if origins.is_empty() {
                             encoder.drop::<Self>(id);
                         }
@@ -186,6 +274,35 @@ where
         }
     }
 
+    /// Reserves capacity for at least `additional` more relations.
+    /// Does nothing for exclusive relations.
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        if !R::EXCLUSIVE {
+            unsafe { &mut *self.non_exclusive }.reserve(additional);
+        }
+    }
+
+    /// Returns relations of the origin entity.
+    ///
+    /// Exclusive relations always have exactly one element.
+    /// For symmetric relations targets are other ends of the relation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{relation::{ChildOf, OriginComponent, TargetComponent}, world::World};
+    /// let mut world = World::new();
+    /// let parent = world.spawn(());
+    /// let child = world.spawn(());
+    /// world.add_relation(child, ChildOf, parent).unwrap();
+    ///
+    /// let origin = world.query_one_mut::<&OriginComponent<ChildOf>>(child).unwrap();
+    /// assert_eq!(origin.origins().len(), 1);
+    /// assert_eq!(origin.origins()[0].target(), parent);
+    ///
+    /// let target = world.query_one_mut::<&TargetComponent<ChildOf>>(parent).unwrap();
+    /// assert_eq!(target.origins(), &[child]);
+    /// ```
     #[must_use]
     pub fn origins(&self) -> &[Origin<R>] {
         match R::EXCLUSIVE {
@@ -195,7 +312,7 @@ where
     }
 
     #[must_use]
-    pub fn origins_mut(&mut self) -> &mut [Origin<R>] {
+    pub(crate) fn origins_mut(&mut self) -> &mut [Origin<R>] {
         match R::EXCLUSIVE {
             false => unsafe { &mut *self.non_exclusive },
             true => core::slice::from_mut(unsafe { &mut *self.exclusive }),
@@ -222,6 +339,7 @@ where
                     .relation
                     .on_drop(id, target, encoder.reborrow());
                 origins.swap_remove(idx);
+                Self::emit_removed(id, target, encoder.reborrow());
                 break;
             }
         }
@@ -243,6 +361,7 @@ where
             // This is also a target.
             R::on_target_drop(origin.target, id, encoder.reborrow());
         }
+        Self::emit_removed(id, origin.target, encoder.reborrow());
         Self::clear_one(origin, id, encoder);
     }
 
@@ -265,11 +384,19 @@ where
                 .on_drop(id, origin.target, encoder.reborrow());
         }
         if new_origin.target != origin.target {
+            Self::emit_removed(id, origin.target, encoder.reborrow());
             Self::clear_one(origin, id, encoder);
         }
         *origin = new_origin;
     }
 
+    /// Reports removal of the relation to readers of relation events.
+    fn emit_removed(id: EntityId, target: EntityId, mut encoder: ActionEncoder) {
+        encoder.closure(move |world| {
+            world.emit_relation_event::<R>(RelationEvent::Removed { origin: id, target })
+        });
+    }
+
     fn clear_one(origin: &mut Origin<R>, id: EntityId, mut encoder: ActionEncoder) {
         if R::SYMMETRIC {
             if origin.target != id {
@@ -321,8 +448,13 @@ where
         unimplemented!("This method is not intended to be called");
     }
 
+    const ON_REMAP: Option<fn(&mut Self, &dyn Fn(EntityId) -> EntityId)> = Some(|origin, f| {
+        for origin in origin.origins_mut() {
+            origin.target = f(origin.target);
+        }
+    });
+
     #[inline]
-    #[must_use]
     fn borrows() -> Vec<ComponentBorrow> {
         let mut output = Vec::new();
         if R::SYMMETRIC {
@@ -336,7 +468,13 @@ where
 }
 
 /// Component that is added to target entity of the non-symmetric relation.
-pub(crate) struct TargetComponent<R> {
+///
+/// It is managed by the [`World`] when relations are added and removed.
+/// It can be fetched with `&TargetComponent<R>` query
+/// to implement custom relation queries.
+///
+/// [`World`]: crate::world::World
+pub struct TargetComponent<R> {
     origins: Vec<EntityId>,
     relation: PhantomData<fn() -> R>,
 }
@@ -360,6 +498,18 @@ where
         self.origins.push(id);
     }
 
+    /// Reserves capacity for at least `additional` more origins.
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        self.origins.reserve(additional);
+    }
+
+    /// Returns origin entities of relations that target this entity.
+    #[inline]
+    #[must_use]
+    pub fn origins(&self) -> &[EntityId] {
+        &self.origins
+    }
+
     /// Called when relation is removed from origin entity.
     /// Or origin entity is dropped.
     fn on_origin_drop(&mut self, id: EntityId, target: EntityId, mut encoder: ActionEncoder) {
@@ -407,8 +557,13 @@ where
         unimplemented!("This method is not intended to be called");
     }
 
+    const ON_REMAP: Option<fn(&mut Self, &dyn Fn(EntityId) -> EntityId)> = Some(|target, f| {
+        for origin in &mut target.origins {
+            *origin = f(*origin);
+        }
+    });
+
     #[inline]
-    #[must_use]
     fn borrows() -> Vec<ComponentBorrow> {
         let mut output = Vec::new();
         borrow_dyn_trait!(Self as RelationTarget => output);
@@ -425,7 +580,6 @@ impl<R> RelationOrigin for OriginComponent<R>
 where
     R: Relation,
 {
-    #[must_use]
     fn targets(&self) -> Vec<EntityId> {
         self.origins().iter().map(|o| o.target).collect()
     }
@@ -458,3 +612,48 @@ where
         self.origins.clone()
     }
 }
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{
+        relation::{Relates, Relation},
+        world::World,
+    };
+
+    /// Tests options of the relation derive.
+    #[test]
+    fn relation_derive_options() {
+        #[derive(Clone, Copy, Relation)]
+        #[edict(exclusive, cascade)]
+        struct AttachedTo;
+
+        #[derive(Clone, Copy, Relation)]
+        #[edict(symmetric, name = "linked")]
+        struct Linked;
+
+        assert!(AttachedTo::EXCLUSIVE);
+        assert!(AttachedTo::OWNED);
+        assert!(!AttachedTo::SYMMETRIC);
+
+        assert!(Linked::SYMMETRIC);
+        assert!(!Linked::EXCLUSIVE);
+        assert!(!Linked::OWNED);
+        assert_eq!(Linked::name(), "linked");
+
+        let mut world = World::new();
+
+        let a = world.spawn(());
+        let b = world.spawn(());
+        let c = world.spawn(());
+
+        world.add_relation(b, AttachedTo, a).unwrap();
+        world.add_relation(c, AttachedTo, b).unwrap();
+        world.add_relation(a, Linked, c).unwrap();
+        assert_eq!(world.query::<Relates<&Linked>>().iter().count(), 2);
+
+        world.despawn(a).unwrap();
+        assert!(!world.is_alive(b));
+        assert!(!world.is_alive(c));
+    }
+}
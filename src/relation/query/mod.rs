@@ -11,8 +11,11 @@
 //! # Queries
 //!
 //! [`Relates`] - matches relation origins and fetches slice of relation instances and targets.
+//! `Modified<Relates<&R>>` - same as [`Relates`], but matches only origins which relations changed after an epoch.
 //! [`RelatesExclusive`] - matches relation origins and fetches exclusive relation instance and target.
 //! [`RelatesTo`] - matches relation origin with specified target and fetches relation instance.
+//! [`RelatesToAny`] - matches relation origin with any of specified targets and fetches relation instance and target.
+//! [`RelatesTarget`] - matches relation origins and fetches component of exclusive relation target.
 //! [`Related`] - matches relation targets and fetches slice of origins.
 //!
 //! # Filters
@@ -34,7 +37,9 @@ mod filter_relates_to;
 mod related;
 mod relates;
 mod relates_exclusive;
+mod relates_target;
 mod relates_to;
+mod relates_to_any;
 
 pub use self::{
     filter_related::{related, FilterRelated},
@@ -42,7 +47,12 @@ pub use self::{
     filter_relates::{relates, FilterRelates},
     filter_relates_to::{relates_to, FilterFetchRelationTo, FilterRelatesTo},
     related::{FetchRelated, Related},
-    relates::{FetchRelatesRead, FetchRelatesWrite, Relates, RelatesReadIter, RelatesWriteIter},
+    relates::{
+        FetchRelatesRead, FetchRelatesWrite, ModifiedFetchRelatesRead, Relates, RelatesReadIter,
+        RelatesWriteIter,
+    },
     relates_exclusive::{FetchRelatesExclusiveRead, FetchRelatesExclusiveWrite, RelatesExclusive},
+    relates_target::{FetchRelatesTarget, RelatesTarget},
     relates_to::{FetchRelatesToRead, FetchRelatesToWrite, RelatesTo},
+    relates_to_any::{FetchRelatesToAnyRead, FetchRelatesToAnyWrite, RelatesToAny},
 };
@@ -4,7 +4,10 @@ use crate::{
     archetype::Archetype,
     entity::EntityId,
     epoch::EpochId,
-    query::{Access, Fetch, ImmutablePhantomQuery, PhantomQuery},
+    query::{
+        Access, Fetch, ImmutablePhantomQuery, ImmutableQuery, IntoQuery, Modified, PhantomQuery,
+        Query,
+    },
     relation::{Origin, OriginComponent, Relation},
 };
 
@@ -331,7 +334,7 @@ where
         };
         debug_assert_eq!(component.id(), TypeId::of::<OriginComponent<R>>());
 
-        let data = unsafe { component.data_mut() };
+        let data = unsafe { component.data_unique() };
         data.epoch.bump(epoch);
 
         FetchRelatesWrite {
@@ -343,3 +346,165 @@ where
         }
     }
 }
+
+/// Fetch for the [`Modified<Relates<&R>>`] query.
+pub struct ModifiedFetchRelatesRead<'a, R: Relation> {
+    after_epoch: EpochId,
+    ptr: NonNull<OriginComponent<R>>,
+    entity_epochs: NonNull<EpochId>,
+    chunk_epochs: NonNull<EpochId>,
+    marker: PhantomData<&'a OriginComponent<R>>,
+}
+
+unsafe impl<'a, R> Fetch<'a> for ModifiedFetchRelatesRead<'a, R>
+where
+    R: Relation + Sync,
+{
+    type Item = RelatesReadIter<'a, R>;
+
+    #[inline]
+    fn dangling() -> Self {
+        ModifiedFetchRelatesRead {
+            after_epoch: EpochId::start(),
+            ptr: NonNull::dangling(),
+            entity_epochs: NonNull::dangling(),
+            chunk_epochs: NonNull::dangling(),
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    unsafe fn visit_chunk(&mut self, chunk_idx: usize) -> bool {
+        let chunk_epoch = unsafe { *self.chunk_epochs.as_ptr().add(chunk_idx) };
+        chunk_epoch.after(self.after_epoch)
+    }
+
+    #[inline]
+    unsafe fn visit_item(&mut self, idx: usize) -> bool {
+        let epoch = unsafe { *self.entity_epochs.as_ptr().add(idx) };
+        epoch.after(self.after_epoch)
+    }
+
+    #[inline]
+    unsafe fn get_item(&mut self, idx: usize) -> RelatesReadIter<'a, R> {
+        let origin_component = unsafe { &*self.ptr.as_ptr().add(idx) };
+
+        RelatesReadIter {
+            iter: origin_component.origins().iter(),
+        }
+    }
+}
+
+impl<R> IntoQuery for Modified<Relates<&R>>
+where
+    R: Relation + Sync,
+{
+    type Query = Self;
+
+    #[inline]
+    fn into_query(self) -> Self {
+        self
+    }
+}
+
+/// Yields relations of origins whose relation list or relation values
+/// were modified after the epoch.
+unsafe impl<R> Query for Modified<Relates<&R>>
+where
+    R: Relation + Sync,
+{
+    type Item<'a> = RelatesReadIter<'a, R>;
+    type Fetch<'a> = ModifiedFetchRelatesRead<'a, R>;
+
+    #[inline]
+    fn access(&self, ty: TypeId) -> Option<Access> {
+        <Relates<&R> as PhantomQuery>::access(ty)
+    }
+
+    #[inline]
+    fn visit_archetype(&self, archetype: &Archetype) -> bool {
+        match archetype.component(TypeId::of::<OriginComponent<R>>()) {
+            None => false,
+            Some(component) => unsafe {
+                debug_assert_eq!(component.id(), TypeId::of::<OriginComponent<R>>());
+                let data = component.data();
+                data.epoch.after(self.after_epoch())
+            },
+        }
+    }
+
+    #[inline]
+    unsafe fn access_archetype(&self, _archetype: &Archetype, f: &dyn Fn(TypeId, Access)) {
+        f(TypeId::of::<OriginComponent<R>>(), Access::Read)
+    }
+
+    #[inline]
+    unsafe fn fetch<'a>(
+        &mut self,
+        archetype: &'a Archetype,
+        _epoch: EpochId,
+    ) -> ModifiedFetchRelatesRead<'a, R> {
+        let component = unsafe {
+            archetype
+                .component(TypeId::of::<OriginComponent<R>>())
+                .unwrap_unchecked()
+        };
+        let data = unsafe { component.data() };
+
+        debug_assert!(data.epoch.after(self.after_epoch()));
+
+        ModifiedFetchRelatesRead {
+            after_epoch: self.after_epoch(),
+            ptr: data.ptr.cast(),
+            entity_epochs: unsafe {
+                NonNull::new_unchecked(data.entity_epochs.as_ptr() as *mut EpochId)
+            },
+            chunk_epochs: unsafe {
+                NonNull::new_unchecked(data.chunk_epochs.as_ptr() as *mut EpochId)
+            },
+            marker: PhantomData,
+        }
+    }
+}
+
+unsafe impl<R> ImmutableQuery for Modified<Relates<&R>> where R: Relation + Sync {}
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::{vec, vec::Vec};
+
+    use crate::{
+        query::Entities,
+        relation::{ChildOf, Relates},
+        world::World,
+    };
+
+    /// Tests that `Modified<Relates<&R>>` yields only origins with changed relations.
+    #[test]
+    fn modified_relates() {
+        let mut world = World::new();
+
+        let target = world.spawn(());
+        let a = world.spawn(());
+        let b = world.spawn(());
+        let c = world.spawn(());
+
+        world.add_relation(a, ChildOf, target).unwrap();
+        world.add_relation(b, ChildOf, target).unwrap();
+
+        let epoch = world.epoch();
+
+        world.add_relation(c, ChildOf, target).unwrap();
+        world.remove_relation::<ChildOf>(a, target).unwrap();
+
+        let changed = world
+            .query::<Entities>()
+            .modified::<Relates<&ChildOf>>(epoch)
+            .iter()
+            .map(|(e, relates)| (e, relates.len()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(changed, vec![(c, 1)]);
+    }
+}
@@ -203,7 +203,7 @@ where
         };
         debug_assert_eq!(component.id(), TypeId::of::<OriginComponent<R>>());
 
-        let data = unsafe { component.data_mut() };
+        let data = unsafe { component.data_unique() };
         data.epoch.bump(epoch);
 
         FetchRelatesExclusiveWrite {
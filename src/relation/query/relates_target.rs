@@ -0,0 +1,288 @@
+use core::{any::TypeId, marker::PhantomData, ptr::NonNull};
+
+use crate::{
+    archetype::Archetype,
+    entity::EntitySet,
+    epoch::EpochId,
+    query::{Access, Fetch, ImmutableQuery, IntoQuery, Query},
+    relation::{OriginComponent, Relation},
+    world::World,
+};
+
+/// Query for component of the target of exclusive relation.
+///
+/// Matches origins of relation `R` and yields
+/// component `T` of the relation target, or `None`
+/// if target does not have the component.
+/// Meant to be combined with other queries in a tuple,
+/// to read origin and target components in a single pass,
+/// like parent-child transform propagation.
+///
+/// Component `T` is borrowed for reading in all archetypes
+/// when query is created and until it is dropped.
+/// Queries that write component `T` can't be used alongside.
+///
+/// # Panics
+///
+/// Creating the query panics if relation `R` is not exclusive
+/// or if component `T` is borrowed for writing.
+///
+/// # Example
+///
+/// ```
+/// # use edict::{component::Component, relation::{ChildOf, RelatesTarget}, world::World};
+/// #[derive(Component)]
+/// struct Offset(u32);
+///
+/// let mut world = World::new();
+/// let parent = world.spawn((Offset(1),));
+/// let child = world.spawn((Offset(2),));
+/// world.add_relation(child, ChildOf, parent).unwrap();
+///
+/// let query = world
+///     .query::<&Offset>()
+///     .extend_query(RelatesTarget::<ChildOf, &Offset>::new(&world));
+///
+/// let sum = query
+///     .iter()
+///     .map(|(offset, parent)| offset.0 + parent.map_or(0, |parent| parent.0))
+///     .sum::<u32>();
+/// assert_eq!(sum, 3);
+/// ```
+pub struct RelatesTarget<'w, R, T> {
+    archetypes: &'w [Archetype],
+    entities: &'w EntitySet,
+    component: TypeId,
+    marker: PhantomData<fn() -> (R, T)>,
+}
+
+impl<'w, R, T> RelatesTarget<'w, R, &T>
+where
+    R: Relation,
+    T: Sync + 'static,
+{
+    /// Creates a new [`RelatesTarget`] query.
+    pub fn new(world: &'w World) -> Self {
+        assert!(
+            R::EXCLUSIVE,
+            "RelatesTarget can be used only with EXCLUSIVE relations"
+        );
+
+        let archetypes = world.archetypes();
+        for (idx, archetype) in archetypes.iter().enumerate() {
+            let Some(component) = archetype.component(TypeId::of::<T>()) else {
+                continue;
+            };
+
+            if !unsafe { component.borrow(Access::Read) } {
+                release(&archetypes[..idx], TypeId::of::<T>());
+                panic!("Failed to lock `{}` from archetype", component.name());
+            }
+        }
+
+        RelatesTarget {
+            archetypes,
+            entities: world.entity_set(),
+            component: TypeId::of::<T>(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<R, T> Clone for RelatesTarget<'_, R, T> {
+    fn clone(&self) -> Self {
+        for archetype in self.archetypes {
+            if let Some(component) = archetype.component(self.component) {
+                // Never fails as component is already borrowed for reading by `self`.
+                let success = unsafe { component.borrow(Access::Read) };
+                assert!(success);
+            }
+        }
+
+        RelatesTarget {
+            archetypes: self.archetypes,
+            entities: self.entities,
+            component: self.component,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<R, T> Drop for RelatesTarget<'_, R, T> {
+    fn drop(&mut self) {
+        release(self.archetypes, self.component);
+    }
+}
+
+/// Releases read borrows of component taken by [`RelatesTarget::new`].
+fn release(archetypes: &[Archetype], id: TypeId) {
+    for archetype in archetypes {
+        if let Some(component) = archetype.component(id) {
+            unsafe { component.release(Access::Read) }
+        }
+    }
+}
+
+/// Fetch for the [`RelatesTarget`] query.
+pub struct FetchRelatesTarget<'a, R: Relation, T> {
+    origins: NonNull<OriginComponent<R>>,
+    archetypes: NonNull<[Archetype]>,
+    entities: NonNull<EntitySet>,
+    marker: PhantomData<(&'a OriginComponent<R>, &'a T)>,
+}
+
+unsafe impl<'a, R, T> Fetch<'a> for FetchRelatesTarget<'a, R, T>
+where
+    R: Relation,
+    T: Sync + 'static,
+{
+    type Item = Option<&'a T>;
+
+    #[inline]
+    fn dangling() -> Self {
+        FetchRelatesTarget {
+            origins: NonNull::dangling(),
+            archetypes: NonNull::<[Archetype; 0]>::dangling(),
+            entities: NonNull::dangling(),
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    unsafe fn get_item(&mut self, idx: usize) -> Option<&'a T> {
+        let origin_component = unsafe { &*self.origins.as_ptr().add(idx) };
+        let target = origin_component.origins()[0].target;
+
+        let entities = unsafe { self.entities.as_ref() };
+        let archetypes = unsafe { self.archetypes.as_ref() };
+
+        // Reserved targets are located in no archetype.
+        let (archetype_idx, target_idx) = entities.get_location(target)?;
+        let archetype = archetypes.get(archetype_idx as usize)?;
+        let component = archetype.component(TypeId::of::<T>())?;
+
+        // Safety: component is borrowed for reading by the query.
+        let data = unsafe { component.data() };
+        Some(unsafe { &*data.ptr.as_ptr().cast::<T>().add(target_idx as usize) })
+    }
+}
+
+impl<'w, R, T> IntoQuery for RelatesTarget<'w, R, &T>
+where
+    R: Relation,
+    T: Sync + 'static,
+{
+    type Query = Self;
+
+    #[inline]
+    fn into_query(self) -> Self {
+        self
+    }
+}
+
+unsafe impl<'w, R, T> Query for RelatesTarget<'w, R, &T>
+where
+    R: Relation,
+    T: Sync + 'static,
+{
+    type Item<'a> = Option<&'a T>;
+    type Fetch<'a> = FetchRelatesTarget<'a, R, T>;
+
+    #[inline]
+    fn access(&self, ty: TypeId) -> Option<Access> {
+        if ty == TypeId::of::<OriginComponent<R>>() || ty == TypeId::of::<T>() {
+            Some(Access::Read)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn visit_archetype(&self, archetype: &Archetype) -> bool {
+        archetype.has_component(TypeId::of::<OriginComponent<R>>())
+    }
+
+    #[inline]
+    unsafe fn access_archetype(&self, _archetype: &Archetype, f: &dyn Fn(TypeId, Access)) {
+        // Component `T` is borrowed in all archetypes by the query itself.
+        f(TypeId::of::<OriginComponent<R>>(), Access::Read)
+    }
+
+    #[inline]
+    unsafe fn fetch<'a>(
+        &mut self,
+        archetype: &'a Archetype,
+        _epoch: EpochId,
+    ) -> FetchRelatesTarget<'a, R, T> {
+        let component = unsafe {
+            archetype
+                .component(TypeId::of::<OriginComponent<R>>())
+                .unwrap_unchecked()
+        };
+
+        debug_assert_eq!(component.id(), TypeId::of::<OriginComponent<R>>());
+
+        let data = unsafe { component.data() };
+
+        FetchRelatesTarget {
+            origins: data.ptr.cast(),
+            archetypes: NonNull::from(self.archetypes),
+            entities: NonNull::from(self.entities),
+            marker: PhantomData,
+        }
+    }
+}
+
+unsafe impl<'w, R, T> ImmutableQuery for RelatesTarget<'w, R, &T>
+where
+    R: Relation,
+    T: Sync + 'static,
+{
+}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{relation::ChildOf, test::U32, world::World};
+
+    use super::RelatesTarget;
+
+    /// Tests that target component is fetched alongside origin components.
+    #[test]
+    fn relates_target() {
+        let mut world = World::new();
+        let root = world.spawn((U32(1),));
+        let bare = world.spawn(());
+        let a = world.spawn((U32(10),));
+        let b = world.spawn((U32(100),));
+        world.add_relation(a, ChildOf, root).unwrap();
+        world.add_relation(b, ChildOf, bare).unwrap();
+
+        let query = world
+            .query::<&U32>()
+            .extend_query(RelatesTarget::<ChildOf, &U32>::new(&world));
+
+        let mut items = query
+            .iter()
+            .map(|(u, target)| (u.0, target.map(|t| t.0)))
+            .collect::<alloc::vec::Vec<_>>();
+        items.sort();
+        assert_eq!(items, [(10, Some(1)), (100, None)]);
+    }
+
+    /// Tests that target component can't be written while query exists.
+    #[test]
+    #[should_panic(expected = "Failed to borrow component")]
+    fn relates_target_write() {
+        let mut world = World::new();
+        let root = world.spawn((U32(1),));
+        let a = world.spawn((U32(10),));
+        world.add_relation(a, ChildOf, root).unwrap();
+
+        let target = RelatesTarget::<ChildOf, &U32>::new(&world);
+        world
+            .query::<&mut U32>()
+            .extend_query(target)
+            .for_each(|_| {});
+    }
+}
@@ -0,0 +1,343 @@
+use alloc::sync::Arc;
+use core::{any::TypeId, fmt, marker::PhantomData, ptr::NonNull};
+
+use crate::{
+    archetype::Archetype,
+    entity::EntityId,
+    epoch::EpochId,
+    query::{Access, Fetch, ImmutableQuery, IntoQuery, Query},
+    relation::{OriginComponent, Relation},
+};
+
+/// Query for origins of relation with any of specified targets.
+///
+/// Yields relation instance and target it matched.
+/// If origin relates to several of the targets, first relation
+/// in the origin's list is yielded.
+pub struct RelatesToAny<R> {
+    targets: Arc<[EntityId]>,
+    phantom: PhantomData<R>,
+}
+
+impl<R> fmt::Debug for RelatesToAny<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RelatesToAny")
+            .field("targets", &self.targets)
+            .finish()
+    }
+}
+
+impl<R> Clone for RelatesToAny<R> {
+    #[inline]
+    fn clone(&self) -> Self {
+        RelatesToAny {
+            targets: self.targets.clone(),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<R> RelatesToAny<R> {
+    /// Returns relation query bound to a set of target entities.
+    pub fn new(targets: impl Into<Arc<[EntityId]>>) -> Self {
+        RelatesToAny {
+            targets: targets.into(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns targets this query is bound to.
+    pub fn targets(&self) -> &[EntityId] {
+        &self.targets
+    }
+}
+
+/// Finds first relation of the origin with one of the targets.
+#[inline]
+fn find_any<R>(origin_component: &OriginComponent<R>, targets: &[EntityId]) -> Option<usize>
+where
+    R: Relation,
+{
+    origin_component
+        .origins()
+        .iter()
+        .position(|origin| targets.contains(&origin.target))
+}
+
+/// Fetch for the [`RelatesToAny<R>`] query.
+pub struct FetchRelatesToAnyRead<'a, R: Relation> {
+    targets: Option<Arc<[EntityId]>>,
+    item_idx: usize,
+    ptr: NonNull<OriginComponent<R>>,
+    marker: PhantomData<&'a OriginComponent<R>>,
+}
+
+unsafe impl<'a, R> Fetch<'a> for FetchRelatesToAnyRead<'a, R>
+where
+    R: Relation + Sync,
+{
+    type Item = (&'a R, EntityId);
+
+    #[inline]
+    fn dangling() -> Self {
+        FetchRelatesToAnyRead {
+            targets: None,
+            ptr: NonNull::dangling(),
+            item_idx: 0,
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    unsafe fn visit_item(&mut self, idx: usize) -> bool {
+        let origin_component = unsafe { &*self.ptr.as_ptr().add(idx) };
+
+        // Dangling fetch never visits items.
+        let targets = unsafe { self.targets.as_deref().unwrap_unchecked() };
+
+        match find_any(origin_component, targets) {
+            None => false,
+            Some(item_idx) => {
+                self.item_idx = item_idx;
+                true
+            }
+        }
+    }
+
+    #[inline]
+    unsafe fn get_item(&mut self, idx: usize) -> (&'a R, EntityId) {
+        let origin_component = unsafe { &*self.ptr.as_ptr().add(idx) };
+        let origin = &origin_component.origins()[self.item_idx];
+        (&origin.relation, origin.target)
+    }
+}
+
+impl<R> IntoQuery for RelatesToAny<&R>
+where
+    R: Relation + Sync,
+{
+    type Query = Self;
+
+    fn into_query(self) -> Self::Query {
+        self
+    }
+}
+
+unsafe impl<R> Query for RelatesToAny<&R>
+where
+    R: Relation + Sync,
+{
+    type Item<'a> = (&'a R, EntityId);
+    type Fetch<'a> = FetchRelatesToAnyRead<'a, R>;
+
+    #[inline]
+    fn access(&self, ty: TypeId) -> Option<Access> {
+        if ty == TypeId::of::<OriginComponent<R>>() {
+            Some(Access::Read)
+        } else {
+            None
+        }
+    }
+
+    fn visit_archetype(&self, archetype: &Archetype) -> bool {
+        archetype.has_component(TypeId::of::<OriginComponent<R>>())
+    }
+
+    #[inline]
+    unsafe fn access_archetype(&self, _archetype: &Archetype, f: &dyn Fn(TypeId, Access)) {
+        f(TypeId::of::<OriginComponent<R>>(), Access::Read)
+    }
+
+    #[inline]
+    unsafe fn fetch<'a>(
+        &mut self,
+        archetype: &'a Archetype,
+        _epoch: EpochId,
+    ) -> FetchRelatesToAnyRead<'a, R> {
+        let component = unsafe {
+            archetype
+                .component(TypeId::of::<OriginComponent<R>>())
+                .unwrap_unchecked()
+        };
+        debug_assert_eq!(component.id(), TypeId::of::<OriginComponent<R>>());
+
+        let data = unsafe { component.data() };
+
+        FetchRelatesToAnyRead {
+            targets: Some(self.targets.clone()),
+            ptr: data.ptr.cast(),
+            item_idx: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+unsafe impl<R> ImmutableQuery for RelatesToAny<&R> where R: Relation + Sync {}
+
+/// Fetch for the `RelatesToAny<R>` query.
+pub struct FetchRelatesToAnyWrite<'a, R: Relation> {
+    targets: Option<Arc<[EntityId]>>,
+    item_idx: usize,
+    epoch: EpochId,
+    ptr: NonNull<OriginComponent<R>>,
+    entity_epochs: NonNull<EpochId>,
+    chunk_epochs: NonNull<EpochId>,
+    marker: PhantomData<&'a mut OriginComponent<R>>,
+}
+
+unsafe impl<'a, R> Fetch<'a> for FetchRelatesToAnyWrite<'a, R>
+where
+    R: Relation + Send,
+{
+    type Item = (&'a mut R, EntityId);
+
+    #[inline]
+    fn dangling() -> Self {
+        FetchRelatesToAnyWrite {
+            targets: None,
+            item_idx: 0,
+            epoch: EpochId::start(),
+            ptr: NonNull::dangling(),
+            entity_epochs: NonNull::dangling(),
+            chunk_epochs: NonNull::dangling(),
+            marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    unsafe fn touch_chunk(&mut self, chunk_idx: usize) {
+        let chunk_epoch = unsafe { &mut *self.chunk_epochs.as_ptr().add(chunk_idx) };
+        chunk_epoch.bump(self.epoch);
+    }
+
+    #[inline]
+    unsafe fn visit_item(&mut self, idx: usize) -> bool {
+        let origin_component = unsafe { &*self.ptr.as_ptr().add(idx) };
+
+        // Dangling fetch never visits items.
+        let targets = unsafe { self.targets.as_deref().unwrap_unchecked() };
+
+        match find_any(origin_component, targets) {
+            None => false,
+            Some(item_idx) => {
+                self.item_idx = item_idx;
+                true
+            }
+        }
+    }
+
+    #[inline]
+    unsafe fn get_item(&mut self, idx: usize) -> (&'a mut R, EntityId) {
+        let entity_epoch = unsafe { &mut *self.entity_epochs.as_ptr().add(idx) };
+        entity_epoch.bump(self.epoch);
+
+        let origin_component = unsafe { &mut *self.ptr.as_ptr().add(idx) };
+        let origin = &mut origin_component.origins_mut()[self.item_idx];
+        (&mut origin.relation, origin.target)
+    }
+}
+
+impl<R> IntoQuery for RelatesToAny<&mut R>
+where
+    R: Relation + Send,
+{
+    type Query = Self;
+
+    fn into_query(self) -> Self::Query {
+        self
+    }
+}
+
+unsafe impl<R> Query for RelatesToAny<&mut R>
+where
+    R: Relation + Send,
+{
+    type Item<'a> = (&'a mut R, EntityId);
+    type Fetch<'a> = FetchRelatesToAnyWrite<'a, R>;
+
+    #[inline]
+    fn access(&self, ty: TypeId) -> Option<Access> {
+        if ty == TypeId::of::<OriginComponent<R>>() {
+            Some(Access::Write)
+        } else {
+            None
+        }
+    }
+
+    fn visit_archetype(&self, archetype: &Archetype) -> bool {
+        archetype.has_component(TypeId::of::<OriginComponent<R>>())
+    }
+
+    #[inline]
+    unsafe fn access_archetype(&self, _archetype: &Archetype, f: &dyn Fn(TypeId, Access)) {
+        f(TypeId::of::<OriginComponent<R>>(), Access::Write)
+    }
+
+    #[inline]
+    unsafe fn fetch<'a>(
+        &mut self,
+        archetype: &'a Archetype,
+        epoch: EpochId,
+    ) -> FetchRelatesToAnyWrite<'a, R> {
+        let component = unsafe {
+            archetype
+                .component(TypeId::of::<OriginComponent<R>>())
+                .unwrap_unchecked()
+        };
+        debug_assert_eq!(component.id(), TypeId::of::<OriginComponent<R>>());
+
+        let data = unsafe { component.data_unique() };
+        data.epoch.bump(epoch);
+
+        FetchRelatesToAnyWrite {
+            targets: Some(self.targets.clone()),
+            item_idx: 0,
+            epoch,
+            ptr: data.ptr.cast(),
+            entity_epochs: unsafe { NonNull::new_unchecked(data.entity_epochs.as_mut_ptr()) },
+            chunk_epochs: unsafe { NonNull::new_unchecked(data.chunk_epochs.as_mut_ptr()) },
+            marker: PhantomData,
+        }
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::{vec, vec::Vec};
+
+    use crate::{query::Entities, relation::Relation, world::World};
+
+    /// Tests that `RelatesToAny` matches origins related to any of the targets.
+    #[test]
+    fn relates_to_any() {
+        #[derive(Clone, Copy)]
+        struct Attacks;
+
+        impl Relation for Attacks {}
+
+        let mut world = World::new();
+
+        let towers = [world.spawn(()), world.spawn(()), world.spawn(())];
+        let other = world.spawn(());
+
+        let a = world.spawn(());
+        let b = world.spawn(());
+        let c = world.spawn(());
+
+        world.add_relation(a, Attacks, towers[0]).unwrap();
+        world.add_relation(b, Attacks, other).unwrap();
+        world.add_relation(b, Attacks, towers[2]).unwrap();
+        world.add_relation(c, Attacks, other).unwrap();
+
+        let mut attackers = world
+            .query::<Entities>()
+            .relates_to_any::<&Attacks>(&towers[..])
+            .iter()
+            .map(|(e, (_, target))| (e, target))
+            .collect::<Vec<_>>();
+        attackers.sort_unstable_by_key(|(e, _)| e.bits());
+
+        assert_eq!(attackers, vec![(a, towers[0]), (b, towers[2])]);
+    }
+}
@@ -15,13 +15,18 @@
 //!     to NOT observe modifications made by writing system that was added later.
 //!     And writing system that is added later is guaranteed
 //!     to observe modifications made by writing system that was added before.
+//! * Systems can be ordered explicitly using labels.
+//!   See [`SystemConfig::before`] and [`SystemConfig::after`].
+//!   Explicit ordering takes precedence over registration order.
 //!
 
 #![allow(missing_docs)]
 
-use alloc::{collections::VecDeque, sync::Arc};
+use alloc::{collections::VecDeque, string::String, sync::Arc};
 use core::{
+    any::{type_name, TypeId},
     cell::UnsafeCell,
+    fmt::{self, Write},
     ops::{Deref, DerefMut},
     ptr::NonNull,
     sync::atomic::{AtomicUsize, Ordering},
@@ -103,8 +108,102 @@ struct ScheduledSystem {
     dependents: Vec<usize>,
     dependencies: usize,
     is_local: bool,
+
+    /// Registration index of the system.
+    seq: usize,
+    labels: Vec<(TypeId, &'static str)>,
+    before: Vec<TypeId>,
+    after: Vec<TypeId>,
+}
+
+impl ScheduledSystem {
+    fn has_label(&self, label: TypeId) -> bool {
+        self.labels.iter().any(|&(id, _)| id == label)
+    }
+
+    /// Returns `true` if this system is explicitly ordered before `other`.
+    fn precedes(&self, other: &ScheduledSystem) -> bool {
+        self.before.iter().any(|&label| other.has_label(label))
+            || other.after.iter().any(|&label| self.has_label(label))
+    }
+
+    fn name(&self) -> String {
+        match self.labels.first() {
+            None => format!("#{}", self.seq),
+            Some((_, name)) => String::from(*name),
+        }
+    }
+}
+
+/// Configures ordering of a system added to the [`Scheduler`].
+///
+/// Returned from [`Scheduler::add_system`].
+pub struct SystemConfig<'a> {
+    system: &'a mut ScheduledSystem,
 }
 
+impl fmt::Debug for SystemConfig<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SystemConfig")
+            .field("name", &self.system.name())
+            .finish_non_exhaustive()
+    }
+}
+
+impl SystemConfig<'_> {
+    /// Marks system with label type `L`.
+    /// Other systems can be ordered relative to all systems with this label.
+    pub fn label<L: 'static>(self) -> Self {
+        self.system
+            .labels
+            .push((TypeId::of::<L>(), type_name::<L>()));
+        self
+    }
+
+    /// Orders system to run before all systems with label `L`.
+    pub fn before<L: 'static>(self) -> Self {
+        self.system.before.push(TypeId::of::<L>());
+        self
+    }
+
+    /// Orders system to run after all systems with label `L`.
+    pub fn after<L: 'static>(self) -> Self {
+        self.system.after.push(TypeId::of::<L>());
+        self
+    }
+}
+
+/// Error returned when explicit ordering of systems contains a cycle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DependencyCycle {
+    systems: Vec<String>,
+}
+
+impl DependencyCycle {
+    /// Returns names of the systems that form the cycle.
+    ///
+    /// System name is the name of its first label
+    /// or `#N` where `N` is system registration index.
+    pub fn systems(&self) -> &[String] {
+        &self.systems
+    }
+}
+
+impl fmt::Display for DependencyCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Systems ordering contains a cycle: ")?;
+        for name in &self.systems {
+            write!(f, "{} -> ", name)?;
+        }
+        match self.systems.first() {
+            None => Ok(()),
+            Some(name) => f.write_str(name),
+        }
+    }
+}
+
+impl std::error::Error for DependencyCycle {}
+
 struct QueueInner<T> {
     items: Mutex<VecDeque<T>>,
     thread: Thread,
@@ -265,22 +364,88 @@ impl Scheduler {
     }
 
     /// Adds system to the scheduler.
-    pub fn add_system<M>(&mut self, system: impl IntoSystem<M>) {
-        self.add_boxed_system(Box::new(system.into_system()));
+    ///
+    /// Returns [`SystemConfig`] to order system relative to other systems.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, scheduler::Scheduler};
+    /// struct Physics;
+    ///
+    /// let mut world = World::new();
+    /// let mut scheduler = Scheduler::new();
+    ///
+    /// scheduler
+    ///     .add_system(|world: &World| assert_eq!(*world.expect_resource::<u32>(), 1))
+    ///     .after::<Physics>();
+    /// scheduler
+    ///     .add_system(|world: &mut World| world.insert_resource(1u32))
+    ///     .label::<Physics>();
+    ///
+    /// scheduler.run_sequential(&mut world);
+    /// ```
+    pub fn add_system<M>(&mut self, system: impl IntoSystem<M>) -> SystemConfig<'_> {
+        self.add_boxed_system(Box::new(system.into_system()))
     }
 
     /// Adds system to the scheduler.
-    pub fn add_boxed_system(&mut self, system: Box<dyn System + Send>) {
+    pub fn add_boxed_system(&mut self, system: Box<dyn System + Send>) -> SystemConfig<'_> {
+        let seq = self.systems.len();
         self.systems.push(ScheduledSystem {
             is_local: system.is_local(),
             system: SyncUnsafeCell::new(system),
             wait: AtomicUsize::new(0),
             dependents: Vec::new(),
             dependencies: 0,
+            seq,
+            labels: Vec::new(),
+            before: Vec::new(),
+            after: Vec::new(),
         });
         self.schedule_cache_id = None;
+
+        SystemConfig {
+            system: self.systems.last_mut().unwrap(),
+        }
+    }
+
+    /// Checks that explicit ordering of systems contains no cycles.
+    pub fn validate(&self) -> Result<(), DependencyCycle> {
+        sort_systems(&self.systems).map(|_| ())
+    }
+
+    /// Exports graph of system dependencies in DOT format.
+    ///
+    /// Explicit dependencies are drawn with solid edges
+    /// and dependencies caused by conflicting access with dashed edges.
+    ///
+    /// # Panics
+    ///
+    /// Panics if explicit ordering of systems contains a cycle.
+    pub fn to_dot(&mut self, world: &World) -> String {
+        self.reschedule(world);
+
+        let mut dot = String::from("digraph schedule {\n");
+        for (idx, system) in self.systems.iter().enumerate() {
+            let _ = writeln!(dot, "    {} [label=\"{}\"];", idx, system.name());
+        }
+        for (idx, system) in self.systems.iter().enumerate() {
+            for &dependent in &system.dependents {
+                let style = if system.precedes(&self.systems[dependent]) {
+                    "solid"
+                } else {
+                    "dashed"
+                };
+                let _ = writeln!(dot, "    {} -> {} [style={}];", idx, dependent, style);
+            }
+        }
+        dot.push_str("}\n");
+        dot
     }
 
+    /// Runs all systems in the scheduler using scoped threads.
+    /// Executes actions recorded by systems afterwards.
     #[cfg(feature = "std")]
     pub fn run_threaded(&mut self, world: &mut World) {
         use crate::action::ActionBufferSliceExt;
@@ -288,6 +453,8 @@ impl Scheduler {
         buffers.execute_all(world);
     }
 
+    /// Runs all systems in the scheduler on the current rayon thread pool.
+    /// Executes actions recorded by systems afterwards.
     #[cfg(feature = "rayon")]
     pub fn run_rayon(&mut self, world: &mut World) {
         use crate::action::ActionBufferSliceExt;
@@ -295,6 +462,8 @@ impl Scheduler {
         buffers.execute_all(world);
     }
 
+    /// Runs all systems in the scheduler on the current thread.
+    /// Executes actions recorded by systems afterwards.
     pub fn run_sequential(&mut self, world: &mut World) {
         use crate::action::ActionBufferSliceExt;
         let buffers = self.run_with(world, &mut MockExecutor);
@@ -302,7 +471,11 @@ impl Scheduler {
     }
 
     /// Runs all systems in the scheduler.
-    /// Provided closure should spawn system execution task.
+    /// Provided executor should spawn system execution task.
+    /// See [`ScopedExecutor`] for requirements to executors.
+    ///
+    /// Returns action buffers filled by systems.
+    /// Caller should execute them after the executor's scope ends.
     ///
     /// Running systems on the current thread instead can be viable for debugging purposes.
     #[must_use]
@@ -371,6 +544,15 @@ impl Scheduler {
             return;
         }
 
+        // Reorder systems to satisfy explicit ordering.
+        let order = match sort_systems(&self.systems) {
+            Ok(order) => order,
+            Err(err) => panic!("{}", err),
+        };
+        let mut systems = self.systems.drain(..).map(Some).collect::<Vec<_>>();
+        self.systems
+            .extend(order.iter().map(|&idx| systems[idx].take().unwrap()));
+
         for i in 0..self.systems.len() {
             // Reset dependencies.
             let a = &mut self.systems[i];
@@ -406,6 +588,14 @@ impl Scheduler {
                     &*b.system.get()
                 };
 
+                if b.precedes(a) {
+                    // Explicit ordering.
+                    self.systems[j].dependents.push(i);
+                    self.systems[i].dependencies += 1;
+                    deps.insert(j);
+                    continue 'j;
+                }
+
                 if conflicts(system_a.world_access(), system_b.world_access()) {
                     // Conflicts on world access.
                     // Add a dependency.
@@ -467,6 +657,55 @@ impl Scheduler {
     }
 }
 
+/// Sorts systems topologically according to explicit ordering.
+/// Unordered systems keep registration order.
+///
+/// Returns indices of systems in sorted order.
+fn sort_systems(systems: &[ScheduledSystem]) -> Result<Vec<usize>, DependencyCycle> {
+    let mut order = Vec::with_capacity(systems.len());
+    let mut sorted = vec![false; systems.len()];
+
+    let is_ready = |sorted: &[bool], idx: usize| {
+        (0..systems.len()).all(|p| sorted[p] || p == idx || !systems[p].precedes(&systems[idx]))
+    };
+
+    while order.len() < systems.len() {
+        let next = (0..systems.len())
+            .filter(|&idx| !sorted[idx] && is_ready(&sorted, idx))
+            .min_by_key(|&idx| systems[idx].seq);
+
+        match next {
+            Some(idx) => {
+                sorted[idx] = true;
+                order.push(idx);
+            }
+            None => {
+                // Every remaining system has unsorted predecessor.
+                // Walk predecessors until one repeats to find the cycle.
+                let mut path = Vec::new();
+                let mut idx = (0..systems.len()).find(|&idx| !sorted[idx]).unwrap();
+                while !path.contains(&idx) {
+                    path.push(idx);
+                    idx = (0..systems.len())
+                        .find(|&p| !sorted[p] && p != idx && systems[p].precedes(&systems[idx]))
+                        .unwrap();
+                }
+                let start = path.iter().position(|&p| p == idx).unwrap();
+
+                return Err(DependencyCycle {
+                    systems: path[start..]
+                        .iter()
+                        .rev()
+                        .map(|&idx| systems[idx].name())
+                        .collect(),
+                });
+            }
+        }
+    }
+
+    Ok(order)
+}
+
 mod test {
     #![cfg(test)]
 
@@ -489,6 +728,50 @@ mod test {
 
         scheduler.run_sequential(&mut world);
     }
+
+    #[test]
+    fn explicit_order() {
+        struct A;
+        struct B;
+
+        let mut world = World::new();
+        world.insert_resource(Vec::<u32>::new());
+
+        let mut scheduler = Scheduler::new();
+        scheduler
+            .add_system(|world: &mut World| world.expect_resource_mut::<Vec<u32>>().push(2))
+            .label::<B>()
+            .after::<A>();
+        scheduler
+            .add_system(|world: &mut World| world.expect_resource_mut::<Vec<u32>>().push(1))
+            .label::<A>();
+        scheduler
+            .add_system(|world: &mut World| world.expect_resource_mut::<Vec<u32>>().push(0))
+            .before::<A>();
+
+        assert!(scheduler.validate().is_ok());
+        scheduler.run_sequential(&mut world);
+        assert_eq!(*world.expect_resource::<Vec<u32>>(), [0, 1, 2]);
+
+        let dot = scheduler.to_dot(&world);
+        assert!(dot.starts_with("digraph schedule {"));
+        assert!(dot.contains(type_name::<A>()));
+        assert!(dot.contains("0 -> 1 [style=solid];"));
+    }
+
+    #[test]
+    fn dependency_cycle() {
+        struct A;
+        struct B;
+
+        let mut scheduler = Scheduler::new();
+        scheduler.add_system(|| {}).label::<A>().after::<B>();
+        scheduler.add_system(|| {}).label::<B>().after::<A>();
+        scheduler.add_system(|| {});
+
+        let err = scheduler.validate().unwrap_err();
+        assert_eq!(err.systems().len(), 2);
+    }
 }
 
 fn conflicts(lhs: Option<Access>, rhs: Option<Access>) -> bool {
@@ -0,0 +1,698 @@
+//! Host for systems defined by script modules, such as WebAssembly modules.
+//!
+//! Script module sees components through a stable ABI:
+//! for each chunk of matching entities it receives entity ids
+//! and flat byte buffers of component values, one buffer per declared component.
+//! Buffers of components declared as written can be modified in place.
+//!
+//! Structural changes are emitted by the module as a command buffer
+//! that is applied to the [`World`] after module returns.
+//! See [`CommandEncoder`] for the format.
+//!
+//! Host does not depend on any particular WebAssembly runtime.
+//! Runtime integration implements [`ScriptModule`] by copying chunk buffers
+//! into module memory, calling exported function and copying
+//! written buffers and command buffer back.
+
+use alloc::vec::Vec;
+use core::{any::TypeId, fmt, mem::size_of, ptr};
+
+use crate::{
+    archetype::{is_enabled, CHUNK_LEN_USIZE},
+    component::Component,
+    entity::EntityId,
+    world::{NoSuchEntity, World},
+};
+
+/// Component types that can be exposed to script modules as raw bytes.
+///
+/// # Safety
+///
+/// Type must not contain padding bytes and any bit pattern must be a valid value.
+pub unsafe trait ScriptData: Component + Copy {}
+
+/// Script module that runs as a system.
+pub trait ScriptModule: Send + 'static {
+    /// Runs the module over chunks of entities that have all declared components.
+    /// Entities with any of declared components disabled are not included.
+    ///
+    /// Columns of each chunk follow order in which components were declared
+    /// with [`ScriptSystem::read`] and [`ScriptSystem::write`].
+    /// Module appends commands to the `commands` buffer.
+    fn run(&mut self, chunks: &mut [ScriptChunk<'_>], commands: &mut Vec<u8>);
+}
+
+/// Chunk of entities exposed to a script module.
+pub struct ScriptChunk<'a> {
+    entities: &'a [EntityId],
+    columns: Vec<ScriptColumn<'a>>,
+}
+
+impl<'a> ScriptChunk<'a> {
+    /// Returns number of entities in the chunk.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Returns `true` if chunk has no entities.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty()
+    }
+
+    /// Returns id of entity with specified index in the chunk
+    /// in form of [`EntityId::bits`].
+    #[inline]
+    pub fn entity(&self, idx: usize) -> u64 {
+        self.entities[idx].bits()
+    }
+
+    /// Returns columns of component values.
+    #[inline]
+    pub fn columns(&mut self) -> &mut [ScriptColumn<'a>] {
+        &mut self.columns
+    }
+}
+
+/// Flat buffer of component values of a chunk.
+pub enum ScriptColumn<'a> {
+    /// Values of component declared with [`ScriptSystem::read`].
+    Read(&'a [u8]),
+
+    /// Values of component declared with [`ScriptSystem::write`].
+    Write(&'a mut [u8]),
+}
+
+impl ScriptColumn<'_> {
+    /// Returns bytes of component values.
+    #[inline]
+    pub fn bytes(&self) -> &[u8] {
+        match self {
+            ScriptColumn::Read(bytes) => bytes,
+            ScriptColumn::Write(bytes) => bytes,
+        }
+    }
+
+    /// Returns mutable bytes of component values.
+    /// Returns `None` for components that are only read.
+    #[inline]
+    pub fn bytes_mut(&mut self) -> Option<&mut [u8]> {
+        match self {
+            ScriptColumn::Read(_) => None,
+            ScriptColumn::Write(bytes) => Some(bytes),
+        }
+    }
+}
+
+const OP_SPAWN: u8 = 0;
+const OP_DESPAWN: u8 = 1;
+const OP_SET: u8 = 2;
+const OP_REMOVE: u8 = 3;
+
+/// Writes commands into a command buffer.
+///
+/// Commands are encoded as opcode byte followed by operands.
+/// Integers are little-endian, entity ids are `u64`,
+/// component indices are `u32` and refer to declared components.
+///
+/// | Command | Opcode | Operands |
+/// |---------|--------|----------|
+/// | spawn   | 0      | component count, then for each: component index and value bytes |
+/// | despawn | 1      | entity |
+/// | set     | 2      | entity, component index, value bytes |
+/// | remove  | 3      | entity, component index |
+///
+/// Size of value bytes is the size of the component type.
+#[derive(Debug)]
+pub struct CommandEncoder<'a> {
+    buffer: &'a mut Vec<u8>,
+}
+
+impl<'a> CommandEncoder<'a> {
+    /// Returns encoder that appends commands to the buffer.
+    #[inline]
+    pub fn new(buffer: &'a mut Vec<u8>) -> Self {
+        CommandEncoder { buffer }
+    }
+
+    /// Encodes command to spawn entity with components.
+    pub fn spawn(&mut self, components: &[(u32, &[u8])]) {
+        self.buffer.push(OP_SPAWN);
+        self.buffer
+            .extend_from_slice(&(components.len() as u32).to_le_bytes());
+        for &(component, bytes) in components {
+            self.buffer.extend_from_slice(&component.to_le_bytes());
+            self.buffer.extend_from_slice(bytes);
+        }
+    }
+
+    /// Encodes command to despawn entity.
+    pub fn despawn(&mut self, entity: u64) {
+        self.buffer.push(OP_DESPAWN);
+        self.buffer.extend_from_slice(&entity.to_le_bytes());
+    }
+
+    /// Encodes command to insert or replace component value.
+    pub fn set(&mut self, entity: u64, component: u32, bytes: &[u8]) {
+        self.buffer.push(OP_SET);
+        self.buffer.extend_from_slice(&entity.to_le_bytes());
+        self.buffer.extend_from_slice(&component.to_le_bytes());
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Encodes command to remove component.
+    pub fn remove(&mut self, entity: u64, component: u32) {
+        self.buffer.push(OP_REMOVE);
+        self.buffer.extend_from_slice(&entity.to_le_bytes());
+        self.buffer.extend_from_slice(&component.to_le_bytes());
+    }
+}
+
+/// Error returned when command buffer emitted by script module is malformed.
+///
+/// Malformed buffer is rejected as a whole, none of its commands are applied.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidCommand {
+    offset: usize,
+}
+
+impl InvalidCommand {
+    /// Returns offset of the malformed command in the buffer.
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl fmt::Display for InvalidCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid script command at offset {}", self.offset)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidCommand {}
+
+/// Component declared for script module.
+struct ScriptComponent {
+    id: TypeId,
+    size: usize,
+    write: bool,
+    insert: fn(&mut World, EntityId, &[u8]) -> Result<(), NoSuchEntity>,
+    remove: fn(&mut World, EntityId),
+}
+
+fn insert_bytes<T>(world: &mut World, id: EntityId, bytes: &[u8]) -> Result<(), NoSuchEntity>
+where
+    T: ScriptData,
+{
+    debug_assert_eq!(bytes.len(), size_of::<T>());
+
+    // Safety: `T` is valid for any bit pattern.
+    let value = unsafe { ptr::read_unaligned(bytes.as_ptr().cast::<T>()) };
+    world.insert(id, value)
+}
+
+fn remove_component<T>(world: &mut World, id: EntityId)
+where
+    T: ScriptData,
+{
+    let _ = world.drop::<T>(id);
+}
+
+/// System that runs [`ScriptModule`].
+///
+/// Declared components are accessed mutably when system runs,
+/// so it runs exclusively with the [`World`].
+///
+/// # Example
+///
+/// ```
+/// # use edict::{component::Component, script::{ScriptChunk, ScriptData, ScriptModule, ScriptSystem}, system::LocalSystem, world::World};
+/// #[derive(Clone, Copy, Component, Debug, PartialEq)]
+/// #[repr(transparent)]
+/// struct Counter(u32);
+///
+/// unsafe impl ScriptData for Counter {}
+///
+/// struct Double;
+///
+/// impl ScriptModule for Double {
+///     fn run(&mut self, chunks: &mut [ScriptChunk<'_>], _commands: &mut Vec<u8>) {
+///         for chunk in chunks {
+///             let bytes = chunk.columns()[0].bytes_mut().unwrap();
+///             for value in bytes.chunks_exact_mut(4) {
+///                 let doubled = u32::from_ne_bytes(value.try_into().unwrap()) * 2;
+///                 value.copy_from_slice(&doubled.to_ne_bytes());
+///             }
+///         }
+///     }
+/// }
+///
+/// let mut world = World::new();
+/// let e = world.spawn((Counter(21),));
+///
+/// let mut system = ScriptSystem::new(Double).write::<Counter>();
+/// system.run(&mut world);
+///
+/// assert_eq!(world.query_one_mut::<&Counter>(e), Ok(&Counter(42)));
+/// ```
+pub struct ScriptSystem<M> {
+    module: M,
+    components: Vec<ScriptComponent>,
+    commands: Vec<u8>,
+
+    /// Error of the last run as [`LocalSystem`](crate::system::LocalSystem).
+    error: Option<InvalidCommand>,
+}
+
+impl<M> fmt::Debug for ScriptSystem<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScriptSystem")
+            .field("components", &self.components.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M> ScriptSystem<M>
+where
+    M: ScriptModule,
+{
+    /// Returns new system that runs the module.
+    #[inline]
+    pub fn new(module: M) -> Self {
+        ScriptSystem {
+            module,
+            components: Vec::new(),
+            commands: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Declares component that module reads.
+    pub fn read<T>(self) -> Self
+    where
+        T: ScriptData,
+    {
+        self.declare::<T>(false)
+    }
+
+    /// Declares component that module reads and writes.
+    pub fn write<T>(self) -> Self
+    where
+        T: ScriptData,
+    {
+        self.declare::<T>(true)
+    }
+
+    fn declare<T>(mut self, write: bool) -> Self
+    where
+        T: ScriptData,
+    {
+        assert!(
+            self.components.iter().all(|c| c.id != TypeId::of::<T>()),
+            "Component `{}` is already declared",
+            T::name()
+        );
+
+        self.components.push(ScriptComponent {
+            id: TypeId::of::<T>(),
+            size: size_of::<T>(),
+            write,
+            insert: insert_bytes::<T>,
+            remove: remove_component::<T>,
+        });
+        self
+    }
+
+    /// Returns module run by this system.
+    #[inline]
+    pub fn module(&mut self) -> &mut M {
+        &mut self.module
+    }
+
+    /// Takes error of the last run as [`LocalSystem`](crate::system::LocalSystem).
+    #[inline]
+    pub fn take_error(&mut self) -> Option<InvalidCommand> {
+        self.error.take()
+    }
+
+    /// Runs the module with the world.
+    /// Commands emitted by the module are applied after it returns.
+    ///
+    /// Fails if command buffer is malformed.
+    /// In this case no commands are applied.
+    pub fn try_run(&mut self, world: &mut World) -> Result<(), InvalidCommand> {
+        world.maintenance();
+
+        let epoch = world.epoch_counter().next();
+        let mut chunks = Vec::new();
+        let mut disabled = Vec::new();
+
+        for archetype in world.archetypes() {
+            if archetype.is_empty()
+                || !self
+                    .components
+                    .iter()
+                    .all(|c| archetype.has_component(c.id))
+            {
+                continue;
+            }
+
+            let entities = archetype.entities();
+
+            // Union of disabled bits of declared components.
+            disabled.clear();
+            for c in &self.components {
+                let component = archetype.component(c.id).unwrap();
+
+                // Safety: world is borrowed mutably, no other borrows exist.
+                let data = unsafe { component.data() };
+                if disabled.len() < data.disabled.len() {
+                    disabled.resize(data.disabled.len(), 0);
+                }
+                for (word, &bits) in disabled.iter_mut().zip(&data.disabled) {
+                    *word |= bits;
+                }
+            }
+
+            for (start, end) in enabled_runs(entities.len(), &disabled) {
+                let columns = self
+                    .components
+                    .iter()
+                    .map(|c| {
+                        let component = archetype.component(c.id).unwrap();
+
+                        // Safety: world is borrowed mutably, no other borrows exist.
+                        // Each chunk borrows disjoint range of the column.
+                        let data = unsafe { component.data_mut() };
+                        let ptr = unsafe { data.ptr.as_ptr().add(start * c.size) };
+                        let len = (end - start) * c.size;
+
+                        if c.write {
+                            data.epoch.bump(epoch);
+                            // Chunk may be split into several runs.
+                            data.chunk_epochs[start / CHUNK_LEN_USIZE].bump_again(epoch);
+                            for entity_epoch in &mut data.entity_epochs[start..end] {
+                                entity_epoch.bump(epoch);
+                            }
+                            ScriptColumn::Write(unsafe {
+                                core::slice::from_raw_parts_mut(ptr, len)
+                            })
+                        } else {
+                            ScriptColumn::Read(unsafe { core::slice::from_raw_parts(ptr, len) })
+                        }
+                    })
+                    .collect();
+
+                chunks.push(ScriptChunk {
+                    entities: &entities[start..end],
+                    columns,
+                });
+            }
+        }
+
+        self.commands.clear();
+        self.module.run(&mut chunks, &mut self.commands);
+        drop(chunks);
+
+        let result = apply_commands(world, &self.components, &self.commands);
+        self.commands.clear();
+        result
+    }
+}
+
+impl<M> crate::system::LocalSystem for ScriptSystem<M>
+where
+    M: ScriptModule,
+{
+    /// Runs the module with the world.
+    ///
+    /// If command buffer emitted by the module is malformed,
+    /// error is kept until taken with [`ScriptSystem::take_error`].
+    fn run(&mut self, world: &mut World) {
+        self.error = self.try_run(world).err();
+    }
+}
+
+/// Returns ranges of entities within chunks that have all declared components enabled.
+fn enabled_runs(len: usize, disabled: &[u64]) -> impl Iterator<Item = (usize, usize)> + '_ {
+    (0..len)
+        .step_by(CHUNK_LEN_USIZE)
+        .flat_map(move |chunk_start| {
+            let chunk_end = len.min(chunk_start + CHUNK_LEN_USIZE);
+            let mut start = chunk_start;
+
+            core::iter::from_fn(move || {
+                while start < chunk_end && !is_enabled(disabled, start) {
+                    start += 1;
+                }
+                if start == chunk_end {
+                    return None;
+                }
+
+                let mut end = start + 1;
+                while end < chunk_end && is_enabled(disabled, end) {
+                    end += 1;
+                }
+
+                let run = (start, end);
+                start = end;
+                Some(run)
+            })
+        })
+}
+
+/// Reads values from command buffer.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.bytes.get(self.offset..self.offset.checked_add(len)?)?;
+        self.offset += len;
+        Some(bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn entity(&mut self) -> Option<EntityId> {
+        EntityId::from_bits(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn component<'b>(&mut self, components: &'b [ScriptComponent]) -> Option<&'b ScriptComponent> {
+        components.get(self.u32()? as usize)
+    }
+}
+
+/// Decoded command.
+enum Command<'a> {
+    Spawn(Vec<(&'a ScriptComponent, &'a [u8])>),
+    Despawn(EntityId),
+    Set(EntityId, &'a ScriptComponent, &'a [u8]),
+    Remove(EntityId, &'a ScriptComponent),
+}
+
+/// Decodes all commands from the buffer.
+fn decode_commands<'a>(
+    components: &'a [ScriptComponent],
+    bytes: &'a [u8],
+) -> Result<Vec<Command<'a>>, InvalidCommand> {
+    let mut reader = Reader { bytes, offset: 0 };
+    let mut commands = Vec::new();
+
+    while reader.offset < bytes.len() {
+        let offset = reader.offset;
+        let invalid = InvalidCommand { offset };
+
+        let op = reader.take(1).ok_or(invalid)?[0];
+        let command = match op {
+            OP_SPAWN => {
+                let count = reader.u32().ok_or(invalid)?;
+                let mut values: Vec<(&ScriptComponent, &[u8])> = Vec::new();
+                for _ in 0..count {
+                    let component = reader.component(components).ok_or(invalid)?;
+                    if values.iter().any(|(c, _)| c.id == component.id) {
+                        return Err(invalid);
+                    }
+                    let value = reader.take(component.size).ok_or(invalid)?;
+                    values.push((component, value));
+                }
+                Command::Spawn(values)
+            }
+            OP_DESPAWN => Command::Despawn(reader.entity().ok_or(invalid)?),
+            OP_SET => {
+                let id = reader.entity().ok_or(invalid)?;
+                let component = reader.component(components).ok_or(invalid)?;
+                let value = reader.take(component.size).ok_or(invalid)?;
+                Command::Set(id, component, value)
+            }
+            OP_REMOVE => {
+                let id = reader.entity().ok_or(invalid)?;
+                let component = reader.component(components).ok_or(invalid)?;
+                Command::Remove(id, component)
+            }
+            _ => return Err(invalid),
+        };
+        commands.push(command);
+    }
+
+    Ok(commands)
+}
+
+fn apply_commands(
+    world: &mut World,
+    components: &[ScriptComponent],
+    bytes: &[u8],
+) -> Result<(), InvalidCommand> {
+    // Whole buffer is validated before any command is applied.
+    let commands = decode_commands(components, bytes)?;
+
+    for command in commands {
+        match command {
+            Command::Spawn(values) => {
+                let id = world.spawn(());
+                for (component, value) in values {
+                    // Entity was just spawned and can't be despawned meanwhile.
+                    let _ = (component.insert)(world, id, value);
+                }
+            }
+            Command::Despawn(id) => {
+                // Entity may be already despawned.
+                let _ = world.despawn(id);
+            }
+            Command::Set(id, component, value) => {
+                // Entity may be already despawned.
+                let _ = (component.insert)(world, id, value);
+            }
+            Command::Remove(id, component) => {
+                (component.remove)(world, id);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::vec::Vec;
+
+    use crate::{component::Component, world::World};
+
+    /// Tests that commands emitted by script modules are applied.
+    #[test]
+    fn script_commands() {
+        use crate::{
+            script::{CommandEncoder, ScriptChunk, ScriptData, ScriptModule, ScriptSystem},
+            system::LocalSystem,
+        };
+
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        #[repr(transparent)]
+        struct Health(u32);
+        impl Component for Health {}
+        unsafe impl ScriptData for Health {}
+
+        /// Despawns dead entities and spawns one replacement for each.
+        struct Reaper;
+
+        impl ScriptModule for Reaper {
+            fn run(&mut self, chunks: &mut [ScriptChunk<'_>], commands: &mut Vec<u8>) {
+                let mut encoder = CommandEncoder::new(commands);
+                for chunk in chunks {
+                    for idx in 0..chunk.len() {
+                        let entity = chunk.entity(idx);
+                        let bytes = &chunk.columns()[0].bytes()[idx * 4..][..4];
+                        if bytes == [0; 4] {
+                            encoder.despawn(entity);
+                            encoder.spawn(&[(0, &10u32.to_ne_bytes())]);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut world = World::new();
+        let alive = world.spawn((Health(5),));
+        let dead = world.spawn((Health(0),));
+
+        let mut system = ScriptSystem::new(Reaper).read::<Health>();
+        system.run(&mut world);
+
+        assert!(world.is_alive(alive));
+        assert!(!world.is_alive(dead));
+
+        let mut health = world.query::<&Health>().iter().copied().collect::<Vec<_>>();
+        health.sort_by_key(|h| h.0);
+        assert_eq!(health, [Health(5), Health(10)]);
+    }
+
+    /// Tests that malformed command buffer is rejected as a whole
+    /// and disabled components are not exposed to the module.
+    #[test]
+    fn script_commands_validated() {
+        use crate::{
+            script::{CommandEncoder, ScriptChunk, ScriptData, ScriptModule, ScriptSystem},
+            system::LocalSystem,
+        };
+
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        #[repr(transparent)]
+        struct Health(u32);
+        impl Component for Health {}
+        unsafe impl ScriptData for Health {}
+
+        /// Spawns entity for each visited one, then emits truncated command.
+        struct Broken;
+
+        impl ScriptModule for Broken {
+            fn run(&mut self, chunks: &mut [ScriptChunk<'_>], commands: &mut Vec<u8>) {
+                let mut encoder = CommandEncoder::new(commands);
+                for chunk in chunks {
+                    for _ in 0..chunk.len() {
+                        encoder.spawn(&[(0, &1u32.to_ne_bytes())]);
+                    }
+                }
+                encoder.set(0, 0, &[1]);
+            }
+        }
+
+        /// Counts visited entities.
+        struct Count(usize);
+
+        impl ScriptModule for Count {
+            fn run(&mut self, chunks: &mut [ScriptChunk<'_>], _commands: &mut Vec<u8>) {
+                self.0 += chunks.iter().map(|chunk| chunk.len()).sum::<usize>();
+            }
+        }
+
+        let mut world = World::new();
+        let a = world.spawn((Health(5),));
+        world.spawn((Health(6),));
+        world.spawn((Health(7),));
+
+        let mut system = ScriptSystem::new(Broken).read::<Health>();
+        system.run(&mut world);
+        assert!(system.take_error().is_some());
+        assert_eq!(world.query::<&Health>().iter().count(), 3);
+
+        // Duplicate component in spawn command.
+        let mut commands = Vec::new();
+        CommandEncoder::new(&mut commands).spawn(&[(0, &[0; 4]), (0, &[0; 4])]);
+        assert!(super::apply_commands(&mut world, &system.components, &commands).is_err());
+        assert_eq!(world.query::<&Health>().iter().count(), 3);
+
+        world.set_enabled::<Health>(a, false).unwrap();
+        let mut system = ScriptSystem::new(Count(0)).write::<Health>();
+        system.run(&mut world);
+        assert_eq!(system.take_error(), None);
+        assert_eq!(system.module().0, 2);
+    }
+}
@@ -20,8 +20,7 @@ pub use self::{
         Res, ResCache, ResMut, ResMutCache, ResMutNoSend, ResMutNoSendCache, ResNoSync,
         ResNoSyncCache,
     },
-    state::{State, StateCache},
-    world::{WorldReadCache, WorldWriteCache},
+    state::{Local, State, StateCache},
 };
 
 /// Marker for [`IntoSystem`] for functions.
@@ -48,6 +48,11 @@ pub trait QueryArg: IntoQuery {
 pub struct QueryRefCache<Q, F> {
     query: Q,
     filter: F,
+
+    /// Id of archetype set and whether query matches any archetype in it.
+    /// Query that matches none skips scanning archetypes
+    /// until archetype set changes.
+    matches: Option<(u64, bool)>,
 }
 
 unsafe impl<'a, Q, F> FnArgGet<'a> for QueryRefCache<Q, F>
@@ -65,9 +70,28 @@ where
     ) -> Self::Arg {
         // Safety: Declares read access.
         let world = unsafe { world.as_ref() };
+        let archetype_set_id = world.archetype_set_id();
+
+        let matches_any = match self.matches {
+            Some((id, matches_any)) if id == archetype_set_id => matches_any,
+            _ => {
+                let matches_any = world
+                    .archetypes()
+                    .iter()
+                    .any(|archetype| FnArgCache::visit_archetype(self, archetype));
+                self.matches = Some((archetype_set_id, matches_any));
+                matches_any
+            }
+        };
+
         let query = self.query.get(world);
         let filter = self.filter.get(world);
-        QueryRef::new(world, query, filter)
+
+        if matches_any {
+            QueryRef::new(world, query, filter)
+        } else {
+            QueryRef::new_matching_none(world, query, filter)
+        }
     }
 }
 
@@ -81,6 +105,7 @@ where
         QueryRefCache {
             query: Q::new(),
             filter: F::new(),
+            matches: None,
         }
     }
 
@@ -179,3 +204,50 @@ macro_rules! impl_query {
 }
 
 for_tuple!(impl_query);
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::vec::Vec;
+
+    use crate::{
+        test::{Str, U32},
+        world::World,
+    };
+
+    /// Tests that system queries matching no archetypes see components spawned later.
+    #[test]
+    fn system_query_matches_none() {
+        use crate::{
+            system::{IntoSystem, ResMut, System},
+            world::QueryRef,
+        };
+        use core::ptr::NonNull;
+
+        fn count(mut query: QueryRef<&U32>, mut counter: ResMut<u32>) {
+            query.for_each(|u| *counter += u.0);
+        }
+
+        let mut world = World::new();
+        world.insert_resource(0u32);
+        let e = world.spawn((Str("qwe"),));
+
+        let mut system = count.into_system();
+        let mut encoders = Vec::new();
+
+        let mut run = |world: &mut World| unsafe {
+            system.run_unchecked(NonNull::from(&*world), &mut encoders);
+        };
+
+        run(&mut world);
+        assert_eq!(*world.expect_resource::<u32>(), 0);
+
+        world.insert(e, U32(1)).unwrap();
+        run(&mut world);
+        assert_eq!(*world.expect_resource::<u32>(), 1);
+
+        world.spawn((U32(2),));
+        run(&mut world);
+        assert_eq!(*world.expect_resource::<u32>(), 4);
+    }
+}
@@ -25,6 +25,23 @@ pub struct State<'a, T> {
     value: &'a mut T,
 }
 
+/// System-local state for function systems.
+///
+/// Alias for [`State`], stored in the system's cache
+/// and initialized with [`Default`] on first run.
+///
+/// # Example
+///
+/// ```
+/// # use edict::{system::{IntoSystem, Local}, world::World};
+/// fn timer(mut ticks: Local<u32>) {
+///     *ticks += 1;
+/// }
+///
+/// let _system = timer.into_system();
+/// ```
+pub type Local<'a, T> = State<'a, T>;
+
 /// [`FnArgCache`] for [`State`] argument.
 #[derive(Default)]
 pub struct StateCache<T> {
@@ -1,6 +1,7 @@
 //! Provides API to define systems compatible with built-in scheduler.
 
 mod func;
+mod validate;
 
 use alloc::vec::Vec;
 use core::{any::TypeId, ptr::NonNull};
@@ -8,10 +9,13 @@ use core::{any::TypeId, ptr::NonNull};
 use crate::{action::ActionBuffer, archetype::Archetype, query::Access, world::World};
 
 pub use self::func::{
-    ActionEncoderCache, FnArg, FnArgCache, FnArgGet, FromWorld, IsFunctionSystem, QueryArg,
+    ActionEncoderCache, FnArg, FnArgCache, FnArgGet, FromWorld, IsFunctionSystem, Local, QueryArg,
     QueryArgCache, QueryArgGet, QueryRefCache, Res, ResCache, ResMut, ResMutCache, ResMutNoSend,
     ResMutNoSendCache, ResNoSync, ResNoSyncCache, State, StateCache,
 };
+pub use self::validate::{
+    validate_systems, AccessConflict, SystemMeta, SystemsReport, UnknownLabel,
+};
 
 /// A queue of `ActionEncoder` instances.
 /// The nature of queue depends on scheduler implementation.
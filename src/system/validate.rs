@@ -0,0 +1,491 @@
+//! Offline validation of declared system accesses.
+
+use alloc::{string::String, vec, vec::Vec};
+use core::{
+    any::{type_name, TypeId},
+    fmt,
+};
+
+use crate::query::Access;
+
+/// Declared accesses and ordering of a system.
+///
+/// Describes system without constructing it,
+/// so that schedules can be checked with [`validate_systems`] in tests.
+#[derive(Clone, Debug)]
+pub struct SystemMeta {
+    name: String,
+    components: Vec<(TypeId, &'static str, Access)>,
+    resources: Vec<(TypeId, &'static str, Access)>,
+    labels: Vec<TypeId>,
+    before: Vec<(TypeId, &'static str)>,
+    after: Vec<(TypeId, &'static str)>,
+}
+
+/// Adds access to the list, upgrading existing access if needed.
+fn add_access(
+    accesses: &mut Vec<(TypeId, &'static str, Access)>,
+    id: TypeId,
+    name: &'static str,
+    access: Access,
+) {
+    match accesses.iter_mut().find(|(other, _, _)| *other == id) {
+        None => accesses.push((id, name, access)),
+        Some((_, _, existing)) => {
+            if access == Access::Write {
+                *existing = Access::Write;
+            }
+        }
+    }
+}
+
+impl SystemMeta {
+    /// Returns new system description without accesses.
+    pub fn new(name: impl Into<String>) -> Self {
+        SystemMeta {
+            name: name.into(),
+            components: Vec::new(),
+            resources: Vec::new(),
+            labels: Vec::new(),
+            before: Vec::new(),
+            after: Vec::new(),
+        }
+    }
+
+    /// Returns name of the system.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Declares that system reads component `T`.
+    #[must_use]
+    pub fn reads<T: 'static>(mut self) -> Self {
+        add_access(
+            &mut self.components,
+            TypeId::of::<T>(),
+            type_name::<T>(),
+            Access::Read,
+        );
+        self
+    }
+
+    /// Declares that system writes component `T`.
+    #[must_use]
+    pub fn writes<T: 'static>(mut self) -> Self {
+        add_access(
+            &mut self.components,
+            TypeId::of::<T>(),
+            type_name::<T>(),
+            Access::Write,
+        );
+        self
+    }
+
+    /// Declares that system reads resource `T`.
+    #[must_use]
+    pub fn reads_resource<T: 'static>(mut self) -> Self {
+        add_access(
+            &mut self.resources,
+            TypeId::of::<T>(),
+            type_name::<T>(),
+            Access::Read,
+        );
+        self
+    }
+
+    /// Declares that system writes resource `T`.
+    #[must_use]
+    pub fn writes_resource<T: 'static>(mut self) -> Self {
+        add_access(
+            &mut self.resources,
+            TypeId::of::<T>(),
+            type_name::<T>(),
+            Access::Write,
+        );
+        self
+    }
+
+    /// Marks system with label type `L`.
+    ///
+    /// Same as `SystemConfig::label` of the built-in scheduler.
+    #[must_use]
+    pub fn label<L: 'static>(mut self) -> Self {
+        self.labels.push(TypeId::of::<L>());
+        self
+    }
+
+    /// Orders system to run before all systems with label `L`.
+    ///
+    /// Same as `SystemConfig::before` of the built-in scheduler.
+    #[must_use]
+    pub fn before<L: 'static>(mut self) -> Self {
+        self.before.push((TypeId::of::<L>(), type_name::<L>()));
+        self
+    }
+
+    /// Orders system to run after all systems with label `L`.
+    ///
+    /// Same as `SystemConfig::after` of the built-in scheduler.
+    #[must_use]
+    pub fn after<L: 'static>(mut self) -> Self {
+        self.after.push((TypeId::of::<L>(), type_name::<L>()));
+        self
+    }
+
+    fn has_label(&self, label: TypeId) -> bool {
+        self.labels.contains(&label)
+    }
+
+    /// Returns `true` if this system is explicitly ordered before `other`.
+    fn precedes(&self, other: &SystemMeta) -> bool {
+        self.before.iter().any(|&(label, _)| other.has_label(label))
+            || other.after.iter().any(|&(label, _)| self.has_label(label))
+    }
+}
+
+/// Pair of systems that access the same component or resource
+/// in conflicting ways and are not ordered explicitly.
+///
+/// Such systems are ordered only by registration order,
+/// which is easy to break by accident.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccessConflict {
+    /// Name of the system that comes first in the list.
+    pub first: String,
+
+    /// Name of the system that comes second in the list.
+    pub second: String,
+
+    /// Name of the component or resource type.
+    pub type_name: &'static str,
+
+    /// `true` if conflicting type is a resource.
+    pub resource: bool,
+}
+
+/// Ordering constraint that refers to a label no system has.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownLabel {
+    /// Name of the system with the constraint.
+    pub system: String,
+
+    /// Name of the label type.
+    pub label: &'static str,
+}
+
+/// Result of [`validate_systems`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SystemsReport {
+    /// Pairs of conflicting systems without explicit ordering.
+    pub conflicts: Vec<AccessConflict>,
+
+    /// Ordering constraints that can never be satisfied
+    /// because no system has the label.
+    pub unknown_labels: Vec<UnknownLabel>,
+
+    /// Names of systems that form a cycle of explicit ordering, if any.
+    pub cycle: Option<Vec<String>>,
+
+    /// Components that are written but never read with [`Access::Read`].
+    pub unread: Vec<&'static str>,
+
+    /// Components that are read but written by no system.
+    pub unwritten: Vec<&'static str>,
+}
+
+impl SystemsReport {
+    /// Returns `true` if no problems were found.
+    pub fn is_ok(&self) -> bool {
+        self.conflicts.is_empty()
+            && self.unknown_labels.is_empty()
+            && self.cycle.is_none()
+            && self.unread.is_empty()
+            && self.unwritten.is_empty()
+    }
+}
+
+impl fmt::Display for SystemsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_ok() {
+            return f.write_str("No problems found");
+        }
+
+        for conflict in &self.conflicts {
+            let kind = if conflict.resource {
+                "resource"
+            } else {
+                "component"
+            };
+            writeln!(
+                f,
+                "Systems `{}` and `{}` conflict on {} `{}` without explicit ordering",
+                conflict.first, conflict.second, kind, conflict.type_name
+            )?;
+        }
+        for unknown in &self.unknown_labels {
+            writeln!(
+                f,
+                "System `{}` is ordered relative to label `{}` that no system has",
+                unknown.system, unknown.label
+            )?;
+        }
+        if let Some(cycle) = &self.cycle {
+            writeln!(
+                f,
+                "Explicit ordering contains a cycle: {}",
+                cycle.join(" -> ")
+            )?;
+        }
+        for name in &self.unread {
+            writeln!(f, "Component `{}` is written but never read", name)?;
+        }
+        for name in &self.unwritten {
+            writeln!(f, "Component `{}` is read but never written", name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns `true` if two accesses can't happen at the same time.
+fn conflicts(lhs: Access, rhs: Access) -> bool {
+    matches!(lhs, Access::Write) || matches!(rhs, Access::Write)
+}
+
+/// Finds a cycle in explicit ordering.
+fn find_cycle(systems: &[SystemMeta]) -> Option<Vec<String>> {
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum Mark {
+        New,
+        InPath,
+        Done,
+    }
+
+    fn visit(
+        systems: &[SystemMeta],
+        idx: usize,
+        marks: &mut [Mark],
+        path: &mut Vec<usize>,
+    ) -> Option<Vec<String>> {
+        marks[idx] = Mark::InPath;
+        path.push(idx);
+
+        for (next, system) in systems.iter().enumerate() {
+            if !systems[idx].precedes(system) {
+                continue;
+            }
+            match marks[next] {
+                Mark::Done => {}
+                Mark::InPath => {
+                    let start = path.iter().position(|&p| p == next).unwrap();
+                    return Some(
+                        path[start..]
+                            .iter()
+                            .map(|&p| systems[p].name.clone())
+                            .collect(),
+                    );
+                }
+                Mark::New => {
+                    if let Some(cycle) = visit(systems, next, marks, path) {
+                        return Some(cycle);
+                    }
+                }
+            }
+        }
+
+        path.pop();
+        marks[idx] = Mark::Done;
+        None
+    }
+
+    let mut marks = vec![Mark::New; systems.len()];
+    let mut path = Vec::new();
+    (0..systems.len()).find_map(|idx| match marks[idx] {
+        Mark::New => visit(systems, idx, &mut marks, &mut path),
+        _ => None,
+    })
+}
+
+/// Validates declared accesses and ordering of systems
+/// without running them or constructing a [`World`](crate::world::World).
+///
+/// Reports conflicting systems that are ordered only by registration order,
+/// ordering constraints that refer to unknown labels,
+/// cycles in explicit ordering
+/// and components that are written by no one or read by no one.
+///
+/// # Example
+///
+/// ```
+/// # use edict::{system::SystemMeta, validate_systems};
+/// struct Pos;
+/// struct Vel;
+/// struct Physics;
+///
+/// let systems = [
+///     SystemMeta::new("physics").label::<Physics>().writes::<Pos>().reads::<Vel>(),
+///     SystemMeta::new("input").writes::<Vel>(),
+///     SystemMeta::new("render").reads::<Pos>().after::<Physics>(),
+/// ];
+///
+/// let report = validate_systems(&systems);
+/// assert_eq!(report.conflicts.len(), 1);
+/// assert_eq!(report.conflicts[0].first, "physics");
+/// assert_eq!(report.conflicts[0].second, "input");
+/// assert!(report.unread.is_empty());
+/// ```
+pub fn validate_systems(systems: &[SystemMeta]) -> SystemsReport {
+    let mut report = SystemsReport {
+        cycle: find_cycle(systems),
+        ..SystemsReport::default()
+    };
+
+    // Transitive closure of explicit ordering.
+    let n = systems.len();
+    let mut ordered = vec![false; n * n];
+    for (a, lhs) in systems.iter().enumerate() {
+        for (b, rhs) in systems.iter().enumerate() {
+            ordered[a * n + b] = lhs.precedes(rhs);
+        }
+    }
+    for k in 0..n {
+        for a in 0..n {
+            if ordered[a * n + k] {
+                for b in 0..n {
+                    if ordered[k * n + b] {
+                        ordered[a * n + b] = true;
+                    }
+                }
+            }
+        }
+    }
+
+    for (a, first) in systems.iter().enumerate() {
+        for (b, second) in systems.iter().enumerate().skip(a + 1) {
+            if ordered[a * n + b] || ordered[b * n + a] {
+                continue;
+            }
+
+            let pairs = [
+                (&first.components, &second.components, false),
+                (&first.resources, &second.resources, true),
+            ];
+            for (lhs, rhs, resource) in pairs {
+                for &(id, name, lhs_access) in lhs {
+                    let conflict = rhs.iter().any(|&(other, _, rhs_access)| {
+                        other == id && conflicts(lhs_access, rhs_access)
+                    });
+                    if conflict {
+                        report.conflicts.push(AccessConflict {
+                            first: first.name.clone(),
+                            second: second.name.clone(),
+                            type_name: name,
+                            resource,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    for system in systems {
+        for &(label, name) in system.before.iter().chain(&system.after) {
+            if !systems.iter().any(|other| other.has_label(label)) {
+                report.unknown_labels.push(UnknownLabel {
+                    system: system.name.clone(),
+                    label: name,
+                });
+            }
+        }
+    }
+
+    let mut components = Vec::new();
+    for system in systems {
+        for &(id, name, _) in &system.components {
+            if !components.iter().any(|&(other, _)| other == id) {
+                components.push((id, name));
+            }
+        }
+    }
+
+    for (id, name) in components {
+        let mut read = false;
+        let mut written = false;
+        for system in systems {
+            for &(other, _, access) in &system.components {
+                if other == id {
+                    match access {
+                        Access::Read => read = true,
+                        Access::Write => written = true,
+                    }
+                }
+            }
+        }
+
+        if !read {
+            report.unread.push(name);
+        }
+        if !written {
+            report.unwritten.push(name);
+        }
+    }
+
+    report
+}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::test::{Str, U32};
+
+    #[test]
+    fn validate_systems() {
+        use crate::system::SystemMeta;
+
+        struct A;
+        struct B;
+        struct Missing;
+        struct Time;
+
+        let systems = [
+            SystemMeta::new("a")
+                .label::<A>()
+                .after::<B>()
+                .writes::<U32>()
+                .reads_resource::<Time>(),
+            SystemMeta::new("b")
+                .label::<B>()
+                .after::<A>()
+                .reads::<U32>(),
+            SystemMeta::new("c")
+                .before::<Missing>()
+                .reads::<Str>()
+                .writes_resource::<Time>(),
+        ];
+
+        let report = crate::validate_systems(&systems);
+        assert!(!report.is_ok());
+
+        let cycle = report.cycle.as_ref().unwrap();
+        assert_eq!(cycle.len(), 2);
+        assert!(cycle.iter().any(|name| name == "a"));
+        assert!(cycle.iter().any(|name| name == "b"));
+
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].first, "a");
+        assert_eq!(report.conflicts[0].second, "c");
+        assert!(report.conflicts[0].resource);
+
+        assert_eq!(report.unknown_labels.len(), 1);
+        assert_eq!(report.unknown_labels[0].system, "c");
+
+        assert!(report.unread.is_empty());
+        assert_eq!(report.unwritten, [core::any::type_name::<Str>()]);
+
+        let systems = [
+            SystemMeta::new("write").label::<A>().writes::<U32>(),
+            SystemMeta::new("read").after::<A>().reads::<U32>(),
+        ];
+        assert!(crate::validate_systems(&systems).is_ok());
+    }
+}
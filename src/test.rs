@@ -8,15 +8,15 @@ use crate::{
 use alloc::{vec, vec::Vec};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-struct Str(&'static str);
+pub(crate) struct Str(pub(crate) &'static str);
 impl Component for Str {}
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-struct U32(u32);
+pub(crate) struct U32(pub(crate) u32);
 impl Component for U32 {}
 
 #[derive(Debug, PartialEq, Eq)]
-struct Bool(bool);
+pub(crate) struct Bool(pub(crate) bool);
 impl Component for Bool {}
 
 /// Tests that entity spawned into world has all components from bundle.
@@ -1,5 +1,6 @@
 use crate::{
     action::{ActionBuffer, ActionChannel},
+    archetype::{ColumnAllocator, GrowthPolicy},
     component::{
         Component, ComponentInfo, ComponentInfoRef, ComponentRegistry, ExternalDropHook,
         ExternalSetHook,
@@ -8,8 +9,11 @@ use crate::{
     res::Res,
 };
 
-use super::{ArchetypeSet, Edges, EpochCounter, World};
-use alloc::boxed::Box;
+use super::{
+    ArchetypeSet, DeferredDespawns, Edges, EpochCounter, Indexes, Invariants, LiveQueries, Quotas,
+    RelationReaders, SortKeys, Subscriptions, Trackers, World,
+};
+use alloc::{boxed::Box, sync::Arc};
 
 /// Builder for [`World`] value.
 ///
@@ -19,6 +23,10 @@ use alloc::boxed::Box;
 pub struct WorldBuilder {
     registry: ComponentRegistry,
     range_alloc: Option<Box<dyn IdRangeAllocator>>,
+    deterministic_ids: bool,
+    quotas: Quotas,
+    growth: GrowthPolicy,
+    column_allocator: Option<Arc<dyn ColumnAllocator>>,
 }
 
 impl WorldBuilder {
@@ -28,6 +36,10 @@ impl WorldBuilder {
         WorldBuilder {
             registry: ComponentRegistry::new(),
             range_alloc: None,
+            deterministic_ids: false,
+            quotas: Quotas::new(),
+            growth: GrowthPolicy::Doubling,
+            column_allocator: None,
         }
     }
 
@@ -35,6 +47,7 @@ impl WorldBuilder {
     #[must_use]
     pub fn build(self) -> World {
         let entities = match self.range_alloc {
+            None if self.deterministic_ids => EntitySet::deterministic(),
             None => EntitySet::new(),
             Some(range_alloc) => EntitySet::with_allocator(range_alloc),
         };
@@ -42,9 +55,20 @@ impl WorldBuilder {
         World {
             epoch: EpochCounter::new(),
             entities,
-            archetypes: ArchetypeSet::new(),
+            archetypes: ArchetypeSet::new(self.growth, self.column_allocator),
             edges: Edges::new(),
             res: Res::new(),
+            trackers: Trackers::new(),
+            live: LiveQueries::new(),
+            subscriptions: Subscriptions::new(),
+            relation_readers: RelationReaders::new(),
+            deferred_despawns: DeferredDespawns::new(),
+            indexes: Indexes::new(),
+            invariants: Invariants::new(),
+            quotas: self.quotas,
+            sort_keys: SortKeys::new(),
+            #[cfg(feature = "undo")]
+            undo_log: super::UndoLog::new(),
             registry: self.registry,
             action_buffer: Some(ActionBuffer::new()),
             action_channel: ActionChannel::new(),
@@ -81,8 +105,82 @@ impl WorldBuilder {
     ///
     /// If allocator is set, [`World`] will allocate IDs from ranges provided by the allocator.
     /// If allocator is exhausted, allocating new entities will panic.
+    ///
+    /// Disables deterministic ID allocation.
     pub fn with_id_range_allocator(mut self, range_alloc: Box<dyn IdRangeAllocator>) -> Self {
         self.range_alloc = Some(range_alloc);
+        self.deterministic_ids = false;
+        self
+    }
+
+    /// Enables deterministic ID allocation.
+    /// Replaces previously set ID range allocator.
+    ///
+    /// [`World`] will allocate IDs sequentially in order of allocation,
+    /// so applying the same operations to worlds in the same order
+    /// yields the same IDs.
+    /// IDs allocated through shared reference to the [`World`],
+    /// for example by [`ActionEncoder`]s of systems running in parallel,
+    /// are ordered by allocation calls and thus follow order of execution.
+    ///
+    /// In this mode ID allocator may be reset to a checkpoint
+    /// with [`World::id_checkpoint`] and [`World::reset_ids`].
+    ///
+    /// [`ActionEncoder`]: crate::action::ActionEncoder
+    pub fn deterministic_ids(mut self) -> Self {
+        self.range_alloc = None;
+        self.deterministic_ids = true;
+        self
+    }
+
+    /// Limits total number of entities in the world.
+    ///
+    /// Spawning more entities panics,
+    /// except [`World::spawn_checked`] that returns an error.
+    /// Entities spawned from reserved ids during maintenance are not limited.
+    ///
+    /// Limit can be changed later with [`World::set_max_entities`].
+    pub fn max_entities(mut self, limit: usize) -> Self {
+        self.quotas.max_entities = Some(limit);
+        self
+    }
+
+    /// Limits number of entities in every archetype.
+    ///
+    /// Spawning more entities into an archetype panics,
+    /// except [`World::spawn_checked`] that returns an error.
+    /// Moving entities between archetypes by inserting or removing components
+    /// is not limited.
+    ///
+    /// Limit can be changed later with [`World::set_max_archetype_entities`].
+    pub fn max_archetype_entities(mut self, limit: usize) -> Self {
+        self.quotas.max_per_archetype = Some(limit);
+        self
+    }
+
+    /// Sets growth policy of archetypes.
+    ///
+    /// [`GrowthPolicy::Exact`] minimizes memory usage on memory-constrained platforms.
+    /// Default is [`GrowthPolicy::Doubling`].
+    ///
+    /// Policy can be changed later with [`World::set_growth_policy`]
+    /// and [`World::set_archetype_growth_policy`].
+    pub fn growth_policy(mut self, policy: GrowthPolicy) -> Self {
+        self.growth = policy;
+        self
+    }
+
+    /// Sets allocator of component columns of all archetypes.
+    ///
+    /// Allows placing columns into shared memory
+    /// to be read by other processes with help of [`World::layout_manifest`].
+    /// By default small columns are allocated from the world's pool
+    /// and larger ones from the global allocator.
+    pub fn column_allocator<A>(mut self, allocator: A) -> Self
+    where
+        A: ColumnAllocator,
+    {
+        self.column_allocator = Some(Arc::new(allocator));
         self
     }
 }
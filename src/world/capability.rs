@@ -0,0 +1,395 @@
+//! Access-restricted view of the [`World`].
+
+use core::{
+    any::{type_name, TypeId},
+    cell::Cell,
+    fmt,
+};
+
+use hashbrown::HashMap;
+
+use crate::{
+    archetype::Archetype,
+    component::ComponentRegistry,
+    entity::EntityId,
+    epoch::EpochId,
+    hash::NoOpHasherBuilder,
+    query::{Access, DefaultQuery, IntoQuery, Query, QueryItem},
+};
+
+use super::{NoSuchEntity, QueryOne, QueryOneError, QueryRef, World};
+
+/// Set of component types a subsystem is allowed to access.
+///
+/// Used to create [`RestrictedWorld`] with [`World::restricted`].
+#[derive(Clone)]
+pub struct WorldCapability {
+    allowed: HashMap<TypeId, (Access, &'static str), NoOpHasherBuilder>,
+}
+
+impl fmt::Debug for WorldCapability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.allowed.values().map(|(access, name)| (name, access)))
+            .finish()
+    }
+}
+
+impl Default for WorldCapability {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WorldCapability {
+    /// Returns new capability that does not allow access to any component.
+    pub fn new() -> Self {
+        WorldCapability {
+            allowed: HashMap::with_hasher(NoOpHasherBuilder),
+        }
+    }
+
+    /// Allows reading component `T`.
+    #[must_use]
+    pub fn read<T>(mut self) -> Self
+    where
+        T: 'static,
+    {
+        self.allowed
+            .entry(TypeId::of::<T>())
+            .or_insert((Access::Read, type_name::<T>()));
+        self
+    }
+
+    /// Allows reading and writing component `T`.
+    #[must_use]
+    pub fn write<T>(mut self) -> Self
+    where
+        T: 'static,
+    {
+        self.allowed
+            .insert(TypeId::of::<T>(), (Access::Write, type_name::<T>()));
+        self
+    }
+
+    /// Returns `true` if capability allows specified access to component.
+    #[inline]
+    pub fn allows(&self, id: TypeId, access: Access) -> bool {
+        match self.allowed.get(&id) {
+            None => false,
+            Some((Access::Write, _)) => true,
+            Some((Access::Read, _)) => access == Access::Read,
+        }
+    }
+}
+
+/// Capability together with the world components it is checked against.
+///
+/// Carried by queries of [`RestrictedWorld`],
+/// so that queries extended after construction are checked as well.
+#[derive(Clone, Copy)]
+pub(crate) struct Restriction<'a> {
+    capability: &'a WorldCapability,
+    registry: &'a ComponentRegistry,
+}
+
+impl<'a> Restriction<'a> {
+    #[inline]
+    pub fn new(world: &'a World, capability: &'a WorldCapability) -> Self {
+        Restriction {
+            capability,
+            registry: &world.registry,
+        }
+    }
+
+    /// Checks that query accesses only allowed components.
+    ///
+    /// Every registered component type is checked,
+    /// so the result doesn't depend on which archetypes exist.
+    /// Archetypes are checked in addition for queries which access
+    /// depends on archetype, like borrowing queries.
+    pub fn check(&self, query: &impl Query, archetypes: &[Archetype]) -> Result<(), AccessDenied> {
+        for info in self.registry.iter_info() {
+            if let Some(access) = query.access(info.id()) {
+                if !self.capability.allows(info.id(), access) {
+                    return Err(AccessDenied {
+                        component: info.name(),
+                        access,
+                    });
+                }
+            }
+        }
+
+        let denied = Cell::new(None);
+
+        for archetype in archetypes {
+            if !query.visit_archetype(archetype) {
+                continue;
+            }
+
+            unsafe {
+                query.access_archetype(archetype, &|id, access| {
+                    if !self.capability.allows(id, access) {
+                        let component = archetype.component(id).unwrap_unchecked();
+                        denied.set(Some(AccessDenied {
+                            component: component.name(),
+                            access,
+                        }));
+                    }
+                });
+            }
+
+            if let Some(denied) = denied.take() {
+                return Err(denied);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Error returned when query of [`RestrictedWorld`]
+/// accesses component not allowed by its [`WorldCapability`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AccessDenied {
+    component: &'static str,
+    access: Access,
+}
+
+impl AccessDenied {
+    /// Returns name of the component that query is not allowed to access.
+    #[inline]
+    pub fn component(&self) -> &'static str {
+        self.component
+    }
+
+    /// Returns kind of access that was denied.
+    #[inline]
+    pub fn access(&self) -> Access {
+        self.access
+    }
+}
+
+impl fmt::Display for AccessDenied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.access {
+            Access::Read => write!(f, "Reading component `{}` is not allowed", self.component),
+            Access::Write => write!(f, "Writing component `{}` is not allowed", self.component),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AccessDenied {}
+
+/// View of the [`World`] that can access only components
+/// allowed by the [`WorldCapability`].
+///
+/// Every query is checked against the capability when it is constructed,
+/// and again when it is extended with more queries or filters.
+///
+/// Created with [`World::restricted`].
+#[derive(Clone, Copy)]
+pub struct RestrictedWorld<'a> {
+    world: &'a World,
+    capability: &'a WorldCapability,
+}
+
+impl fmt::Debug for RestrictedWorld<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RestrictedWorld")
+            .field("capability", self.capability)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a> RestrictedWorld<'a> {
+    /// Returns capability of this view.
+    #[inline]
+    pub fn capability(&self) -> &'a WorldCapability {
+        self.capability
+    }
+
+    /// Queries the world to iterate over entities and components specified by the query type.
+    ///
+    /// Fails if query accesses components not allowed by the capability.
+    /// Returned query keeps the restriction,
+    /// methods that extend it with not allowed access panic.
+    pub fn query<Q>(&self) -> Result<QueryRef<'a, (Q::Query,), ()>, AccessDenied>
+    where
+        Q: DefaultQuery,
+    {
+        self.query_with(Q::default_query())
+    }
+
+    /// Queries the world to iterate over entities and components specified by the query.
+    ///
+    /// Fails if query accesses components not allowed by the capability.
+    /// Returned query keeps the restriction,
+    /// methods that extend it with not allowed access panic.
+    pub fn query_with<Q>(&self, query: Q) -> Result<QueryRef<'a, (Q::Query,), ()>, AccessDenied>
+    where
+        Q: IntoQuery,
+    {
+        QueryRef::new_restricted(
+            self.world,
+            (query.into_query(),),
+            (),
+            Restriction::new(self.world, self.capability),
+        )
+    }
+
+    /// Queries components from specified entity.
+    /// Returns world borrow from which query item can be fetched.
+    ///
+    /// Fails if query accesses components not allowed by the capability.
+    pub fn query_one<Q>(
+        &self,
+        id: EntityId,
+    ) -> Result<Result<QueryOne<'a, Q>, NoSuchEntity>, AccessDenied>
+    where
+        Q: DefaultQuery,
+    {
+        Restriction::new(self.world, self.capability)
+            .check(&Q::default_query(), self.world.archetypes())?;
+        Ok(self.world.query_one::<Q>(id))
+    }
+
+    /// Queries components from specified entity.
+    /// Calls provided closure with query item.
+    ///
+    /// Fails with `Ok(Err(_))` if query is not satisfied by the entity or entity is missing.
+    pub fn for_one<Q, F, R>(
+        &self,
+        id: EntityId,
+        f: F,
+    ) -> Result<Result<R, QueryOneError>, AccessDenied>
+    where
+        Q: DefaultQuery,
+        F: for<'b> FnOnce(QueryItem<'b, Q>) -> R,
+    {
+        let mut query = self.query_with(Q::default_query())?;
+        Ok(query.for_one(id, f))
+    }
+
+    /// Returns `true` if specified entity is alive.
+    #[inline]
+    pub fn is_alive(&self, id: EntityId) -> bool {
+        self.world.is_alive(id)
+    }
+
+    /// Returns current world epoch.
+    #[inline]
+    pub fn epoch(&self) -> EpochId {
+        self.world.epoch()
+    }
+}
+
+impl World {
+    /// Returns view of the world restricted by the capability.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::{World, WorldCapability}, ExampleComponent};
+    /// #[derive(Component)]
+    /// struct Internal;
+    ///
+    /// let mut world = World::new();
+    /// world.spawn((ExampleComponent, Internal));
+    ///
+    /// let capability = WorldCapability::new().read::<ExampleComponent>();
+    /// let restricted = world.restricted(&capability);
+    ///
+    /// assert!(restricted.query::<&ExampleComponent>().is_ok());
+    /// assert!(restricted.query::<&mut ExampleComponent>().is_err());
+    /// assert!(restricted.query::<&Internal>().is_err());
+    /// ```
+    #[inline]
+    pub fn restricted<'a>(&'a self, capability: &'a WorldCapability) -> RestrictedWorld<'a> {
+        RestrictedWorld {
+            world: self,
+            capability,
+        }
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{component::Component, epoch::EpochId, query::Access, world::World};
+
+    use super::WorldCapability;
+
+    #[derive(Debug, PartialEq)]
+    struct Public(u32);
+    impl Component for Public {}
+
+    struct Private;
+    impl Component for Private {}
+
+    /// Tests that restricted world allows only queries within capability.
+    #[test]
+    fn capability_restriction() {
+        let mut world = World::new();
+        let e = world.spawn((Public(1), Private));
+
+        let capability = WorldCapability::new().read::<Public>();
+        let restricted = world.restricted(&capability);
+
+        assert_eq!(restricted.query::<&Public>().unwrap().iter().count(), 1);
+        assert_eq!(
+            restricted.for_one::<&Public, _, _>(e, |public| public.0),
+            Ok(Ok(1))
+        );
+
+        let denied = restricted.query::<&mut Public>().err().unwrap();
+        assert_eq!(denied.access(), Access::Write);
+        assert_eq!(denied.component(), core::any::type_name::<Public>());
+
+        let denied = restricted.query::<Option<&Private>>().err().unwrap();
+        assert_eq!(denied.access(), Access::Read);
+        assert!(restricted.query_one::<&Private>(e).is_err());
+
+        // Filters do not access components.
+        assert_eq!(
+            restricted
+                .query::<&Public>()
+                .unwrap()
+                .with::<Private>()
+                .iter()
+                .count(),
+            1
+        );
+    }
+
+    /// Tests that reading is not downgraded from writing and writing allows reading.
+    #[test]
+    fn capability_write() {
+        let mut world = World::new();
+        world.spawn((Public(1),));
+
+        let capability = WorldCapability::new().write::<Public>().read::<Public>();
+        let restricted = world.restricted(&capability);
+
+        assert!(restricted.query::<&Public>().is_ok());
+        assert!(restricted.query::<&mut Public>().is_ok());
+    }
+
+    /// Tests that extending restricted query with denied access panics.
+    #[test]
+    #[should_panic(expected = "Writing component")]
+    fn capability_restriction_extended() {
+        let mut world = World::new();
+        world.spawn((Public(1),));
+
+        let capability = WorldCapability::new().read::<Public>();
+        let restricted = world.restricted(&capability);
+
+        let _ = restricted
+            .query::<&Public>()
+            .unwrap()
+            .modified::<&mut Public>(EpochId::start());
+    }
+}
@@ -0,0 +1,206 @@
+//! Human-readable dump of the [`World`] structure.
+
+use alloc::vec::Vec;
+use core::{
+    any::TypeId,
+    fmt::{self, Display},
+    ptr::NonNull,
+};
+
+use smallvec::SmallVec;
+
+use crate::{archetype::Archetype, query::Access, relation::RelationOrigin};
+
+use super::World;
+
+/// Hierarchical formatter of the [`World`] contents.
+///
+/// Prints archetypes with entity counts and component names,
+/// followed by relation edges of entities in each archetype.
+///
+/// Created with [`World::debug_dump`].
+#[derive(Clone)]
+pub struct DebugDump<'a> {
+    world: &'a World,
+    filter: SmallVec<[TypeId; 4]>,
+    entities: bool,
+}
+
+impl<'a> DebugDump<'a> {
+    pub(super) fn new(world: &'a World) -> Self {
+        DebugDump {
+            world,
+            filter: SmallVec::new(),
+            entities: false,
+        }
+    }
+
+    /// Restricts the dump to archetypes that contain component `T`.
+    ///
+    /// Multiple filters are combined, archetype must contain all of them.
+    #[must_use]
+    pub fn with<T>(self) -> Self
+    where
+        T: 'static,
+    {
+        self.with_id(TypeId::of::<T>())
+    }
+
+    /// Restricts the dump to archetypes that contain component with specified id.
+    ///
+    /// Multiple filters are combined, archetype must contain all of them.
+    #[must_use]
+    pub fn with_id(mut self, id: TypeId) -> Self {
+        self.filter.push(id);
+        self
+    }
+
+    /// Enables listing of entity ids in each archetype.
+    #[must_use]
+    pub fn entities(mut self, enabled: bool) -> Self {
+        self.entities = enabled;
+        self
+    }
+
+    fn visit_archetype(&self, archetype: &Archetype) -> bool {
+        self.filter.iter().all(|&id| archetype.has_component(id))
+    }
+
+    fn fmt_archetype(
+        &self,
+        idx: usize,
+        archetype: &Archetype,
+        f: &mut fmt::Formatter,
+    ) -> fmt::Result {
+        writeln!(f, "  Archetype #{}: {} entities", idx, archetype.len())?;
+
+        let mut names = archetype
+            .infos()
+            .map(|info| info.name())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+
+        if !names.is_empty() {
+            writeln!(f, "    Components:")?;
+            for name in names {
+                writeln!(f, "      {}", name)?;
+            }
+        }
+
+        if self.entities && !archetype.is_empty() {
+            writeln!(f, "    Entities:")?;
+            for id in archetype.entities() {
+                writeln!(f, "      {}", id)?;
+            }
+        }
+
+        let relations = archetype
+            .borrow_indices(TypeId::of::<dyn RelationOrigin>())
+            .unwrap_or(&[]);
+
+        if !relations.is_empty() && !archetype.is_empty() {
+            writeln!(f, "    Relations:")?;
+            for &(id, borrow_idx) in relations {
+                let component = unsafe { archetype.component(id).unwrap_unchecked() };
+
+                writeln!(f, "      {}:", component.name())?;
+
+                // Skip components that are currently borrowed mutably.
+                if !unsafe { component.borrow(Access::Read) } {
+                    writeln!(f, "        <borrowed>")?;
+                    continue;
+                }
+
+                let borrow_fn = component.borrows()[borrow_idx].borrow::<dyn RelationOrigin>();
+                let size = component.layout().size();
+                let data = unsafe { component.data() };
+
+                let result =
+                    archetype
+                        .entities()
+                        .iter()
+                        .enumerate()
+                        .try_for_each(|(entity_idx, entity)| {
+                            let origin = unsafe {
+                                borrow_fn(
+                                    NonNull::new_unchecked(
+                                        data.ptr.as_ptr().add(entity_idx * size),
+                                    ),
+                                    core::marker::PhantomData,
+                                )
+                            };
+
+                            for target in origin.targets() {
+                                writeln!(f, "        {} -> {}", entity, target)?;
+                            }
+                            Ok(())
+                        });
+
+                unsafe { component.release(Access::Read) };
+                result?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for DebugDump<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let archetypes = self.world.archetypes();
+
+        writeln!(
+            f,
+            "World: {} entities, {} archetypes",
+            archetypes.iter().map(Archetype::len).sum::<usize>(),
+            archetypes.len()
+        )?;
+
+        for (idx, archetype) in archetypes.iter().enumerate() {
+            if self.visit_archetype(archetype) {
+                self.fmt_archetype(idx, archetype, f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for DebugDump<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{
+        relation::ChildOf,
+        test::{Bool, Str, U32},
+        world::World,
+    };
+
+    #[cfg(feature = "debug-dump")]
+    #[test]
+    fn debug_dump() {
+        use alloc::format;
+
+        let mut world = World::new();
+
+        let target = world.spawn((U32(1),));
+        let origin = world.spawn((U32(2), Str("origin")));
+        world.spawn((Bool(true),));
+        world.add_relation(origin, ChildOf, target).unwrap();
+
+        let dump = format!("{}", world.debug_dump());
+        assert!(dump.contains(core::any::type_name::<U32>()));
+        assert!(dump.contains(core::any::type_name::<Bool>()));
+        assert!(dump.contains(&format!("{} -> {}", origin, target)));
+
+        let dump = format!("{}", world.debug_dump().with::<Str>());
+        assert!(dump.contains(core::any::type_name::<Str>()));
+        assert!(!dump.contains(core::any::type_name::<Bool>()));
+    }
+}
@@ -0,0 +1,155 @@
+//! Two-phase despawning of entities.
+
+use alloc::{sync::Arc, vec::Vec};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{action::ActionBuffer, entity::EntityId};
+
+use super::{NoSuchEntity, World};
+
+/// Guard returned by [`World::despawn_deferred`].
+///
+/// Completes when entity is despawned in [`World::maintenance`].
+/// Dropping the guard does not cancel despawning.
+#[derive(Clone, Debug)]
+#[must_use = "Guard is the only way to observe completion of the despawn"]
+pub struct DespawnGuard {
+    id: EntityId,
+    complete: Arc<AtomicBool>,
+}
+
+impl DespawnGuard {
+    /// Returns id of the entity being despawned.
+    #[inline]
+    pub fn id(&self) -> EntityId {
+        self.id
+    }
+
+    /// Returns `true` if entity storage was reclaimed.
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.complete.load(Ordering::Acquire)
+    }
+}
+
+/// Entities waiting to be despawned in maintenance.
+pub(super) struct DeferredDespawns {
+    pending: Vec<(EntityId, Arc<AtomicBool>)>,
+}
+
+impl DeferredDespawns {
+    pub fn new() -> Self {
+        DeferredDespawns {
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl World {
+    /// Despawns entity in two phases.
+    ///
+    /// All components of the entity are disabled immediately,
+    /// so `&T` and `&mut T` queries skip it,
+    /// same as with [`World::set_enabled`].
+    /// Entity itself stays alive and its id keeps resolving
+    /// until next [`World::maintenance`], where it is despawned.
+    /// This allows systems running in the same frame to send cleanup messages
+    /// referring to the entity.
+    ///
+    /// Queries that ignore enabled state, like [`Entities`] or `Modified<&T>`,
+    /// still see the entity until it is despawned.
+    ///
+    /// Returns guard that reports when despawning is complete.
+    ///
+    /// [`Entities`]: crate::query::Entities
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut world = World::new();
+    /// let e = world.spawn((ExampleComponent,));
+    ///
+    /// let guard = world.despawn_deferred(e).unwrap();
+    /// assert_eq!(world.query::<&ExampleComponent>().iter().count(), 0);
+    /// assert!(world.is_alive(e));
+    /// assert!(!guard.is_complete());
+    ///
+    /// world.maintenance();
+    /// assert!(!world.is_alive(e));
+    /// assert!(guard.is_complete());
+    /// ```
+    pub fn despawn_deferred(&mut self, id: EntityId) -> Result<DespawnGuard, NoSuchEntity> {
+        self.maintenance();
+
+        let (archetype_idx, idx) = self.entities.get_location(id).ok_or(NoSuchEntity)?;
+        debug_assert_ne!(archetype_idx, u32::MAX, "Reserved entities are spawned");
+
+        let archetype = &self.archetypes[archetype_idx as usize];
+        for component in archetype.ids() {
+            let component = archetype.component(component).unwrap();
+
+            // Safety: world is borrowed mutably, no other borrows exist.
+            let data = unsafe { component.data_mut() };
+            data.set_enabled(idx as usize, false);
+        }
+
+        let complete = Arc::new(AtomicBool::new(false));
+        self.deferred_despawns.pending.push((id, complete.clone()));
+
+        Ok(DespawnGuard { id, complete })
+    }
+
+    /// Despawns entities passed to [`World::despawn_deferred`].
+    pub(super) fn complete_deferred_despawns(&mut self) {
+        if self.deferred_despawns.pending.is_empty() {
+            return;
+        }
+
+        let pending = core::mem::take(&mut self.deferred_despawns.pending);
+
+        // Maintenance may run inside a mutating call that holds world's buffer.
+        let mut buffer = ActionBuffer::new();
+        for (id, complete) in pending {
+            // Entity may be already despawned by other means.
+            let _ = self.despawn_with_buffer(id, &mut buffer);
+            complete.store(true, Ordering::Release);
+        }
+        buffer.execute(self);
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{
+        query::Entities,
+        test::{Str, U32},
+        world::{NoSuchEntity, World},
+    };
+
+    #[test]
+    fn despawn_deferred() {
+        let mut world = World::new();
+        let a = world.spawn((U32(1), Str("a")));
+        world.spawn((U32(2),));
+
+        let guard = world.despawn_deferred(a).unwrap();
+        assert_eq!(guard.id(), a);
+        assert!(!guard.is_complete());
+
+        // Hidden from queries, but still resolvable.
+        assert_eq!(world.query::<&U32>().iter().count(), 1);
+        assert_eq!(world.query::<&Str>().iter().count(), 0);
+        assert!(world.is_alive(a));
+        assert_eq!(world.query_one_mut::<Entities>(a), Ok(a));
+
+        assert_eq!(world.query_mut::<&mut U32>().iter_mut().count(), 1);
+
+        world.maintenance();
+        assert!(guard.is_complete());
+        assert!(!world.is_alive(a));
+        assert_eq!(world.query::<&U32>().iter().count(), 1);
+        assert_eq!(world.despawn_deferred(a).unwrap_err(), NoSuchEntity);
+    }
+}
@@ -0,0 +1,234 @@
+//! Comparison of component sets of two entities.
+
+use alloc::vec::Vec;
+use core::{any::TypeId, ptr::NonNull};
+
+use crate::{
+    archetype::{Archetype, ArchetypeComponent},
+    entity::EntityId,
+    query::Access,
+};
+
+use super::{NoSuchEntity, World};
+
+/// Kind of difference between components of two entities.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DiffKind {
+    /// Component is present only on the first entity.
+    OnlyFirst,
+
+    /// Component is present only on the second entity.
+    OnlySecond,
+
+    /// Component is present on both entities and values are not equal.
+    ///
+    /// Reported only for components registered with [`ComponentInfoRef::comparable`].
+    ///
+    /// [`ComponentInfoRef::comparable`]: crate::component::ComponentInfoRef::comparable
+    Value,
+}
+
+/// Difference of one component reported by [`World::diff_entities`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ComponentDiff {
+    /// Id of the component type.
+    pub id: TypeId,
+
+    /// Name of the component type.
+    pub name: &'static str,
+
+    /// Kind of difference.
+    pub kind: DiffKind,
+}
+
+/// Returns archetype and index of the entity.
+/// Reserved entities have no archetype.
+fn locate(world: &World, id: EntityId) -> Result<Option<(&Archetype, usize)>, NoSuchEntity> {
+    let (archetype_idx, idx) = world.entities.get_location(id).ok_or(NoSuchEntity)?;
+    if archetype_idx == u32::MAX {
+        return Ok(None);
+    }
+    Ok(Some((
+        &world.archetypes[archetype_idx as usize],
+        idx as usize,
+    )))
+}
+
+/// Returns pointer to the component of the entity at `idx`.
+///
+/// # Safety
+///
+/// `idx` must be in bounds of the archetype.
+/// Component must be borrowed for reading.
+unsafe fn component_ptr(component: &ArchetypeComponent, idx: usize) -> NonNull<u8> {
+    unsafe {
+        NonNull::new_unchecked(
+            component
+                .data()
+                .ptr
+                .as_ptr()
+                .add(idx * component.layout().size()),
+        )
+    }
+}
+
+impl World {
+    /// Compares sets of components of two entities.
+    ///
+    /// Reports components present on only one of the entities.
+    /// Components present on both are compared by value
+    /// if they are registered with [`ComponentInfoRef::comparable`],
+    /// otherwise they are considered equal.
+    ///
+    /// Useful to detect drift of an entity from its prefab
+    /// or to assert on entity state in tests.
+    ///
+    /// [`ComponentInfoRef::comparable`]: crate::component::ComponentInfoRef::comparable
+    ///
+    /// # Panics
+    ///
+    /// Panics if comparable component present on both entities is borrowed mutably.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::{DiffKind, World}, ExampleComponent};
+    /// #[derive(PartialEq, Component)]
+    /// struct Pos(i32);
+    ///
+    /// let mut builder = World::builder();
+    /// builder.register_component::<Pos>().comparable();
+    /// let mut world = builder.build();
+    ///
+    /// let prefab = world.spawn((Pos(0),));
+    /// let instance = world.spawn((Pos(0),));
+    /// assert!(world.diff_entities(prefab, instance).unwrap().is_empty());
+    ///
+    /// world.insert(instance, Pos(1)).unwrap();
+    /// world.insert(instance, ExampleComponent).unwrap();
+    ///
+    /// let diff = world.diff_entities(prefab, instance).unwrap();
+    /// assert_eq!(diff.len(), 2);
+    /// assert!(diff.iter().any(|d| d.kind == DiffKind::Value));
+    /// assert!(diff.iter().any(|d| d.kind == DiffKind::OnlySecond));
+    /// ```
+    pub fn diff_entities(
+        &self,
+        a: EntityId,
+        b: EntityId,
+    ) -> Result<Vec<ComponentDiff>, NoSuchEntity> {
+        let a = locate(self, a)?;
+        let b = locate(self, b)?;
+
+        let mut diff = Vec::new();
+
+        if let Some((archetype, idx)) = a {
+            for id in archetype.ids() {
+                let component = archetype.component(id).unwrap();
+
+                let other = b.and_then(|(archetype, idx)| Some((archetype.component(id)?, idx)));
+                let kind = match other {
+                    None => DiffKind::OnlyFirst,
+                    Some((other, other_idx)) => {
+                        if !component.is_comparable() {
+                            continue;
+                        }
+
+                        // Safety: indices are in bounds and components are borrowed.
+                        let eq = unsafe {
+                            if !component.borrow(Access::Read) {
+                                panic!("Component `{}` is borrowed mutably", component.name());
+                            }
+                            if !other.borrow(Access::Read) {
+                                component.release(Access::Read);
+                                panic!("Component `{}` is borrowed mutably", component.name());
+                            }
+
+                            let eq = component.eq_one(
+                                component_ptr(component, idx),
+                                component_ptr(other, other_idx),
+                            );
+
+                            component.release(Access::Read);
+                            other.release(Access::Read);
+                            eq
+                        };
+
+                        if eq {
+                            continue;
+                        }
+                        DiffKind::Value
+                    }
+                };
+
+                diff.push(ComponentDiff {
+                    id,
+                    name: component.name(),
+                    kind,
+                });
+            }
+        }
+
+        if let Some((archetype, _)) = b {
+            for id in archetype.ids() {
+                if a.map_or(false, |(archetype, _)| archetype.has_component(id)) {
+                    continue;
+                }
+
+                let component = archetype.component(id).unwrap();
+                diff.push(ComponentDiff {
+                    id,
+                    name: component.name(),
+                    kind: DiffKind::OnlySecond,
+                });
+            }
+        }
+
+        Ok(diff)
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{
+        test::{Bool, Str, U32},
+        world::{NoSuchEntity, World},
+    };
+
+    #[test]
+    fn diff_entities() {
+        use crate::world::DiffKind;
+        use core::any::TypeId;
+
+        let mut builder = World::builder();
+        builder.register_component::<U32>().comparable();
+        let mut world = builder.build();
+
+        let a = world.spawn((U32(1), Str("a")));
+        let b = world.spawn((U32(1), Bool(true)));
+
+        let mut diff = world.diff_entities(a, b).unwrap();
+        diff.sort_by_key(|d| d.name);
+        assert_eq!(diff.len(), 2);
+        assert_eq!(diff[0].id, TypeId::of::<Bool>());
+        assert_eq!(diff[0].kind, DiffKind::OnlySecond);
+        assert_eq!(diff[1].id, TypeId::of::<Str>());
+        assert_eq!(diff[1].kind, DiffKind::OnlyFirst);
+
+        world.insert(b, U32(2)).unwrap();
+        world.insert(b, Str("b")).unwrap();
+        world.remove::<Bool>(b).unwrap();
+
+        // `Str` is not comparable.
+        let diff = world.diff_entities(a, b).unwrap();
+        assert_eq!(diff.len(), 1);
+        assert_eq!(diff[0].id, TypeId::of::<U32>());
+        assert_eq!(diff[0].kind, DiffKind::Value);
+
+        assert!(world.diff_entities(a, a).unwrap().is_empty());
+
+        world.despawn(b).unwrap();
+        assert_eq!(world.diff_entities(a, b), Err(NoSuchEntity));
+    }
+}
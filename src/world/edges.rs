@@ -188,7 +188,11 @@ impl Edges {
             cold();
             match archetypes.iter().position(|a| {
                 bundle.with_ids(|ids| {
-                    let ids = archetypes[src as usize].ids().chain(ids.iter().copied());
+                    // Components replaced by the bundle are counted once.
+                    let ids = archetypes[src as usize]
+                        .ids()
+                        .filter(|aid| !ids.contains(aid))
+                        .chain(ids.iter().copied());
                     a.matches(ids)
                 })
             }) {
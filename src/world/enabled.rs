@@ -0,0 +1,125 @@
+//! Enabling and disabling components without removal.
+
+use core::any::TypeId;
+
+use crate::entity::EntityId;
+
+use super::{EntityError, World};
+
+impl World {
+    /// Enables or disables component `T` of the entity.
+    ///
+    /// Disabled component stays in place, so entity doesn't move
+    /// between archetypes, but `&T` and `&mut T` queries skip the entity
+    /// until component is enabled again.
+    /// Other queries of the component, like `Modified<&T>`, ignore enabled state.
+    ///
+    /// Enabled state follows component when entity moves between archetypes.
+    /// Inserting new value keeps the state, removing component resets it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut world = World::new();
+    /// let e = world.spawn((ExampleComponent,));
+    ///
+    /// world.set_enabled::<ExampleComponent>(e, false).unwrap();
+    /// assert_eq!(world.query::<&ExampleComponent>().iter().count(), 0);
+    /// assert_eq!(world.is_enabled::<ExampleComponent>(e), Ok(false));
+    ///
+    /// world.set_enabled::<ExampleComponent>(e, true).unwrap();
+    /// assert_eq!(world.query::<&ExampleComponent>().iter().count(), 1);
+    /// ```
+    pub fn set_enabled<T>(&mut self, id: EntityId, enabled: bool) -> Result<(), EntityError>
+    where
+        T: 'static,
+    {
+        self.maintenance();
+
+        let (archetype_idx, idx) = self
+            .entities
+            .get_location(id)
+            .ok_or(EntityError::NoSuchEntity)?;
+        if archetype_idx == u32::MAX {
+            return Err(EntityError::MissingComponents);
+        }
+
+        let component = self.archetypes[archetype_idx as usize]
+            .component(TypeId::of::<T>())
+            .ok_or(EntityError::MissingComponents)?;
+
+        // Safety: world is borrowed mutably, no other borrows exist.
+        let data = unsafe { component.data_mut() };
+        data.set_enabled(idx as usize, enabled);
+        Ok(())
+    }
+
+    /// Checks if component `T` of the entity is enabled.
+    ///
+    /// See [`World::set_enabled`].
+    pub fn is_enabled<T>(&self, id: EntityId) -> Result<bool, EntityError>
+    where
+        T: 'static,
+    {
+        let (archetype_idx, idx) = self
+            .entities
+            .get_location(id)
+            .ok_or(EntityError::NoSuchEntity)?;
+        if archetype_idx == u32::MAX {
+            return Err(EntityError::MissingComponents);
+        }
+
+        let component = self.archetypes[archetype_idx as usize]
+            .component(TypeId::of::<T>())
+            .ok_or(EntityError::MissingComponents)?;
+
+        // Safety: enabled bits are modified only with mutable borrow of the world.
+        let data = unsafe { component.data() };
+        Ok(data.is_enabled(idx as usize))
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{
+        test::{Bool, Str, U32},
+        world::{EntityError, World},
+    };
+
+    /// Tests that disabled components are skipped by queries
+    /// and keep their state when entities move.
+    #[test]
+    fn enabled_components() {
+        let mut world = World::new();
+
+        let a = world.spawn((U32(0),));
+        let b = world.spawn((U32(1),));
+        let c = world.spawn((U32(2),));
+
+        world.set_enabled::<U32>(c, false).unwrap();
+        assert_eq!(world.query::<&U32>().iter().count(), 2);
+        assert_eq!(world.query::<&mut U32>().iter_mut().count(), 2);
+
+        // `c` is swapped into the slot of `a`.
+        world.despawn(a).unwrap();
+        assert_eq!(world.is_enabled::<U32>(b), Ok(true));
+        assert_eq!(world.is_enabled::<U32>(c), Ok(false));
+
+        // `c` moves to another archetype.
+        world.insert(c, Str("c")).unwrap();
+        assert_eq!(world.is_enabled::<U32>(c), Ok(false));
+        assert_eq!(world.is_enabled::<Str>(c), Ok(true));
+        assert_eq!(world.query::<(&U32, &Str)>().iter().count(), 0);
+        assert_eq!(world.query::<&Str>().iter().count(), 1);
+
+        world.set_enabled::<U32>(c, true).unwrap();
+        assert_eq!(world.query::<&U32>().iter().count(), 2);
+
+        assert_eq!(
+            world.set_enabled::<Bool>(b, false),
+            Err(EntityError::MissingComponents)
+        );
+    }
+}
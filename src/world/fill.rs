@@ -0,0 +1,130 @@
+//! Bulk overwrite of component values.
+
+use core::any::TypeId;
+
+use crate::{
+    archetype::{chunk_idx, CHUNK_LEN_USIZE},
+    component::Component,
+    query::{Fetch, ImmutableQuery, IntoQuery, Query},
+};
+
+use super::World;
+
+impl World {
+    /// Writes clone of `value` into component `T` of all entities that have it.
+    ///
+    /// Returns number of overwritten components.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Clone, Component, Debug, PartialEq)]
+    /// struct Force(f32);
+    ///
+    /// let mut world = World::new();
+    /// let a = world.spawn((Force(1.0),));
+    /// let b = world.spawn((Force(2.0),));
+    ///
+    /// assert_eq!(world.fill(Force(0.0)), 2);
+    /// assert_eq!(world.query_one_mut::<&Force>(a), Ok(&Force(0.0)));
+    /// assert_eq!(world.query_one_mut::<&Force>(b), Ok(&Force(0.0)));
+    /// ```
+    #[inline]
+    pub fn fill<T>(&mut self, value: T) -> usize
+    where
+        T: Component + Clone,
+    {
+        self.fill_filtered((), value)
+    }
+
+    /// Writes clone of `value` into component `T` of all entities
+    /// that have it and match the `filter`.
+    ///
+    /// Values are written column-wise, archetype by archetype.
+    /// Epoch of each chunk is bumped once.
+    ///
+    /// Returns number of overwritten components.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, query::With, world::World};
+    /// #[derive(Clone, Component, Debug, PartialEq)]
+    /// struct Force(f32);
+    ///
+    /// #[derive(Component)]
+    /// struct Frozen;
+    ///
+    /// let mut world = World::new();
+    /// let a = world.spawn((Force(1.0),));
+    /// let b = world.spawn((Force(2.0), Frozen));
+    ///
+    /// assert_eq!(world.fill_filtered(With::<Frozen>::query(), Force(0.0)), 1);
+    /// assert_eq!(world.query_one_mut::<&Force>(a), Ok(&Force(1.0)));
+    /// assert_eq!(world.query_one_mut::<&Force>(b), Ok(&Force(0.0)));
+    /// ```
+    pub fn fill_filtered<T, F>(&mut self, filter: F, value: T) -> usize
+    where
+        T: Component + Clone,
+        F: IntoQuery,
+        F::Query: ImmutableQuery,
+    {
+        self.maintenance();
+
+        let mut filter = filter.into_query();
+        let epoch = self.epoch.next_mut();
+        let mut count = 0;
+
+        for archetype in self.archetypes.iter() {
+            if archetype.is_empty() || !filter.visit_archetype(archetype) {
+                continue;
+            }
+
+            let component = match archetype.component(TypeId::of::<T>()) {
+                None => continue,
+                Some(component) => component,
+            };
+
+            // Safety: world is borrowed mutably, no other borrows exist.
+            let data = unsafe { component.data_mut() };
+            data.epoch.bump(epoch);
+
+            let ptr = data.ptr.cast::<T>().as_ptr();
+            let entity_epochs = data.entity_epochs.as_mut_ptr();
+            let chunk_epochs = data.chunk_epochs.as_mut_ptr();
+
+            let mut fetch = unsafe { filter.fetch(archetype, epoch) };
+            let len = archetype.len();
+
+            for chunk in 0..=chunk_idx(len - 1) {
+                if !unsafe { fetch.visit_chunk(chunk) } {
+                    continue;
+                }
+
+                let start = chunk * CHUNK_LEN_USIZE;
+                let end = len.min(start + CHUNK_LEN_USIZE);
+                let mut touched = false;
+
+                for idx in start..end {
+                    if !unsafe { fetch.visit_item(idx) } {
+                        continue;
+                    }
+
+                    unsafe {
+                        *ptr.add(idx) = value.clone();
+                        (*entity_epochs.add(idx)).bump(epoch);
+                    }
+                    touched = true;
+                    count += 1;
+                }
+
+                if touched {
+                    unsafe { (*chunk_epochs.add(chunk)).bump(epoch) }
+                }
+            }
+        }
+
+        count
+    }
+}
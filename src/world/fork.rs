@@ -0,0 +1,238 @@
+//! Forking of the [`World`] for speculative simulation.
+
+use core::fmt;
+
+use crate::{
+    action::{ActionBuffer, ActionChannel},
+    epoch::EpochCounter,
+    res::Res,
+};
+
+use super::{
+    DeferredDespawns, Edges, Indexes, LiveQueries, RelationReaders, Subscriptions, Trackers, World,
+};
+
+/// Error returned by [`World::fork`]
+/// when entity has component that is not registered as cloneable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NotCloneable {
+    name: &'static str,
+}
+
+impl NotCloneable {
+    /// Returns name of the component that is not cloneable.
+    #[inline]
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl fmt::Display for NotCloneable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Component `{}` is not registered as cloneable",
+            self.name
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NotCloneable {}
+
+impl World {
+    /// Returns independent copy-on-write copy of the world.
+    ///
+    /// Component columns are shared by the world and the fork
+    /// until either of them modifies the column, at which point
+    /// that world clones components of the column into its own storage.
+    /// Forking is cheap and speculative simulation that touches few components
+    /// copies only columns it writes.
+    ///
+    /// Entities keep their ids, component epochs are copied,
+    /// so change detection in the fork continues from the state of this world.
+    /// Entities spawned in the fork get ids that would be spawned next
+    /// in this world, so ids may collide with entities spawned here after the fork.
+    ///
+    /// Components of all entities must be registered with [`ComponentInfoRef::cloneable`].
+    /// Resources, change trackers, live queries, subscriptions,
+    /// readers of relation events and indexes are not copied.
+    ///
+    /// This is useful for speculative simulation,
+    /// like AI lookahead or client-side prediction.
+    ///
+    /// [`ComponentInfoRef::cloneable`]: crate::component::ComponentInfoRef::cloneable
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Clone, Component, Debug, PartialEq)]
+    /// struct Pos(i32);
+    ///
+    /// let mut builder = World::builder();
+    /// builder.register_component::<Pos>().cloneable();
+    /// let mut world = builder.build();
+    ///
+    /// let e = world.spawn((Pos(0),));
+    ///
+    /// let mut fork = world.fork().unwrap();
+    /// fork.query_one_mut::<&mut Pos>(e).unwrap().0 = 1;
+    ///
+    /// assert_eq!(world.query_one_mut::<&Pos>(e), Ok(&Pos(0)));
+    /// assert_eq!(fork.query_one_mut::<&Pos>(e), Ok(&Pos(1)));
+    /// ```
+    pub fn fork(&mut self) -> Result<World, NotCloneable> {
+        self.maintenance();
+
+        for archetype in self.archetypes.iter() {
+            if archetype.is_empty() {
+                continue;
+            }
+            if let Some(info) = archetype.infos().find(|info| !info.is_cloneable()) {
+                return Err(NotCloneable { name: info.name() });
+            }
+        }
+
+        Ok(World {
+            epoch: EpochCounter::starting_at(self.epoch.current_mut()),
+            entities: self.entities.fork(),
+            archetypes: self.archetypes.fork(),
+            edges: Edges::new(),
+            registry: self.registry.fork(),
+            res: Res::new(),
+            trackers: Trackers::new(),
+            live: LiveQueries::new(),
+            subscriptions: Subscriptions::new(),
+            relation_readers: RelationReaders::new(),
+            deferred_despawns: DeferredDespawns::new(),
+            indexes: Indexes::new(),
+            invariants: self.invariants.fork(),
+            quotas: self.quotas,
+            sort_keys: self.sort_keys.fork(),
+            #[cfg(feature = "undo")]
+            undo_log: super::UndoLog::new(),
+            action_buffer: Some(ActionBuffer::new()),
+            action_channel: ActionChannel::new(),
+        })
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::vec::Vec;
+
+    use crate::{
+        query::Entities,
+        test::{Bool, Str, U32},
+        world::World,
+    };
+
+    /// Tests that forked world is independent from the original.
+    #[test]
+    fn world_fork() {
+        let mut builder = World::builder();
+        builder.register_component::<U32>().cloneable();
+        builder.register_component::<Str>().cloneable();
+        let mut world = builder.build();
+
+        let a = world.spawn((U32(1),));
+        let b = world.spawn((U32(2), Str("b")));
+        let gone = world.spawn((Str("gone"),));
+        world.despawn(gone).unwrap();
+
+        let epoch = world.epoch();
+        world.query_one_mut::<&mut U32>(b).unwrap().0 = 3;
+
+        let mut fork = world.fork().unwrap();
+        assert_eq!(fork.query_one_mut::<&U32>(a), Ok(&U32(1)));
+        assert_eq!(fork.query_one_mut::<&Str>(b), Ok(&Str("b")));
+
+        let modified = fork
+            .query::<Entities>()
+            .modified::<&U32>(epoch)
+            .iter()
+            .map(|(e, _)| e)
+            .collect::<Vec<_>>();
+        assert_eq!(modified, [b]);
+
+        fork.query_one_mut::<&mut U32>(a).unwrap().0 = 10;
+        let c = fork.spawn((U32(4),));
+        assert_ne!(c, a);
+        assert_ne!(c, b);
+        fork.despawn(b).unwrap();
+
+        assert_eq!(world.query_one_mut::<&U32>(a), Ok(&U32(1)));
+        assert_eq!(world.query_one_mut::<&U32>(b), Ok(&U32(3)));
+        assert_eq!(fork.query::<&U32>().iter().count(), 2);
+
+        world.spawn((Bool(true),));
+        assert_eq!(
+            world.fork().unwrap_err().name(),
+            core::any::type_name::<Bool>()
+        );
+    }
+
+    /// Tests that columns are shared until modified
+    /// and shared components are dropped once.
+    #[test]
+    fn fork_copy_on_write() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        use crate::component::Component;
+
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(Clone)]
+        struct Counted(u32);
+
+        impl Component for Counted {}
+
+        impl Drop for Counted {
+            fn drop(&mut self) {
+                DROPS.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        fn column<T: Component + Sync>(world: &World) -> *const T {
+            world.query::<&T>().iter().next().unwrap()
+        }
+
+        let mut builder = World::builder();
+        builder.register_component::<U32>().cloneable();
+        builder.register_component::<Counted>().cloneable();
+        let mut world = builder.build();
+
+        for i in 0..10 {
+            world.spawn((U32(i), Counted(i)));
+        }
+
+        let mut fork = world.fork().unwrap();
+        assert_eq!(column::<Counted>(&world), column::<Counted>(&fork));
+        assert_eq!(column::<U32>(&world), column::<U32>(&fork));
+
+        fork.query_mut::<&mut Counted>().for_each(|c| c.0 += 10);
+        assert_ne!(column::<Counted>(&world), column::<Counted>(&fork));
+        assert_eq!(column::<U32>(&world), column::<U32>(&fork));
+
+        let sum = |world: &World| world.query::<&Counted>().iter().map(|c| c.0).sum::<u32>();
+        assert_eq!(sum(&world), 45);
+        assert_eq!(sum(&fork), 145);
+
+        drop(fork);
+        assert_eq!(DROPS.load(Ordering::Relaxed), 10);
+
+        // Last world sharing the column takes it without cloning.
+        let mut fork = world.fork().unwrap();
+        drop(world);
+        assert_eq!(DROPS.load(Ordering::Relaxed), 10);
+
+        fork.query_mut::<&mut Counted>().for_each(|c| c.0 += 1);
+        assert_eq!(sum(&fork), 55);
+        assert_eq!(DROPS.load(Ordering::Relaxed), 10);
+
+        drop(fork);
+        assert_eq!(DROPS.load(Ordering::Relaxed), 20);
+    }
+}
@@ -0,0 +1,147 @@
+//! Guards that hold borrow locks of single components.
+
+use core::{any::TypeId, fmt, marker::PhantomData, ops::Deref, ptr::NonNull};
+
+use crate::{archetype::ArchetypeComponent, entity::EntityId, query::Access};
+
+use super::{EntityError, World};
+
+/// Shared borrow of entity's component.
+///
+/// Guard owns borrow lock of the component column
+/// and releases it on drop.
+/// Unlike references produced by queries it does not borrow any query state,
+/// and it is `Send` and `Sync` for `Sync` components,
+/// so it can be held across await points in async tasks.
+///
+/// While guard is alive, queries that access the component mutably
+/// fail to lock the column in the entity's archetype.
+///
+/// Created with [`World::borrow_component`].
+pub struct ComponentGuard<'a, T> {
+    component: &'a ArchetypeComponent,
+    value: NonNull<T>,
+    marker: PhantomData<&'a T>,
+}
+
+// Safety: guard provides only shared access to `T`.
+// Lock release is thread-safe.
+unsafe impl<T> Send for ComponentGuard<'_, T> where T: Sync {}
+unsafe impl<T> Sync for ComponentGuard<'_, T> where T: Sync {}
+
+impl<T> Deref for ComponentGuard<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        // Safety: column is locked for reading while guard is alive.
+        unsafe { self.value.as_ref() }
+    }
+}
+
+impl<T> fmt::Debug for ComponentGuard<'_, T>
+where
+    T: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T> Drop for ComponentGuard<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            self.component.release(Access::Read);
+        }
+    }
+}
+
+impl World {
+    /// Borrows component of the entity.
+    ///
+    /// Returned guard holds borrow lock of the component
+    /// and can be held across await points.
+    ///
+    /// # Panics
+    ///
+    /// Panics if component is borrowed mutably.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut world = World::new();
+    /// let e = world.spawn((ExampleComponent,));
+    ///
+    /// let guard = world.borrow_component::<ExampleComponent>(e).unwrap();
+    ///
+    /// std::thread::scope(|scope| {
+    ///     scope.spawn(move || drop(guard));
+    /// });
+    ///
+    /// for _ in world.query::<&mut ExampleComponent>().iter_mut() {}
+    /// ```
+    pub fn borrow_component<T>(&self, id: EntityId) -> Result<ComponentGuard<'_, T>, EntityError>
+    where
+        T: Sync + 'static,
+    {
+        let (archetype_idx, idx) = self
+            .entities
+            .get_location(id)
+            .ok_or(EntityError::NoSuchEntity)?;
+
+        if archetype_idx == u32::MAX {
+            return Err(EntityError::MissingComponents);
+        }
+
+        let component = self.archetypes[archetype_idx as usize]
+            .component(TypeId::of::<T>())
+            .ok_or(EntityError::MissingComponents)?;
+
+        unsafe {
+            if !component.borrow(Access::Read) {
+                panic!("Failed to lock `{}` from archetype", component.name());
+            }
+
+            let data = component.data();
+            let value = NonNull::new_unchecked(data.ptr.as_ptr().cast::<T>().add(idx as usize));
+
+            Ok(ComponentGuard {
+                component,
+                value,
+                marker: PhantomData,
+            })
+        }
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{
+        test::U32,
+        world::{EntityError, World},
+    };
+
+    /// Tests that component guard holds borrow lock until dropped.
+    #[test]
+    fn component_guard() {
+        let mut world = World::new();
+        let e = world.spawn((U32(1),));
+        let empty = world.spawn(());
+
+        let guard = world.borrow_component::<U32>(e).unwrap();
+        assert_eq!(*guard, U32(1));
+        assert_eq!(
+            world.borrow_component::<U32>(empty).unwrap_err(),
+            EntityError::MissingComponents
+        );
+
+        assert!(world.query::<&U32>().try_ensure_borrow().is_ok());
+        assert!(world.query::<&mut U32>().try_ensure_borrow().is_err());
+
+        drop(guard);
+        assert!(world.query::<&mut U32>().try_ensure_borrow().is_ok());
+    }
+}
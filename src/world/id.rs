@@ -0,0 +1,92 @@
+//! Identifiers of worlds.
+
+use core::{
+    fmt,
+    num::NonZeroU64,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+static NEXT_WORLD_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Unique identifier of a [`World`](super::World).
+///
+/// Every world gets new identifier when built or forked.
+/// Entity ids of the original world stay valid in the fork.
+///
+/// With `"world-id"` feature enabled, entity ids are stamped
+/// with identifier of the world that allocated them.
+/// Stamp is shown in debug output of [`EntityId`],
+/// and passing stamped id to another world panics
+/// instead of silently accessing unrelated entity.
+/// Ids created with [`EntityId::from_bits`] are not stamped.
+///
+/// [`EntityId`]: crate::entity::EntityId
+/// [`EntityId::from_bits`]: crate::entity::EntityId::from_bits
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct WorldId {
+    value: NonZeroU64,
+}
+
+impl WorldId {
+    /// Returns new unique world id.
+    pub(crate) fn new() -> Self {
+        let value = NEXT_WORLD_ID.fetch_add(1, Ordering::Relaxed);
+        WorldId {
+            value: NonZeroU64::new(value).expect("World id counter overflow"),
+        }
+    }
+
+    /// Returns integer value of the id.
+    #[inline]
+    pub fn get(&self) -> u64 {
+        self.value.get()
+    }
+}
+
+impl fmt::Debug for WorldId {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "WorldId({})", self.value)
+    }
+}
+
+impl fmt::Display for WorldId {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "world#{}", self.value)
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{test::U32, world::World};
+
+    #[test]
+    fn world_id() {
+        let mut builder = World::builder();
+        builder.register_component::<U32>().cloneable();
+        let mut world = builder.build();
+        let other = World::new();
+        assert_ne!(world.id(), other.id());
+
+        let e = world.spawn((U32(0),));
+        let mut fork = world.fork().unwrap();
+        assert_ne!(fork.id(), world.id());
+        assert_eq!(fork.query_one_mut::<&U32>(e), Ok(&U32(0)));
+    }
+
+    #[test]
+    #[cfg(feature = "world-id")]
+    #[should_panic(expected = "belongs to")]
+    fn world_id_mismatch() {
+        let mut world = World::new();
+        let mut other = World::new();
+
+        let e = world.spawn((U32(0),));
+        assert_eq!(e.world(), Some(world.id()));
+
+        let _ = other.despawn(e);
+    }
+}
@@ -0,0 +1,333 @@
+//! Indexes of entities by keys extracted from components.
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{
+    any::{Any, TypeId},
+    fmt,
+    hash::Hash,
+};
+
+use hashbrown::HashMap;
+
+use crate::{
+    archetype::{chunk_idx, Archetype, CHUNK_LEN_USIZE},
+    component::Component,
+    entity::EntityId,
+    epoch::EpochId,
+};
+
+use super::World;
+
+/// Index of entities by key extracted from component `T`.
+///
+/// Created with [`World::add_index`] and updated by the world
+/// during maintenance, which happens at the start of every method
+/// that borrows world mutably, or when [`World::maintenance`] is called explicitly.
+/// Keys are re-extracted only for components modified since last maintenance.
+///
+/// Many entities may share the same key.
+pub struct Indexed<T, K> {
+    extract: fn(&T) -> K,
+    entities: HashMap<K, Vec<EntityId>>,
+    keys: HashMap<EntityId, K>,
+    after_epoch: EpochId,
+}
+
+impl<T, K> fmt::Debug for Indexed<T, K>
+where
+    K: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.entities.iter()).finish()
+    }
+}
+
+impl<T, K> Indexed<T, K>
+where
+    K: Hash + Eq + Clone,
+{
+    /// Returns entities with specified key.
+    #[inline]
+    pub fn get(&self, key: &K) -> &[EntityId] {
+        match self.entities.get(key) {
+            None => &[],
+            Some(entities) => entities,
+        }
+    }
+
+    /// Returns key of the entity.
+    /// Returns `None` if entity is not indexed.
+    #[inline]
+    pub fn key(&self, entity: EntityId) -> Option<&K> {
+        self.keys.get(&entity)
+    }
+
+    /// Returns number of indexed entities.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns `true` if no entities are indexed.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    fn update(&mut self, entity: EntityId, key: K) {
+        if self.keys.get(&entity) == Some(&key) {
+            return;
+        }
+
+        self.remove(entity);
+        self.entities.entry(key.clone()).or_default().push(entity);
+        self.keys.insert(entity, key);
+    }
+
+    /// Indexes all entities with component `T`.
+    fn fill(&mut self, archetypes: &[Archetype])
+    where
+        T: 'static,
+    {
+        for archetype in archetypes {
+            let Some(component) = archetype.component(TypeId::of::<T>()) else {
+                continue;
+            };
+
+            // Safety: world is borrowed mutably, no fetches may be alive.
+            let data = unsafe { component.data() };
+            for (idx, &entity) in archetype.entities().iter().enumerate() {
+                // Safety: `idx` is within archetype and column stores `T`.
+                let value = unsafe { &*data.ptr.cast::<T>().as_ptr().add(idx) };
+                self.update(entity, (self.extract)(value));
+            }
+        }
+    }
+
+    fn remove(&mut self, entity: EntityId) {
+        let Some(key) = self.keys.remove(&entity) else {
+            return;
+        };
+
+        let entities = self.entities.get_mut(&key).unwrap();
+        let idx = entities.iter().position(|&e| e == entity).unwrap();
+        entities.swap_remove(idx);
+
+        if entities.is_empty() {
+            self.entities.remove(&key);
+        }
+    }
+}
+
+/// Type-erased index stored in the world.
+trait AnyIndex: Send + Sync {
+    fn harvest(&mut self, archetypes: &[Archetype], journal: &[(EntityId, u32)], epoch: EpochId);
+
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T, K> AnyIndex for Indexed<T, K>
+where
+    T: Component,
+    K: Hash + Eq + Clone + Send + Sync + 'static,
+{
+    fn harvest(&mut self, archetypes: &[Archetype], journal: &[(EntityId, u32)], epoch: EpochId) {
+        // Drop entities that lost the component or were despawned.
+        for &(id, archetype) in journal {
+            if archetype == u32::MAX
+                || !archetypes[archetype as usize].has_component(TypeId::of::<T>())
+            {
+                self.remove(id);
+            }
+        }
+
+        // Inserted components are modified too.
+        for archetype in archetypes {
+            let Some(component) = archetype.component(TypeId::of::<T>()) else {
+                continue;
+            };
+
+            // Safety: world is borrowed mutably, no fetches may be alive.
+            let data = unsafe { component.data() };
+            if !data.epoch.after(self.after_epoch) {
+                continue;
+            }
+
+            let entities = archetype.entities();
+            let mut chunk_start = 0;
+            while chunk_start < entities.len() {
+                let chunk_end = (chunk_start + CHUNK_LEN_USIZE).min(entities.len());
+
+                if data.chunk_epochs[chunk_idx(chunk_start)].after(self.after_epoch) {
+                    for idx in chunk_start..chunk_end {
+                        if data.entity_epochs[idx].after(self.after_epoch) {
+                            // Safety: `idx` is within archetype and column stores `T`.
+                            let value = unsafe { &*data.ptr.cast::<T>().as_ptr().add(idx) };
+                            self.update(entities[idx], (self.extract)(value));
+                        }
+                    }
+                }
+
+                chunk_start = chunk_end;
+            }
+        }
+
+        self.after_epoch = epoch;
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Collection of indexes registered in the world.
+pub(super) struct Indexes {
+    indexes: HashMap<TypeId, Box<dyn AnyIndex>>,
+}
+
+impl Indexes {
+    pub fn new() -> Self {
+        Indexes {
+            indexes: HashMap::new(),
+        }
+    }
+
+    /// Returns `true` if no indexes are registered.
+    pub fn is_empty(&self) -> bool {
+        self.indexes.is_empty()
+    }
+
+    /// Applies changes of components to all indexes.
+    pub fn harvest(
+        &mut self,
+        archetypes: &[Archetype],
+        journal: &[(EntityId, u32)],
+        epoch: EpochId,
+    ) {
+        for index in self.indexes.values_mut() {
+            index.harvest(archetypes, journal, epoch);
+        }
+    }
+}
+
+impl World {
+    /// Registers index of entities by key extracted from component `T`.
+    ///
+    /// Entities are indexed by keys of their current components immediately.
+    /// Afterwards index is updated during maintenance
+    /// from components inserted or modified since previous maintenance.
+    /// Registering the same index again replaces it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Component)]
+    /// struct Cell(i32, i32);
+    ///
+    /// let mut world = World::new();
+    /// let a = world.spawn((Cell(0, 0),));
+    /// let b = world.spawn((Cell(1, 0),));
+    ///
+    /// world.add_index::<Cell, (i32, i32)>(|cell| (cell.0, cell.1));
+    /// assert_eq!(world.lookup_by::<Cell, _>(&(0, 0)), [a]);
+    ///
+    /// world.query_one_mut::<&mut Cell>(b).unwrap().0 = 0;
+    /// world.maintenance();
+    ///
+    /// let mut entities = world.lookup_by::<Cell, _>(&(0, 0)).to_vec();
+    /// entities.sort_by_key(|e| e.bits());
+    /// assert_eq!(entities, [a, b]);
+    /// ```
+    pub fn add_index<T, K>(&mut self, extract: fn(&T) -> K)
+    where
+        T: Component,
+        K: Hash + Eq + Clone + Send + Sync + 'static,
+    {
+        self.maintenance();
+
+        let mut index = Indexed {
+            extract,
+            entities: HashMap::new(),
+            keys: HashMap::new(),
+            after_epoch: self.epoch.current_mut(),
+        };
+        index.fill(&self.archetypes);
+
+        self.indexes
+            .indexes
+            .insert(TypeId::of::<Indexed<T, K>>(), Box::new(index));
+        self.entities.enable_journal();
+    }
+
+    /// Returns index registered with [`World::add_index`].
+    pub fn index<T, K>(&self) -> Option<&Indexed<T, K>>
+    where
+        T: Component,
+        K: Hash + Eq + Clone + Send + Sync + 'static,
+    {
+        let index = self.indexes.indexes.get(&TypeId::of::<Indexed<T, K>>())?;
+        index.as_any().downcast_ref()
+    }
+
+    /// Returns entities with component `T` that has specified key.
+    ///
+    /// Index reflects state of the world at last maintenance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if index is not registered with [`World::add_index`].
+    #[track_caller]
+    pub fn lookup_by<T, K>(&self, key: &K) -> &[EntityId]
+    where
+        T: Component,
+        K: Hash + Eq + Clone + Send + Sync + 'static,
+    {
+        match self.index::<T, K>() {
+            None => panic!(
+                "Index of `{}` by `{}` is not registered",
+                core::any::type_name::<T>(),
+                core::any::type_name::<K>()
+            ),
+            Some(index) => index.get(key),
+        }
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{
+        test::{Str, U32},
+        world::World,
+    };
+
+    /// Tests that index follows modifications, removals and despawns.
+    #[test]
+    fn index_lookup() {
+        let mut world = World::new();
+        let a = world.spawn((U32(1),));
+        let b = world.spawn((U32(2), Str("b")));
+
+        world.add_index::<U32, u32>(|v| v.0 % 2);
+        assert_eq!(world.lookup_by::<U32, u32>(&1), [a]);
+        assert_eq!(world.lookup_by::<U32, u32>(&0), [b]);
+
+        world.query_one_mut::<&mut U32>(a).unwrap().0 = 4;
+        let c = world.spawn((U32(3),));
+        world.maintenance();
+
+        let mut even = world.lookup_by::<U32, u32>(&0).to_vec();
+        even.sort_by_key(|e| e.bits());
+        assert_eq!(even, [a, b]);
+        assert_eq!(world.lookup_by::<U32, u32>(&1), [c]);
+
+        world.remove::<U32>(b).unwrap();
+        world.despawn(c).unwrap();
+        world.maintenance();
+
+        assert_eq!(world.lookup_by::<U32, u32>(&0), [a]);
+        assert!(world.lookup_by::<U32, u32>(&1).is_empty());
+        assert_eq!(world.index::<U32, u32>().unwrap().len(), 1);
+    }
+}
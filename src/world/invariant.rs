@@ -0,0 +1,226 @@
+//! Validation of invariants that span several components.
+
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+use crate::{entity::EntityId, epoch::EpochId};
+
+use super::World;
+
+/// Function that checks invariant of the entity.
+/// Returns description of the problem if invariant is broken.
+pub type InvariantFn = fn(&World, EntityId) -> Result<(), String>;
+
+/// Violation of an invariant registered with [`World::add_invariant`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvariantViolation {
+    /// Name of the violated invariant.
+    pub invariant: &'static str,
+
+    /// Entity that violates the invariant.
+    pub entity: EntityId,
+
+    /// Action that last modified the entity before violation was found.
+    /// `None` if it is not known.
+    pub operation: Option<&'static str>,
+
+    /// Description of the problem returned by the invariant.
+    pub message: String,
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invariant `{}` is violated by entity {}",
+            self.invariant, self.entity
+        )?;
+        if let Some(operation) = self.operation {
+            write!(f, " after `{}`", operation)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvariantViolation {}
+
+/// Registered invariants and log of executed actions.
+pub(super) struct Invariants {
+    checks: Vec<(&'static str, InvariantFn)>,
+
+    /// Epochs before executed actions and their names.
+    /// Filled only in debug builds when there are invariants to check.
+    log: Vec<(EpochId, &'static str)>,
+}
+
+impl Invariants {
+    pub fn new() -> Self {
+        Invariants {
+            checks: Vec::new(),
+            log: Vec::new(),
+        }
+    }
+
+    pub fn fork(&self) -> Self {
+        Invariants {
+            checks: self.checks.clone(),
+            log: Vec::new(),
+        }
+    }
+}
+
+impl World {
+    /// Registers invariant that must hold for every entity.
+    ///
+    /// In debug builds invariants are checked for all entities
+    /// after each flush of actions that executed at least one action.
+    /// Violation panics with name of the invariant, offending entity
+    /// and the action that last modified it.
+    /// In release builds invariants are checked only by [`World::check_invariants`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Component)]
+    /// struct Health(u32);
+    ///
+    /// #[derive(Component)]
+    /// struct MaxHealth(u32);
+    ///
+    /// let mut world = World::new();
+    /// world.add_invariant("health within max", |world, e| {
+    ///     let mut query = world.query_one::<(&Health, &MaxHealth)>(e).unwrap();
+    ///     if let Some((health, max)) = query.get() {
+    ///         if health.0 > max.0 {
+    ///             return Err("health exceeds max".into());
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// });
+    ///
+    /// let e = world.spawn((Health(10), MaxHealth(5)));
+    /// let violation = world.check_invariants().unwrap_err();
+    /// assert_eq!(violation.entity, e);
+    /// ```
+    pub fn add_invariant(&mut self, name: &'static str, check: InvariantFn) {
+        self.invariants.checks.push((name, check));
+    }
+
+    /// Checks registered invariants for all entities.
+    /// Returns first found violation.
+    ///
+    /// This is a full scan of the world, intended for debugging and tests.
+    pub fn check_invariants(&self) -> Result<(), InvariantViolation> {
+        self.find_violation(&[])
+    }
+
+    /// Returns `true` if executed actions should be logged.
+    #[inline]
+    pub(crate) fn log_actions(&self) -> bool {
+        cfg!(debug_assertions) && !self.invariants.checks.is_empty()
+    }
+
+    /// Records action that is about to be executed.
+    pub(crate) fn log_action(&mut self, name: &'static str) {
+        let epoch = self.epoch.current_mut();
+        self.invariants.log.push((epoch, name));
+    }
+
+    /// Checks invariants after flush of logged actions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if invariant is violated.
+    pub(crate) fn check_flushed_invariants(&mut self) {
+        if self.invariants.log.is_empty() {
+            return;
+        }
+
+        let log = core::mem::take(&mut self.invariants.log);
+        if let Err(violation) = self.find_violation(&log) {
+            panic!("{}", violation);
+        }
+    }
+
+    fn find_violation(&self, log: &[(EpochId, &'static str)]) -> Result<(), InvariantViolation> {
+        if self.invariants.checks.is_empty() {
+            return Ok(());
+        }
+
+        for archetype in self.archetypes.iter() {
+            for (idx, &entity) in archetype.entities().iter().enumerate() {
+                for &(invariant, check) in &self.invariants.checks {
+                    let Err(message) = check(self, entity) else {
+                        continue;
+                    };
+
+                    let operation = match log {
+                        [] => None,
+                        log => {
+                            // Find last action that modified the entity.
+                            // Log is not empty only when world is borrowed mutably.
+                            let mut modified = archetype.spawn_epochs()[idx];
+                            for info in archetype.infos() {
+                                let component = archetype.component(info.id()).unwrap();
+                                let epoch = unsafe { component.data().entity_epochs[idx] };
+                                if epoch.after(modified) {
+                                    modified = epoch;
+                                }
+                            }
+
+                            log.iter()
+                                .rev()
+                                .find(|(epoch, _)| modified.after(*epoch))
+                                .map(|(_, name)| *name)
+                        }
+                    };
+
+                    return Err(InvariantViolation {
+                        invariant,
+                        entity,
+                        operation,
+                        message,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{test::U32, world::World};
+
+    /// Tests that broken invariant is reported with the action that broke it.
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "ActionEncoder::insert")]
+    fn invariant_after_flush() {
+        use crate::action::ActionBuffer;
+
+        let mut world = World::new();
+        world.add_invariant("u32 is even", |world, e| {
+            let mut query = world.query_one::<&U32>(e).unwrap();
+            match query.get() {
+                Some(U32(v)) if v % 2 != 0 => Err(alloc::string::String::from("u32 is odd")),
+                _ => Ok(()),
+            }
+        });
+
+        let e = world.spawn((U32(0),));
+        assert_eq!(world.check_invariants(), Ok(()));
+
+        let mut buffer = ActionBuffer::new();
+        buffer.encoder(&world).insert(e, U32(2));
+        buffer.execute(&mut world);
+        assert_eq!(world.check_invariants(), Ok(()));
+
+        buffer.encoder(&world).insert(e, U32(1));
+        buffer.execute(&mut world);
+    }
+}
@@ -0,0 +1,340 @@
+//! Queries that maintain set of matched entities incrementally.
+
+use alloc::{
+    boxed::Box,
+    sync::{Arc, Weak},
+    vec::Vec,
+};
+use core::{fmt, marker::PhantomData};
+
+use hashbrown::HashSet;
+use parking_lot::Mutex;
+
+use crate::{
+    archetype::Archetype,
+    entity::{EntityId, EntitySet},
+    query::{DefaultQuery, IntoQuery, Query},
+};
+
+use super::World;
+
+/// Changes of membership not yet observed by [`LiveQuery`].
+#[derive(Default)]
+pub(super) struct Pending {
+    added: HashSet<EntityId>,
+    removed: HashSet<EntityId>,
+}
+
+/// Query that maintains set of matched entities incrementally.
+///
+/// Entities are matched by archetype, that is by set of components they have.
+/// Per-entity filters, like [`Modified`], are not considered.
+///
+/// Membership is updated as entities are spawned, despawned
+/// and move between archetypes, without re-evaluating the query.
+/// Call [`LiveQuery::update`] once per frame to observe changes
+/// with [`LiveQuery::added`] and [`LiveQuery::removed`].
+///
+/// Created with [`World::live_query`].
+/// Dropping the query unregisters it from the world.
+///
+/// [`Modified`]: crate::query::Modified
+///
+/// # Example
+///
+/// ```
+/// # use edict::{world::World, ExampleComponent};
+/// let mut world = World::new();
+/// let a = world.spawn((ExampleComponent,));
+///
+/// let mut live = world.live_query::<&ExampleComponent>();
+/// live.update(&mut world);
+/// assert_eq!(live.added(), [a]);
+///
+/// let b = world.spawn((ExampleComponent,));
+/// world.despawn(a).unwrap();
+///
+/// live.update(&mut world);
+/// assert_eq!(live.added(), [b]);
+/// assert_eq!(live.removed(), [a]);
+/// assert_eq!(live.iter().collect::<Vec<_>>(), [b]);
+/// ```
+pub struct LiveQuery<Q> {
+    pending: Arc<Mutex<Pending>>,
+    members: HashSet<EntityId>,
+    added: Vec<EntityId>,
+    removed: Vec<EntityId>,
+    marker: PhantomData<fn() -> Q>,
+}
+
+impl<Q> fmt::Debug for LiveQuery<Q> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LiveQuery")
+            .field("members", &self.members.len())
+            .field("added", &self.added)
+            .field("removed", &self.removed)
+            .finish()
+    }
+}
+
+impl<Q> LiveQuery<Q> {
+    /// Collects changes of membership since last update.
+    ///
+    /// Entities that entered and left the query between updates are not reported.
+    pub fn update(&mut self, world: &mut World) {
+        world.maintenance();
+
+        let mut pending = self.pending.lock();
+
+        self.added.clear();
+        self.added.extend(pending.added.drain());
+        self.removed.clear();
+        self.removed.extend(pending.removed.drain());
+
+        for id in &self.removed {
+            self.members.remove(id);
+        }
+        self.members.extend(self.added.iter().copied());
+    }
+
+    /// Returns entities that started matching the query before last update.
+    #[inline]
+    pub fn added(&self) -> &[EntityId] {
+        &self.added
+    }
+
+    /// Returns entities that stopped matching the query before last update.
+    #[inline]
+    pub fn removed(&self) -> &[EntityId] {
+        &self.removed
+    }
+
+    /// Returns iterator over entities that matched the query at last update.
+    #[inline]
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = EntityId> + '_ {
+        self.members.iter().copied()
+    }
+
+    /// Returns number of entities that matched the query at last update.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Returns `true` if no entities matched the query at last update.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Checks if entity matched the query at last update.
+    #[inline]
+    pub fn contains(&self, id: EntityId) -> bool {
+        self.members.contains(&id)
+    }
+}
+
+/// World-side state of a [`LiveQuery`].
+struct LiveQueryState {
+    visit_archetype: Box<dyn Fn(&Archetype) -> bool + Send + Sync>,
+
+    /// Cached result of `visit_archetype` per archetype index.
+    matches: Vec<bool>,
+    members: HashSet<EntityId>,
+    pending: Weak<Mutex<Pending>>,
+}
+
+impl LiveQueryState {
+    fn matches(&mut self, archetypes: &[Archetype], archetype: u32) -> bool {
+        if archetype == u32::MAX {
+            return false;
+        }
+
+        while self.matches.len() < archetypes.len() {
+            let archetype = &archetypes[self.matches.len()];
+            self.matches.push((self.visit_archetype)(archetype));
+        }
+        self.matches[archetype as usize]
+    }
+
+    fn apply(
+        &mut self,
+        archetypes: &[Archetype],
+        id: EntityId,
+        archetype: u32,
+        pending: &mut Pending,
+    ) {
+        let matches = self.matches(archetypes, archetype);
+
+        if matches && self.members.insert(id) {
+            if !pending.removed.remove(&id) {
+                pending.added.insert(id);
+            }
+        } else if !matches && self.members.remove(&id) && !pending.added.remove(&id) {
+            pending.removed.insert(id);
+        }
+    }
+}
+
+/// Collection of live queries registered in the world.
+pub(super) struct LiveQueries {
+    queries: Vec<LiveQueryState>,
+}
+
+impl LiveQueries {
+    pub fn new() -> Self {
+        LiveQueries {
+            queries: Vec::new(),
+        }
+    }
+
+    pub fn add<Q>(
+        &mut self,
+        query: Q,
+        archetypes: &[Archetype],
+        entities: &mut EntitySet,
+    ) -> Arc<Mutex<Pending>>
+    where
+        Q: Query + Send + Sync + 'static,
+    {
+        let pending = Arc::new(Mutex::new(Pending::default()));
+
+        let mut state = LiveQueryState {
+            visit_archetype: Box::new(move |archetype| query.visit_archetype(archetype)),
+            matches: Vec::new(),
+            members: HashSet::new(),
+            pending: Arc::downgrade(&pending),
+        };
+
+        {
+            let mut pending = pending.lock();
+            for (idx, archetype) in archetypes.iter().enumerate() {
+                if state.matches(archetypes, idx as u32) {
+                    for &id in archetype.entities() {
+                        state.apply(archetypes, id, idx as u32, &mut pending);
+                    }
+                }
+            }
+        }
+
+        self.queries.push(state);
+        entities.enable_journal();
+
+        pending
+    }
+
+    /// Returns `true` if no live queries are registered.
+    pub fn is_empty(&self) -> bool {
+        self.queries.is_empty()
+    }
+
+    /// Applies archetype changes of entities to all live queries
+    /// and drops states of queries that were dropped.
+    pub fn harvest(&mut self, archetypes: &[Archetype], journal: &[(EntityId, u32)]) {
+        self.queries
+            .retain_mut(|state| match state.pending.upgrade() {
+                None => false,
+                Some(pending) => {
+                    let mut pending = pending.lock();
+                    for &(id, archetype) in journal {
+                        state.apply(archetypes, id, archetype, &mut pending);
+                    }
+                    true
+                }
+            });
+    }
+}
+
+impl World {
+    /// Returns [`LiveQuery`] that maintains set of entities
+    /// matched by the query incrementally.
+    ///
+    /// Entities that match the query at the moment of the call
+    /// are reported as added by the first [`LiveQuery::update`].
+    #[inline]
+    pub fn live_query<Q>(&mut self) -> LiveQuery<Q>
+    where
+        Q: DefaultQuery,
+        Q::Query: Send + Sync + 'static,
+    {
+        self.new_live_query(Q::default_query())
+    }
+
+    /// Returns [`LiveQuery`] for query instance.
+    ///
+    /// See [`World::live_query`].
+    #[inline]
+    pub fn live_query_with<Q>(&mut self, query: Q) -> LiveQuery<Q>
+    where
+        Q: IntoQuery,
+        Q::Query: Send + Sync + 'static,
+    {
+        self.new_live_query(query.into_query())
+    }
+
+    fn new_live_query<Q, T>(&mut self, query: T) -> LiveQuery<Q>
+    where
+        T: Query + Send + Sync + 'static,
+    {
+        self.maintenance();
+
+        let pending = self.live.add(query, &self.archetypes, &mut self.entities);
+
+        LiveQuery {
+            pending,
+            members: HashSet::new(),
+            added: Vec::new(),
+            removed: Vec::new(),
+            marker: PhantomData,
+        }
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{
+        test::{Str, U32},
+        world::World,
+    };
+
+    /// Tests that live query tracks entities entering and leaving it.
+    #[test]
+    fn live_query() {
+        let mut world = World::new();
+
+        let a = world.spawn((U32(0),));
+        let b = world.spawn((Str("b"),));
+
+        let mut live = world.live_query::<(&U32, &Str)>();
+        live.update(&mut world);
+        assert!(live.added().is_empty());
+        assert!(live.is_empty());
+
+        world.insert(a, Str("a")).unwrap();
+        world.insert(b, U32(1)).unwrap();
+        live.update(&mut world);
+
+        let mut added = live.added().to_vec();
+        added.sort_by_key(|id| id.bits());
+        assert_eq!(added, [a, b]);
+        assert_eq!(live.len(), 2);
+
+        world.remove::<Str>(a).unwrap();
+        world.despawn(b).unwrap();
+        live.update(&mut world);
+
+        let mut removed = live.removed().to_vec();
+        removed.sort_by_key(|id| id.bits());
+        assert!(live.added().is_empty());
+        assert_eq!(removed, [a, b]);
+        assert!(live.is_empty());
+
+        // Entering and leaving between updates is not reported.
+        let c = world.spawn((U32(2), Str("c")));
+        world.despawn(c).unwrap();
+        live.update(&mut world);
+        assert!(live.added().is_empty());
+        assert!(live.removed().is_empty());
+    }
+}
@@ -0,0 +1,155 @@
+//! Layout manifest that describes where component columns are located.
+
+use alloc::vec::Vec;
+
+use crate::{entity::EntityId, epoch::EpochId};
+
+use super::World;
+
+/// Location and layout of a component column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnLayout {
+    /// Name of the component type.
+    pub name: &'static str,
+
+    /// Stable name of the component type, if registered with one.
+    ///
+    /// Unlike type ids and names, stable names are the same
+    /// across builds and processes, so readers should identify columns by them.
+    pub stable_name: Option<&'static str>,
+
+    /// Size of the component in bytes.
+    /// Components are stored contiguously with this stride.
+    pub size: usize,
+
+    /// Alignment of the component in bytes.
+    pub align: usize,
+
+    /// Address of the first component in the address space of this process.
+    /// Dangling if archetype has no capacity or component is zero-sized.
+    pub address: usize,
+
+    /// Offset of the first component within shared memory region
+    /// as reported by [`ColumnAllocator::shared_offset`].
+    ///
+    /// [`ColumnAllocator::shared_offset`]: crate::archetype::ColumnAllocator::shared_offset
+    pub offset: Option<usize>,
+}
+
+/// Layout of an archetype.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArchetypeLayout {
+    /// Index of the archetype in the world.
+    pub index: u32,
+
+    /// Entities of the archetype in row order.
+    pub entities: Vec<EntityId>,
+
+    /// Number of rows allocated in each column.
+    pub capacity: usize,
+
+    /// Columns of the archetype in unspecified order.
+    pub columns: Vec<ColumnLayout>,
+}
+
+/// Snapshot of component columns layout of the [`World`].
+///
+/// Published for out-of-process readers, e.g. editors and inspectors,
+/// that map columns allocated with a shared-memory [`ColumnAllocator`]
+/// to read component values live.
+///
+/// Manifest is valid until the next structural change of the world,
+/// as columns may be reallocated and rows moved.
+/// Readers should compare [`LayoutManifest::epoch`] with the world epoch
+/// and request new manifest when it changes.
+///
+/// [`ColumnAllocator`]: crate::archetype::ColumnAllocator
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LayoutManifest {
+    /// World epoch at which manifest was taken.
+    pub epoch: EpochId,
+
+    /// Non-empty archetypes of the world.
+    pub archetypes: Vec<ArchetypeLayout>,
+}
+
+impl World {
+    /// Returns manifest of component columns layout.
+    ///
+    /// Runs maintenance first, so manifest includes all pending entities.
+    /// See [`LayoutManifest`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Component)]
+    /// #[edict(stable_name = "example::Pos")]
+    /// struct Pos(f32);
+    ///
+    /// let mut world = World::new();
+    /// let e = world.spawn((Pos(1.0),));
+    ///
+    /// let manifest = world.layout_manifest();
+    /// let archetype = &manifest.archetypes[0];
+    /// assert_eq!(archetype.entities, [e]);
+    ///
+    /// let column = &archetype.columns[0];
+    /// assert_eq!(column.stable_name, Some("example::Pos"));
+    /// assert_eq!(column.size, 4);
+    ///
+    /// let pos = unsafe { &*(column.address as *const Pos) };
+    /// assert_eq!(pos.0, 1.0);
+    /// ```
+    pub fn layout_manifest(&mut self) -> LayoutManifest {
+        self.maintenance();
+
+        let mut archetypes = Vec::new();
+
+        for (index, archetype) in self.archetypes.iter().enumerate() {
+            if archetype.is_empty() {
+                continue;
+            }
+
+            let allocator = archetype.column_allocator();
+
+            let columns = archetype
+                .ids()
+                .map(|id| {
+                    let component = archetype.component(id).unwrap();
+
+                    // Safety: world is borrowed mutably, no other borrows exist.
+                    let ptr = unsafe { component.data().ptr };
+
+                    let offset = match allocator {
+                        Some(allocator) if component.layout().size() != 0 => {
+                            allocator.shared_offset(ptr)
+                        }
+                        _ => None,
+                    };
+
+                    ColumnLayout {
+                        name: component.name(),
+                        stable_name: component.stable_name(),
+                        size: component.layout().size(),
+                        align: component.layout().align(),
+                        address: ptr.as_ptr() as usize,
+                        offset,
+                    }
+                })
+                .collect();
+
+            archetypes.push(ArchetypeLayout {
+                index: index as u32,
+                entities: archetype.entities().to_vec(),
+                capacity: archetype.capacity(),
+                columns,
+            });
+        }
+
+        LayoutManifest {
+            epoch: self.epoch(),
+            archetypes,
+        }
+    }
+}
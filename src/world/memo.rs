@@ -0,0 +1,248 @@
+//! Caching of values derived from query results.
+
+use alloc::vec::Vec;
+use core::{cell::Cell, fmt, marker::PhantomData};
+
+use crate::{
+    epoch::EpochId,
+    query::{DefaultQuery, ImmutableQuery, IntoQuery, MutQuery, Query},
+};
+
+use super::{QueryRef, World};
+
+/// Value of type `T` derived from results of query `Q`.
+///
+/// Value is computed on first access and recomputed only when
+/// components accessed by the query were modified since last computation,
+/// or when number of entities in any archetype matched by the query changed.
+/// This suits aggregates that are expensive to compute and rarely change,
+/// like total mass or bounding box of all colliders.
+///
+/// Modifications are detected per archetype,
+/// so modification of one entity invalidates the value for all of them.
+///
+/// Query must be immutable, otherwise computing the value would invalidate it.
+///
+/// # Example
+///
+/// ```
+/// # use edict::{component::Component, world::{Memo, World}};
+/// #[derive(Component)]
+/// struct Mass(f32);
+///
+/// let mut world = World::new();
+/// let e = world.spawn((Mass(1.0),));
+/// world.spawn((Mass(2.0),));
+///
+/// let mut total = Memo::<f32, &Mass>::new();
+/// let mut computed = 0;
+///
+/// for _ in 0..2 {
+///     let value = total.get(&world, |mut query| {
+///         computed += 1;
+///         query.fold(0.0, |acc, mass| acc + mass.0)
+///     });
+///     assert_eq!(*value, 3.0);
+/// }
+/// assert_eq!(computed, 1);
+///
+/// world.query_one_mut::<&mut Mass>(e).unwrap().0 = 5.0;
+/// assert!(total.is_stale(&world));
+/// assert_eq!(*total.get(&world, |mut query| query.fold(0.0, |acc, mass| acc + mass.0)), 7.0);
+/// ```
+pub struct Memo<T, Q: IntoQuery> {
+    query: Q::Query,
+    value: Option<T>,
+
+    /// World epoch at which value was computed.
+    epoch: EpochId,
+
+    /// Indices and lengths of non-empty archetypes matched by the query
+    /// at the time value was computed.
+    archetypes: Vec<(usize, usize)>,
+    marker: PhantomData<fn() -> Q>,
+}
+
+impl<T, Q> fmt::Debug for Memo<T, Q>
+where
+    T: fmt::Debug,
+    Q: IntoQuery,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Memo")
+            .field("value", &self.value)
+            .field("epoch", &self.epoch)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, Q> Default for Memo<T, Q>
+where
+    Q: DefaultQuery,
+    Q::Query: ImmutableQuery,
+{
+    #[inline]
+    fn default() -> Self {
+        Memo::new()
+    }
+}
+
+impl<T, Q> Memo<T, Q>
+where
+    Q: DefaultQuery,
+    Q::Query: ImmutableQuery,
+{
+    /// Returns new memo with default query.
+    /// Value is computed on first access.
+    #[inline]
+    pub fn new() -> Self {
+        Memo::with_query(Q::default_query())
+    }
+}
+
+impl<T, Q> Memo<T, Q>
+where
+    Q: IntoQuery,
+    Q::Query: ImmutableQuery,
+{
+    /// Returns new memo with specified query.
+    /// Value is computed on first access.
+    #[inline]
+    pub fn with_query(query: Q::Query) -> Self {
+        Memo {
+            query,
+            value: None,
+            epoch: EpochId::start(),
+            archetypes: Vec::new(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns cached value without checking if it is up to date.
+    /// Returns `None` if value was never computed or was invalidated.
+    #[inline]
+    pub fn cached(&self) -> Option<&T> {
+        self.value.as_ref()
+    }
+
+    /// Drops cached value, so that it is recomputed on next access.
+    #[inline]
+    pub fn invalidate(&mut self) {
+        self.value = None;
+    }
+
+    /// Returns `true` if cached value is missing or outdated.
+    pub fn is_stale(&self, world: &World) -> bool {
+        if self.value.is_none() {
+            return true;
+        }
+
+        let mut archetypes = self.archetypes.iter();
+        for (idx, archetype) in world.archetypes().iter().enumerate() {
+            if archetype.is_empty() || !self.query.visit_archetype(archetype) {
+                continue;
+            }
+
+            if archetypes.next() != Some(&(idx, archetype.len())) {
+                return true;
+            }
+
+            let modified = Cell::new(false);
+
+            // Safety: Only epoch is read, it is modified atomically.
+            unsafe {
+                self.query.access_archetype(archetype, &|id, _| {
+                    let component = archetype.component(id).unwrap();
+                    if component.data().epoch.after(self.epoch) {
+                        modified.set(true);
+                    }
+                });
+            }
+
+            if modified.get() {
+                return true;
+            }
+        }
+
+        archetypes.next().is_some()
+    }
+
+    /// Returns up-to-date value.
+    ///
+    /// Calls `f` with query to compute the value
+    /// if it was never computed or is outdated,
+    /// otherwise returns cached value.
+    ///
+    /// Memo must be used with the same world on every call,
+    /// otherwise value computed for another world may be returned.
+    pub fn get<F>(&mut self, world: &World, f: F) -> &T
+    where
+        F: FnOnce(QueryRef<'_, (MutQuery<'_, Q::Query>,), ()>) -> T,
+    {
+        if self.is_stale(world) {
+            self.value = None;
+            self.epoch = world.epoch();
+
+            self.archetypes.clear();
+            for (idx, archetype) in world.archetypes().iter().enumerate() {
+                if !archetype.is_empty() && self.query.visit_archetype(archetype) {
+                    self.archetypes.push((idx, archetype.len()));
+                }
+            }
+
+            let value = f(world.query_with(MutQuery::new(&mut self.query)));
+            self.value = Some(value);
+        }
+
+        self.value.as_ref().unwrap()
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{
+        test::{Str, U32},
+        world::World,
+    };
+
+    #[test]
+    fn memo() {
+        use crate::world::Memo;
+
+        fn sum(world: &World, memo: &mut Memo<u32, &'static U32>) -> u32 {
+            *memo.get(world, |mut query| query.fold(0, |acc, u| acc + u.0))
+        }
+
+        let mut world = World::new();
+        let a = world.spawn((U32(1),));
+        world.spawn((U32(2), Str("b")));
+
+        let mut memo = Memo::new();
+        assert!(memo.is_stale(&world));
+        assert_eq!(sum(&world, &mut memo), 3);
+        assert!(!memo.is_stale(&world));
+
+        // Unrelated components do not invalidate the value.
+        let b = world.spawn((Str("c"),));
+        world.query_one_mut::<&mut Str>(b).unwrap().0 = "d";
+        assert!(!memo.is_stale(&world));
+
+        world.query_one_mut::<&mut U32>(a).unwrap().0 = 4;
+        assert!(memo.is_stale(&world));
+        assert_eq!(sum(&world, &mut memo), 6);
+
+        world.spawn((U32(3),));
+        assert!(memo.is_stale(&world));
+        assert_eq!(sum(&world, &mut memo), 9);
+
+        world.despawn(a).unwrap();
+        assert!(memo.is_stale(&world));
+        assert_eq!(sum(&world, &mut memo), 5);
+
+        memo.invalidate();
+        assert_eq!(memo.cached(), None);
+        assert_eq!(sum(&world, &mut memo), 5);
+        assert_eq!(memo.cached(), Some(&5));
+    }
+}
@@ -0,0 +1,204 @@
+//! Merging of one [`World`] into another.
+
+use core::any::TypeId;
+
+use alloc::vec::Vec;
+
+use hashbrown::HashMap;
+
+use crate::{bundle::BundleDesc, entity::EntityId, hash::MulHasherBuilder};
+
+use super::World;
+
+/// Component ids of merged archetype, used to find matching archetype.
+struct MergeIds(Vec<TypeId>);
+
+impl BundleDesc for MergeIds {
+    #[inline]
+    fn key() -> Option<TypeId> {
+        None
+    }
+
+    #[inline]
+    fn with_ids<R>(&self, f: impl FnOnce(&[TypeId]) -> R) -> R {
+        f(&self.0)
+    }
+}
+
+impl World {
+    /// Moves all entities from `other` world into this one.
+    ///
+    /// Entities get new ids in this world.
+    /// Ids of entities stored in relations are remapped accordingly,
+    /// as well as ids stored in components with [`Component::ON_REMAP`] hook.
+    /// Ids of entities that are not from `other` world are kept as is.
+    /// Components of each archetype are moved column by column.
+    ///
+    /// Resources of the `other` world are dropped.
+    ///
+    /// Returns pairs of old and new entity ids.
+    ///
+    /// [`Component::ON_REMAP`]: crate::component::Component::ON_REMAP
+    ///
+    /// # Panics
+    ///
+    /// Panics if merged entities would exceed entity quotas of this world.
+    /// Quotas are checked before any entity is moved.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{relation::ChildOf, world::World, ExampleComponent};
+    /// let mut world = World::new();
+    /// world.spawn((ExampleComponent,));
+    ///
+    /// let mut chunk = World::new();
+    /// let parent = chunk.spawn((ExampleComponent,));
+    /// let child = chunk.spawn(());
+    /// chunk.add_relation(child, ChildOf, parent).unwrap();
+    ///
+    /// let ids = world.merge(chunk);
+    /// assert_eq!(ids.len(), 2);
+    /// assert_eq!(world.query::<&ExampleComponent>().iter().count(), 2);
+    /// ```
+    pub fn merge(&mut self, mut other: World) -> Vec<(EntityId, EntityId)> {
+        self.maintenance();
+        other.maintenance();
+
+        // Find destination archetypes first to check quotas before moving anything.
+        let mut targets = Vec::new();
+        for (src_idx, src) in other.archetypes.iter().enumerate() {
+            if src.is_empty() {
+                continue;
+            }
+
+            let ids = MergeIds(src.ids().collect());
+            let dst_idx =
+                self.edges
+                    .spawn(&mut self.registry, &mut self.archetypes, &ids, |registry| {
+                        for info in src.infos() {
+                            registry.get_or_register_raw(info.clone());
+                        }
+                    });
+            targets.push((src_idx, dst_idx, src.len()));
+        }
+
+        let counts = targets
+            .iter()
+            .map(|&(_, dst_idx, len)| (dst_idx, len))
+            .collect::<Vec<_>>();
+        if let Err(err) = self.quotas.check_many(&self.archetypes, &counts) {
+            panic!("{}", err);
+        }
+
+        let mut map = HashMap::with_hasher(MulHasherBuilder);
+        let mut pairs = Vec::new();
+
+        for &(src_idx, _, _) in &targets {
+            for &old in other.archetypes[src_idx].entities() {
+                let new = self.entities.spawn();
+                map.insert(old.bits(), new);
+                pairs.push((old, new));
+            }
+        }
+
+        let remap = |id: EntityId| map.get(&id.bits()).copied().unwrap_or(id);
+        let epoch = self.epoch.next_mut();
+
+        for (src_idx, dst_idx, _) in targets {
+            let src = &mut other.archetypes[src_idx];
+
+            let ids = src
+                .entities()
+                .iter()
+                .map(|&id| remap(id))
+                .collect::<Vec<_>>();
+
+            let dst = &mut self.archetypes[dst_idx as usize];
+            let start = unsafe { dst.append(src, &ids, epoch, &remap) };
+
+            for (idx, &id) in ids.iter().enumerate() {
+                self.entities.set_location(id, dst_idx, start + idx as u32);
+            }
+        }
+
+        pairs
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::{vec, vec::Vec};
+
+    use crate::{
+        query::Entities,
+        relation::ChildOf,
+        test::{Str, U32},
+        world::World,
+    };
+
+    /// Tests that merged entities get new ids and relations are remapped.
+    #[test]
+    fn world_merge() {
+        let mut world = World::new();
+        let existing = world.spawn((U32(0),));
+
+        let mut other = World::new();
+        let parent = other.spawn((U32(1),));
+        let child = other.spawn((Str("child"),));
+        other.add_relation(child, ChildOf, parent).unwrap();
+
+        let ids = world.merge(other);
+        assert_eq!(ids.len(), 2);
+
+        let new_id = |old| ids.iter().find(|(o, _)| *o == old).unwrap().1;
+        let new_parent = new_id(parent);
+        let new_child = new_id(child);
+
+        assert_ne!(new_parent, existing);
+        assert_eq!(world.query_one_mut::<&U32>(new_parent), Ok(&U32(1)));
+        assert_eq!(world.query_one_mut::<&Str>(new_child), Ok(&Str("child")));
+        assert_eq!(world.query::<&U32>().iter().count(), 2);
+
+        let children = world
+            .query::<Entities>()
+            .relates_to_any::<&ChildOf>([new_parent])
+            .iter()
+            .map(|(e, _)| e)
+            .collect::<Vec<_>>();
+        assert_eq!(children, vec![new_child]);
+    }
+
+    #[test]
+    #[should_panic(expected = "quota")]
+    fn world_merge_quota() {
+        let mut world = World::builder().max_archetype_entities(2).build();
+        world.spawn((U32(0),));
+
+        let mut other = World::new();
+        other.spawn((U32(1),));
+        other.spawn((U32(2),));
+
+        world.merge(other);
+    }
+
+    #[test]
+    fn world_merge_reuses_archetype() {
+        let mut world = World::new();
+        let existing = world.spawn((U32(0), Str("a")));
+        let archetypes = world.archetypes().len();
+
+        let mut other = World::new();
+        other.spawn((Str("b"), U32(1)));
+        world.merge(other);
+
+        assert_eq!(world.archetypes().len(), archetypes);
+        assert_eq!(world.query::<(&U32, &Str)>().iter().count(), 2);
+
+        // Lookup is cached for subsequent spawns.
+        world.spawn((U32(2), Str("c")));
+        assert_eq!(world.archetypes().len(), archetypes);
+        assert!(world.is_alive(existing));
+    }
+}
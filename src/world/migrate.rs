@@ -0,0 +1,167 @@
+//! Migration of component data between component types.
+
+use alloc::boxed::Box;
+use core::{any::TypeId, ptr::NonNull};
+
+use crate::component::{Component, ComponentInfo};
+
+use super::{Edges, World};
+
+/// Describes how to migrate components of one type into another.
+///
+/// Used with [`World::migrate_components`] when component type layout changes,
+/// for example when code that defines the component is reloaded.
+pub struct ComponentMigration {
+    from: TypeId,
+    to: ComponentInfo,
+    migrate: Box<dyn FnMut(NonNull<u8>, NonNull<u8>)>,
+}
+
+impl ComponentMigration {
+    /// Returns migration from `Old` component type to `New` component type
+    /// that converts each value with provided function.
+    pub fn new<Old, New>(mut f: impl FnMut(Old) -> New + 'static) -> Self
+    where
+        Old: 'static,
+        New: Component,
+    {
+        ComponentMigration {
+            from: TypeId::of::<Old>(),
+            to: ComponentInfo::of::<New>(),
+            migrate: Box::new(move |src, dst| unsafe {
+                let old = src.cast::<Old>().as_ptr().read();
+                dst.cast::<New>().as_ptr().write(f(old));
+            }),
+        }
+    }
+
+    /// Returns migration from component with `from` id
+    /// to component described by `to`.
+    ///
+    /// Old type may no longer exist in the program,
+    /// and its id can be found with [`World::component_id_by_stable_name`].
+    ///
+    /// # Safety
+    ///
+    /// `migrate` must move component of type `from` out of the first pointer
+    /// and write component of type described by `to` into the second pointer.
+    pub unsafe fn from_raw(
+        from: TypeId,
+        to: ComponentInfo,
+        migrate: impl FnMut(NonNull<u8>, NonNull<u8>) + 'static,
+    ) -> Self {
+        ComponentMigration {
+            from,
+            to,
+            migrate: Box::new(migrate),
+        }
+    }
+}
+
+impl World {
+    /// Migrates components with provided migrations.
+    ///
+    /// Columns of old component types are rewritten in place with new component types.
+    /// Entities keep their ids, locations, epochs and enabled state of migrated components.
+    /// Registration of old component type is replaced with the new one,
+    /// so new type may reuse stable name of the old type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if entity has both old and new components of a migration.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::{ComponentMigration, World}};
+    /// #[derive(Component)]
+    /// struct PosV1(f32);
+    ///
+    /// #[derive(Component, Debug, PartialEq)]
+    /// struct PosV2(f32, f32);
+    ///
+    /// let mut world = World::new();
+    /// let e = world.spawn((PosV1(1.0),));
+    ///
+    /// world.migrate_components([ComponentMigration::new(|PosV1(x)| PosV2(x, 0.0))]);
+    ///
+    /// assert!(!world.has_component::<PosV1>(e).unwrap());
+    /// assert_eq!(world.query_one_mut::<&PosV2>(e), Ok(&PosV2(1.0, 0.0)));
+    /// ```
+    pub fn migrate_components(&mut self, migrations: impl IntoIterator<Item = ComponentMigration>) {
+        self.maintenance();
+
+        for mut migration in migrations {
+            self.registry.unregister(migration.from);
+            let to = self.registry.get_or_register_raw(migration.to).clone();
+
+            for archetype in self.archetypes.iter_mut() {
+                // Safety: migration moves `from` component into `to` component.
+                unsafe {
+                    archetype.migrate_component(migration.from, &to, &mut *migration.migrate);
+                }
+            }
+        }
+
+        // Cached archetype transitions and query matches refer to old components.
+        self.edges = Edges::new();
+        self.archetypes.renew_id();
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::vec::Vec;
+
+    use crate::{component::Component, query::Entities, test::U32, world::World};
+
+    /// Tests that migrated components keep entities, epochs and borrows consistent.
+    #[test]
+    fn migrate_components() {
+        use crate::world::ComponentMigration;
+
+        #[derive(Debug, PartialEq)]
+        struct Old(u32);
+        impl Component for Old {
+            const STABLE_NAME: Option<&'static str> = Some("test::Migrated");
+        }
+
+        #[derive(Debug, PartialEq)]
+        struct New(u64);
+        impl Component for New {
+            const STABLE_NAME: Option<&'static str> = Some("test::Migrated");
+        }
+
+        let mut world = World::new();
+        let a = world.spawn((Old(1), U32(1)));
+        let b = world.spawn((Old(2),));
+        let c = world.spawn((U32(3),));
+
+        let before = world.archetype_set_id();
+        let old = world.component_id_by_stable_name("test::Migrated").unwrap();
+        assert_eq!(old, core::any::TypeId::of::<Old>());
+
+        world.migrate_components([ComponentMigration::new(|Old(x)| New(x as u64 * 10))]);
+
+        assert_ne!(world.archetype_set_id(), before);
+        assert_eq!(
+            world.component_id_by_stable_name("test::Migrated"),
+            Some(core::any::TypeId::of::<New>())
+        );
+        assert_eq!(world.query::<&Old>().iter().count(), 0);
+
+        let mut values = world
+            .query::<(Entities, &New)>()
+            .iter()
+            .map(|(e, v)| (e, v.0))
+            .collect::<Vec<_>>();
+        values.sort();
+        assert_eq!(values, [(a, 10), (b, 20)]);
+
+        world.insert(c, New(30)).unwrap();
+        world.remove::<New>(a).unwrap();
+        assert_eq!(world.query_one_mut::<&U32>(a), Ok(&U32(1)));
+        assert_eq!(world.query::<&New>().iter().count(), 2);
+    }
+}
@@ -1,12 +1,12 @@
 //! Self-contained ECS [`World`].
 
-use alloc::{borrow::ToOwned, vec, vec::Vec};
+use alloc::{borrow::ToOwned, boxed::Box, string::String, sync::Arc, vec, vec::Vec};
 use core::{
     any::{type_name, TypeId},
     cell::Cell,
     convert::TryFrom,
     fmt::{self, Debug},
-    hash::Hash,
+    hash::{Hash, Hasher},
     iter::FromIterator,
     iter::FusedIterator,
     marker::PhantomData,
@@ -18,29 +18,108 @@ use atomicell::{Ref, RefMut};
 
 use crate::{
     action::{ActionBuffer, ActionChannel, ActionEncoder, ActionSender},
-    archetype::{chunk_idx, Archetype},
+    archetype::{chunk_idx, Archetype, ColumnAllocator, ColumnStore, GrowthPolicy},
     bundle::{
         Bundle, BundleDesc, ComponentBundle, ComponentBundleDesc, DynamicBundle,
-        DynamicComponentBundle,
+        DynamicComponentBundle, ReplaceBundle,
     },
-    component::{Component, ComponentInfo, ComponentRegistry},
-    entity::{EntityId, EntitySet},
+    component::{Component, ComponentBorrow, ComponentInfo, ComponentRegistry},
+    entity::{EntityId, EntitySet, IdCheckpoint, Location, RowPin},
     epoch::{EpochCounter, EpochId},
-    query::{DefaultQuery, Fetch, IntoQuery, Query, QueryItem},
-    relation::{OriginComponent, Relation, TargetComponent},
+    hash::StableHasher,
+    pool::ColumnPool,
+    query::{
+        validate_query, Access, DefaultQuery, Entities, Fetch, IntoQuery, Query, QueryConflict,
+        QueryItem,
+    },
+    relation::{OriginComponent, RelatesTarget, Relation, TargetComponent},
     res::Res,
 };
 
-use self::edges::Edges;
+use self::{
+    deferred::DeferredDespawns, edges::Edges, index::Indexes, invariant::Invariants,
+    live::LiveQueries, quota::Quotas, relation_events::RelationReaders, sort::SortKeys,
+    subscribe::Subscriptions, track::Trackers,
+};
 
 pub use self::{
     builder::WorldBuilder,
-    query::{QueryOne, QueryRef},
+    capability::{AccessDenied, RestrictedWorld, WorldCapability},
+    deferred::DespawnGuard,
+    diff::{ComponentDiff, DiffKind},
+    fork::NotCloneable,
+    guard::ComponentGuard,
+    id::WorldId,
+    index::Indexed,
+    invariant::{InvariantFn, InvariantViolation},
+    live::LiveQuery,
+    manifest::{ArchetypeLayout, ColumnLayout, LayoutManifest},
+    memo::Memo,
+    migrate::ComponentMigration,
+    query::{GroupScratch, Groups, QueryCursor, QueryOne, QueryRef},
+    query_async::QueryFuture,
+    quota::QuotaExceeded,
+    relation_constraints::RelationViolation,
+    relation_events::{RelationEvent, RelationEvents},
+    relation_pairs::RelationPairs,
+    scope::Scope,
+    split::{QueryView, ResourceView},
+    sub_world::{SubWorldFilter, SubWorldView},
+    subscribe::{EntityEvent, EntityEvents},
+    track::ChangeTracker,
+    transaction::Transaction,
+    view::{ViewQueries, WorldView},
+    watch::{ChangeKind, ComponentChange, WatchHandle},
 };
 
+#[cfg(feature = "debug-dump")]
+pub use self::debug_dump::DebugDump;
+
+#[cfg(feature = "undo")]
+pub use self::undo::UndoLog;
+
+/// Changes are not recorded without `undo` feature.
+#[cfg(not(feature = "undo"))]
+enum PendingChange {}
+
 mod builder;
+mod capability;
+#[cfg(feature = "debug-dump")]
+mod debug_dump;
+mod deferred;
+mod diff;
 mod edges;
+mod enabled;
+mod fill;
+mod fork;
+mod guard;
+mod id;
+mod index;
+mod invariant;
+mod live;
+mod manifest;
+mod memo;
+mod merge;
+mod migrate;
 mod query;
+mod query_async;
+mod quota;
+mod relation_constraints;
+mod relation_events;
+mod relation_pairs;
+mod scope;
+mod sort;
+mod split;
+mod sub_world;
+mod subscribe;
+mod track;
+mod transaction;
+mod typed;
+#[cfg(feature = "undo")]
+mod undo;
+mod view;
+mod watch;
+mod weak;
 
 /// Limits on reserving of space for entities and components
 /// in archetypes when `spawn_batch` is used.
@@ -59,6 +138,16 @@ struct ArchetypeSet {
     id: u64,
 
     archetypes: Vec<Archetype>,
+
+    /// Growth policy of new archetypes.
+    growth: GrowthPolicy,
+
+    /// Column allocator of new archetypes.
+    /// Either custom allocator or the pool of this set.
+    allocator: Option<Arc<dyn ColumnAllocator>>,
+
+    /// Pool of small columns used when custom allocator is not configured.
+    pool: Option<Arc<ColumnPool>>,
 }
 
 impl Deref for ArchetypeSet {
@@ -76,11 +165,17 @@ impl DerefMut for ArchetypeSet {
 }
 
 impl ArchetypeSet {
-    fn new() -> Self {
-        let null_archetype = Archetype::new(core::iter::empty());
+    fn new(growth: GrowthPolicy, allocator: Option<Arc<dyn ColumnAllocator>>) -> Self {
+        let (allocator, pool) = with_pool(allocator);
+        let mut null_archetype = Archetype::new(core::iter::empty());
+        null_archetype.set_growth_policy(growth);
+        null_archetype.set_column_allocator(allocator.clone());
         ArchetypeSet {
             id: 0,
             archetypes: vec![null_archetype],
+            growth,
+            allocator,
+            pool,
         }
     }
 
@@ -93,11 +188,59 @@ impl ArchetypeSet {
             Ok(u32::MAX) | Err(_) => panic!("Too many archetypes"),
             Ok(len) => len,
         };
-        let new_archetype = f(&self.archetypes);
+        let mut new_archetype = f(&self.archetypes);
+        new_archetype.set_growth_policy(self.growth);
+        new_archetype.set_column_allocator(self.allocator.clone());
         self.archetypes.push(new_archetype);
         self.id = NEXT_ARCHETYPE_SET_ID.fetch_add(1, Ordering::Relaxed);
         len
     }
+
+    /// Assigns new id after components of archetypes were changed in place.
+    fn renew_id(&mut self) {
+        self.id = NEXT_ARCHETYPE_SET_ID.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns copy of the set with archetypes sharing columns with this set.
+    /// Copy gets its own pool, custom allocator is shared.
+    fn fork(&mut self) -> Self {
+        let (allocator, pool) = match self.pool {
+            None => (self.allocator.clone(), None),
+            Some(_) => with_pool(None),
+        };
+
+        ArchetypeSet {
+            id: NEXT_ARCHETYPE_SET_ID.fetch_add(1, Ordering::Relaxed),
+            archetypes: self
+                .archetypes
+                .iter_mut()
+                .map(|archetype| archetype.fork(allocator.clone()))
+                .collect(),
+            growth: self.growth,
+            allocator,
+            pool,
+        }
+    }
+
+    /// Releases unused pages of the column pool.
+    fn trim_pool(&self) {
+        if let Some(pool) = &self.pool {
+            pool.trim();
+        }
+    }
+}
+
+/// Returns new column pool as allocator if custom allocator is not provided.
+/// Columns are not pooled with "column-guard" feature.
+fn with_pool(
+    allocator: Option<Arc<dyn ColumnAllocator>>,
+) -> (Option<Arc<dyn ColumnAllocator>>, Option<Arc<ColumnPool>>) {
+    if allocator.is_some() || cfg!(feature = "column-guard") {
+        return (allocator, None);
+    }
+
+    let pool = Arc::new(ColumnPool::new());
+    (Some(pool.clone()), Some(pool))
 }
 
 pub(crate) fn iter_reserve_hint(iter: &impl Iterator) -> usize {
@@ -149,6 +292,37 @@ pub struct World {
 
     res: Res,
 
+    /// Change trackers registered with [`World::track`].
+    trackers: Trackers,
+
+    /// Live queries registered with [`World::live_query`].
+    live: LiveQueries,
+
+    /// Entity subscriptions registered with [`World::subscribe`].
+    subscriptions: Subscriptions,
+
+    /// Readers registered with [`World::relation_events`].
+    relation_readers: RelationReaders,
+
+    /// Entities passed to [`World::despawn_deferred`].
+    deferred_despawns: DeferredDespawns,
+
+    /// Indexes registered with [`World::add_index`].
+    indexes: Indexes,
+
+    /// Invariants registered with [`World::add_invariant`].
+    invariants: Invariants,
+
+    /// Limits on number of entities.
+    quotas: Quotas,
+
+    /// Sort keys registered with [`World::sort_by_key`].
+    sort_keys: SortKeys,
+
+    /// Log of recorded operations for [`World::undo`] and [`World::redo`].
+    #[cfg(feature = "undo")]
+    undo_log: UndoLog,
+
     /// Internal action encoder.
     /// This encoder is used to record commands from component hooks.
     /// Commands are immediately executed at the end of the mutating call.
@@ -240,6 +414,154 @@ impl World {
         self.registry.ensure_external_registered::<T>();
     }
 
+    /// Explicitly registers component with provided type information.
+    /// Does nothing if component with the same id is already registered.
+    #[cfg(feature = "ffi")]
+    pub(crate) fn ensure_raw_registered(&mut self, info: ComponentInfo) {
+        self.registry.get_or_register_raw(info);
+    }
+
+    /// Makes component `T` borrowable as additional types.
+    ///
+    /// Borrows are applied to the component registration
+    /// and to all existing archetypes that contain the component.
+    /// This allows queries like [`QueryBorrowAny`] to see components
+    /// through traits defined after the component type.
+    ///
+    /// Borrows with targets already supported by the component are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if component `T` is not registered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{borrow_dyn_trait, component::Component, world::World};
+    /// #[derive(Component, Debug)]
+    /// struct Foo;
+    ///
+    /// let mut world = World::new();
+    /// world.spawn((Foo,));
+    ///
+    /// let mut borrows = Vec::new();
+    /// borrow_dyn_trait!(Foo as core::fmt::Debug => borrows);
+    /// world.export_borrows::<Foo>(borrows);
+    ///
+    /// let count = world
+    ///     .new_query()
+    ///     .borrow_any::<&(dyn core::fmt::Debug + Sync)>()
+    ///     .iter()
+    ///     .count();
+    /// assert_eq!(count, 1);
+    /// ```
+    ///
+    /// [`QueryBorrowAny`]: crate::query::QueryBorrowAny
+    pub fn export_borrows<T>(&mut self, borrows: impl IntoIterator<Item = ComponentBorrow>)
+    where
+        T: 'static,
+    {
+        let borrows = borrows.into_iter().collect::<Vec<_>>();
+
+        match self.registry.get_info_mut(TypeId::of::<T>()) {
+            None => panic!("Component {} is not registered", type_name::<T>()),
+            Some(info) => info.extend_borrows(&borrows),
+        }
+
+        for archetype in self.archetypes.iter_mut() {
+            archetype.extend_borrows(TypeId::of::<T>(), &borrows);
+        }
+    }
+
+    /// Returns id of the registered component with specified name.
+    ///
+    /// Components are indexed by name when they are registered,
+    /// either by the [`Component::name`] or name set with [`ComponentInfoRef::name`].
+    /// If several components share the same name, the first registered one is returned.
+    ///
+    /// [`ComponentInfoRef::name`]: crate::component::ComponentInfoRef::name
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use core::any::TypeId;
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut builder = World::builder();
+    /// builder.register_component::<ExampleComponent>().name("example");
+    /// let world = builder.build();
+    ///
+    /// assert_eq!(world.component_id_by_name("example"), Some(TypeId::of::<ExampleComponent>()));
+    /// assert_eq!(world.component_id_by_name("missing"), None);
+    /// ```
+    #[inline]
+    pub fn component_id_by_name(&self, name: &str) -> Option<TypeId> {
+        self.registry.id_by_name(name)
+    }
+
+    /// Returns id of the registered component with specified stable name.
+    ///
+    /// See [`Component::STABLE_NAME`] and [`ComponentInfoRef::stable_name`].
+    ///
+    /// [`ComponentInfoRef::stable_name`]: crate::component::ComponentInfoRef::stable_name
+    #[inline]
+    pub fn component_id_by_stable_name(&self, stable_name: &str) -> Option<TypeId> {
+        self.registry.id_by_stable_name(stable_name)
+    }
+
+    /// Exports stable names of all registered components that have one.
+    /// Names are sorted, so that the export is deterministic.
+    ///
+    /// Exported list can be stored alongside serialized data
+    /// to refer to components by index in the list.
+    /// Use [`World::import_components`] to map the list back to component ids.
+    pub fn export_components(&self) -> Vec<&'static str> {
+        let mut names = self.registry.stable_names().collect::<Vec<_>>();
+        names.sort_unstable();
+        names
+    }
+
+    /// Maps list of stable component names, possibly exported by another build,
+    /// to ids of components registered in this world.
+    ///
+    /// Fails if any of the names is not registered.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use core::any::TypeId;
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Component)]
+    /// struct Health(u32);
+    ///
+    /// let mut builder = World::builder();
+    /// builder.register_component::<Health>().stable_name("game::Health");
+    /// let world = builder.build();
+    ///
+    /// let exported = world.export_components();
+    /// assert_eq!(exported, ["game::Health"]);
+    ///
+    /// let ids = world.import_components(&exported).unwrap();
+    /// assert_eq!(ids, [TypeId::of::<Health>()]);
+    ///
+    /// assert!(world.import_components(&["game::Mana"]).is_err());
+    /// ```
+    pub fn import_components<S>(&self, stable_names: &[S]) -> Result<Vec<TypeId>, UnknownComponent>
+    where
+        S: AsRef<str>,
+    {
+        stable_names
+            .iter()
+            .map(|name| {
+                let name = name.as_ref();
+                self.registry
+                    .id_by_stable_name(name)
+                    .ok_or_else(|| UnknownComponent {
+                        stable_name: name.to_owned(),
+                    })
+            })
+            .collect()
+    }
+
     /// Returns unique identified of archetype set.
     /// This ID changes each time new archetype is added or removed.
     /// IDs of different worlds are never equal within the same process.
@@ -273,6 +595,49 @@ impl World {
         self.entities.alloc()
     }
 
+    /// Returns checkpoint of entity ID allocator.
+    /// Allocated but not yet spawned entities are spawned first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if world was not built with [`WorldBuilder::deterministic_ids`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut world = World::builder().deterministic_ids().build();
+    /// let checkpoint = world.id_checkpoint();
+    ///
+    /// let a = world.spawn((ExampleComponent,));
+    /// world.despawn(a).unwrap();
+    ///
+    /// world.reset_ids(checkpoint);
+    /// let b = world.spawn((ExampleComponent,));
+    /// assert_eq!(a, b);
+    /// ```
+    pub fn id_checkpoint(&mut self) -> IdCheckpoint {
+        self.maintenance();
+        self.entities.checkpoint()
+    }
+
+    /// Resets entity ID allocator to the checkpoint,
+    /// so IDs allocated after the checkpoint will be allocated again
+    /// in the same order.
+    ///
+    /// Entities spawned after the checkpoint must be despawned first,
+    /// for example by rolling back the world state.
+    /// Allocated but not yet spawned entities are spawned first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if world was not built with [`WorldBuilder::deterministic_ids`]
+    /// or if entity with ID allocated after the checkpoint is alive.
+    pub fn reset_ids(&mut self, checkpoint: IdCheckpoint) {
+        self.maintenance();
+        self.entities.reset_allocator(checkpoint);
+    }
+
     /// Spawns a new entity in this world with provided bundle of components.
     /// Returns [`EntityId`] to the newly spawned entity.
     /// Spawned entity is populated with all components from the bundle.
@@ -280,7 +645,9 @@ impl World {
     ///
     /// # Panics
     ///
-    /// Panics if new id cannot be allocated.
+    /// Panics if new id cannot be allocated
+    /// or if entity limit configured for the world is exceeded.
+    /// See [`World::spawn_checked`].
     ///
     /// # Example
     ///
@@ -301,6 +668,130 @@ impl World {
         self.spawn_impl(bundle, register_bundle::<B>)
     }
 
+    /// Attempts to spawn a new entity in this world with provided bundle of components.
+    ///
+    /// Unlike [`World::spawn`] this method fails with `Err(CapacityExceeded)`
+    /// instead of panicking when target archetype has fixed capacity
+    /// and is full.
+    /// See [`World::fix_archetype_capacity`].
+    pub fn try_spawn<B>(&mut self, bundle: B) -> Result<EntityId, CapacityExceeded>
+    where
+        B: DynamicComponentBundle,
+    {
+        self.maintenance();
+
+        if !bundle.valid() {
+            panic!(
+                "Specified bundle `{}` is not valid. Check for duplicate component types",
+                type_name::<B>()
+            );
+        }
+
+        let archetype_idx = self.edges.spawn(
+            &mut self.registry,
+            &mut self.archetypes,
+            &bundle,
+            |registry| register_bundle(registry, &bundle),
+        );
+
+        if !self.archetypes[archetype_idx as usize].has_room(1) {
+            return Err(CapacityExceeded);
+        }
+
+        Ok(self.spawn_impl(bundle, register_bundle::<B>))
+    }
+
+    /// Allocates fixed capacity for the archetype
+    /// with exactly the set of components from bundle `B`.
+    ///
+    /// Component storage of the archetype is never reallocated afterwards.
+    /// Methods that would require growth of the archetype panic,
+    /// except [`World::try_spawn`] that returns an error.
+    ///
+    /// Note that despawning or moving entities out of the archetype
+    /// swaps the last entity into vacated row.
+    /// Use [`World::pin_entity_row`] to get notified when this happens.
+    ///
+    /// Fails with `Err(CapacityExceeded)` if archetype already contains more entities
+    /// or has smaller fixed capacity.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::{CapacityExceeded, World}, ExampleComponent};
+    /// let mut world = World::new();
+    /// world.fix_archetype_capacity::<(ExampleComponent,)>(1).unwrap();
+    ///
+    /// assert!(world.try_spawn((ExampleComponent,)).is_ok());
+    /// assert_eq!(world.try_spawn((ExampleComponent,)), Err(CapacityExceeded));
+    /// ```
+    pub fn fix_archetype_capacity<B>(&mut self, capacity: usize) -> Result<(), CapacityExceeded>
+    where
+        B: ComponentBundle,
+    {
+        self.maintenance();
+
+        let archetype_idx = self.edges.spawn(
+            &mut self.registry,
+            &mut self.archetypes,
+            &PhantomData::<B>,
+            |registry| register_bundle(registry, &PhantomData::<B>),
+        );
+
+        match self.archetypes[archetype_idx as usize].fix_capacity(capacity) {
+            true => Ok(()),
+            false => Err(CapacityExceeded),
+        }
+    }
+
+    /// Sets growth policy of all archetypes of the world,
+    /// including archetypes created later.
+    ///
+    /// Default policy is [`GrowthPolicy::Doubling`].
+    /// See also [`WorldBuilder::growth_policy`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{archetype::GrowthPolicy, world::World, ExampleComponent};
+    /// let mut world = World::new();
+    /// world.set_growth_policy(GrowthPolicy::Exact);
+    ///
+    /// let entity = world.spawn((ExampleComponent,));
+    /// let archetype = world.locate(entity).unwrap().archetype;
+    /// assert!(matches!(
+    ///     world.archetypes()[archetype as usize].growth_policy(),
+    ///     GrowthPolicy::Exact
+    /// ));
+    /// ```
+    pub fn set_growth_policy(&mut self, policy: GrowthPolicy) {
+        self.archetypes.growth = policy;
+        for archetype in self.archetypes.iter_mut() {
+            archetype.set_growth_policy(policy);
+        }
+    }
+
+    /// Sets growth policy of the archetype
+    /// with exactly the set of components from bundle `B`.
+    ///
+    /// Overrides policy set with [`World::set_growth_policy`]
+    /// until it is called again.
+    pub fn set_archetype_growth_policy<B>(&mut self, policy: GrowthPolicy)
+    where
+        B: ComponentBundle,
+    {
+        self.maintenance();
+
+        let archetype_idx = self.edges.spawn(
+            &mut self.registry,
+            &mut self.archetypes,
+            &PhantomData::<B>,
+            |registry| register_bundle(registry, &PhantomData::<B>),
+        );
+
+        self.archetypes[archetype_idx as usize].set_growth_policy(policy);
+    }
+
     /// Spawns a new entity in this world with specific ID and bundle of components.
     /// The id must be unused by the world.
     /// Spawned entity is populated with all components from the bundle.
@@ -329,6 +820,48 @@ impl World {
         self.spawn_with_id_impl(id, bundle, register_bundle::<B>)
     }
 
+    /// Spawns a new entity with specific ID and bundle of components.
+    ///
+    /// Unlike [`World::spawn_with_id`] this method fails with `Err(IdOccupied)`
+    /// if entity with the id is already spawned.
+    /// The id is never allocated by the world afterwards,
+    /// even if it is within range the world allocates from.
+    ///
+    /// This allows mirroring entity ids assigned elsewhere,
+    /// like by a network server, or restoring saved ids.
+    /// Returns the id stamped by this world.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{entity::EntityId, world::{IdOccupied, World}, ExampleComponent};
+    /// let mut world = World::new();
+    /// let id = EntityId::from_bits(2).unwrap();
+    ///
+    /// world.spawn_at(id, (ExampleComponent,)).unwrap();
+    /// assert_eq!(world.spawn_at(id, ()), Err(IdOccupied));
+    ///
+    /// // Allocator skips occupied id.
+    /// let a = world.spawn(());
+    /// let b = world.spawn(());
+    /// assert_ne!(a, id);
+    /// assert_ne!(b, id);
+    /// ```
+    pub fn spawn_at<B>(&mut self, id: EntityId, bundle: B) -> Result<EntityId, IdOccupied>
+    where
+        B: DynamicComponentBundle,
+    {
+        self.maintenance();
+
+        if self.entities.is_occupied(id) {
+            return Err(IdOccupied);
+        }
+
+        let id = id.stamp(self.id());
+        self.spawn_with_id_impl(id, bundle, register_bundle::<B>);
+        Ok(id)
+    }
+
     /// Spawns entity with specific ID if it is not already spawned.
     #[inline]
     pub fn spawn_if_missing(&mut self, id: EntityId) -> bool {
@@ -446,17 +979,25 @@ impl World {
             );
         }
 
-        self.entities.spawn_at(id);
-
         let archetype_idx = self.edges.spawn(
             &mut self.registry,
             &mut self.archetypes,
             &bundle,
             |registry| register_bundle(registry, &bundle),
         );
+
+        if let Err(err) = self.quotas.check(&self.archetypes, archetype_idx) {
+            panic!("{}", err);
+        }
+
+        let change = self.begin_change(id, None);
+
+        self.entities.spawn_at(id);
         let epoch = self.epoch.next_mut();
         let idx = self.archetypes[archetype_idx as usize].spawn(id, bundle, epoch);
         self.entities.set_location(id, archetype_idx, idx);
+
+        self.end_change(change);
     }
 
     /// Returns an iterator which spawns and yield entities
@@ -475,6 +1016,8 @@ impl World {
     ///
     /// When returned iterator is dropped, no more entities will be spawned
     /// even if bundles iterator has items left.
+    ///
+    /// Returned iterator panics when entity limit configured for the world is exceeded.
     #[inline]
     pub fn spawn_batch<B, I>(&mut self, bundles: I) -> SpawnBatch<'_, I::IntoIter>
     where
@@ -547,6 +1090,7 @@ impl World {
         );
 
         let epoch = self.epoch.next_mut();
+        let quota = self.quotas.remaining(&self.archetypes, archetype_idx);
 
         let archetype = &mut self.archetypes[archetype_idx as usize];
         let entities = &mut self.entities;
@@ -557,6 +1101,7 @@ impl World {
             archetype_idx,
             archetype,
             entities,
+            quota,
         }
     }
 
@@ -603,6 +1148,8 @@ impl World {
     ) -> Result<(), NoSuchEntity> {
         self.maintenance();
 
+        let change = self.begin_change(id, None);
+
         let (archetype, idx) = self.entities.despawn(id)?;
 
         let encoder = ActionEncoder::new(buffer, &self.entities);
@@ -613,9 +1160,52 @@ impl World {
             self.entities.set_location(id, archetype, idx)
         }
 
+        self.end_change(change);
         Ok(())
     }
 
+    /// Despawns all entities in the world.
+    ///
+    /// Components are dropped and relation hooks are executed
+    /// same way as with [`World::despawn`].
+    /// Archetypes with their allocated storage,
+    /// as well as resources are kept for reuse.
+    ///
+    /// Entities spawned by drop hooks during clearing are not despawned.
+    ///
+    /// Recorded changes are discarded from the undo log,
+    /// since they refer to entities that no longer exist.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut world = World::new();
+    /// let entity = world.spawn((ExampleComponent,));
+    ///
+    /// world.clear();
+    /// assert!(!world.is_alive(entity));
+    /// assert_eq!(world.query::<&ExampleComponent>().iter().count(), 0);
+    /// ```
+    pub fn clear(&mut self) {
+        self.maintenance();
+
+        with_buffer!(self, buffer => {
+            for archetype in self.archetypes.iter_mut() {
+                // Despawn from the end to avoid moving entities.
+                while let Some(&id) = archetype.entities().last() {
+                    let (_, idx) = self.entities.despawn(id).unwrap();
+                    let encoder = ActionEncoder::new(buffer, &self.entities);
+                    let opt_id = unsafe { archetype.despawn_unchecked(id, idx, encoder) };
+                    debug_assert!(opt_id.is_none());
+                }
+            }
+        });
+
+        #[cfg(feature = "undo")]
+        self.undo_log.clear();
+    }
+
     /// Attempts to inserts component to the specified entity.
     ///
     /// If entity already had component of that type,
@@ -717,6 +1307,8 @@ impl World {
         let (src_archetype, idx) = self.entities.get_location(id).ok_or(NoSuchEntity)?;
         debug_assert!(src_archetype < u32::MAX, "Allocated entities were spawned");
 
+        let change = self.begin_change(id, Some(&[TypeId::of::<T>()]));
+
         let epoch = self.epoch.next_mut();
 
         let encoder = ActionEncoder::new(buffer, &self.entities);
@@ -726,6 +1318,7 @@ impl World {
                 self.archetypes[src_archetype as usize].set(id, idx, component, epoch, encoder);
             }
 
+            self.end_change(change);
             return Ok(());
         }
 
@@ -756,27 +1349,98 @@ impl World {
             self.entities.set_location(src_id, src_archetype, idx);
         }
 
+        self.end_change(change);
         Ok(())
     }
 
-    /// Removes component from the specified entity and returns its value.
+    /// Returns mutable reference to the component of the specified entity.
+    /// If entity does not have the component, inserts default value first.
     ///
-    /// If entity does not have component of this type, fails with `Err(EntityError::MissingComponent)`.
     /// If entity is not alive, fails with `Err(NoSuchEntity)`.
-    #[inline]
-    pub fn remove<T>(&mut self, id: EntityId) -> Result<T, EntityError>
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Component, Default)]
+    /// struct Counter(u32);
+    ///
+    /// let mut world = World::new();
+    /// let entity = world.spawn(());
+    ///
+    /// world.ensure_component::<Counter>(entity).unwrap().0 += 1;
+    /// world.ensure_component::<Counter>(entity).unwrap().0 += 1;
+    /// assert_eq!(world.ensure_component::<Counter>(entity).unwrap().0, 2);
+    /// ```
+    pub fn ensure_component<T>(&mut self, id: EntityId) -> Result<&mut T, NoSuchEntity>
     where
-        T: 'static,
+        T: Component + Default,
     {
         self.maintenance();
 
         let (src_archetype, idx) = self.entities.get_location(id).ok_or(NoSuchEntity)?;
         debug_assert!(src_archetype < u32::MAX, "Allocated entities were spawned");
 
-        if !self.archetypes[src_archetype as usize].has_component(TypeId::of::<T>()) {
-            return Err(EntityError::MissingComponents);
+        if self.archetypes[src_archetype as usize].has_component(TypeId::of::<T>()) {
+            let epoch = self.epoch.next_mut();
+            let archetype = &mut self.archetypes[src_archetype as usize];
+            return Ok(unsafe { archetype.get_mut::<T>(idx, epoch) });
         }
 
+        let epoch = self.epoch.next_mut();
+
+        let dst_archetype = self.edges.insert(
+            TypeId::of::<T>(),
+            &mut self.registry,
+            &mut self.archetypes,
+            src_archetype,
+            register_one::<T>,
+        );
+
+        debug_assert_ne!(src_archetype, dst_archetype);
+
+        let (before, after) = self
+            .archetypes
+            .split_at_mut(src_archetype.max(dst_archetype) as usize);
+
+        let (src, dst) = match src_archetype < dst_archetype {
+            true => (&mut before[src_archetype as usize], &mut after[0]),
+            false => (&mut after[0], &mut before[dst_archetype as usize]),
+        };
+
+        let (dst_idx, opt_src_id) = unsafe { src.insert(id, dst, idx, T::default(), epoch) };
+
+        self.entities.set_location(id, dst_archetype, dst_idx);
+
+        if let Some(src_id) = opt_src_id {
+            self.entities.set_location(src_id, src_archetype, idx);
+        }
+
+        let epoch = self.epoch.next_mut();
+        let archetype = &mut self.archetypes[dst_archetype as usize];
+        Ok(unsafe { archetype.get_mut::<T>(dst_idx, epoch) })
+    }
+
+    /// Removes component from the specified entity and returns its value.
+    ///
+    /// If entity does not have component of this type, fails with `Err(EntityError::MissingComponent)`.
+    /// If entity is not alive, fails with `Err(NoSuchEntity)`.
+    #[inline]
+    pub fn remove<T>(&mut self, id: EntityId) -> Result<T, EntityError>
+    where
+        T: 'static,
+    {
+        self.maintenance();
+
+        let (src_archetype, idx) = self.entities.get_location(id).ok_or(NoSuchEntity)?;
+        debug_assert!(src_archetype < u32::MAX, "Allocated entities were spawned");
+
+        if !self.archetypes[src_archetype as usize].has_component(TypeId::of::<T>()) {
+            return Err(EntityError::MissingComponents);
+        }
+
+        let change = self.begin_change(id, Some(&[TypeId::of::<T>()]));
+
         let dst_archetype =
             self.edges
                 .remove(&mut self.archetypes, src_archetype, TypeId::of::<T>());
@@ -800,6 +1464,7 @@ impl World {
             self.entities.set_location(src_id, src_archetype, idx);
         }
 
+        self.end_change(change);
         Ok(component)
     }
 
@@ -842,6 +1507,8 @@ impl World {
             return Err(EntityError::MissingComponents);
         }
 
+        let change = self.begin_change(id, Some(&[tid]));
+
         let dst_archetype = self.edges.remove(&mut self.archetypes, src_archetype, tid);
 
         debug_assert_ne!(src_archetype, dst_archetype);
@@ -864,6 +1531,7 @@ impl World {
             self.entities.set_location(src_id, src_archetype, idx);
         }
 
+        self.end_change(change);
         Ok(())
     }
 
@@ -963,6 +1631,104 @@ impl World {
         self.insert_bundle_impl(id, bundle, assert_registered_bundle::<B>, buffer)
     }
 
+    /// Inserts bundle of components to the specified entity
+    /// and returns values of components the entity already had.
+    ///
+    /// Works like [`World::insert_bundle`],
+    /// except that replaced values are returned instead of being dropped,
+    /// so replace and drop hooks are not called for them.
+    /// Result contains `Some` for each component type that entity had
+    /// and `None` for each component type that was added.
+    ///
+    /// If entity is not alive, fails with `Err(NoSuchEntity)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Debug, PartialEq, Eq, Component)]
+    /// struct Health(u32);
+    ///
+    /// #[derive(Debug, PartialEq, Eq, Component)]
+    /// struct Armor(u32);
+    ///
+    /// let mut world = World::new();
+    /// let entity = world.spawn((Health(10),));
+    ///
+    /// let old = world.replace_bundle(entity, (Health(5), Armor(3))).unwrap();
+    /// assert_eq!(old, (Some(Health(10)), None));
+    ///
+    /// let old = world.replace_bundle(entity, (Armor(4),)).unwrap();
+    /// assert_eq!(old, (Some(Armor(3)),));
+    /// assert_eq!(world.query_one_mut::<&Armor>(entity), Ok(&Armor(4)));
+    /// ```
+    pub fn replace_bundle<B>(
+        &mut self,
+        id: EntityId,
+        bundle: B,
+    ) -> Result<B::Replaced, NoSuchEntity>
+    where
+        B: ReplaceBundle,
+    {
+        if !bundle.valid() {
+            panic!(
+                "Specified bundle `{}` is not valid. Check for duplicate component types",
+                type_name::<B>()
+            );
+        }
+
+        self.maintenance();
+
+        let (src_archetype, idx) = self.entities.get_location(id).ok_or(NoSuchEntity)?;
+        debug_assert!(src_archetype < u32::MAX, "Allocated entities were spawned");
+
+        if bundle.with_ids(|ids| ids.is_empty()) {
+            // Safety: bundle is empty, nothing is taken.
+            return Ok(unsafe { self.archetypes[src_archetype as usize].take_bundle::<B>(idx) });
+        }
+
+        let epoch = self.epoch.next_mut();
+
+        let dst_archetype = self.edges.insert_bundle(
+            &mut self.registry,
+            &mut self.archetypes,
+            src_archetype,
+            &bundle,
+            |registry| register_bundle(registry, &bundle),
+        );
+
+        if dst_archetype == src_archetype {
+            let archetype = &mut self.archetypes[src_archetype as usize];
+
+            // Safety: taken values are overwritten with bundle right away.
+            let replaced = unsafe { archetype.take_bundle::<B>(idx) };
+            unsafe { archetype.overwrite_bundle(id, idx, bundle, epoch) };
+            return Ok(replaced);
+        }
+
+        let (before, after) = self
+            .archetypes
+            .split_at_mut(src_archetype.max(dst_archetype) as usize);
+
+        let (src, dst) = match src_archetype < dst_archetype {
+            true => (&mut before[src_archetype as usize], &mut after[0]),
+            false => (&mut after[0], &mut before[dst_archetype as usize]),
+        };
+
+        // Safety: taken values are relocated and then overwritten with bundle.
+        let replaced = unsafe { src.take_bundle::<B>(idx) };
+        let (dst_idx, opt_src_id) =
+            unsafe { src.insert_overwrite_bundle(id, dst, idx, bundle, epoch) };
+
+        self.entities.set_location(id, dst_archetype, dst_idx);
+
+        if let Some(src_id) = opt_src_id {
+            self.entities.set_location(src_id, src_archetype, idx);
+        }
+
+        Ok(replaced)
+    }
+
     fn insert_bundle_impl<B, F>(
         &mut self,
         id: EntityId,
@@ -990,6 +1756,8 @@ impl World {
             return Ok(());
         }
 
+        let change = bundle.with_ids(|ids| self.begin_change(id, Some(ids)));
+
         let epoch = self.epoch.next_mut();
 
         let dst_archetype = self.edges.insert_bundle(
@@ -1010,6 +1778,7 @@ impl World {
                     ActionEncoder::new(buffer, &self.entities),
                 )
             }
+            self.end_change(change);
             return Ok(());
         }
 
@@ -1039,6 +1808,170 @@ impl World {
             self.entities.set_location(src_id, src_archetype, idx);
         }
 
+        self.end_change(change);
+        Ok(())
+    }
+
+    /// Inserts bundles of components to many entities.
+    /// This is moral equivalent to calling `World::insert_bundle` for each pair,
+    /// but more efficient.
+    ///
+    /// Entities are grouped by their archetype,
+    /// so destination archetype is resolved once per group
+    /// and space for all moved entities is reserved at once.
+    /// Bundles are applied in iteration order within each group.
+    ///
+    /// If any entity is not alive, fails with `Err(NoSuchEntity)`
+    /// and no bundles are applied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World, ExampleComponent};
+    /// #[derive(Component, Debug, PartialEq)]
+    /// struct Health(u32);
+    ///
+    /// let mut world = World::new();
+    /// let a = world.spawn(());
+    /// let b = world.spawn((ExampleComponent,));
+    ///
+    /// world
+    ///     .apply_bundles([(a, (ExampleComponent, Health(1))), (b, (ExampleComponent, Health(2)))])
+    ///     .unwrap();
+    /// assert_eq!(world.query_one_mut::<&Health>(a), Ok(&Health(1)));
+    /// assert_eq!(world.query_one_mut::<&Health>(b), Ok(&Health(2)));
+    /// ```
+    pub fn apply_bundles<B, I>(&mut self, bundles: I) -> Result<(), NoSuchEntity>
+    where
+        I: IntoIterator<Item = (EntityId, B)>,
+        B: ComponentBundle,
+    {
+        if !B::static_valid() {
+            panic!(
+                "Specified bundle `{}` is not valid. Check for duplicate component types",
+                type_name::<B>()
+            );
+        }
+
+        self.maintenance();
+
+        let mut items = Vec::new();
+        for (id, bundle) in bundles {
+            let (archetype, _) = self.entities.get_location(id).ok_or(NoSuchEntity)?;
+            debug_assert!(archetype < u32::MAX, "Allocated entities were spawned");
+            items.push((archetype, id, bundle));
+        }
+
+        if items.is_empty() || B::static_with_ids(|ids| ids.is_empty()) {
+            return Ok(());
+        }
+
+        // Each bundle is recorded as separate change, in iteration order.
+        #[cfg(feature = "undo")]
+        if self.undo_log.is_recording() {
+            with_buffer!(self, buffer => {
+                for (_, id, bundle) in items {
+                    // Entities are checked above and despawns are deferred.
+                    self.insert_bundle_impl(id, bundle, register_bundle::<B>, buffer).unwrap();
+                }
+            });
+            return Ok(());
+        }
+
+        // Stable sort keeps order of bundles applied to the same entity.
+        items.sort_by_key(|(archetype, _, _)| *archetype);
+
+        with_buffer!(self, buffer => {
+            let mut items = items.into_iter();
+
+            while let Some(&(src_archetype, _, ref bundle)) = items.as_slice().first() {
+                let dst_archetype = self.edges.insert_bundle(
+                    &mut self.registry,
+                    &mut self.archetypes,
+                    src_archetype,
+                    bundle,
+                    |registry| register_bundle(registry, bundle),
+                );
+
+                let group_len = items
+                    .as_slice()
+                    .iter()
+                    .take_while(|(archetype, _, _)| *archetype == src_archetype)
+                    .count();
+                let group = items.by_ref().take(group_len);
+
+                if dst_archetype == src_archetype {
+                    let archetype = &mut self.archetypes[src_archetype as usize];
+                    for (_, id, bundle) in group {
+                        let (_, idx) = self.entities.get_location(id).unwrap();
+
+                        // Same entity may receive several bundles,
+                        // each is a separate modification.
+                        let epoch = self.epoch.next_mut();
+                        unsafe {
+                            archetype.set_bundle(
+                                id,
+                                idx,
+                                bundle,
+                                epoch,
+                                ActionEncoder::new(buffer, &self.entities),
+                            );
+                        }
+                    }
+                    continue;
+                }
+
+                let (before, after) = self
+                    .archetypes
+                    .split_at_mut(src_archetype.max(dst_archetype) as usize);
+
+                let (src, dst) = match src_archetype < dst_archetype {
+                    true => (&mut before[src_archetype as usize], &mut after[0]),
+                    false => (&mut after[0], &mut before[dst_archetype as usize]),
+                };
+
+                dst.reserve(group_len);
+
+                for (_, id, bundle) in group {
+                    let (archetype, idx) = self.entities.get_location(id).unwrap();
+                    let epoch = self.epoch.next_mut();
+
+                    if archetype == dst_archetype {
+                        // Entity was moved by previous bundle in this call.
+                        unsafe {
+                            dst.set_bundle(
+                                id,
+                                idx,
+                                bundle,
+                                epoch,
+                                ActionEncoder::new(buffer, &self.entities),
+                            );
+                        }
+                        continue;
+                    }
+
+                    debug_assert_eq!(archetype, src_archetype);
+
+                    let (dst_idx, opt_src_id) = unsafe {
+                        src.insert_bundle(
+                            id,
+                            dst,
+                            idx,
+                            bundle,
+                            epoch,
+                            ActionEncoder::new(buffer, &self.entities),
+                        )
+                    };
+
+                    self.entities.set_location(id, dst_archetype, dst_idx);
+
+                    if let Some(src_id) = opt_src_id {
+                        self.entities.set_location(src_id, src_archetype, idx);
+                    }
+                }
+            }
+        });
+
         Ok(())
     }
 
@@ -1106,6 +2039,8 @@ impl World {
             return Ok(());
         }
 
+        let change = B::static_with_ids(|ids| self.begin_change(id, Some(ids)));
+
         let dst_archetype = self
             .edges
             .remove_bundle::<B>(&mut self.archetypes, src_archetype);
@@ -1130,6 +2065,7 @@ impl World {
             self.entities.set_location(src_id, src_archetype, idx);
         }
 
+        self.end_change(change);
         Ok(())
     }
 
@@ -1177,6 +2113,10 @@ impl World {
         self.entities.get_location(origin).ok_or(NoSuchEntity)?;
         self.entities.get_location(target).ok_or(NoSuchEntity)?;
 
+        let added = self
+            .query_one_mut::<&OriginComponent<R>>(origin)
+            .map_or(true, |c| c.origins().iter().all(|o| o.target() != target));
+
         self.epoch.next_mut();
 
         if R::SYMMETRIC {
@@ -1209,14 +2149,160 @@ impl World {
                 buffer,
             );
 
-            insert_component(
-                self,
-                target,
-                (),
-                |()| TargetComponent::<R>::new(origin),
-                |component, (), _| component.add(origin),
-                buffer,
-            );
+            // Target already knows the origin if relation is replaced.
+            if added {
+                insert_component(
+                    self,
+                    target,
+                    (),
+                    |()| TargetComponent::<R>::new(origin),
+                    |component, (), _| component.add(origin),
+                    buffer,
+                );
+            }
+        }
+
+        if added {
+            // Reported after removals caused by replacing exclusive relation.
+            ActionEncoder::new(buffer, &self.entities).closure(move |world| {
+                world.emit_relation_event::<R>(RelationEvent::Added { origin, target });
+                if R::SYMMETRIC && target != origin {
+                    world.emit_relation_event::<R>(RelationEvent::Added {
+                        origin: target,
+                        target: origin,
+                    });
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// Adds relations from one origin to many targets.
+    ///
+    /// Works as [`World::add_relation`] called for each pair,
+    /// but storage of relations on the origin is grown only once.
+    /// Useful to build dense graphs.
+    ///
+    /// If any of the entities is not alive, fails with `Err(NoSuchEntity)`
+    /// and no relations are added.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{relation::{OriginComponent, Relation}, world::World};
+    /// #[derive(Clone, Copy)]
+    /// struct Adjacent(f32);
+    ///
+    /// impl Relation for Adjacent {}
+    ///
+    /// let mut world = World::new();
+    /// let node = world.spawn(());
+    /// let neighbors = [world.spawn(()), world.spawn(()), world.spawn(())];
+    ///
+    /// world
+    ///     .relate_many(node, neighbors.iter().map(|&n| (Adjacent(1.0), n)))
+    ///     .unwrap();
+    ///
+    /// let origin = world.query_one_mut::<&OriginComponent<Adjacent>>(node).unwrap();
+    /// assert_eq!(origin.origins().len(), 3);
+    /// ```
+    #[inline]
+    pub fn relate_many<R, I>(&mut self, origin: EntityId, relations: I) -> Result<(), NoSuchEntity>
+    where
+        R: Relation,
+        I: IntoIterator<Item = (R, EntityId)>,
+    {
+        with_buffer!(self, buffer => {
+            self.relate_many_with_buffer(origin, relations, buffer)
+        })
+    }
+
+    fn relate_many_with_buffer<R, I>(
+        &mut self,
+        origin: EntityId,
+        relations: I,
+        buffer: &mut ActionBuffer,
+    ) -> Result<(), NoSuchEntity>
+    where
+        R: Relation,
+        I: IntoIterator<Item = (R, EntityId)>,
+    {
+        let relations: Vec<_> = relations.into_iter().collect();
+
+        self.entities.get_location(origin).ok_or(NoSuchEntity)?;
+        for &(_, target) in &relations {
+            self.entities.get_location(target).ok_or(NoSuchEntity)?;
+        }
+
+        let mut relations = relations.into_iter();
+        if let Some((relation, target)) = relations.next() {
+            self.add_relation_with_buffer(origin, relation, target, buffer)?;
+
+            if let Ok(component) = self.query_one_mut::<&mut OriginComponent<R>>(origin) {
+                component.reserve(relations.len());
+            }
+        }
+
+        for (relation, target) in relations {
+            self.add_relation_with_buffer(origin, relation, target, buffer)?;
+        }
+        Ok(())
+    }
+
+    /// Adds relations from many origins to one target.
+    ///
+    /// Works as [`World::add_relation`] called for each pair,
+    /// but storage of relations on the target is grown only once.
+    ///
+    /// If any of the entities is not alive, fails with `Err(NoSuchEntity)`
+    /// and no relations are added.
+    #[inline]
+    pub fn related_from_many<R, I>(
+        &mut self,
+        target: EntityId,
+        relations: I,
+    ) -> Result<(), NoSuchEntity>
+    where
+        R: Relation,
+        I: IntoIterator<Item = (EntityId, R)>,
+    {
+        with_buffer!(self, buffer => {
+            self.related_from_many_with_buffer(target, relations, buffer)
+        })
+    }
+
+    fn related_from_many_with_buffer<R, I>(
+        &mut self,
+        target: EntityId,
+        relations: I,
+        buffer: &mut ActionBuffer,
+    ) -> Result<(), NoSuchEntity>
+    where
+        R: Relation,
+        I: IntoIterator<Item = (EntityId, R)>,
+    {
+        let relations: Vec<_> = relations.into_iter().collect();
+
+        self.entities.get_location(target).ok_or(NoSuchEntity)?;
+        for &(origin, _) in &relations {
+            self.entities.get_location(origin).ok_or(NoSuchEntity)?;
+        }
+
+        let mut relations = relations.into_iter();
+        if let Some((origin, relation)) = relations.next() {
+            self.add_relation_with_buffer(origin, relation, target, buffer)?;
+
+            if R::SYMMETRIC {
+                if let Ok(component) = self.query_one_mut::<&mut OriginComponent<R>>(target) {
+                    component.reserve(relations.len());
+                }
+            } else if let Ok(component) = self.query_one_mut::<&mut TargetComponent<R>>(target) {
+                component.reserve(relations.len());
+            }
+        }
+
+        for (origin, relation) in relations {
+            self.add_relation_with_buffer(origin, relation, target, buffer)?;
         }
         Ok(())
     }
@@ -1587,14 +2673,233 @@ impl World {
         QueryRef::new(self, (query.into_query(),), ())
     }
 
-    /// Starts building new query.
+    /// Calls a closure on each item of the query
+    /// providing [`ActionEncoder`] to record actions alongside.
     ///
-    /// Returned query matches all entities and yields `()` for every one of them.
-    #[inline]
-    pub fn new_query_mut<'a>(&'a mut self) -> QueryRef<'a, (), ()> {
-        unsafe { self.new_query_unchecked() }
-    }
-
+    /// Recorded actions are executed after iteration completes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut world = World::new();
+    /// let entity = world.spawn((ExampleComponent,));
+    ///
+    /// world.for_each_with_encoder::<edict::query::Entities, _>(|id, mut encoder| {
+    ///     encoder.drop::<ExampleComponent>(id);
+    /// });
+    ///
+    /// assert_eq!(world.has_component::<ExampleComponent>(entity), Ok(false));
+    /// ```
+    pub fn for_each_with_encoder<Q, F>(&mut self, f: F)
+    where
+        Q: DefaultQuery,
+        F: for<'a, 'e> FnMut(QueryItem<'a, (Q,)>, ActionEncoder<'e>),
+    {
+        self.maintenance();
+
+        let mut buffer = ActionBuffer::new();
+        let mut encoder = ActionEncoder::new(&mut buffer, &self.entities);
+        self.query::<Q>().for_each_with(&mut encoder, f);
+
+        ActionBuffer::execute(&mut buffer, self);
+    }
+
+    /// Calls a closure on each item of the query
+    /// together with component `T` of the target of exclusive relation `R`.
+    ///
+    /// Only entities with relation `R` are visited.
+    /// Closure receives `None` if target does not have component `T`.
+    ///
+    /// This allows computations like parent-child transform propagation
+    /// in a single pass.
+    /// See [`RelatesTarget`] query to combine with other queries directly.
+    ///
+    /// # Panics
+    ///
+    /// Panics if query `Q` writes component `T`.
+    /// Panics if relation `R` is not exclusive.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, relation::ChildOf, world::World};
+    /// #[derive(Component)]
+    /// struct Offset(u32);
+    ///
+    /// let mut world = World::new();
+    /// let parent = world.spawn((Offset(1),));
+    /// let child = world.spawn((Offset(2),));
+    /// world.add_relation(child, ChildOf, parent).unwrap();
+    ///
+    /// let mut sum = 0;
+    /// world.for_each_with_target::<&Offset, ChildOf, Offset, _>(|offset, parent| {
+    ///     sum += offset.0 + parent.map_or(0, |parent| parent.0);
+    /// });
+    /// assert_eq!(sum, 3);
+    /// ```
+    pub fn for_each_with_target<Q, R, T, F>(&self, mut f: F)
+    where
+        Q: DefaultQuery,
+        R: Relation,
+        T: Sync + 'static,
+        F: for<'a> FnMut(QueryItem<'a, Q>, Option<&'a T>),
+    {
+        if let Some(Access::Write) = Q::default_query().access(TypeId::of::<T>()) {
+            panic!(
+                "Query `{}` must not write target component `{}`",
+                type_name::<Q>(),
+                type_name::<T>()
+            );
+        }
+
+        self.query_with(Q::default_query())
+            .extend_query(RelatesTarget::<R, &T>::new(self))
+            .for_each(|(item, target)| f(item, target));
+    }
+
+    /// Despawns all entities that match the query
+    /// and for which predicate returns `false`.
+    ///
+    /// Entities are collected during iteration
+    /// and despawned in bulk after it completes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Component)]
+    /// struct Lifetime(u32);
+    ///
+    /// let mut world = World::new();
+    /// let a = world.spawn((Lifetime(0),));
+    /// let b = world.spawn((Lifetime(3),));
+    ///
+    /// world.retain::<&Lifetime, _>(|lifetime| lifetime.0 > 0);
+    ///
+    /// assert!(!world.is_alive(a));
+    /// assert!(world.is_alive(b));
+    /// ```
+    pub fn retain<Q, F>(&mut self, f: F)
+    where
+        Q: DefaultQuery,
+        F: for<'a> FnMut(QueryItem<'a, (Q,)>) -> bool,
+    {
+        let ids = self.collect_rejected::<Q, F>(f);
+
+        with_buffer!(self, buffer => {
+            for id in ids {
+                // Entity may be despawned by hooks of previously despawned entities.
+                let _ = self.despawn_with_buffer(id, buffer);
+            }
+        })
+    }
+
+    /// Drops component `T` from all entities that match the query
+    /// and for which predicate returns `false`.
+    ///
+    /// Entities are collected during iteration
+    /// and modified in bulk after it completes.
+    /// Entities that do not have component `T` are left intact.
+    pub fn retain_component<T, Q, F>(&mut self, f: F)
+    where
+        T: 'static,
+        Q: DefaultQuery,
+        F: for<'a> FnMut(QueryItem<'a, (Q,)>) -> bool,
+    {
+        let ids = self.collect_rejected::<Q, F>(f);
+
+        with_buffer!(self, buffer => {
+            for id in ids {
+                let _ = self.drop_erased_with_buffer(id, TypeId::of::<T>(), buffer);
+            }
+        })
+    }
+
+    /// Returns ids of entities that match the query
+    /// and for which predicate returns `false`.
+    fn collect_rejected<Q, F>(&mut self, mut f: F) -> Vec<EntityId>
+    where
+        Q: DefaultQuery,
+        F: for<'a> FnMut(QueryItem<'a, (Q,)>) -> bool,
+    {
+        self.maintenance();
+
+        let mut ids = Vec::new();
+        self.query_mut::<(Entities, Q)>().for_each(|(id, item)| {
+            if !f(item) {
+                ids.push(id);
+            }
+        });
+        ids
+    }
+
+    /// Checks that query does not access any component in conflicting ways,
+    /// like `(&mut T, &T)`, in any existing archetype.
+    ///
+    /// Such queries panic when borrowing components.
+    /// This method allows to detect the problem early
+    /// and get error with the name of the offending component.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut world = World::new();
+    /// world.spawn((ExampleComponent,));
+    ///
+    /// assert!(world.validate_query::<(&ExampleComponent, &ExampleComponent)>().is_ok());
+    /// assert!(world.validate_query::<(&mut ExampleComponent, &ExampleComponent)>().is_err());
+    /// ```
+    pub fn validate_query<Q>(&self) -> Result<(), QueryConflict>
+    where
+        Q: DefaultQuery,
+    {
+        validate_query(&Q::default_query(), &self.archetypes)
+    }
+
+    /// Returns view of the world through a set of queries.
+    ///
+    /// Queries are checked to be disjoint once, when view is created,
+    /// so they can be iterated mutably at the same time
+    /// without runtime borrow checks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if two queries in the set access same component in any archetype
+    /// and at least one of them mutably.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World, ExampleComponent};
+    /// #[derive(Component)]
+    /// struct Marker;
+    ///
+    /// let mut world = World::new();
+    /// world.spawn((ExampleComponent, Marker));
+    ///
+    /// let mut view = world.view::<(&mut ExampleComponent, &mut Marker)>();
+    /// let (mut a, mut b) = view.queries();
+    ///
+    /// for (_, _) in a.iter_mut().zip(b.iter_mut()) {}
+    /// ```
+    pub fn view<T>(&mut self) -> WorldView<'_, T>
+    where
+        T: ViewQueries,
+    {
+        self.maintenance();
+        WorldView::new(self)
+    }
+
+    /// Starts building new query.
+    ///
+    /// Returned query matches all entities and yields `()` for every one of them.
+    #[inline]
+    pub fn new_query_mut<'a>(&'a mut self) -> QueryRef<'a, (), ()> {
+        unsafe { self.new_query_unchecked() }
+    }
+
     /// Starts building new query.
     ///
     /// Returned query matches all entities and yields `()` for every one of them.
@@ -1611,6 +2916,21 @@ impl World {
         QueryRef::new(self, (), ())
     }
 
+    /// Returns unique identifier of this world.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::world::World;
+    /// let world = World::new();
+    /// let other = World::new();
+    /// assert_ne!(world.id(), other.id());
+    /// ```
+    #[inline]
+    pub fn id(&self) -> WorldId {
+        self.entities.world()
+    }
+
     /// Returns current world epoch.
     ///
     /// This value can be modified concurrently if [`&World`] is shared.
@@ -1622,33 +2942,402 @@ impl World {
         self.epoch.current()
     }
 
+    /// Advances world epoch and returns new epoch.
+    ///
+    /// Every query iteration advances world epoch by itself.
+    /// Read-only queries iterated with explicit epoch,
+    /// like [`QueryRef::iter_with_epoch`], do not,
+    /// and this method can be used to advance epoch once per frame instead.
+    #[inline]
+    pub fn advance_epoch(&self) -> EpochId {
+        self.epoch.next()
+    }
+
+    /// Returns counter of structural changes in the world.
+    ///
+    /// The value changes when new archetype is created
+    /// or entity is spawned, despawned or moved to another archetype
+    /// by inserting or removing components.
+    /// Modification of component values doesn't change it.
+    ///
+    /// Systems that maintain structures derived from set of entities
+    /// and their components may compare it with previously seen value
+    /// to skip rebuilding.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut world = World::new();
+    /// let e = world.spawn(());
+    ///
+    /// let seen = world.structure_epoch();
+    /// world.insert(e, ExampleComponent).unwrap();
+    /// assert_ne!(world.structure_epoch(), seen);
+    ///
+    /// let seen = world.structure_epoch();
+    /// *world.query_one_mut::<&mut ExampleComponent>(e).unwrap() = ExampleComponent;
+    /// assert_eq!(world.structure_epoch(), seen);
+    /// ```
+    #[inline]
+    pub fn structure_epoch(&self) -> u64 {
+        // Archetypes are never removed,
+        // so the sum increases with either counter.
+        self.entities.moves() + self.archetypes.len() as u64
+    }
+
     /// Returns atomic reference to epoch counter.
     #[inline]
     pub fn epoch_counter(&self) -> &EpochCounter {
         &self.epoch
     }
 
+    /// Marks component `T` of the entity as modified without borrowing it.
+    ///
+    /// Use this when component data was changed in a way the world can't observe,
+    /// e.g. written through a raw pointer by external middleware,
+    /// so that [`Modified`] queries and change trackers see the update.
+    ///
+    /// [`Modified`]: crate::query::Modified
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{query::Modified, world::World, ExampleComponent};
+    /// let mut world = World::new();
+    /// let e = world.spawn((ExampleComponent,));
+    ///
+    /// let epoch = world.epoch();
+    /// world.touch::<ExampleComponent>(e).unwrap();
+    ///
+    /// let query = Modified::<&ExampleComponent>::new(epoch);
+    /// assert_eq!(world.query_with(query).iter().count(), 1);
+    /// ```
+    pub fn touch<T>(&mut self, id: EntityId) -> Result<(), EntityError>
+    where
+        T: 'static,
+    {
+        self.maintenance();
+
+        let (archetype_idx, idx) = self
+            .entities
+            .get_location(id)
+            .ok_or(EntityError::NoSuchEntity)?;
+        if archetype_idx == u32::MAX {
+            return Err(EntityError::MissingComponents);
+        }
+
+        let archetype = &mut self.archetypes[archetype_idx as usize];
+        if !archetype.has_component(TypeId::of::<T>()) {
+            return Err(EntityError::MissingComponents);
+        }
+
+        let epoch = self.epoch.next_mut();
+
+        // Safety: archetype contains the component and epoch is advanced.
+        unsafe {
+            archetype.get_mut::<T>(idx, epoch);
+        }
+        Ok(())
+    }
+
     /// Checks if entity has component of specified type.
     ///
-    /// If entity is not alive, fails with `Err(NoSuchEntity)`.
-    #[inline]
-    pub fn has_component<T: 'static>(&self, id: EntityId) -> Result<bool, NoSuchEntity> {
-        let (archetype_idx, _idx) = self.entities.get_location(id).ok_or(NoSuchEntity)?;
-        if archetype_idx == u32::MAX {
-            return Ok(false);
-        }
-        Ok(self.archetypes[archetype_idx as usize].has_component(TypeId::of::<T>()))
-    }
+    /// If entity is not alive, fails with `Err(NoSuchEntity)`.
+    #[inline]
+    pub fn has_component<T: 'static>(&self, id: EntityId) -> Result<bool, NoSuchEntity> {
+        let (archetype_idx, _idx) = self.entities.get_location(id).ok_or(NoSuchEntity)?;
+        if archetype_idx == u32::MAX {
+            return Ok(false);
+        }
+        Ok(self.archetypes[archetype_idx as usize].has_component(TypeId::of::<T>()))
+    }
+
+    /// Checks if entity is alive.
+    #[inline]
+    pub fn is_alive(&self, id: EntityId) -> bool {
+        self.entities.get_location(id).is_some()
+    }
+
+    /// Returns current location of the entity.
+    ///
+    /// See [`Location`] for rules of when location changes.
+    /// Entities that are reserved but not yet spawned
+    /// have archetype index `u32::MAX`.
+    ///
+    /// If entity is not alive, fails with `Err(NoSuchEntity)`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut world = World::new();
+    /// let entity = world.spawn(());
+    /// let location = world.locate(entity).unwrap();
+    ///
+    /// world.insert(entity, ExampleComponent).unwrap();
+    /// assert_ne!(world.locate(entity), Ok(location));
+    /// ```
+    #[inline]
+    pub fn locate(&self, id: EntityId) -> Result<Location, NoSuchEntity> {
+        let (archetype, idx) = self.entities.get_location(id).ok_or(NoSuchEntity)?;
+        Ok(Location { archetype, idx })
+    }
+
+    /// Pins current location of the entity.
+    ///
+    /// While returned [`RowPin`] is valid, entity stays in the same archetype
+    /// at the same row, and [`World::query_pinned`] can be used to access
+    /// its components without entity lookup.
+    ///
+    /// Pin breaks when entity is moved by a structural change
+    /// of its own or of another entity in the same archetype, or is despawned.
+    /// `on_invalidate` is called with entity id at that moment.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut world = World::new();
+    /// let first = world.spawn((ExampleComponent,));
+    /// let second = world.spawn((ExampleComponent,));
+    /// let pin = world.pin_entity_row(second, |_| {}).unwrap();
+    ///
+    /// assert!(world.query_pinned::<&ExampleComponent>(&pin).is_some());
+    ///
+    /// // Despawning first entity moves second one into vacated row.
+    /// world.despawn(first).unwrap();
+    /// assert!(!pin.is_valid());
+    /// assert!(world.query_pinned::<&ExampleComponent>(&pin).is_none());
+    /// ```
+    pub fn pin_entity_row(
+        &mut self,
+        id: EntityId,
+        on_invalidate: impl FnOnce(EntityId) + Send + 'static,
+    ) -> Result<RowPin, NoSuchEntity> {
+        self.maintenance();
+        self.entities.pin(id, Box::new(on_invalidate))
+    }
+
+    /// Queries components from entity pinned with [`World::pin_entity_row`].
+    /// Returns `None` if pin was invalidated
+    /// or does not point to the entity in this world.
+    ///
+    /// Unlike [`World::query_one`], entity location is taken from the pin,
+    /// and is only checked against archetype row, skipping entity lookup.
+    #[inline]
+    pub fn query_pinned<'a, Q>(&'a self, pin: &RowPin) -> Option<QueryOne<'a, Q>>
+    where
+        Q: DefaultQuery,
+    {
+        if !pin.is_valid() {
+            return None;
+        }
+
+        // Pin may come from another world.
+        let archetype = self.archetypes.get(pin.archetype() as usize)?;
+        if archetype.entities().get(pin.row() as usize) != Some(&pin.id()) {
+            return None;
+        }
+
+        Some(QueryOne::new(
+            Q::default_query().into_query(),
+            archetype,
+            pin.row(),
+            &self.epoch,
+        ))
+    }
+
+    /// Iterate over component info of all registered components
+    pub fn iter_component_info(&self) -> impl Iterator<Item = &ComponentInfo> {
+        self.registry.iter_info()
+    }
+
+    /// Hibernates archetypes that were not modified
+    /// and had no entities spawned after specified epoch.
+    ///
+    /// Unused storage of hibernated archetypes is released
+    /// and their component columns are moved out of column allocator
+    /// into global allocator blocks that fit components exactly.
+    /// Columns are moved back transparently on first access,
+    /// e.g. when a query visits the archetype or an entity is added.
+    /// Archetypes with fixed capacity or sharing columns with forks
+    /// of the world, see [`World::fork`], are left intact.
+    ///
+    /// Returns number of archetypes hibernated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Component)]
+    /// struct Health(u32);
+    ///
+    /// let mut world = World::new();
+    /// world.spawn((Health(100),));
+    ///
+    /// let epoch = world.epoch();
+    /// assert_eq!(world.hibernate_cold_archetypes(epoch), 1);
+    ///
+    /// // Column is moved back when query visits the archetype.
+    /// let total = world.query::<&Health>().iter().map(|h| h.0).sum::<u32>();
+    /// assert_eq!(total, 100);
+    /// ```
+    pub fn hibernate_cold_archetypes(&mut self, after_epoch: EpochId) -> usize {
+        self.hibernate_cold_archetypes_impl(after_epoch, None)
+    }
+
+    /// Hibernates archetypes that were not modified
+    /// and had no entities spawned after specified epoch,
+    /// moving their component columns into the `store`.
+    ///
+    /// Works like [`World::hibernate_cold_archetypes`] except that columns
+    /// are passed to the `store`, which may keep them compressed or on disk.
+    /// Columns of components with move hooks are kept in global allocator.
+    ///
+    /// Returns number of archetypes hibernated.
+    pub fn hibernate_cold_archetypes_with(
+        &mut self,
+        after_epoch: EpochId,
+        store: Arc<dyn ColumnStore>,
+    ) -> usize {
+        self.hibernate_cold_archetypes_impl(after_epoch, Some(&store))
+    }
+
+    fn hibernate_cold_archetypes_impl(
+        &mut self,
+        after_epoch: EpochId,
+        store: Option<&Arc<dyn ColumnStore>>,
+    ) -> usize {
+        self.maintenance();
+
+        let mut count = 0;
+        for archetype in self.archetypes.iter_mut() {
+            if !archetype.last_modified().after(after_epoch) && archetype.hibernate(store) {
+                count += 1;
+            }
+        }
+
+        // Release pool pages freed by hibernated columns.
+        self.archetypes.trim_pool();
+        count
+    }
+
+    /// Swaps values of component `T` of all entities in the archetype
+    /// with values from the slice.
+    ///
+    /// Values are matched with entities by index in the archetype,
+    /// see [`Location::idx`] and [`World::locate`].
+    /// All components in the column are marked as modified once.
+    /// Replace hooks are not called, previous values are left in the slice.
+    ///
+    /// This allows to recompute whole column elsewhere, e.g. on a background thread,
+    /// and publish results at once, reusing the buffer for the next computation.
+    ///
+    /// Fails with `Err(MissingComponents)` if archetype does not contain component `T`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if archetype index is out of bounds
+    /// or if slice length is not equal to number of entities in the archetype.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Component, Debug, PartialEq)]
+    /// struct Cost(u32);
+    ///
+    /// let mut world = World::new();
+    /// let a = world.spawn((Cost(0),));
+    /// let b = world.spawn((Cost(0),));
+    ///
+    /// let archetype = world.locate(a).unwrap().archetype;
+    /// let mut buffer = vec![Cost(0), Cost(0)];
+    /// buffer[world.locate(b).unwrap().idx as usize] = Cost(5);
+    ///
+    /// world.swap_column(archetype, &mut buffer).unwrap();
+    /// assert_eq!(world.query_one_mut::<&Cost>(b), Ok(&Cost(5)));
+    /// ```
+    #[track_caller]
+    pub fn swap_column<T>(
+        &mut self,
+        archetype: u32,
+        values: &mut [T],
+    ) -> Result<(), MissingComponents>
+    where
+        T: 'static,
+    {
+        self.maintenance();
+
+        let archetype = &mut self.archetypes[archetype as usize];
+        assert_eq!(
+            values.len(),
+            archetype.len(),
+            "Number of values must be equal to number of entities in the archetype"
+        );
+
+        let epoch = self.epoch.next_mut();
+
+        // Safety: length is checked above and epoch is advanced.
+        match unsafe { archetype.swap_column(values, epoch) } {
+            true => Ok(()),
+            false => Err(MissingComponents),
+        }
+    }
+
+    /// Computes checksum of the selected components of all entities.
+    ///
+    /// Components are hashed column-wise, archetype by archetype,
+    /// together with ids of the entities that own them.
+    /// The hasher is not seeded, so worlds that performed the same operations
+    /// in the same order produce equal checksums on different peers,
+    /// which makes this suitable for lockstep desync detection.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the listed components present in the world
+    /// was not registered with [`ComponentInfoRef::hashable`],
+    /// or if it is borrowed mutably by a query.
+    ///
+    /// [`ComponentInfoRef::hashable`]: crate::component::ComponentInfoRef::hashable
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use core::any::TypeId;
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Component, Hash)]
+    /// struct Pos(i32, i32);
+    ///
+    /// let mut builder = World::builder();
+    /// builder.register_component::<Pos>().hashable();
+    /// let mut world = builder.build();
+    ///
+    /// let entity = world.spawn((Pos(0, 0),));
+    /// let before = world.checksum(&[TypeId::of::<Pos>()]);
+    ///
+    /// world.insert(entity, Pos(1, 0)).unwrap();
+    /// assert_ne!(world.checksum(&[TypeId::of::<Pos>()]), before);
+    /// ```
+    pub fn checksum(&self, components: &[TypeId]) -> u64 {
+        let mut hasher = StableHasher::new();
 
-    /// Checks if entity is alive.
-    #[inline]
-    pub fn is_alive(&self, id: EntityId) -> bool {
-        self.entities.get_location(id).is_some()
-    }
+        for archetype in self.archetypes.iter() {
+            if archetype.len() == 0 || !components.iter().any(|&id| archetype.has_component(id)) {
+                continue;
+            }
 
-    /// Iterate over component info of all registered components
-    pub fn iter_component_info(&self) -> impl Iterator<Item = &ComponentInfo> {
-        self.registry.iter_info()
+            for id in archetype.entities() {
+                hasher.write_u64(id.bits());
+            }
+
+            for &id in components {
+                archetype.hash_column(id, &mut hasher);
+            }
+        }
+
+        hasher.finish()
     }
 
     /// Returns a slice of all materialized archetypes.
@@ -1656,6 +3345,25 @@ impl World {
         &self.archetypes
     }
 
+    /// Returns hierarchical formatter of the world structure.
+    ///
+    /// Dump lists archetypes with entity counts, names of components
+    /// and relation edges between entities.
+    /// Use [`DebugDump::with`] to restrict output to archetypes with specific components.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut world = World::new();
+    /// world.spawn((ExampleComponent,));
+    /// println!("{}", world.debug_dump().with::<ExampleComponent>());
+    /// ```
+    #[cfg(feature = "debug-dump")]
+    pub fn debug_dump(&self) -> DebugDump<'_> {
+        DebugDump::new(self)
+    }
+
     /// Inserts resource instance.
     /// Old value is replaced.
     ///
@@ -2031,7 +3739,90 @@ impl World {
             while let Some(f) = self.action_channel.execute() {
                 f(self, buffer);
             }
-        })
+        });
+        self.check_flushed_invariants();
+    }
+
+    /// Starts tracking modifications of component `T`.
+    ///
+    /// Returned [`ChangeTracker`] yields ids of entities
+    /// whose component `T` was modified after this call.
+    /// Modifications are harvested in bulk during world maintenance,
+    /// so systems that only need entity ids do not pay for query fetches.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut world = World::new();
+    /// let entity = world.spawn(());
+    /// let tracker = world.track::<ExampleComponent>();
+    ///
+    /// world.insert(entity, ExampleComponent).unwrap();
+    /// world.maintenance();
+    ///
+    /// assert_eq!(tracker.drain().collect::<Vec<_>>(), vec![entity]);
+    /// assert!(tracker.is_empty());
+    /// ```
+    pub fn track<T>(&mut self) -> ChangeTracker<T>
+    where
+        T: 'static,
+    {
+        self.maintenance();
+        let epoch = self.epoch.current_mut();
+        self.trackers.add(epoch)
+    }
+
+    /// Runs closure that stages structural changes through [`Transaction`].
+    ///
+    /// If closure returns `Ok`, all staged changes are applied to the world
+    /// before this method returns.
+    /// If closure returns `Err`, staged changes are discarded
+    /// and entity ids allocated within transaction are never spawned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut world = World::new();
+    /// let entity = world.spawn(());
+    ///
+    /// let result = world.transaction(|tx| {
+    ///     tx.insert(entity, ExampleComponent);
+    ///     tx.spawn((ExampleComponent,));
+    ///     Err::<(), _>("not enough gold")
+    /// });
+    ///
+    /// assert_eq!(result, Err("not enough gold"));
+    /// assert_eq!(world.has_component::<ExampleComponent>(entity), Ok(false));
+    ///
+    /// world.transaction(|tx| {
+    ///     tx.insert(entity, ExampleComponent);
+    ///     Ok::<_, ()>(())
+    /// }).unwrap();
+    ///
+    /// assert_eq!(world.has_component::<ExampleComponent>(entity), Ok(true));
+    /// ```
+    pub fn transaction<T, E>(
+        &mut self,
+        f: impl FnOnce(&mut Transaction) -> Result<T, E>,
+    ) -> Result<T, E> {
+        self.maintenance();
+
+        let mut buffer = ActionBuffer::new();
+        let result = f(&mut Transaction::new(self, &mut buffer));
+
+        match result {
+            Ok(_) => {
+                ActionBuffer::execute(&mut buffer, self);
+            }
+            Err(_) => {
+                drop(buffer);
+                self.entities.discard_allocated();
+            }
+        }
+
+        result
     }
 
     /// Returns [`EntitySet`] from the [`World`].
@@ -2049,20 +3840,105 @@ impl World {
         self.action_buffer = action_buffer;
     }
 
+    /// Installs plugin into this world and the scheduler.
+    ///
+    /// See [`Plugin`](crate::plugin::Plugin) for details.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn install(
+        &mut self,
+        scheduler: &mut crate::scheduler::Scheduler,
+        plugin: impl crate::plugin::Plugin,
+    ) {
+        plugin.install(self, scheduler);
+    }
+
+    /// Drops components which drop was deferred.
+    ///
+    /// Components registered with [`ComponentInfoRef::deferred_drop`]
+    /// are not dropped when entity is despawned or component is removed.
+    /// Their drop hooks are called immediately,
+    /// but values are moved into per-archetype queues
+    /// and dropped only when this method is called.
+    /// This allows to move expensive drops out of hot paths.
+    ///
+    /// [`ComponentInfoRef::deferred_drop`]: crate::component::ComponentInfoRef::deferred_drop
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Component)]
+    /// struct Mesh(Vec<u8>);
+    ///
+    /// let mut builder = World::builder();
+    /// builder.register_component::<Mesh>().deferred_drop();
+    /// let mut world = builder.build();
+    ///
+    /// let e = world.spawn((Mesh(vec![0; 1024]),));
+    /// world.despawn(e).unwrap();
+    ///
+    /// // Mesh is dropped here.
+    /// world.flush_drops();
+    /// ```
+    pub fn flush_drops(&mut self) {
+        for archetype in self.archetypes.iter_mut() {
+            archetype.flush_drops();
+        }
+    }
+
     /// Runs world maintenance.
     ///
     /// Users typically do not need to call this method,
     /// it is automatically called in every method that borrows world mutably.
     ///
     /// The only observable effect of manual call to this method
-    /// is execution of actions encoded with [`ActionSender`].
+    /// is harvesting of modifications into [`ChangeTracker`]s.
+    /// Pages of the world's small column pool that have no columns left
+    /// are released here as well.
+    ///
+    /// With `"column-guard"` feature enabled, canaries around component columns
+    /// and poison of freed columns are checked here.
+    /// Corruption caused by out-of-bounds writes or writes through stale pointers panics.
     #[inline]
-    fn maintenance(&mut self) {
+    pub fn maintenance(&mut self) {
+        #[cfg(feature = "column-guard")]
+        crate::guard::check_archetypes(&self.archetypes);
+
         let epoch = self.epoch.current_mut();
         let archetype = &mut self.archetypes[0];
         self.entities
             .spawn_allocated(|id| archetype.spawn(id, (), epoch));
+
+        self.complete_deferred_despawns();
+        self.sort_archetypes();
+
+        self.null_weak_entities();
+        self.trackers.harvest(&self.archetypes, epoch);
+
+        let journal = self.entities.take_journal();
+        self.live.harvest(&self.archetypes, &journal);
+        self.indexes.harvest(&self.archetypes, &journal, epoch);
+        if self.live.is_empty() && self.indexes.is_empty() {
+            self.entities.disable_journal();
+        }
+
+        self.subscriptions
+            .harvest(&self.archetypes, &self.entities, epoch);
+
+        self.archetypes.trim_pool();
+    }
+}
+
+#[cfg(not(feature = "undo"))]
+impl World {
+    #[inline(always)]
+    fn begin_change(&self, _id: EntityId, _types: Option<&[TypeId]>) -> Option<PendingChange> {
+        None
     }
+
+    #[inline(always)]
+    fn end_change(&mut self, _pending: Option<PendingChange>) {}
 }
 
 /// Spawning iterator. Produced by [`World::spawn_batch`].
@@ -2072,6 +3948,19 @@ pub struct SpawnBatch<'a, I> {
     archetype_idx: u32,
     archetype: &'a mut Archetype,
     entities: &'a mut EntitySet,
+
+    /// Number of entities that can be spawned before quota is exceeded.
+    quota: usize,
+}
+
+/// Takes one entity from the quota of spawn batch.
+#[inline]
+#[track_caller]
+fn take_quota(quota: &mut usize) {
+    if *quota == 0 {
+        panic!("Entity quota is exceeded by spawn batch");
+    }
+    *quota -= 1;
 }
 
 impl<B, I> SpawnBatch<'_, I>
@@ -2089,8 +3978,10 @@ where
         let archetype = &mut self.archetype;
         let archetype_idx = self.archetype_idx;
         let epoch = self.epoch;
+        let quota = &mut self.quota;
 
         self.bundles.for_each(|bundle| {
+            take_quota(quota);
             let id = entities.spawn();
             let idx = archetype.spawn(id, bundle, epoch);
             entities.set_location(id, archetype_idx, idx);
@@ -2108,6 +3999,7 @@ where
     fn next(&mut self) -> Option<EntityId> {
         let bundle = self.bundles.next()?;
 
+        take_quota(&mut self.quota);
         let id = self.entities.spawn();
         let idx = self.archetype.spawn(id, bundle, self.epoch);
         self.entities.set_location(id, self.archetype_idx, idx);
@@ -2119,6 +4011,7 @@ where
         // `SpawnBatch` explicitly does NOT spawn entities that are skipped.
         let bundle = self.bundles.nth(n)?;
 
+        take_quota(&mut self.quota);
         let id = self.entities.spawn();
         let idx = self.archetype.spawn(id, bundle, self.epoch);
         self.entities.set_location(id, self.archetype_idx, idx);
@@ -2142,8 +4035,10 @@ where
         let archetype = &mut self.archetype;
         let archetype_idx = self.archetype_idx;
         let epoch = self.epoch;
+        let quota = &mut self.quota;
 
         self.bundles.fold(init, |acc, bundle| {
+            take_quota(quota);
             let id = entities.spawn();
             let idx = archetype.spawn(id, bundle, epoch);
             entities.set_location(id, archetype_idx, idx);
@@ -2185,6 +4080,7 @@ where
     fn next_back(&mut self) -> Option<EntityId> {
         let bundle = self.bundles.next_back()?;
 
+        take_quota(&mut self.quota);
         let id = self.entities.spawn();
         let idx = self.archetype.spawn(id, bundle, self.epoch);
 
@@ -2198,6 +4094,7 @@ where
         // for which the only reference is immediately dropped
         let bundle = self.bundles.nth_back(n)?;
 
+        take_quota(&mut self.quota);
         let id = self.entities.spawn();
         let idx = self.archetype.spawn(id, bundle, self.epoch);
 
@@ -2217,8 +4114,10 @@ where
         let archetype = &mut self.archetype;
         let archetype_idx = self.archetype_idx;
         let epoch = self.epoch;
+        let quota = &mut self.quota;
 
         self.bundles.rfold(init, |acc, bundle| {
+            take_quota(quota);
             let id = entities.spawn();
             let idx = archetype.spawn(id, bundle, epoch);
             entities.set_location(id, archetype_idx, idx);
@@ -2248,6 +4147,34 @@ impl fmt::Display for NoSuchEntity {
 #[cfg(feature = "std")]
 impl std::error::Error for NoSuchEntity {}
 
+/// Error returned by [`World::spawn_at`]
+/// in case specified [`EntityId`] is already used by live entity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IdOccupied;
+
+impl fmt::Display for IdOccupied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Specified entity id is already in use")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for IdOccupied {}
+
+/// Error returned when archetype with fixed capacity
+/// cannot fit more entities.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CapacityExceeded;
+
+impl fmt::Display for CapacityExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Archetype capacity is exceeded")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CapacityExceeded {}
+
 /// Error returned in case specified entity does not contain
 /// component of required type.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -2262,6 +4189,33 @@ impl fmt::Display for MissingComponents {
 #[cfg(feature = "std")]
 impl std::error::Error for MissingComponents {}
 
+/// Error returned when component with specified stable name is not registered.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UnknownComponent {
+    stable_name: String,
+}
+
+impl UnknownComponent {
+    /// Returns stable name that is not registered.
+    #[inline]
+    pub fn stable_name(&self) -> &str {
+        &self.stable_name
+    }
+}
+
+impl fmt::Display for UnknownComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Component with stable name `{}` is not registered",
+            self.stable_name
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnknownComponent {}
+
 /// Error returned if either entity reference is invalid
 /// or component of required type is not found for an entity.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -2629,3 +4583,435 @@ impl WorldLocal<'_> {
         unsafe { self.world.res.get_local_mut() }.unwrap()
     }
 }
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::{vec, vec::Vec};
+
+    use crate::{
+        component::Component,
+        query::Entities,
+        relation::{ChildOf, Relation},
+        test::{Bool, Str, U32},
+        world::{NoSuchEntity, QueryOneError, World},
+    };
+
+    /// Tests that retain despawns rejected entities only.
+    #[test]
+    fn retain() {
+        let mut world = World::new();
+
+        let a = world.spawn((U32(1),));
+        let b = world.spawn((U32(2), Str("b")));
+        let c = world.spawn((Str("c"),));
+
+        world.retain::<&U32, _>(|value| value.0 % 2 == 0);
+
+        assert!(!world.is_alive(a));
+        assert!(world.is_alive(b));
+        assert!(world.is_alive(c));
+
+        world.retain_component::<Str, &Str, _>(|value| value.0 == "b");
+        assert_eq!(world.has_component::<Str>(b), Ok(true));
+        assert_eq!(world.has_component::<Str>(c), Ok(false));
+    }
+
+    /// Tests that clearing despawns all entities and keeps archetypes.
+    #[test]
+    fn world_clear() {
+        let mut world = World::new();
+
+        let parent = world.spawn((U32(0),));
+        let child = world.spawn((U32(1), Str("child")));
+        world.add_relation(child, ChildOf, parent).unwrap();
+
+        let archetypes = world.archetypes().len();
+        world.clear();
+
+        assert!(!world.is_alive(parent));
+        assert!(!world.is_alive(child));
+        assert_eq!(world.query::<Entities>().iter().count(), 0);
+        assert_eq!(world.archetypes().len(), archetypes);
+
+        world.spawn((U32(2), Str("new")));
+        assert_eq!(world.query::<&U32>().iter().count(), 1);
+    }
+
+    /// Tests that structure epoch changes only on structural changes.
+    #[test]
+    fn structure_epoch() {
+        let mut world = World::new();
+
+        let epoch = world.structure_epoch();
+        let e = world.spawn((U32(0),));
+        assert_ne!(world.structure_epoch(), epoch);
+
+        let epoch = world.structure_epoch();
+        *world.query_one_mut::<&mut U32>(e).unwrap() = U32(1);
+        world.insert(e, U32(2)).unwrap();
+        assert_eq!(world.structure_epoch(), epoch);
+
+        world.insert(e, Str("e")).unwrap();
+        assert_ne!(world.structure_epoch(), epoch);
+
+        let epoch = world.structure_epoch();
+        world.despawn(e).unwrap();
+        assert_ne!(world.structure_epoch(), epoch);
+    }
+
+    /// Tests that deferred drops happen only on flush while drop hooks run immediately.
+    #[test]
+    fn deferred_drop() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        static DROPS: AtomicUsize = AtomicUsize::new(0);
+        static HOOKS: AtomicUsize = AtomicUsize::new(0);
+
+        struct Large([u64; 16]);
+        impl Component for Large {
+            fn on_drop(
+                &mut self,
+                _id: crate::entity::EntityId,
+                _encoder: crate::action::ActionEncoder,
+            ) {
+                HOOKS.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        impl Drop for Large {
+            fn drop(&mut self) {
+                DROPS.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let mut builder = World::builder();
+        builder.register_component::<Large>().deferred_drop();
+        let mut world = builder.build();
+
+        let entities = (0..10)
+            .map(|i| world.spawn((Large([i; 16]), U32(i as u32))))
+            .collect::<Vec<_>>();
+
+        for &e in &entities[..5] {
+            world.despawn(e).unwrap();
+        }
+        world.drop::<Large>(entities[5]).unwrap();
+
+        assert_eq!(HOOKS.load(Ordering::Relaxed), 6);
+        assert_eq!(DROPS.load(Ordering::Relaxed), 0);
+
+        for large in world.query::<&Large>().iter() {
+            assert!(large.0.iter().all(|&x| x == large.0[0]));
+        }
+
+        world.flush_drops();
+        assert_eq!(DROPS.load(Ordering::Relaxed), 6);
+
+        world.despawn(entities[6]).unwrap();
+        drop(world);
+        assert_eq!(DROPS.load(Ordering::Relaxed), 10);
+    }
+
+    /// Tests that bulk bundle application matches per-entity insertion.
+    #[test]
+    fn apply_bundles() {
+        let mut world = World::new();
+        let a = world.spawn(());
+        let b = world.spawn((U32(1),));
+        let c = world.spawn((Str("c"),));
+        let d = world.spawn(());
+
+        world
+            .apply_bundles([
+                (a, (U32(10), Bool(true))),
+                (b, (U32(20), Bool(false))),
+                (d, (U32(40), Bool(true))),
+                (a, (U32(11), Bool(false))),
+            ])
+            .unwrap();
+
+        assert_eq!(world.query_one_mut::<&U32>(a), Ok(&U32(11)));
+        assert_eq!(world.query_one_mut::<&Bool>(a), Ok(&Bool(false)));
+        assert_eq!(world.query_one_mut::<&U32>(b), Ok(&U32(20)));
+        assert_eq!(world.query_one_mut::<&U32>(d), Ok(&U32(40)));
+        assert_eq!(world.has_component::<U32>(c), Ok(false));
+
+        world.despawn(d).unwrap();
+        assert_eq!(
+            world.apply_bundles([(c, (U32(30),)), (d, (U32(0),))]),
+            Err(NoSuchEntity)
+        );
+        assert_eq!(world.has_component::<U32>(c), Ok(false));
+    }
+
+    /// Tests that entity location changes only when entity is moved.
+    #[test]
+    fn locate() {
+        let mut world = World::new();
+        let a = world.spawn((U32(0),));
+        let b = world.spawn((U32(1),));
+
+        let location = world.locate(b).unwrap();
+        world.query_one_mut::<&mut U32>(b).unwrap().0 = 2;
+        assert_eq!(world.locate(b), Ok(location));
+
+        // Last entity takes place of the despawned one.
+        world.despawn(a).unwrap();
+        let moved = world.locate(b).unwrap();
+        assert_eq!(moved.archetype, location.archetype);
+        assert_eq!(moved.idx, 0);
+
+        world.despawn(b).unwrap();
+        assert_eq!(world.locate(b), Err(NoSuchEntity));
+    }
+
+    #[test]
+    fn swap_column() {
+        let mut world = World::new();
+        let a = world.spawn((U32(1),));
+        let b = world.spawn((U32(2),));
+        world.spawn((Str("other"),));
+
+        let archetype = world.locate(a).unwrap().archetype;
+        let epoch = world.epoch();
+
+        let mut values = vec![U32(0); 2];
+        values[world.locate(b).unwrap().idx as usize] = U32(20);
+        values[world.locate(a).unwrap().idx as usize] = U32(10);
+
+        world.swap_column(archetype, &mut values).unwrap();
+        assert_eq!(world.query_one_mut::<&U32>(a), Ok(&U32(10)));
+        assert_eq!(world.query_one_mut::<&U32>(b), Ok(&U32(20)));
+
+        values.sort_by_key(|v| v.0);
+        assert_eq!(values, [U32(1), U32(2)]);
+
+        let modified = world
+            .query_with(crate::query::Modified::<&U32>::new(epoch))
+            .iter()
+            .count();
+        assert_eq!(modified, 2);
+
+        assert_eq!(
+            world.swap_column(archetype, &mut [Str("x"), Str("y")]),
+            Err(crate::world::MissingComponents)
+        );
+    }
+
+    #[test]
+    fn relate_many() {
+        use crate::relation::{OriginComponent, TargetComponent};
+
+        #[derive(Clone, Copy)]
+        struct Edge(u32);
+
+        impl Relation for Edge {}
+
+        let mut world = World::new();
+        let hub = world.spawn(());
+        let nodes: Vec<_> = (0..5).map(|_| world.spawn(())).collect();
+
+        world
+            .relate_many(hub, nodes.iter().map(|&n| (Edge(1), n)))
+            .unwrap();
+        world
+            .related_from_many(hub, nodes.iter().map(|&n| (n, Edge(2))))
+            .unwrap();
+
+        let origin = world.query_one_mut::<&OriginComponent<Edge>>(hub).unwrap();
+        assert_eq!(origin.origins().len(), 5);
+        assert!(origin.origins().iter().all(|o| o.relation().0 == 1));
+
+        let target = world.query_one_mut::<&TargetComponent<Edge>>(hub).unwrap();
+        assert_eq!(target.origins(), &nodes[..]);
+
+        let dead = world.spawn(());
+        world.despawn(dead).unwrap();
+
+        let other = world.spawn(());
+        assert_eq!(
+            world.relate_many(other, [(Edge(0), nodes[0]), (Edge(0), dead)]),
+            Err(NoSuchEntity)
+        );
+        assert_eq!(
+            world.query_one_mut::<&OriginComponent<Edge>>(other).err(),
+            Some(QueryOneError::NotSatisfied)
+        );
+    }
+
+    #[test]
+    fn spawn_at() {
+        use crate::{entity::EntityId, world::IdOccupied};
+
+        let mut world = World::new();
+        let first = world.spawn(());
+
+        let id = EntityId::from_bits(first.bits() + 1).unwrap();
+        let reserved = EntityId::from_bits(first.bits() + 3).unwrap();
+
+        assert_eq!(world.spawn_at(id, (U32(1),)), Ok(id));
+        assert_eq!(world.spawn_at(id, (U32(2),)), Err(IdOccupied));
+        assert_eq!(world.query_one_mut::<&U32>(id), Ok(&U32(1)));
+
+        // Allocated ids skip occupied ones.
+        let a = world.spawn(());
+        assert_eq!(a.bits(), first.bits() + 2);
+
+        let b = world.allocate();
+        assert_eq!(b.bits(), first.bits() + 3);
+        assert_eq!(world.spawn_at(reserved, ()), Err(IdOccupied));
+
+        world
+            .spawn_at(EntityId::from_bits(first.bits() + 4).unwrap(), ())
+            .unwrap();
+        let c = world.allocate();
+        world.maintenance();
+        assert_eq!(c.bits(), first.bits() + 5);
+        assert!(world.is_alive(c));
+
+        world.despawn(id).unwrap();
+        assert_eq!(world.spawn_at(id, ()), Ok(id));
+        assert_eq!(world.query::<Entities>().iter().count(), 6);
+    }
+
+    /// Tests that replaced bundle components are returned without calling hooks.
+    #[test]
+    fn replace_bundle() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        static HOOKS: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(Debug, PartialEq, Eq)]
+        struct Hooked(u32);
+        impl Component for Hooked {
+            fn on_drop(
+                &mut self,
+                _id: crate::entity::EntityId,
+                _encoder: crate::action::ActionEncoder,
+            ) {
+                HOOKS.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let mut world = World::new();
+        let a = world.spawn((Hooked(1),));
+        let b = world.spawn((Hooked(2), U32(2)));
+
+        // Moves `a` to new archetype.
+        assert_eq!(
+            world.replace_bundle(a, (Hooked(10), U32(10))),
+            Ok((Some(Hooked(1)), None))
+        );
+
+        // Keeps `b` in the same archetype.
+        assert_eq!(
+            world.replace_bundle(b, (U32(20), Hooked(20))),
+            Ok((Some(U32(2)), Some(Hooked(2))))
+        );
+
+        assert_eq!(HOOKS.load(Ordering::Relaxed), 0);
+        assert_eq!(
+            world.query_one_mut::<(&Hooked, &U32)>(a),
+            Ok((&Hooked(10), &U32(10)))
+        );
+        assert_eq!(
+            world.query_one_mut::<(&Hooked, &U32)>(b),
+            Ok((&Hooked(20), &U32(20)))
+        );
+
+        assert_eq!(world.replace_bundle(a, ()), Ok(()));
+
+        world.despawn(a).unwrap();
+        assert_eq!(world.replace_bundle(a, (U32(0),)), Err(NoSuchEntity));
+    }
+
+    #[test]
+    fn query_pinned_other_world() {
+        let mut world = World::new();
+        let e = world.spawn((U32(1),));
+        let pin = world.pin_entity_row(e, |_| {}).unwrap();
+
+        // Different entity occupies the same row.
+        let mut other = World::new();
+        let tmp = other.spawn(());
+        other.spawn((U32(2),));
+        other.despawn(tmp).unwrap();
+        assert!(other.query_pinned::<&U32>(&pin).is_none());
+        assert!(World::new().query_pinned::<&U32>(&pin).is_none());
+
+        let mut one = world.query_pinned::<&U32>(&pin).unwrap();
+        assert_eq!(one.get(), Some(&U32(1)));
+    }
+
+    /// Tests that hibernated columns are moved back on access
+    /// and columns of components with move hooks stay out of the store.
+    #[test]
+    fn hibernate_cold_archetypes() {
+        use alloc::sync::Arc;
+        use core::mem::MaybeUninit;
+        use parking_lot::Mutex;
+
+        use crate::archetype::ColumnStore;
+
+        #[derive(Default)]
+        struct VecStore(Mutex<Vec<Vec<MaybeUninit<u8>>>>);
+
+        unsafe impl ColumnStore for VecStore {
+            fn store(&self, bytes: &[MaybeUninit<u8>]) -> u64 {
+                let mut columns = self.0.lock();
+                columns.push(bytes.to_vec());
+                columns.len() as u64 - 1
+            }
+
+            fn load(&self, key: u64, bytes: &mut [MaybeUninit<u8>]) {
+                bytes.copy_from_slice(&self.0.lock()[key as usize]);
+            }
+        }
+
+        struct SelfAddr(usize);
+
+        impl Component for SelfAddr {
+            const ON_MOVE: Option<fn(&mut Self, *const Self)> = Some(|component, _| {
+                let addr = &*component as *const Self as usize;
+                component.0 = addr;
+            });
+        }
+
+        let mut world = World::new();
+        let entities = (0..10)
+            .map(|i| world.spawn((U32(i), SelfAddr(0))))
+            .collect::<Vec<_>>();
+        let hot = world.spawn((Str("hot"),));
+
+        world.query_mut::<&mut SelfAddr>().for_each(|component| {
+            component.0 = &*component as *const SelfAddr as usize;
+        });
+
+        let epoch = world.epoch();
+        world.insert(hot, Str("still hot")).unwrap();
+
+        let store = Arc::new(VecStore::default());
+        assert_eq!(
+            world.hibernate_cold_archetypes_with(epoch, store.clone()),
+            1
+        );
+        assert_eq!(store.0.lock().len(), 1);
+
+        // Already hibernated.
+        assert_eq!(
+            world.hibernate_cold_archetypes_with(epoch, store.clone()),
+            0
+        );
+
+        for (i, &e) in entities.iter().enumerate() {
+            let (value, addr) = world.query_one_mut::<(&U32, &SelfAddr)>(e).unwrap();
+            assert_eq!(value.0, i as u32);
+            assert_eq!(addr.0, addr as *const SelfAddr as usize);
+        }
+
+        assert_eq!(world.hibernate_cold_archetypes(epoch), 1);
+        world.spawn((U32(10), SelfAddr(0)));
+        assert_eq!(world.query::<&U32>().iter().map(|v| v.0).sum::<u32>(), 55);
+        assert_eq!(world.query_one_mut::<&Str>(hot), Ok(&Str("still hot")));
+    }
+}
@@ -1,26 +1,40 @@
-use alloc::borrow::ToOwned;
+use alloc::{borrow::ToOwned, sync::Arc, vec::Vec};
 use core::{
     any::TypeId,
     cell::Cell,
     convert::Infallible,
+    hash::Hash,
     marker::PhantomData,
     mem::ManuallyDrop,
-    ops::{Deref, DerefMut},
+    ops::{Deref, DerefMut, Range},
 };
 
+use hashbrown::HashMap;
+
 use crate::{
-    archetype::{chunk_idx, first_of_chunk, Archetype, CHUNK_LEN_USIZE},
+    action::ActionEncoder,
+    archetype::{chunk_idx, Archetype, ArchetypeComponent, CHUNK_LEN_USIZE},
     entity::{EntityId, EntitySet},
     query::{
-        Copied, Fetch, FilteredQuery, ImmutableQuery, IntoQuery, Modified, MutQuery, Not,
-        PhantomQuery, Query, QueryBorrowAll, QueryBorrowAny, QueryBorrowOne, QueryItem, QueryIter,
-        With, Without,
+        ChunkRange, Copied, Entities, Fetch, FilteredQuery, ImmutableQuery, IntoQuery,
+        MetricsCounters, Modified, ModifiedFilter, MutQuery, Not, PhantomQuery, Query,
+        QueryBorrowAll, QueryBorrowAllFrom, QueryBorrowAny, QueryBorrowOne, QueryItem, QueryIter,
+        QuerySlice, SliceFetch, Spawned, SplitByArchetype, Stride, With, Without,
     },
-    relation::{Related, Relates, RelatesExclusive, RelatesTo},
+    relation::{Related, Relates, RelatesExclusive, RelatesTo, RelatesToAny},
     world::{NoSuchEntity, QueryOneError},
 };
 
-use super::{EpochCounter, EpochId, World};
+#[cfg(feature = "metrics")]
+use crate::query::QueryMetrics;
+
+#[cfg(feature = "rayon")]
+use crate::{archetype::chunks_count, query::Access};
+
+use super::{
+    capability::{AccessDenied, Restriction},
+    EpochCounter, EpochId, World,
+};
 
 pub trait ExtendTuple<E>: Sized {
     type Output;
@@ -64,6 +78,172 @@ pub struct QueryRef<'a, Q: IntoQuery, F: IntoQuery = ()> {
     epoch: &'a EpochCounter,
     filtered_query: FilteredQuery<F::Query, Q::Query>,
     borrowed: Cell<BorrowState>,
+    metrics: MetricsCounters,
+    restriction: Option<Restriction<'a>>,
+}
+
+/// Position of a query scan that can be resumed later.
+///
+/// Used with [`QueryRef::for_each_budget`] to spread scan over all entities
+/// across multiple calls, e.g. one step per frame.
+///
+/// Cursor remembers archetype and row to continue from
+/// and the last visited entity.
+/// If entity was removed from the archetype since last step,
+/// the entity moved into its place is visited next.
+/// Other structural changes between steps may cause
+/// some entities to be skipped or visited twice.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QueryCursor {
+    archetype: u32,
+    row: u32,
+    last: Option<EntityId>,
+    finished: bool,
+}
+
+impl QueryCursor {
+    /// Returns cursor positioned at the start of the scan.
+    #[inline]
+    pub const fn new() -> Self {
+        QueryCursor {
+            archetype: 0,
+            row: 0,
+            last: None,
+            finished: false,
+        }
+    }
+
+    /// Moves cursor back to the start of the scan.
+    #[inline]
+    pub fn reset(&mut self) {
+        *self = QueryCursor::new();
+    }
+
+    /// Returns `true` if the scan has reached the end of the last archetype.
+    #[inline]
+    pub const fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+/// Reusable storage of entity groups for [`QueryRef::group_by`].
+///
+/// Keeps allocated groups between calls,
+/// so grouping entities every frame doesn't allocate
+/// once the scratch has seen enough keys and entities.
+pub struct GroupScratch<K> {
+    /// Index of the group for each key.
+    indices: HashMap<K, usize>,
+
+    /// Groups of entities, first `len` are in use.
+    groups: Vec<Vec<EntityId>>,
+    len: usize,
+}
+
+impl<K> Default for GroupScratch<K> {
+    #[inline]
+    fn default() -> Self {
+        GroupScratch::new()
+    }
+}
+
+impl<K> GroupScratch<K> {
+    /// Returns new empty scratch.
+    #[inline]
+    pub fn new() -> Self {
+        GroupScratch {
+            indices: HashMap::default(),
+            groups: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns number of groups.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no groups.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all groups, keeping allocated memory.
+    pub fn clear(&mut self) {
+        self.indices.clear();
+        for group in &mut self.groups[..self.len] {
+            group.clear();
+        }
+        self.len = 0;
+    }
+
+    /// Returns iterator over keys and entities of the groups
+    /// in unspecified order.
+    #[inline]
+    pub fn groups(&self) -> Groups<'_, K> {
+        Groups {
+            indices: self.indices.iter(),
+            groups: &self.groups,
+        }
+    }
+}
+
+impl<K> GroupScratch<K>
+where
+    K: Hash + Eq,
+{
+    /// Returns entities of the group with specified key.
+    #[inline]
+    pub fn get(&self, key: &K) -> &[EntityId] {
+        match self.indices.get(key) {
+            None => &[],
+            Some(&idx) => &self.groups[idx],
+        }
+    }
+
+    fn push(&mut self, key: K, id: EntityId) {
+        let idx = *self.indices.entry(key).or_insert_with(|| {
+            if self.len == self.groups.len() {
+                self.groups.push(Vec::new());
+            }
+            self.len += 1;
+            self.len - 1
+        });
+        self.groups[idx].push(id);
+    }
+}
+
+/// Iterator over groups of [`GroupScratch`].
+///
+/// Yields key of the group and entities in it,
+/// in order they were visited by the query.
+pub struct Groups<'a, K> {
+    indices: hashbrown::hash_map::Iter<'a, K, usize>,
+    groups: &'a [Vec<EntityId>],
+}
+
+impl<'a, K> Iterator for Groups<'a, K> {
+    type Item = (&'a K, &'a [EntityId]);
+
+    #[inline]
+    fn next(&mut self) -> Option<(&'a K, &'a [EntityId])> {
+        let (key, &idx) = self.indices.next()?;
+        Some((key, &self.groups[idx]))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl<K> ExactSizeIterator for Groups<'_, K> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.indices.len()
+    }
 }
 
 struct QueryRefParts<'a, Q: IntoQuery, F: IntoQuery> {
@@ -72,6 +252,7 @@ struct QueryRefParts<'a, Q: IntoQuery, F: IntoQuery> {
     epoch: &'a EpochCounter,
     filtered_query: FilteredQuery<F::Query, Q::Query>,
     borrowed: BorrowState,
+    restriction: Option<Restriction<'a>>,
 }
 
 impl<'a, Q, F> Drop for QueryRef<'a, Q, F>
@@ -98,6 +279,25 @@ where
             epoch: world.epoch_counter(),
             filtered_query: FilteredQuery { filter, query },
             borrowed: Cell::new(NotBorrowed),
+            metrics: MetricsCounters::new(),
+            restriction: None,
+        }
+    }
+
+    /// Constructs query from query part, filter part and world
+    /// that is known to match no archetypes of the world.
+    ///
+    /// Such query skips scanning archetypes entirely.
+    #[inline]
+    pub(crate) fn new_matching_none(world: &'a World, query: Q::Query, filter: F::Query) -> Self {
+        QueryRef {
+            archetypes: &[],
+            entities: &world.entities,
+            epoch: world.epoch_counter(),
+            filtered_query: FilteredQuery { filter, query },
+            borrowed: Cell::new(NotBorrowed),
+            metrics: MetricsCounters::new(),
+            restriction: None,
         }
     }
 
@@ -110,7 +310,48 @@ where
             epoch: world.epoch_counter(),
             filtered_query: FilteredQuery { filter, query },
             borrowed: Cell::new(Unchecked),
+            metrics: MetricsCounters::new(),
+            restriction: None,
+        }
+    }
+
+    /// Constructs query from query part, filter part and world
+    /// that may access only components allowed by the restriction.
+    #[inline]
+    pub(crate) fn new_restricted(
+        world: &'a World,
+        query: Q::Query,
+        filter: F::Query,
+        restriction: Restriction<'a>,
+    ) -> Result<Self, AccessDenied> {
+        let filtered_query = FilteredQuery { filter, query };
+        restriction.check(&filtered_query, world.archetypes())?;
+
+        Ok(QueryRef {
+            archetypes: world.archetypes(),
+            entities: &world.entities,
+            epoch: world.epoch_counter(),
+            filtered_query,
+            borrowed: Cell::new(NotBorrowed),
+            metrics: MetricsCounters::new(),
+            restriction: Some(restriction),
+        })
+    }
+
+    /// Checks restriction of the query, if any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if query accesses components not allowed by the restriction.
+    #[inline]
+    #[track_caller]
+    fn restricted(self) -> Self {
+        if let Some(restriction) = &self.restriction {
+            if let Err(denied) = restriction.check(&self.filtered_query, self.archetypes) {
+                panic!("{}", denied);
+            }
         }
+        self
     }
 
     #[inline]
@@ -124,6 +365,7 @@ where
             epoch: me.epoch,
             filtered_query: unsafe { core::ptr::read(&mut me.filtered_query) },
             borrowed: me.borrowed.get(),
+            restriction: me.restriction,
         }
     }
 
@@ -141,7 +383,10 @@ where
                 filter: parts.filtered_query.filter,
             },
             borrowed: Cell::new(parts.borrowed),
+            metrics: MetricsCounters::new(),
+            restriction: parts.restriction,
         }
+        .restricted()
     }
 
     /// Adds specified query.
@@ -164,7 +409,10 @@ where
                 filter: parts.filtered_query.filter,
             },
             borrowed: Cell::new(parts.borrowed),
+            metrics: MetricsCounters::new(),
+            restriction: parts.restriction,
         }
+        .restricted()
     }
 
     /// Adds filter that skips entities that don't have specified component.
@@ -184,7 +432,10 @@ where
                 filter: (PhantomData, parts.filtered_query.filter),
             },
             borrowed: Cell::new(parts.borrowed),
+            metrics: MetricsCounters::new(),
+            restriction: parts.restriction,
         }
+        .restricted()
     }
 
     /// Adds filter that skips entities that have specified component.
@@ -204,7 +455,10 @@ where
                 filter: (Not(PhantomData), parts.filtered_query.filter),
             },
             borrowed: Cell::new(parts.borrowed),
+            metrics: MetricsCounters::new(),
+            restriction: parts.restriction,
         }
+        .restricted()
     }
 
     /// Adds filter to the query.
@@ -224,7 +478,10 @@ where
                 filter: (filter, parts.filtered_query.filter),
             },
             borrowed: Cell::new(parts.borrowed),
+            metrics: MetricsCounters::new(),
+            restriction: parts.restriction,
         }
+        .restricted()
     }
 
     /// Adds query to fetch modified components.
@@ -250,12 +507,110 @@ where
                 filter: parts.filtered_query.filter,
             },
             borrowed: Cell::new(parts.borrowed),
+            metrics: MetricsCounters::new(),
+            restriction: parts.restriction,
         }
+        .restricted()
     }
 
-    /// Adds query to fetch modified components.
+    /// Adds filter that visits only one of every `n` entities, selected by `phase`.
+    ///
+    /// Selection is stable across entity moves,
+    /// so incrementing `phase` each frame visits every entity once every `n` frames.
+    /// See [`Stride`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero.
+    #[inline]
+    pub fn filter_stride(self, n: u64, phase: u64) -> QueryRef<'a, Q, (Stride, F)> {
+        let parts = self.deconstruct();
+
+        QueryRef {
+            archetypes: parts.archetypes,
+            entities: parts.entities,
+            epoch: parts.epoch,
+            filtered_query: FilteredQuery {
+                query: parts.filtered_query.query,
+                filter: (Stride::new(n, phase), parts.filtered_query.filter),
+            },
+            borrowed: Cell::new(parts.borrowed),
+            metrics: MetricsCounters::new(),
+            restriction: parts.restriction,
+        }
+        .restricted()
+    }
+
+    /// Adds filter that visits only chunks with indices in the range
+    /// in every archetype.
+    ///
+    /// Borrow locks are acquired only for those chunks,
+    /// so queries with disjoint chunk ranges may write the same component
+    /// at the same time, for example from jobs of a job system.
+    /// See [`ChunkRange`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut world = World::new();
+    /// for _ in 0..1000 {
+    ///     world.spawn((ExampleComponent,));
+    /// }
+    ///
+    /// let mut first = world.query::<&mut ExampleComponent>().filter_chunks(0..2);
+    /// let mut rest = world.query::<&mut ExampleComponent>().filter_chunks(2..usize::MAX);
+    ///
+    /// let count = first.iter_mut().count() + rest.iter_mut().count();
+    /// assert_eq!(count, 1000);
+    /// ```
+    #[inline]
+    pub fn filter_chunks(self, range: Range<usize>) -> QueryRef<'a, Q, (ChunkRange, F)> {
+        let parts = self.deconstruct();
+
+        QueryRef {
+            archetypes: parts.archetypes,
+            entities: parts.entities,
+            epoch: parts.epoch,
+            filtered_query: FilteredQuery {
+                query: parts.filtered_query.query,
+                filter: (ChunkRange::new(range), parts.filtered_query.filter),
+            },
+            borrowed: Cell::new(parts.borrowed),
+            metrics: MetricsCounters::new(),
+            restriction: parts.restriction,
+        }
+        .restricted()
+    }
+
+    /// Adds filter that skips entities which component `T`
+    /// was not modified after specified epoch.
+    ///
+    /// Unlike [`QueryRef::modified`] the component is not fetched.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Component)]
+    /// struct Velocity(f32);
+    ///
+    /// #[derive(Component, Debug, PartialEq)]
+    /// struct Name(&'static str);
+    ///
+    /// let mut world = World::new();
+    /// let a = world.spawn((Velocity(0.0), Name("a")));
+    /// world.spawn((Velocity(0.0), Name("b")));
+    ///
+    /// let epoch = world.epoch();
+    /// world.query_one_mut::<&mut Velocity>(a).unwrap().0 = 1.0;
+    ///
+    /// let query = world.query::<&Name>().filter_modified::<Velocity>(epoch);
+    /// let changed = query.iter().collect::<Vec<_>>();
+    /// assert_eq!(changed, [&Name("a")]);
+    /// ```
     #[inline]
-    pub fn filter_modified<T>(self, after_epoch: EpochId) -> QueryRef<'a, Q, (Modified<With<T>>, F)>
+    pub fn filter_modified<T>(self, after_epoch: EpochId) -> QueryRef<'a, Q, (ModifiedFilter<T>, F)>
     where
         T: 'static,
     {
@@ -270,7 +625,30 @@ where
                 filter: (Modified::new(after_epoch), parts.filtered_query.filter),
             },
             borrowed: Cell::new(parts.borrowed),
+            metrics: MetricsCounters::new(),
+            restriction: parts.restriction,
+        }
+        .restricted()
+    }
+
+    /// Adds filter that skips entities spawned not after specified epoch.
+    #[inline]
+    pub fn filter_spawned(self, after_epoch: EpochId) -> QueryRef<'a, Q, (Spawned, F)> {
+        let parts = self.deconstruct();
+
+        QueryRef {
+            archetypes: parts.archetypes,
+            entities: parts.entities,
+            epoch: parts.epoch,
+            filtered_query: FilteredQuery {
+                query: parts.filtered_query.query,
+                filter: (Spawned::new(after_epoch), parts.filtered_query.filter),
+            },
+            borrowed: Cell::new(parts.borrowed),
+            metrics: MetricsCounters::new(),
+            restriction: parts.restriction,
         }
+        .restricted()
     }
 
     /// Adds query to fetch copy of component.
@@ -294,7 +672,10 @@ where
                 filter: parts.filtered_query.filter,
             },
             borrowed: Cell::new(parts.borrowed),
+            metrics: MetricsCounters::new(),
+            restriction: parts.restriction,
         }
+        .restricted()
     }
 
     /// Extends query to borrow from components.
@@ -318,7 +699,10 @@ where
                 filter: parts.filtered_query.filter,
             },
             borrowed: Cell::new(parts.borrowed),
+            metrics: MetricsCounters::new(),
+            restriction: parts.restriction,
         }
+        .restricted()
     }
 
     /// Extends query to borrow from components.
@@ -344,7 +728,36 @@ where
                 filter: parts.filtered_query.filter,
             },
             borrowed: Cell::new(parts.borrowed),
+            metrics: MetricsCounters::new(),
+            restriction: parts.restriction,
         }
+        .restricted()
+    }
+
+    /// Extends query to borrow from component with specified name.
+    ///
+    /// Name is resolved among components present in the world archetypes.
+    /// Returns `None` if there is no such component.
+    ///
+    /// See [`World::component_id_by_name`] to resolve names of all registered components.
+    pub fn borrow_one_named<T>(
+        self,
+        name: &str,
+    ) -> Option<QueryRef<'a, TuplePlus<Q, QueryBorrowOne<T>>, F>>
+    where
+        QueryBorrowOne<T>: Query,
+        Q: ExtendTuple<QueryBorrowOne<T>>,
+        Q::Query: ExtendTuple<QueryBorrowOne<T>>,
+        TuplePlus<Q, QueryBorrowOne<T>>: IntoQuery<Query = TuplePlus<Q::Query, QueryBorrowOne<T>>>,
+    {
+        let id = self
+            .archetypes
+            .iter()
+            .flat_map(|archetype| archetype.infos())
+            .find(|info| info.name() == name)?
+            .id();
+
+        Some(self.borrow_one(id))
     }
 
     /// Extends query to borrow from components.
@@ -368,7 +781,38 @@ where
                 filter: parts.filtered_query.filter,
             },
             borrowed: Cell::new(parts.borrowed),
+            metrics: MetricsCounters::new(),
+            restriction: parts.restriction,
         }
+        .restricted()
+    }
+
+    /// Extends query to borrow from components
+    /// and report component each value is borrowed from.
+    #[inline]
+    pub fn borrow_all_from<T>(self) -> QueryRef<'a, TuplePlus<Q, QueryBorrowAllFrom<T>>, F>
+    where
+        QueryBorrowAllFrom<T>: PhantomQuery,
+        Q: ExtendTuple<QueryBorrowAllFrom<T>>,
+        Q::Query: ExtendTuple<PhantomData<fn() -> QueryBorrowAllFrom<T>>>,
+        TuplePlus<Q, QueryBorrowAllFrom<T>>:
+            IntoQuery<Query = TuplePlus<Q::Query, PhantomData<fn() -> QueryBorrowAllFrom<T>>>>,
+    {
+        let parts = self.deconstruct();
+
+        QueryRef {
+            archetypes: parts.archetypes,
+            entities: parts.entities,
+            epoch: parts.epoch,
+            filtered_query: FilteredQuery {
+                query: parts.filtered_query.query.extend_tuple(PhantomData),
+                filter: parts.filtered_query.filter,
+            },
+            borrowed: Cell::new(parts.borrowed),
+            metrics: MetricsCounters::new(),
+            restriction: parts.restriction,
+        }
+        .restricted()
     }
 
     /// Adds query to fetch relation.
@@ -392,7 +836,10 @@ where
                 filter: parts.filtered_query.filter,
             },
             borrowed: Cell::new(parts.borrowed),
+            metrics: MetricsCounters::new(),
+            restriction: parts.restriction,
         }
+        .restricted()
     }
 
     /// Adds query to fetch relation.
@@ -416,7 +863,10 @@ where
                 filter: parts.filtered_query.filter,
             },
             borrowed: Cell::new(parts.borrowed),
+            metrics: MetricsCounters::new(),
+            restriction: parts.restriction,
         }
+        .restricted()
     }
 
     /// Adds query to fetch relation.
@@ -439,7 +889,43 @@ where
                 filter: parts.filtered_query.filter,
             },
             borrowed: Cell::new(parts.borrowed),
+            metrics: MetricsCounters::new(),
+            restriction: parts.restriction,
+        }
+        .restricted()
+    }
+
+    /// Adds query to fetch relation with any of specified targets.
+    /// Query item is a pair of relation instance and matched target.
+    #[inline]
+    pub fn relates_to_any<R>(
+        self,
+        targets: impl Into<Arc<[EntityId]>>,
+    ) -> QueryRef<'a, TuplePlus<Q, RelatesToAny<R>>, F>
+    where
+        RelatesToAny<R>: Query,
+        Q: ExtendTuple<RelatesToAny<R>>,
+        Q::Query: ExtendTuple<RelatesToAny<R>>,
+        TuplePlus<Q, RelatesToAny<R>>: IntoQuery<Query = TuplePlus<Q::Query, RelatesToAny<R>>>,
+    {
+        let parts = self.deconstruct();
+
+        QueryRef {
+            archetypes: parts.archetypes,
+            entities: parts.entities,
+            epoch: parts.epoch,
+            filtered_query: FilteredQuery {
+                query: parts
+                    .filtered_query
+                    .query
+                    .extend_tuple(RelatesToAny::new(targets)),
+                filter: parts.filtered_query.filter,
+            },
+            borrowed: Cell::new(parts.borrowed),
+            metrics: MetricsCounters::new(),
+            restriction: parts.restriction,
         }
+        .restricted()
     }
 
     /// Adds query to fetch relation.
@@ -463,7 +949,10 @@ where
                 filter: parts.filtered_query.filter,
             },
             borrowed: Cell::new(parts.borrowed),
+            metrics: MetricsCounters::new(),
+            restriction: parts.restriction,
         }
+        .restricted()
     }
 
     /// Borrow from archetypes
@@ -477,6 +966,49 @@ where
         self.borrowed.set(Borrowed);
     }
 
+    /// Tries to borrow from archetypes.
+    /// Returns component that is locked by someone else on failure.
+    pub(crate) fn try_ensure_borrow(&self) -> Result<(), &'a ArchetypeComponent> {
+        if self.borrowed.get() != NotBorrowed {
+            return Ok(());
+        }
+
+        if let Err(component) = try_acquire_archetypes(self.archetypes, &self.filtered_query) {
+            self.metrics.lock_waited();
+            return Err(component);
+        }
+
+        self.borrowed.set(Borrowed);
+        Ok(())
+    }
+
+    /// Returns statistics of this query collected since it was created.
+    ///
+    /// Refining query with methods like [`QueryRef::with`] creates new query
+    /// with empty statistics.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut world = World::new();
+    /// world.spawn((ExampleComponent,));
+    /// world.spawn((ExampleComponent,));
+    ///
+    /// let mut query = world.query::<&ExampleComponent>();
+    /// query.for_each(|_| {});
+    /// assert_eq!(query.iter().count(), 2);
+    ///
+    /// let metrics = query.metrics();
+    /// assert_eq!(metrics.archetypes_visited, 2);
+    /// assert_eq!(metrics.items_yielded, 4);
+    /// ```
+    #[cfg(feature = "metrics")]
+    #[inline]
+    pub fn metrics(&self) -> QueryMetrics {
+        self.metrics.get()
+    }
+
     /// Release borrow locks from archetypes.
     /// Borrow locks are acquired with [`QueryRef::get_one`], [`QueryRef::iter`] and [`QueryRef::iter_mut`] methods.
     /// Borrow locks are automatically released when the [`QueryRef`] is dropped.
@@ -485,7 +1017,7 @@ where
     /// For example in system with conflicting queries it is possible
     /// to use this method to release borrows from one query and then use another query.
     pub fn release(&mut self) {
-        if *self.borrowed.get_mut() == Borrowed {
+        if *self.borrowed.get_mut() != Borrowed {
             return;
         }
 
@@ -516,7 +1048,10 @@ where
             };
         }
 
-        let archetype = &self.archetypes[archetype_idx as usize];
+        // Archetypes are omitted if query is known to match none.
+        let Some(archetype) = self.archetypes.get(archetype_idx as usize) else {
+            return Err(QueryOneError::NotSatisfied);
+        };
 
         debug_assert!(archetype.len() >= idx as usize, "Entity index is valid");
 
@@ -625,11 +1160,53 @@ where
         Q::Query: ImmutableQuery + Clone,
         F::Query: Clone,
     {
-        self.ensure_borrow();
+        self.iter_with_epoch(self.epoch.next())
+    }
 
-        let epoch = self.epoch.next();
+    /// Returns iterator over query results
+    /// using specified epoch instead of advancing world epoch.
+    ///
+    /// Read-only queries do not mark components as modified,
+    /// so advancing epoch for them is not necessary.
+    ///
+    /// # Panics
+    ///
+    /// Panics in debug builds if `epoch` is after current world epoch.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut world = World::new();
+    /// world.spawn((ExampleComponent,));
+    ///
+    /// let frame = world.advance_epoch();
+    /// assert_eq!(world.query::<&ExampleComponent>().iter_with_epoch(frame).count(), 1);
+    /// assert_eq!(world.query::<&ExampleComponent>().iter_with_epoch(frame).count(), 1);
+    /// assert_eq!(world.epoch(), frame);
+    /// ```
+    #[inline]
+    pub fn iter_with_epoch(
+        &self,
+        epoch: EpochId,
+    ) -> QueryIter<'_, FilteredQuery<F::Query, Q::Query>>
+    where
+        Q::Query: ImmutableQuery + Clone,
+        F::Query: Clone,
+    {
+        debug_assert!(
+            !epoch.after(self.epoch.current()),
+            "Epoch is after current world epoch"
+        );
 
-        QueryIter::new(self.filtered_query.clone(), epoch, self.archetypes)
+        self.ensure_borrow();
+
+        QueryIter::new(
+            self.filtered_query.clone(),
+            epoch,
+            self.archetypes,
+            &self.metrics,
+        )
     }
 
     /// Returns iterator over query results.
@@ -645,6 +1222,54 @@ where
             MutQuery::new(&mut self.filtered_query),
             epoch,
             self.archetypes,
+            &self.metrics,
+        )
+    }
+
+    /// Splits query into independent iterators, one per matching archetype.
+    ///
+    /// Each yielded [`ArchetypeQueryIter`] is `Send` when query items are,
+    /// so they can be distributed across threads of a custom job system.
+    /// Borrow locks of all matching archetypes are acquired by this method
+    /// and are held by this [`QueryRef`] until it is dropped or released.
+    ///
+    /// [`ArchetypeQueryIter`]: crate::query::ArchetypeQueryIter
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World, ExampleComponent};
+    /// #[derive(Component)]
+    /// struct Marker;
+    ///
+    /// let mut world = World::new();
+    /// world.spawn((ExampleComponent,));
+    /// world.spawn((ExampleComponent, Marker));
+    ///
+    /// let mut query = world.query_mut::<&mut ExampleComponent>();
+    /// let count = std::thread::scope(|scope| {
+    ///     let handles = query
+    ///         .split_by_archetype()
+    ///         .map(|iter| scope.spawn(move || iter.count()))
+    ///         .collect::<Vec<_>>();
+    ///
+    ///     handles.into_iter().map(|h| h.join().unwrap()).sum::<usize>()
+    /// });
+    /// assert_eq!(count, 2);
+    /// ```
+    #[inline]
+    pub fn split_by_archetype(
+        &mut self,
+    ) -> SplitByArchetype<'_, MutQuery<'_, FilteredQuery<F::Query, Q::Query>>> {
+        self.ensure_borrow();
+
+        let epoch = self.epoch.next();
+
+        SplitByArchetype::new(
+            MutQuery::new(&mut self.filtered_query),
+            epoch,
+            self.archetypes,
+            &self.metrics,
         )
     }
 
@@ -666,8 +1291,23 @@ where
         self.fold((), move |(), item| f(item));
     }
 
-    /// Calls a closure on each query item.
-    /// Breaks when closure returns `Err` and returns that value.
+    /// Calls a closure on each query item
+    /// providing [`ActionEncoder`] to record actions alongside.
+    ///
+    /// Recorded actions are executed by the owner of the encoder.
+    /// See [`World::for_each_with_encoder`] that applies them automatically.
+    ///
+    /// This method does not allow references from items to escape the closure.
+    #[inline]
+    pub fn for_each_with<Fun>(&mut self, encoder: &mut ActionEncoder, mut f: Fun)
+    where
+        Fun: for<'b, 'e> FnMut(QueryItem<'b, Q>, ActionEncoder<'e>),
+    {
+        self.for_each(|item| f(item, encoder.reborrow()));
+    }
+
+    /// Calls a closure on each query item.
+    /// Breaks when closure returns `Err` and returns that value.
     ///
     /// This method does not allow references from items to escape the closure.
     /// This allows it to lock only archetype which is currently iterated.
@@ -685,6 +1325,343 @@ where
         self.try_fold((), move |(), item| f(item))
     }
 
+    /// Calls a closure on at most `budget` query items,
+    /// starting from position stored in the `cursor`.
+    /// Returns number of items visited.
+    ///
+    /// Cursor is advanced past visited items, so next call continues
+    /// where this one stopped.
+    /// When all archetypes are scanned the cursor becomes finished
+    /// and this method does nothing until it is [reset](QueryCursor::reset).
+    ///
+    /// Like [`QueryRef::for_each`], this method locks only archetype which is currently iterated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::{QueryCursor, World}, ExampleComponent};
+    /// let mut world = World::new();
+    /// for _ in 0..10 {
+    ///     world.spawn((ExampleComponent,));
+    /// }
+    ///
+    /// let mut cursor = QueryCursor::new();
+    /// let mut frames = 0;
+    /// while !cursor.is_finished() {
+    ///     world
+    ///         .query::<&ExampleComponent>()
+    ///         .for_each_budget(&mut cursor, 4, |_| {});
+    ///     frames += 1;
+    /// }
+    /// assert_eq!(frames, 3);
+    /// ```
+    pub fn for_each_budget<Fun>(
+        &mut self,
+        cursor: &mut QueryCursor,
+        budget: usize,
+        mut f: Fun,
+    ) -> usize
+    where
+        Fun: for<'b> FnMut(QueryItem<'b, Q>),
+    {
+        if cursor.finished {
+            return 0;
+        }
+
+        // Re-resolve last visited entity in case it was moved.
+        if let Some(last) = cursor.last {
+            match self.entities.get_location(last) {
+                Some((archetype_idx, idx)) if archetype_idx == cursor.archetype => {
+                    cursor.row = idx + 1;
+                }
+                _ => {
+                    // Last entity was replaced by one that was not visited yet.
+                    cursor.row = cursor.row.saturating_sub(1);
+                }
+            }
+        }
+
+        let epoch = self.epoch.next();
+        let borrowed = self.borrowed.get() != BorrowState::NotBorrowed;
+        let mut query = MutQuery::new(&mut self.filtered_query);
+        let mut visited = 0;
+
+        while let Some(archetype) = self.archetypes.get(cursor.archetype as usize) {
+            if visited == budget {
+                return visited;
+            }
+
+            let len = archetype.len();
+            let start = cursor.row as usize;
+
+            if start < len && query.visit_archetype(archetype) {
+                if !borrowed {
+                    unsafe {
+                        query.access_archetype(archetype, &|id, access| {
+                            let component = archetype.component(id).unwrap_unchecked();
+                            let success = component.borrow_chunks(access, query.chunk_range());
+                            assert!(
+                                success,
+                                "Failed to borrow component `{}` from archetype",
+                                component.name()
+                            );
+                        });
+                    }
+                }
+
+                let mut guard;
+                let query = if borrowed {
+                    &mut query
+                } else {
+                    guard = borrow_archetype(archetype, &mut query);
+                    &mut *guard
+                };
+
+                let mut fetch = unsafe { query.fetch(archetype, epoch) };
+
+                let mut chunk_start = start;
+                while chunk_start < len {
+                    let chunk = chunk_idx(chunk_start);
+                    let chunk_end = len.min((chunk + 1) * CHUNK_LEN_USIZE);
+                    let mut touched = false;
+
+                    if unsafe { fetch.visit_chunk(chunk) } {
+                        for idx in chunk_start..chunk_end {
+                            if visited == budget {
+                                return visited;
+                            }
+
+                            cursor.row = idx as u32 + 1;
+                            cursor.last = Some(archetype.entities()[idx]);
+
+                            if !unsafe { fetch.visit_item(idx) } {
+                                continue;
+                            }
+
+                            if !touched {
+                                unsafe { fetch.touch_chunk(chunk) };
+                                touched = true;
+                            }
+
+                            f(unsafe { fetch.get_item(idx) });
+                            visited += 1;
+                        }
+                    }
+
+                    chunk_start = chunk_end;
+                }
+            }
+
+            cursor.archetype += 1;
+            cursor.row = 0;
+            cursor.last = None;
+        }
+
+        cursor.finished = true;
+        visited
+    }
+
+    /// Calls a closure on slices of query items.
+    ///
+    /// Each slice contains items of consecutive entities from one chunk of an archetype.
+    /// Operating on slices instead of single items
+    /// gives compiler a chance to vectorize the loop.
+    ///
+    /// Available only for queries and filters that never skip individual entities.
+    /// Slices include components disabled with [`World::set_enabled`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Component)]
+    /// struct Pos(f32);
+    ///
+    /// #[derive(Component)]
+    /// struct Vel(f32);
+    ///
+    /// let mut world = World::new();
+    /// for i in 0..1000 {
+    ///     world.spawn((Pos(0.0), Vel(i as f32)));
+    /// }
+    ///
+    /// world
+    ///     .query::<(&mut Pos, &Vel)>()
+    ///     .for_each_slice(|(pos, vel)| {
+    ///         for (pos, vel) in pos.iter_mut().zip(vel) {
+    ///             pos.0 += vel.0;
+    ///         }
+    ///     });
+    /// ```
+    #[inline]
+    pub fn for_each_slice<Fun>(&mut self, mut f: Fun)
+    where
+        for<'b> <F::Query as Query>::Fetch<'b>: SliceFetch<'b>,
+        for<'b> <Q::Query as Query>::Fetch<'b>: SliceFetch<'b>,
+        Fun: for<'b> FnMut(QuerySlice<'b, Q>),
+    {
+        let epoch = self.epoch.next();
+        let borrowed = self.borrowed.get() != BorrowState::NotBorrowed;
+
+        for archetype in self.archetypes {
+            if archetype.is_empty() {
+                continue;
+            }
+
+            if !self.filtered_query.visit_archetype(archetype) {
+                continue;
+            }
+
+            self.metrics.archetype_visited();
+
+            if borrowed {
+                let fetch = unsafe { self.filtered_query.fetch(archetype, epoch) };
+                unsafe { for_each_slice_archetype(fetch, archetype.len(), &self.metrics, &mut f) };
+                continue;
+            }
+
+            unsafe {
+                self.filtered_query
+                    .access_archetype(archetype, &|id, access| {
+                        let component = archetype.component(id).unwrap_unchecked();
+                        let success =
+                            component.borrow_chunks(access, self.filtered_query.chunk_range());
+                        assert!(
+                            success,
+                            "Failed to borrow component `{}` from archetype",
+                            component.name()
+                        );
+                    });
+            }
+
+            let mut query = borrow_archetype(archetype, &mut self.filtered_query);
+
+            let fetch = unsafe { query.fetch(archetype, epoch) };
+            unsafe { for_each_slice_archetype(fetch, archetype.len(), &self.metrics, &mut f) };
+        }
+    }
+
+    /// Transforms query items and appends results to the `out` buffer.
+    ///
+    /// Space for all entities of matching archetypes is reserved upfront,
+    /// so buffer is reallocated at most once.
+    /// Useful to extract per-instance data for rendering.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Component)]
+    /// struct Pos(f32, f32);
+    ///
+    /// #[derive(Component)]
+    /// struct Color(u32);
+    ///
+    /// let mut world = World::new();
+    /// world.spawn((Pos(0.0, 1.0), Color(0xff)));
+    /// world.spawn((Pos(2.0, 3.0),));
+    ///
+    /// let mut instances = Vec::new();
+    /// world
+    ///     .query::<(&Pos, Option<&Color>)>()
+    ///     .gather_into(&mut instances, |(pos, color)| {
+    ///         [pos.0, pos.1, color.map_or(0.0, |c| c.0 as f32)]
+    ///     });
+    ///
+    /// assert_eq!(instances.len(), 2);
+    /// ```
+    pub fn gather_into<T, Fun>(&mut self, out: &mut Vec<T>, mut f: Fun)
+    where
+        Fun: for<'b> FnMut(QueryItem<'b, Q>) -> T,
+    {
+        let upper = self
+            .archetypes
+            .iter()
+            .filter(|archetype| self.filtered_query.visit_archetype(archetype))
+            .map(|archetype| archetype.len())
+            .sum();
+        out.reserve(upper);
+
+        self.for_each(|item| out.push(f(item)));
+    }
+
+    /// Calls a closure on each pair of items from this and `other` query
+    /// that have equal keys.
+    ///
+    /// Keys of `other` query items are put into hash table first,
+    /// then items of this query are matched against it.
+    /// So `other` should be the smaller of two queries.
+    /// Only references to `other` items are stored,
+    /// no query results are copied.
+    ///
+    /// `other` query stays borrowed until it is dropped or released.
+    /// Panics if this query conflicts with `other`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Component)]
+    /// struct Team(u32);
+    ///
+    /// #[derive(Component)]
+    /// struct Health(u32);
+    ///
+    /// #[derive(Component)]
+    /// struct Aura(u32);
+    ///
+    /// let mut world = World::new();
+    /// world.spawn((Team(0), Health(10)));
+    /// world.spawn((Team(1), Health(10)));
+    /// world.spawn((Team(0), Aura(5)));
+    /// world.spawn((Team(0), Aura(1)));
+    ///
+    /// let auras = world.query::<(&Team, &Aura)>();
+    /// world.query::<(&Team, &mut Health)>().join(
+    ///     &auras,
+    ///     |(team, _)| team.0,
+    ///     |(team, _)| team.0,
+    ///     |(_, health), (_, aura)| health.0 += aura.0,
+    /// );
+    ///
+    /// let mut health = world.query::<&Health>().iter().map(|h| h.0).collect::<Vec<_>>();
+    /// health.sort();
+    /// assert_eq!(health, [10, 16]);
+    /// ```
+    pub fn join<'b, Q2, F2, K, KeyA, KeyB, Fun>(
+        &mut self,
+        other: &'b QueryRef<'_, Q2, F2>,
+        mut key: KeyA,
+        mut other_key: KeyB,
+        mut f: Fun,
+    ) where
+        Q2: IntoQuery,
+        Q2::Query: ImmutableQuery + Clone,
+        F2: IntoQuery,
+        F2::Query: ImmutableQuery + Clone,
+        K: Hash + Eq,
+        KeyA: for<'c> FnMut(&QueryItem<'c, Q>) -> K,
+        KeyB: FnMut(&QueryItem<'b, Q2>) -> K,
+        Fun: for<'c> FnMut(&mut QueryItem<'c, Q>, &QueryItem<'b, Q2>),
+    {
+        let mut table = HashMap::<K, Vec<QueryItem<'b, Q2>>>::new();
+        for item in other.iter() {
+            table.entry(other_key(&item)).or_default().push(item);
+        }
+
+        if table.is_empty() {
+            return;
+        }
+
+        self.for_each(|mut item| {
+            if let Some(others) = table.get(&key(&item)) {
+                for other in others {
+                    f(&mut item, other);
+                }
+            }
+        });
+    }
+
     /// Folds every query item into an accumulator by applying an operation, returning the final result.
     ///
     /// This method does not allow references from items to escape the closure.
@@ -731,10 +1708,292 @@ where
             self.archetypes,
             epoch,
             self.borrowed.get() != BorrowState::NotBorrowed,
+            &self.metrics,
             acc,
             f,
         )
     }
+
+    /// Checks if any query item satisfies the predicate.
+    /// Stops at the first item that does.
+    ///
+    /// Like [`QueryRef::try_fold`], this method locks only archetype which is currently iterated,
+    /// so the lock is released as soon as the predicate resolves.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut world = World::new();
+    /// world.spawn((ExampleComponent,));
+    ///
+    /// assert!(world.query::<&ExampleComponent>().any(|_| true));
+    /// assert!(!world.query::<&ExampleComponent>().any(|_| false));
+    /// ```
+    #[inline]
+    pub fn any<Fun>(&mut self, mut f: Fun) -> bool
+    where
+        Fun: for<'b> FnMut(QueryItem<'b, Q>) -> bool,
+    {
+        self.try_for_each(|item| if f(item) { Err(()) } else { Ok(()) })
+            .is_err()
+    }
+
+    /// Checks if all query items satisfy the predicate.
+    /// Stops at the first item that does not.
+    ///
+    /// Returns `true` if query yields no items.
+    /// See [`QueryRef::any`] for locking behavior.
+    #[inline]
+    pub fn all<Fun>(&mut self, mut f: Fun) -> bool
+    where
+        Fun: for<'b> FnMut(QueryItem<'b, Q>) -> bool,
+    {
+        self.try_for_each(|item| if f(item) { Ok(()) } else { Err(()) })
+            .is_ok()
+    }
+
+    /// Returns first value produced by the closure for query items.
+    /// Stops at the first item for which closure returns `Some`.
+    ///
+    /// Items may not escape the closure,
+    /// so it maps found item to the value that is returned.
+    /// See [`QueryRef::any`] for locking behavior.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Component)]
+    /// struct Health(u32);
+    ///
+    /// let mut world = World::new();
+    /// world.spawn((Health(10),));
+    /// world.spawn((Health(0),));
+    ///
+    /// let dead = world
+    ///     .query::<&Health>()
+    ///     .find(|health| (health.0 == 0).then_some(health.0));
+    /// assert_eq!(dead, Some(0));
+    /// ```
+    #[inline]
+    pub fn find<T, Fun>(&mut self, mut f: Fun) -> Option<T>
+    where
+        Fun: for<'b> FnMut(QueryItem<'b, Q>) -> Option<T>,
+    {
+        match self.try_for_each(|item| match f(item) {
+            None => Ok(()),
+            Some(value) => Err(value),
+        }) {
+            Ok(()) => None,
+            Err(value) => Some(value),
+        }
+    }
+
+    /// Returns id of the first entity whose query item satisfies the predicate.
+    /// Stops at the first item that does.
+    ///
+    /// See [`QueryRef::any`] for locking behavior.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Component)]
+    /// struct Health(u32);
+    ///
+    /// let mut world = World::new();
+    /// world.spawn((Health(10),));
+    /// let dead = world.spawn((Health(0),));
+    ///
+    /// assert_eq!(world.query::<&Health>().find_entity(|health| health.0 == 0), Some(dead));
+    /// ```
+    #[inline]
+    pub fn find_entity<Fun>(&mut self, mut f: Fun) -> Option<EntityId>
+    where
+        Fun: for<'b> FnMut(QueryItem<'b, Q>) -> bool,
+    {
+        let epoch = self.epoch.next();
+
+        let res = try_fold(
+            (Entities::query(), MutQuery::new(&mut self.filtered_query)),
+            self.archetypes,
+            epoch,
+            self.borrowed.get() != BorrowState::NotBorrowed,
+            &self.metrics,
+            (),
+            |(), (id, item)| if f(item) { Err(id) } else { Ok(()) },
+        );
+
+        res.err()
+    }
+
+    /// Buckets entities matched by the query by key returned from the closure.
+    ///
+    /// Entities are put into `scratch` that is cleared first,
+    /// and iterator over the groups is returned.
+    /// Reusing the same scratch every frame avoids allocations.
+    /// Items of each group can then be visited with [`QueryRef::get_one`].
+    ///
+    /// See [`QueryRef::any`] for locking behavior.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::{GroupScratch, World}};
+    /// #[derive(Component)]
+    /// struct Team(u32);
+    ///
+    /// #[derive(Component)]
+    /// struct Health(u32);
+    ///
+    /// let mut world = World::new();
+    /// world.spawn((Team(0), Health(10)));
+    /// world.spawn((Team(1), Health(20)));
+    /// world.spawn((Team(0), Health(30)));
+    ///
+    /// let mut scratch = GroupScratch::new();
+    /// let mut query = world.query::<(&Team, &Health)>();
+    ///
+    /// let mut totals = Vec::new();
+    /// for (&team, entities) in query.group_by(&mut scratch, |(team, _)| team.0) {
+    ///     let mut total = 0;
+    ///     for &e in entities {
+    ///         let (_, health) = query.get_one(e).unwrap();
+    ///         total += health.0;
+    ///     }
+    ///     totals.push((team, total));
+    /// }
+    ///
+    /// totals.sort();
+    /// assert_eq!(totals, [(0, 40), (1, 20)]);
+    /// ```
+    pub fn group_by<'s, K, Fun>(
+        &mut self,
+        scratch: &'s mut GroupScratch<K>,
+        mut key: Fun,
+    ) -> Groups<'s, K>
+    where
+        K: Hash + Eq,
+        Fun: for<'b> FnMut(QueryItem<'b, Q>) -> K,
+    {
+        scratch.clear();
+
+        let epoch = self.epoch.next();
+
+        let res = try_fold(
+            (Entities::query(), MutQuery::new(&mut self.filtered_query)),
+            self.archetypes,
+            epoch,
+            self.borrowed.get() != BorrowState::NotBorrowed,
+            &self.metrics,
+            (),
+            |(), (id, item)| {
+                scratch.push(key(item), id);
+                Ok::<_, Infallible>(())
+            },
+        );
+
+        match res {
+            Ok(()) => {}
+            Err(infallible) => match infallible {},
+        }
+
+        scratch.groups()
+    }
+
+    /// Folds query items in parallel and reduces partial results.
+    ///
+    /// Every chunk of matching archetypes is folded separately,
+    /// starting with value produced by `identity`.
+    /// Archetypes and ranges of their chunks are distributed
+    /// among threads of the current rayon thread pool,
+    /// and partial results are combined with `reduce`.
+    /// Order of folding and reduction is unspecified,
+    /// so `reduce` should be associative and `identity` should be neutral for it.
+    ///
+    /// Query items must be `Send`, which means that components are read only
+    /// if they are `Sync`, and components that are written are `Send`.
+    /// Fetches are created by threads that use them and are never sent.
+    ///
+    /// Borrow locks of all matching archetypes are acquired by this method
+    /// and are held by this [`QueryRef`] until it is dropped or released.
+    ///
+    /// # Panics
+    ///
+    /// Panics if query writes components that do not belong
+    /// to the archetype it visits, as items of different entities
+    /// would alias then.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Component)]
+    /// struct Mass(f32);
+    ///
+    /// let mut world = World::new();
+    /// for i in 0..1000 {
+    ///     world.spawn((Mass(i as f32),));
+    /// }
+    ///
+    /// let total = world
+    ///     .query::<&Mass>()
+    ///     .fold_reduce(|| 0.0, |acc, mass| acc + mass.0, |a, b| a + b);
+    /// assert_eq!(total, 499500.0);
+    /// ```
+    #[cfg(feature = "rayon")]
+    pub fn fold_reduce<'b, T, Id, Fold, Reduce>(
+        &'b mut self,
+        identity: Id,
+        fold: Fold,
+        reduce: Reduce,
+    ) -> T
+    where
+        T: Send,
+        Id: Fn() -> T + Send + Sync,
+        Fold: Fn(T, QueryItem<'b, Q>) -> T + Send + Sync,
+        Reduce: Fn(T, T) -> T + Send + Sync,
+        Q::Query: Clone + Sync,
+        F::Query: Clone + Sync,
+        QueryItem<'b, Q>: Send,
+    {
+        use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
+
+        self.ensure_borrow();
+
+        let epoch = self.epoch.next();
+        let archetypes: &'b [Archetype] = self.archetypes;
+        let query = &self.filtered_query;
+
+        for archetype in archetypes {
+            if archetype.is_empty() || !query.visit_archetype(archetype) {
+                continue;
+            }
+
+            unsafe {
+                query.access_archetype(archetype, &|id, access| {
+                    assert!(
+                        access == Access::Read || archetype.has_component(id),
+                        "`fold_reduce` query writes component outside of visited archetype"
+                    );
+                });
+            }
+        }
+
+        archetypes
+            .par_iter()
+            .filter(|archetype| !archetype.is_empty() && query.visit_archetype(archetype))
+            .flat_map(|archetype| {
+                (0..chunks_count(archetype.len())).into_par_iter().map_init(
+                    || unsafe { query.clone().fetch(archetype, epoch) },
+                    |fetch, chunk| unsafe {
+                        fold_chunk(fetch, archetype.len(), chunk, identity(), &fold)
+                    },
+                )
+            })
+            .reduce(&identity, &reduce)
+    }
 }
 
 impl<'a, Q, F> IntoIterator for &'a mut QueryRef<'_, Q, F>
@@ -788,7 +2047,10 @@ where
         };
     }
 
-    let archetype = unsafe { archetypes.get_unchecked(archetype_idx as usize) };
+    // Archetypes are omitted if query is known to match none.
+    let Some(archetype) = archetypes.get(archetype_idx as usize) else {
+        return Err(QueryOneError::NotSatisfied);
+    };
 
     debug_assert!(archetype.len() >= idx as usize, "Entity index is valid");
 
@@ -798,8 +2060,13 @@ where
 
     unsafe {
         query.access_archetype(archetype, &|id, access| {
-            let success = archetype.component(id).unwrap_unchecked().borrow(access);
-            assert!(success, "Failed to borrow from archetype");
+            let component = archetype.component(id).unwrap_unchecked();
+            let success = component.borrow_chunks(access, query.chunk_range());
+            assert!(
+                success,
+                "Failed to borrow component `{}` from archetype",
+                component.name()
+            );
         });
     }
 
@@ -841,7 +2108,10 @@ where
         };
     }
 
-    let archetype = unsafe { archetypes.get_unchecked(archetype_idx as usize) };
+    // Archetypes are omitted if query is known to match none.
+    let Some(archetype) = archetypes.get(archetype_idx as usize) else {
+        return Err(QueryOneError::NotSatisfied);
+    };
 
     debug_assert!(archetype.len() >= idx as usize, "Entity index is valid");
 
@@ -870,6 +2140,7 @@ fn try_fold<Q, T, E, Fun>(
     archetypes: &[Archetype],
     epoch: EpochId,
     borrowed: bool,
+    metrics: &MetricsCounters,
     acc: T,
     f: Fun,
 ) -> Result<T, E>
@@ -878,9 +2149,9 @@ where
     Fun: FnMut(T, QueryItem<'_, Q>) -> Result<T, E>,
 {
     if borrowed {
-        try_fold_pre_borrowed_impl(query, archetypes, epoch, acc, f)
+        try_fold_pre_borrowed_impl(query, archetypes, epoch, metrics, acc, f)
     } else {
-        try_fold_impl(query, archetypes, epoch, acc, f)
+        try_fold_impl(query, archetypes, epoch, metrics, acc, f)
     }
 }
 
@@ -888,6 +2159,7 @@ fn try_fold_impl<Q, T, E, Fun>(
     mut query: Q,
     archetypes: &[Archetype],
     epoch: EpochId,
+    metrics: &MetricsCounters,
     mut acc: T,
     mut f: Fun,
 ) -> Result<T, E>
@@ -906,36 +2178,21 @@ where
 
         unsafe {
             query.access_archetype(archetype, &|id, access| {
-                let success = archetype.component(id).unwrap_unchecked().borrow(access);
-                assert!(success, "Failed to borrow from archetype");
+                let component = archetype.component(id).unwrap_unchecked();
+                let success = component.borrow_chunks(access, query.chunk_range());
+                assert!(
+                    success,
+                    "Failed to borrow component `{}` from archetype",
+                    component.name()
+                );
             });
         }
 
         let mut query = borrow_archetype(archetype, &mut query);
 
-        let mut fetch = unsafe { query.fetch(archetype, epoch) };
-
-        let mut indices = 0..archetype.len();
-        let mut touch_chunk = false;
-
-        while let Some(idx) = indices.next() {
-            if let Some(chunk_idx) = first_of_chunk(idx) {
-                if !unsafe { fetch.visit_chunk(chunk_idx) } {
-                    indices.nth(CHUNK_LEN_USIZE - 1);
-                    continue;
-                }
-                touch_chunk = true;
-            }
-            if !unsafe { fetch.visit_item(idx) } {
-                continue;
-            }
-            if touch_chunk {
-                unsafe { fetch.touch_chunk(chunk_idx(idx)) }
-                touch_chunk = false;
-            }
-            let item = unsafe { fetch.get_item(idx) };
-            acc = f(acc, item)?;
-        }
+        metrics.archetype_visited();
+        let fetch = unsafe { query.fetch(archetype, epoch) };
+        acc = unsafe { try_fold_archetype(fetch, archetype.len(), metrics, acc, &mut f)? };
     }
     Ok(acc)
 }
@@ -944,6 +2201,7 @@ fn try_fold_pre_borrowed_impl<Q, T, E, Fun>(
     mut query: Q,
     archetypes: &[Archetype],
     epoch: EpochId,
+    metrics: &MetricsCounters,
     mut acc: T,
     mut f: Fun,
 ) -> Result<T, E>
@@ -960,33 +2218,173 @@ where
             continue;
         }
 
-        let mut fetch = unsafe { query.fetch(archetype, epoch) };
+        metrics.archetype_visited();
+        let fetch = unsafe { query.fetch(archetype, epoch) };
+        acc = unsafe { try_fold_archetype(fetch, archetype.len(), metrics, acc, &mut f)? };
+    }
+    Ok(acc)
+}
 
-        let mut indices = 0..archetype.len();
-        let mut touch_chunk = false;
+/// Calls closure for slices of all chunks of the archetype fetch.
+///
+/// # Safety
+///
+/// `fetch` must be created for archetype with `len` entities.
+#[inline(always)]
+unsafe fn for_each_slice_archetype<'a, F, Fun>(
+    mut fetch: F,
+    len: usize,
+    metrics: &MetricsCounters,
+    f: &mut Fun,
+) where
+    F: SliceFetch<'a>,
+    Fun: FnMut(F::Slice),
+{
+    let mut chunk_start = 0;
 
-        while let Some(idx) = indices.next() {
-            if let Some(chunk_idx) = first_of_chunk(idx) {
-                if !unsafe { fetch.visit_chunk(chunk_idx) } {
-                    indices.nth(CHUNK_LEN_USIZE - 1);
-                    continue;
-                }
-                touch_chunk = true;
+    while chunk_start < len {
+        let chunk_end = len.min(chunk_start + CHUNK_LEN_USIZE);
+        let chunk = chunk_idx(chunk_start);
+        let range = chunk_start..chunk_end;
+        chunk_start = chunk_end;
+
+        if !unsafe { fetch.visit_chunk(chunk) } {
+            metrics.chunk_skipped();
+            continue;
+        }
+
+        unsafe { fetch.touch_chunk(chunk) };
+        metrics.items_yielded(range.len());
+        f(unsafe { fetch.get_chunk_slice(range) });
+    }
+}
+
+/// Folds over all items of the archetype fetch.
+///
+/// Items are processed chunk by chunk, so that the inner loop
+/// is free of chunk boundary checks.
+/// Chunk is touched only once, before first visited item.
+/// Memory of the next chunk is prefetched while current one is processed,
+/// and fetches that visit all items skip visiting checks altogether.
+///
+/// # Safety
+///
+/// `fetch` must be created for archetype with `len` entities.
+#[inline(always)]
+unsafe fn try_fold_archetype<'a, F, T, E, Fun>(
+    mut fetch: F,
+    len: usize,
+    metrics: &MetricsCounters,
+    mut acc: T,
+    f: &mut Fun,
+) -> Result<T, E>
+where
+    F: Fetch<'a>,
+    Fun: FnMut(T, F::Item) -> Result<T, E>,
+{
+    let mut chunk_start = 0;
+
+    if fetch.visits_all() {
+        // Fast path without skip checks.
+        while chunk_start < len {
+            let chunk_end = len.min(chunk_start + CHUNK_LEN_USIZE);
+            let chunk = chunk_idx(chunk_start);
+            let indices = chunk_start..chunk_end;
+            chunk_start = chunk_end;
+
+            if chunk_end < len {
+                unsafe { fetch.prefetch_chunk(chunk + 1) };
+            }
+
+            unsafe { fetch.touch_chunk(chunk) };
+            for idx in indices {
+                let item = unsafe { fetch.get_item(idx) };
+                metrics.items_yielded(1);
+                acc = f(acc, item)?;
+            }
+        }
+
+        return Ok(acc);
+    }
+
+    while chunk_start < len {
+        let chunk_end = len.min(chunk_start + CHUNK_LEN_USIZE);
+        let chunk = chunk_idx(chunk_start);
+        let mut indices = chunk_start..chunk_end;
+        chunk_start = chunk_end;
+
+        if chunk_end < len {
+            unsafe { fetch.prefetch_chunk(chunk + 1) };
+        }
+
+        if !unsafe { fetch.visit_chunk(chunk) } {
+            metrics.chunk_skipped();
+            continue;
+        }
+
+        // Find first visited item to touch the chunk.
+        for idx in indices.by_ref() {
+            if unsafe { fetch.visit_item(idx) } {
+                unsafe { fetch.touch_chunk(chunk) };
+                let item = unsafe { fetch.get_item(idx) };
+                metrics.items_yielded(1);
+                acc = f(acc, item)?;
+                break;
             }
+        }
+
+        // Rest of the chunk is already touched.
+        for idx in indices {
             if !unsafe { fetch.visit_item(idx) } {
                 continue;
             }
-            if touch_chunk {
-                unsafe { fetch.touch_chunk(chunk_idx(idx)) }
-                touch_chunk = false;
-            }
             let item = unsafe { fetch.get_item(idx) };
+            metrics.items_yielded(1);
             acc = f(acc, item)?;
         }
     }
+
     Ok(acc)
 }
 
+/// Folds over items of one chunk.
+///
+/// # Safety
+///
+/// `fetch` must be created for archetype with `len` entities
+/// and chunk must not be visited by any other fetch of the query.
+#[cfg(feature = "rayon")]
+#[inline(always)]
+unsafe fn fold_chunk<'a, Fe, T, Fun>(
+    fetch: &mut Fe,
+    len: usize,
+    chunk: usize,
+    mut acc: T,
+    f: &Fun,
+) -> T
+where
+    Fe: Fetch<'a>,
+    Fun: Fn(T, Fe::Item) -> T,
+{
+    if !unsafe { fetch.visit_chunk(chunk) } {
+        return acc;
+    }
+
+    let mut touched = false;
+    for idx in chunk * CHUNK_LEN_USIZE..len.min((chunk + 1) * CHUNK_LEN_USIZE) {
+        if !unsafe { fetch.visit_item(idx) } {
+            continue;
+        }
+        if !touched {
+            unsafe { fetch.touch_chunk(chunk) };
+            touched = true;
+        }
+        let item = unsafe { fetch.get_item(idx) };
+        acc = f(acc, item);
+    }
+    acc
+}
+
 enum QueryOneState<'a> {
     Existing(&'a Archetype, u32),
     Reserved(EntityId),
@@ -1107,48 +2505,62 @@ where
 }
 
 fn acquire_archetypes(archetypes: &[Archetype], query: &impl Query) {
-    struct ReleaseOnFailure<'a, Q: Query> {
-        archetypes: &'a [Archetype],
-        query: &'a Q,
-        len: usize,
+    if let Err(component) = try_acquire_archetypes(archetypes, query) {
+        panic!("Failed to lock `{}` from archetype", component.name());
     }
+}
 
-    impl<'a, Q> Drop for ReleaseOnFailure<'a, Q>
-    where
-        Q: Query,
-    {
-        fn drop(&mut self) {
-            for archetype in &self.archetypes[..self.len] {
-                unsafe {
-                    if self.query.visit_archetype(archetype) {
-                        self.query.access_archetype(archetype, &|id, access| {
-                            archetype.component(id).unwrap_unchecked().release(access);
-                        });
-                    }
-                }
-            }
+/// Tries to lock components of all archetypes visited by the query.
+///
+/// On failure releases all acquired locks
+/// and returns component that could not be locked.
+fn try_acquire_archetypes<'a>(
+    archetypes: &'a [Archetype],
+    query: &impl Query,
+) -> Result<(), &'a ArchetypeComponent> {
+    for (idx, archetype) in archetypes.iter().enumerate() {
+        if !query.visit_archetype(archetype) {
+            continue;
         }
-    }
 
-    let mut guard = ReleaseOnFailure {
-        archetypes,
-        query,
-        len: 0,
-    };
+        let failed = Cell::new(None);
+        let acquired = Cell::new(0usize);
 
-    for archetype in archetypes {
         unsafe {
-            if query.visit_archetype(archetype) {
+            query.access_archetype(archetype, &|id, access| {
+                if failed.get().is_some() {
+                    return;
+                }
+                let component = archetype.component(id).unwrap_unchecked();
+                if component.borrow_chunks(access, query.chunk_range()) {
+                    acquired.set(acquired.get() + 1);
+                } else {
+                    failed.set(Some(component));
+                }
+            });
+        }
+
+        if let Some(component) = failed.get() {
+            // Release locks acquired in this archetype.
+            let left = Cell::new(acquired.get());
+            unsafe {
                 query.access_archetype(archetype, &|id, access| {
-                    let success = archetype.component(id).unwrap_unchecked().borrow(access);
-                    assert!(success, "Failed to lock '{:?}' from archetype", id);
+                    if left.get() > 0 {
+                        left.set(left.get() - 1);
+                        archetype
+                            .component(id)
+                            .unwrap_unchecked()
+                            .release_chunks(access, query.chunk_range());
+                    }
                 });
             }
+
+            release_archetypes(&archetypes[..idx], query);
+            return Err(component);
         }
-        guard.len += 1;
     }
 
-    core::mem::forget(guard);
+    Ok(())
 }
 
 fn release_archetypes(archetypes: &[Archetype], query: &impl Query) {
@@ -1156,7 +2568,10 @@ fn release_archetypes(archetypes: &[Archetype], query: &impl Query) {
         unsafe {
             if query.visit_archetype(archetype) {
                 query.access_archetype(archetype, &|id, access| {
-                    archetype.component(id).unwrap_unchecked().release(access);
+                    archetype
+                        .component(id)
+                        .unwrap_unchecked()
+                        .release_chunks(access, query.chunk_range());
                 });
             }
         }
@@ -1206,7 +2621,7 @@ where
                     self.archetype
                         .component(id)
                         .unwrap_unchecked()
-                        .release(access);
+                        .release_chunks(access, self.query.chunk_range());
                 });
             }
         }
@@ -1214,3 +2629,242 @@ where
 
     QueryRelease { query, archetype }
 }
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::{vec, vec::Vec};
+
+    use crate::{
+        query::Entities,
+        test::{Bool, Str, U32},
+        world::{QueryCursor, World},
+    };
+
+    /// Tests that folding visits items across chunks
+    /// with and without skip checks.
+    #[test]
+    fn fold_chunks() {
+        use crate::query::Modified;
+
+        let mut world = World::new();
+        let entities = (0..1000u32)
+            .map(|i| world.spawn((U32(i),)))
+            .collect::<Vec<_>>();
+
+        let mut count = 0;
+        world.query::<&mut U32>().for_each(|u| {
+            u.0 += 1;
+            count += 1;
+        });
+        assert_eq!(count, 1000);
+
+        let sum = world.query::<&U32>().fold(0, |acc, u| acc + u.0);
+        assert_eq!(sum, (1..=1000).sum::<u32>());
+
+        // Disabled component forces skip checks.
+        world.set_enabled::<U32>(entities[300], false).unwrap();
+        let sum = world
+            .query::<(Entities, &U32)>()
+            .fold(0, |acc, (_, u)| acc + u.0);
+        assert_eq!(sum, (1..=1000).sum::<u32>() - 301);
+
+        let after = world.epoch();
+        world.query::<&mut U32>().for_each(|u| u.0 = 0);
+        assert_eq!(
+            world
+                .query_with(Modified::<&U32>::new(after))
+                .iter()
+                .filter(|u| u.0 == 0)
+                .count(),
+            999
+        );
+    }
+
+    /// Tests that components can be borrowed by name.
+    #[test]
+    fn borrow_one_named() {
+        let mut world = World::new();
+        world.spawn((U32(1),));
+
+        let name = core::any::type_name::<U32>();
+        assert_eq!(
+            world.component_id_by_name(name),
+            Some(core::any::TypeId::of::<U32>())
+        );
+
+        let values = world
+            .new_query()
+            .borrow_one_named::<&U32>(name)
+            .unwrap()
+            .iter()
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec![U32(1)]);
+
+        assert!(world
+            .new_query()
+            .borrow_one_named::<&U32>("missing")
+            .is_none());
+    }
+
+    /// Tests that `for_each_slice` visits all entities and marks them modified.
+    #[test]
+    fn for_each_slice() {
+        let mut world = World::new();
+
+        for i in 0..1000 {
+            world.spawn((U32(i),));
+        }
+        world.spawn((U32(1000), Str("a")));
+
+        let epoch = world.epoch();
+
+        let mut count = 0;
+        world.query::<&mut U32>().for_each_slice(|values| {
+            count += values.len();
+            for value in values {
+                value.0 += 1;
+            }
+        });
+        assert_eq!(count, 1001);
+
+        let modified = world
+            .query::<Entities>()
+            .modified::<&U32>(epoch)
+            .iter()
+            .count();
+        assert_eq!(modified, 1001);
+    }
+
+    /// Tests that join visits all pairs with equal keys.
+    #[test]
+    fn query_join() {
+        let mut world = World::new();
+
+        let a = world.spawn((U32(1), Str("a")));
+        let b = world.spawn((U32(2), Str("b")));
+        world.spawn((U32(3), Str("c")));
+
+        let x = world.spawn((U32(1), Bool(true)));
+        let y = world.spawn((U32(1), Bool(false)));
+        let z = world.spawn((U32(2), Bool(true)));
+
+        let others = world.query::<(Entities, &U32)>().with::<Bool>();
+
+        let mut pairs = Vec::new();
+        world.query::<(Entities, &U32, &Str)>().join(
+            &others,
+            |(_, key, _)| key.0,
+            |(_, key)| key.0,
+            |(e, _, _), (other, _)| pairs.push((*e, *other)),
+        );
+        pairs.sort_by_key(|(e, other)| (e.bits(), other.bits()));
+
+        assert_eq!(pairs, [(a, x), (a, y), (b, z)]);
+    }
+
+    /// Tests that parallel fold visits every item once.
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn fold_reduce() {
+        let mut world = World::new();
+
+        for i in 0..2000 {
+            world.spawn((U32(i),));
+        }
+        for i in 0..1000 {
+            world.spawn((U32(i), Bool(true)));
+        }
+
+        let sum = world.query::<&mut U32>().fold_reduce(
+            || 0u64,
+            |acc, value| {
+                value.0 += 1;
+                acc + value.0 as u64
+            },
+            |a, b| a + b,
+        );
+        assert_eq!(sum, (1..=2000).sum::<u64>() + (1..=1000).sum::<u64>());
+
+        let max = world
+            .query::<&U32>()
+            .fold_reduce(|| 0, |acc, value| acc.max(value.0), u32::max);
+        assert_eq!(max, 2000);
+    }
+
+    #[test]
+    fn query_cursor() {
+        let mut world = World::new();
+        let ids: Vec<_> = (0..10).map(|i| world.spawn((U32(i),))).collect();
+        for i in 10..15 {
+            world.spawn((U32(i), Str("other")));
+        }
+
+        let mut cursor = QueryCursor::new();
+        let mut seen = Vec::new();
+
+        let visited = world
+            .query::<&U32>()
+            .for_each_budget(&mut cursor, 4, |v| seen.push(v.0));
+        assert_eq!(visited, 4);
+        assert!(!cursor.is_finished());
+
+        // Last visited entity is replaced by one that was not visited yet.
+        world.despawn(ids[3]).unwrap();
+
+        while !cursor.is_finished() {
+            world
+                .query::<&U32>()
+                .for_each_budget(&mut cursor, 4, |v| seen.push(v.0));
+        }
+
+        seen.sort_unstable();
+        assert_eq!(seen, (0..15).collect::<Vec<_>>());
+
+        assert_eq!(
+            world
+                .query::<&U32>()
+                .for_each_budget(&mut cursor, 4, |_| {}),
+            0
+        );
+
+        cursor.reset();
+        assert_eq!(
+            world
+                .query::<&U32>()
+                .for_each_budget(&mut cursor, 100, |_| {}),
+            14
+        );
+    }
+
+    /// Tests that entities are grouped by key and scratch is reused between calls.
+    #[test]
+    fn group_by() {
+        use crate::world::GroupScratch;
+
+        let mut world = World::new();
+        let a = world.spawn((U32(0), Str("a")));
+        let b = world.spawn((U32(1),));
+        let c = world.spawn((U32(0),));
+
+        let mut scratch = GroupScratch::new();
+
+        let groups = world.query::<&U32>().group_by(&mut scratch, |v| v.0);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(scratch.get(&0), [a, c]);
+        assert_eq!(scratch.get(&1), [b]);
+
+        world.insert(b, U32(0)).unwrap();
+        world.despawn(c).unwrap();
+
+        let mut groups = world
+            .query::<&U32>()
+            .group_by(&mut scratch, |v| v.0)
+            .collect::<Vec<_>>();
+        groups.sort();
+        assert_eq!(groups, [(&0, &[a, b][..])]);
+        assert_eq!(scratch.len(), 1);
+        assert_eq!(scratch.get(&1), []);
+    }
+}
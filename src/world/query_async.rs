@@ -0,0 +1,174 @@
+//! Queries that wait for component locks asynchronously.
+
+use core::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    ptr,
+    task::{Context, Poll},
+};
+
+use crate::query::{DefaultQuery, IntoQuery};
+
+use super::{QueryRef, World};
+
+/// Future that resolves to [`QueryRef`] with borrow locks acquired.
+///
+/// If some of the components accessed by the query are locked
+/// by another query, future waits until they are released.
+///
+/// Created with [`World::query_async`] and [`World::query_with_async`].
+#[must_use = "futures do nothing unless polled"]
+pub struct QueryFuture<'a, Q: IntoQuery, F: IntoQuery = ()> {
+    query: Option<QueryRef<'a, Q, F>>,
+}
+
+// Query is never pinned.
+impl<Q, F> Unpin for QueryFuture<'_, Q, F>
+where
+    Q: IntoQuery,
+    F: IntoQuery,
+{
+}
+
+impl<Q, F> fmt::Debug for QueryFuture<'_, Q, F>
+where
+    Q: IntoQuery,
+    F: IntoQuery,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryFuture")
+            .field("done", &self.query.is_none())
+            .finish()
+    }
+}
+
+impl<'a, Q, F> QueryFuture<'a, Q, F>
+where
+    Q: IntoQuery,
+    F: IntoQuery,
+{
+    /// Returns future that acquires borrow locks for the query.
+    #[inline]
+    pub fn new(query: QueryRef<'a, Q, F>) -> Self {
+        QueryFuture { query: Some(query) }
+    }
+}
+
+impl<'a, Q, F> Future for QueryFuture<'a, Q, F>
+where
+    Q: IntoQuery,
+    F: IntoQuery,
+{
+    type Output = QueryRef<'a, Q, F>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<QueryRef<'a, Q, F>> {
+        let me = self.get_mut();
+        let query = me
+            .query
+            .as_ref()
+            .expect("QueryFuture polled after completion");
+
+        let mut waiting = None;
+        loop {
+            match query.try_ensure_borrow() {
+                Ok(()) => return Poll::Ready(me.query.take().unwrap()),
+                Err(component) => {
+                    // Lock was still held after waker was registered,
+                    // so its release will wake the task.
+                    if waiting.map_or(false, |waiting| ptr::eq(waiting, component)) {
+                        return Poll::Pending;
+                    }
+                    component.wait(cx.waker());
+                    waiting = Some(component);
+                }
+            }
+        }
+    }
+}
+
+impl World {
+    /// Queries the world asynchronously.
+    ///
+    /// Unlike [`World::query`], returned future does not panic
+    /// when components are locked by other queries,
+    /// but waits until locks are released.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// # async fn example(world: &World) {
+    /// let query = world.query_async::<&ExampleComponent>().await;
+    /// for _ in query.iter() {}
+    /// # }
+    /// ```
+    #[inline]
+    pub fn query_async<Q>(&self) -> QueryFuture<'_, (Q,), ()>
+    where
+        Q: DefaultQuery,
+    {
+        QueryFuture::new(self.query::<Q>())
+    }
+
+    /// Queries the world asynchronously using query instance.
+    ///
+    /// See [`World::query_async`].
+    #[inline]
+    pub fn query_with_async<Q>(&self, query: Q) -> QueryFuture<'_, (Q,), ()>
+    where
+        Q: IntoQuery,
+    {
+        QueryFuture::new(self.query_with(query))
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::{vec, vec::Vec};
+
+    use crate::{test::U32, world::World};
+
+    /// Tests that `query_async` waits for conflicting borrow to be released.
+    #[test]
+    fn query_async() {
+        use alloc::{sync::Arc, task::Wake};
+        use core::{
+            future::Future,
+            pin::Pin,
+            sync::atomic::{AtomicBool, Ordering},
+            task::{Context, Poll, Waker},
+        };
+
+        struct Flag(AtomicBool);
+
+        impl Wake for Flag {
+            fn wake(self: Arc<Self>) {
+                self.0.store(true, Ordering::SeqCst);
+            }
+        }
+
+        let mut world = World::new();
+        world.spawn((U32(1),));
+
+        let flag = Arc::new(Flag(AtomicBool::new(false)));
+        let waker = Waker::from(flag.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        let mut writer = world.query::<&mut U32>();
+        writer.iter_mut().for_each(|U32(value)| *value += 1);
+
+        let mut reader = world.query_async::<&U32>();
+        assert!(Pin::new(&mut reader).poll(&mut cx).is_pending());
+        assert!(!flag.0.load(Ordering::SeqCst));
+
+        drop(writer);
+        assert!(flag.0.load(Ordering::SeqCst));
+
+        match Pin::new(&mut reader).poll(&mut cx) {
+            Poll::Ready(query) => assert_eq!(query.iter().collect::<Vec<_>>(), vec![&U32(2)]),
+            Poll::Pending => panic!("Query must be ready after lock is released"),
+        };
+    }
+}
@@ -0,0 +1,223 @@
+//! Limits on number of entities in the world.
+
+use core::fmt;
+
+use crate::{archetype::Archetype, bundle::DynamicComponentBundle, entity::EntityId};
+
+use super::{register_bundle, World};
+
+/// Error returned when spawning would exceed limit
+/// configured with [`WorldBuilder::max_entities`] or [`WorldBuilder::max_archetype_entities`].
+///
+/// [`WorldBuilder::max_entities`]: super::WorldBuilder::max_entities
+/// [`WorldBuilder::max_archetype_entities`]: super::WorldBuilder::max_archetype_entities
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum QuotaExceeded {
+    /// Limit on total number of entities in the world is reached.
+    Entities {
+        /// Configured limit.
+        limit: usize,
+    },
+
+    /// Limit on number of entities in the archetype is reached.
+    Archetype {
+        /// Configured limit.
+        limit: usize,
+    },
+}
+
+impl fmt::Display for QuotaExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuotaExceeded::Entities { limit } => {
+                write!(f, "World entity quota of {} is exceeded", limit)
+            }
+            QuotaExceeded::Archetype { limit } => {
+                write!(f, "Archetype entity quota of {} is exceeded", limit)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for QuotaExceeded {}
+
+/// Configured limits on number of entities.
+#[derive(Clone, Copy)]
+pub(super) struct Quotas {
+    pub max_entities: Option<usize>,
+    pub max_per_archetype: Option<usize>,
+}
+
+impl Quotas {
+    pub const fn new() -> Self {
+        Quotas {
+            max_entities: None,
+            max_per_archetype: None,
+        }
+    }
+
+    /// Returns how many more entities can be spawned into the archetype.
+    pub fn remaining(&self, archetypes: &[Archetype], archetype_idx: u32) -> usize {
+        let mut remaining = usize::MAX;
+
+        if let Some(limit) = self.max_per_archetype {
+            let len = archetypes[archetype_idx as usize].len();
+            remaining = remaining.min(limit.saturating_sub(len));
+        }
+
+        if let Some(limit) = self.max_entities {
+            let total = archetypes.iter().map(Archetype::len).sum::<usize>();
+            remaining = remaining.min(limit.saturating_sub(total));
+        }
+
+        remaining
+    }
+
+    /// Checks that one more entity can be spawned into the archetype.
+    pub fn check(&self, archetypes: &[Archetype], archetype_idx: u32) -> Result<(), QuotaExceeded> {
+        if let Some(limit) = self.max_per_archetype {
+            if archetypes[archetype_idx as usize].len() >= limit {
+                return Err(QuotaExceeded::Archetype { limit });
+            }
+        }
+
+        if let Some(limit) = self.max_entities {
+            let total = archetypes.iter().map(Archetype::len).sum::<usize>();
+            if total >= limit {
+                return Err(QuotaExceeded::Entities { limit });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that specified numbers of entities
+    /// can be added to archetypes all at once.
+    pub fn check_many(
+        &self,
+        archetypes: &[Archetype],
+        counts: &[(u32, usize)],
+    ) -> Result<(), QuotaExceeded> {
+        if let Some(limit) = self.max_per_archetype {
+            for &(archetype_idx, count) in counts {
+                let len = archetypes[archetype_idx as usize].len();
+                if len.saturating_add(count) > limit {
+                    return Err(QuotaExceeded::Archetype { limit });
+                }
+            }
+        }
+
+        if let Some(limit) = self.max_entities {
+            let total = archetypes.iter().map(Archetype::len).sum::<usize>();
+            let added = counts.iter().map(|&(_, count)| count).sum::<usize>();
+            if total.saturating_add(added) > limit {
+                return Err(QuotaExceeded::Entities { limit });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl World {
+    /// Sets limit on total number of entities in the world.
+    /// `None` removes the limit.
+    ///
+    /// Lowering the limit below current number of entities does not despawn any,
+    /// but no new entities can be spawned until enough are despawned.
+    ///
+    /// See [`WorldBuilder::max_entities`](super::WorldBuilder::max_entities).
+    #[inline]
+    pub fn set_max_entities(&mut self, limit: Option<usize>) {
+        self.quotas.max_entities = limit;
+    }
+
+    /// Sets limit on number of entities in every archetype.
+    /// `None` removes the limit.
+    ///
+    /// See [`WorldBuilder::max_archetype_entities`](super::WorldBuilder::max_archetype_entities).
+    #[inline]
+    pub fn set_max_archetype_entities(&mut self, limit: Option<usize>) {
+        self.quotas.max_per_archetype = limit;
+    }
+
+    /// Attempts to spawn a new entity in this world with provided bundle of components.
+    ///
+    /// Unlike [`World::spawn`] this method fails with `Err(QuotaExceeded)`
+    /// instead of panicking when spawning would exceed configured entity limits.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::{QuotaExceeded, World}, ExampleComponent};
+    /// let mut world = World::builder().max_entities(1).build();
+    ///
+    /// assert!(world.spawn_checked((ExampleComponent,)).is_ok());
+    /// assert_eq!(
+    ///     world.spawn_checked((ExampleComponent,)),
+    ///     Err(QuotaExceeded::Entities { limit: 1 })
+    /// );
+    /// ```
+    pub fn spawn_checked<B>(&mut self, bundle: B) -> Result<EntityId, QuotaExceeded>
+    where
+        B: DynamicComponentBundle,
+    {
+        self.maintenance();
+
+        if !bundle.valid() {
+            panic!(
+                "Specified bundle `{}` is not valid. Check for duplicate component types",
+                core::any::type_name::<B>()
+            );
+        }
+
+        let archetype_idx = self.edges.spawn(
+            &mut self.registry,
+            &mut self.archetypes,
+            &bundle,
+            |registry| register_bundle(registry, &bundle),
+        );
+
+        self.quotas.check(&self.archetypes, archetype_idx)?;
+
+        Ok(self.spawn_impl(bundle, register_bundle::<B>))
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{
+        test::{Bool, Str, U32},
+        world::{QuotaExceeded, World},
+    };
+
+    #[test]
+    fn quotas() {
+        let mut world = World::builder().max_archetype_entities(2).build();
+
+        world.spawn_checked((U32(0),)).unwrap();
+        world.spawn_checked((U32(1),)).unwrap();
+        assert_eq!(
+            world.spawn_checked((U32(2),)),
+            Err(QuotaExceeded::Archetype { limit: 2 })
+        );
+
+        // Other archetypes have their own counters.
+        let e = world.spawn_checked((Str("a"),)).unwrap();
+
+        world.set_max_entities(Some(3));
+        assert_eq!(
+            world.spawn_checked((Bool(true),)),
+            Err(QuotaExceeded::Entities { limit: 3 })
+        );
+
+        world.despawn(e).unwrap();
+        world.spawn_checked((Bool(true),)).unwrap();
+
+        world.set_max_entities(None);
+        world.set_max_archetype_entities(None);
+        assert_eq!(world.spawn_batch((0..10).map(|i| (U32(i),))).count(), 10);
+    }
+}
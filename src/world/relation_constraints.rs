@@ -0,0 +1,257 @@
+//! Validation of relation constraints.
+
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+use hashbrown::HashMap;
+
+use crate::{
+    entity::EntityId,
+    query::Entities,
+    relation::{Relates, Relation},
+};
+
+use super::World;
+
+/// Violation of constraints declared by a [`Relation`].
+///
+/// Returned by [`World::validate_relation`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RelationViolation {
+    /// Origin has more targets than [`Relation::MAX_TARGETS`].
+    TooManyTargets {
+        /// Origin of the relations.
+        origin: EntityId,
+
+        /// Number of targets of the origin.
+        targets: usize,
+    },
+
+    /// Target with [`Relation::EXCLUSIVE_TARGET`] is related by several origins.
+    SharedTarget {
+        /// Target of the relations.
+        target: EntityId,
+
+        /// Origins that relate to the target, sorted by id.
+        origins: Vec<EntityId>,
+    },
+
+    /// Relations with [`Relation::ACYCLIC`] form a cycle.
+    Cycle {
+        /// Entities that form the cycle, starting with the smallest id.
+        /// Each entity relates to the next one and the last relates to the first.
+        entities: Vec<EntityId>,
+    },
+}
+
+impl fmt::Display for RelationViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RelationViolation::TooManyTargets { origin, targets } => {
+                write!(f, "Entity {} has too many targets ({})", origin, targets)
+            }
+            RelationViolation::SharedTarget { target, origins } => {
+                write!(f, "Entity {} is related by several origins:", target)?;
+                for origin in origins {
+                    write!(f, " {}", origin)?;
+                }
+                Ok(())
+            }
+            RelationViolation::Cycle { entities } => {
+                f.write_str("Relations form a cycle:")?;
+                for entity in entities {
+                    write!(f, " {} ->", entity)?;
+                }
+                match entities.first() {
+                    None => Ok(()),
+                    Some(first) => write!(f, " {}", first),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RelationViolation {}
+
+impl World {
+    /// Checks that relations of type `R` satisfy constraints declared by the relation type.
+    /// Reports all violations found with ids of involved entities.
+    ///
+    /// See [`Relation::ACYCLIC`], [`Relation::MAX_TARGETS`] and [`Relation::EXCLUSIVE_TARGET`].
+    ///
+    /// This is a full scan of the relations, intended for debugging and tests.
+    /// For example it can be called in `debug_assert!` after systems run.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{relation::Relation, world::{RelationViolation, World}};
+    /// #[derive(Clone, Copy)]
+    /// struct Parent;
+    ///
+    /// impl Relation for Parent {
+    ///     const ACYCLIC: bool = true;
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let a = world.spawn(());
+    /// let b = world.spawn(());
+    ///
+    /// world.add_relation(a, Parent, b).unwrap();
+    /// assert!(world.validate_relation::<Parent>().is_ok());
+    /// ```
+    pub fn validate_relation<R>(&self) -> Result<(), Vec<RelationViolation>>
+    where
+        R: Relation,
+    {
+        let mut violations = Vec::new();
+
+        let mut nodes = Vec::new();
+        let mut edges = HashMap::<EntityId, Vec<EntityId>>::new();
+        let mut targets_order = Vec::new();
+        let mut origins = HashMap::<EntityId, Vec<EntityId>>::new();
+
+        self.query::<(Entities, Relates<&R>)>()
+            .for_each(|(origin, relates)| {
+                let targets = relates.map(|(_, target)| target).collect::<Vec<_>>();
+
+                if targets.len() > R::MAX_TARGETS {
+                    violations.push(RelationViolation::TooManyTargets {
+                        origin,
+                        targets: targets.len(),
+                    });
+                }
+
+                if R::EXCLUSIVE_TARGET {
+                    for &target in &targets {
+                        let origins = origins.entry(target).or_insert_with(|| {
+                            targets_order.push(target);
+                            Vec::new()
+                        });
+                        origins.push(origin);
+                    }
+                }
+
+                nodes.push(origin);
+                edges.insert(origin, targets);
+            });
+
+        for target in targets_order {
+            let mut origins = origins.remove(&target).unwrap();
+            if origins.len() > 1 {
+                // Report origins independently of storage order.
+                origins.sort_unstable_by_key(|id| id.bits());
+                violations.push(RelationViolation::SharedTarget { target, origins });
+            }
+        }
+
+        if R::ACYCLIC && !R::SYMMETRIC {
+            find_cycles(&nodes, &edges, &mut violations);
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+}
+
+/// Finds cycles in the relation graph with depth-first search.
+fn find_cycles(
+    nodes: &[EntityId],
+    edges: &HashMap<EntityId, Vec<EntityId>>,
+    violations: &mut Vec<RelationViolation>,
+) {
+    // `true` while node is on the stack, `false` when it is done.
+    let mut state = HashMap::<EntityId, bool>::new();
+
+    for &start in nodes {
+        if state.contains_key(&start) {
+            continue;
+        }
+
+        let mut stack = vec![(start, 0)];
+        state.insert(start, true);
+
+        while let Some(&(node, next)) = stack.last() {
+            let targets = edges.get(&node).map_or(&[][..], |targets| &targets[..]);
+
+            match targets.get(next) {
+                None => {
+                    state.insert(node, false);
+                    stack.pop();
+                }
+                Some(&target) => {
+                    stack.last_mut().unwrap().1 += 1;
+
+                    match state.get(&target) {
+                        None => {
+                            state.insert(target, true);
+                            stack.push((target, 0));
+                        }
+                        Some(true) => {
+                            let start = stack.iter().position(|&(n, _)| n == target).unwrap();
+                            let mut entities =
+                                stack[start..].iter().map(|&(n, _)| n).collect::<Vec<_>>();
+
+                            // Start cycle from the smallest id independently of storage order.
+                            let min = (0..entities.len())
+                                .min_by_key(|&idx| entities[idx].bits())
+                                .unwrap();
+                            entities.rotate_left(min);
+
+                            violations.push(RelationViolation::Cycle { entities });
+                        }
+                        Some(false) => {}
+                    }
+                }
+            }
+        }
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::vec;
+
+    use crate::{relation::Relation, world::World};
+
+    /// Tests that relation constraints are validated.
+    #[test]
+    fn relation_constraints() {
+        use crate::world::RelationViolation;
+
+        #[derive(Clone, Copy, Relation)]
+        #[edict(acyclic, exclusive_target, max_targets = 1)]
+        struct Link;
+
+        let mut world = World::new();
+
+        let a = world.spawn(());
+        let b = world.spawn(());
+        let c = world.spawn(());
+
+        world.add_relation(a, Link, b).unwrap();
+        world.add_relation(b, Link, c).unwrap();
+        assert_eq!(world.validate_relation::<Link>(), Ok(()));
+
+        world.add_relation(c, Link, a).unwrap();
+        world.add_relation(c, Link, b).unwrap();
+
+        let violations = world.validate_relation::<Link>().unwrap_err();
+        assert!(violations.contains(&RelationViolation::TooManyTargets {
+            origin: c,
+            targets: 2
+        }));
+        assert!(violations.contains(&RelationViolation::SharedTarget {
+            target: b,
+            origins: vec![a, c]
+        }));
+        assert!(violations.contains(&RelationViolation::Cycle {
+            entities: vec![a, b, c]
+        }));
+    }
+}
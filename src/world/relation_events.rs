@@ -0,0 +1,260 @@
+//! Delivery of events about relations being added and removed.
+
+use alloc::{
+    sync::{Arc, Weak},
+    vec::Vec,
+};
+use core::{any::TypeId, fmt, marker::PhantomData};
+
+use hashbrown::HashMap;
+use parking_lot::Mutex;
+
+use crate::{entity::EntityId, relation::Relation};
+
+use super::World;
+
+/// Event about relation reported by [`RelationEvents`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RelationEvent {
+    /// Relation was added between origin and target.
+    Added {
+        /// Origin of the relation.
+        origin: EntityId,
+
+        /// Target of the relation.
+        target: EntityId,
+    },
+
+    /// Relation between origin and target was removed.
+    Removed {
+        /// Origin of the relation.
+        origin: EntityId,
+
+        /// Target of the relation.
+        target: EntityId,
+    },
+}
+
+/// Receives events about relations of type `R`.
+///
+/// Created with [`World::relation_events`].
+/// Event is reported when relation is added to a pair of entities
+/// or removed from it, either explicitly, by replacing target of exclusive relation,
+/// or automatically when origin or target is despawned.
+/// Replacing value of existing relation is not reported.
+///
+/// Symmetric relations are reported in both directions.
+///
+/// Dropping the handle unregisters it from the world.
+pub struct RelationEvents<R> {
+    events: Arc<Mutex<Vec<RelationEvent>>>,
+    relation: PhantomData<fn() -> R>,
+}
+
+impl<R> fmt::Debug for RelationEvents<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RelationEvents")
+            .field("relation", &core::any::type_name::<R>())
+            .field("events", &self.events.lock().len())
+            .finish()
+    }
+}
+
+impl<R> RelationEvents<R> {
+    /// Returns events collected since last drain in order of delivery.
+    pub fn drain(&self) -> alloc::vec::IntoIter<RelationEvent> {
+        core::mem::take(&mut *self.events.lock()).into_iter()
+    }
+
+    /// Returns `true` if no events were collected since last drain.
+    pub fn is_empty(&self) -> bool {
+        self.events.lock().is_empty()
+    }
+}
+
+/// Readers of relation events registered in the world.
+pub(super) struct RelationReaders {
+    readers: HashMap<TypeId, Vec<Weak<Mutex<Vec<RelationEvent>>>>>,
+}
+
+impl RelationReaders {
+    pub fn new() -> Self {
+        RelationReaders {
+            readers: HashMap::new(),
+        }
+    }
+
+    /// Delivers event to all live readers of relation `R`
+    /// and drops readers that were dropped.
+    fn emit<R>(&mut self, event: RelationEvent)
+    where
+        R: 'static,
+    {
+        let Some(readers) = self.readers.get_mut(&TypeId::of::<R>()) else {
+            return;
+        };
+
+        readers.retain(|reader| match reader.upgrade() {
+            None => false,
+            Some(events) => {
+                events.lock().push(event);
+                true
+            }
+        });
+
+        if readers.is_empty() {
+            self.readers.remove(&TypeId::of::<R>());
+        }
+    }
+}
+
+impl World {
+    /// Returns reader of events about relations of type `R`.
+    ///
+    /// Returned [`RelationEvents`] receives events about relations
+    /// added and removed after this call.
+    /// This allows to react on new and broken relations
+    /// without polling relation queries.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{relation::ChildOf, world::{RelationEvent, World}};
+    /// let mut world = World::new();
+    /// let events = world.relation_events::<ChildOf>();
+    ///
+    /// let parent = world.spawn(());
+    /// let child = world.spawn(());
+    /// world.add_relation(child, ChildOf, parent).unwrap();
+    ///
+    /// assert_eq!(
+    ///     events.drain().collect::<Vec<_>>(),
+    ///     [RelationEvent::Added { origin: child, target: parent }]
+    /// );
+    ///
+    /// // Despawning parent despawns the child.
+    /// world.despawn(parent).unwrap();
+    ///
+    /// assert_eq!(
+    ///     events.drain().collect::<Vec<_>>(),
+    ///     [RelationEvent::Removed { origin: child, target: parent }]
+    /// );
+    /// ```
+    pub fn relation_events<R>(&mut self) -> RelationEvents<R>
+    where
+        R: Relation,
+    {
+        self.maintenance();
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+
+        self.relation_readers
+            .readers
+            .entry(TypeId::of::<R>())
+            .or_default()
+            .push(Arc::downgrade(&events));
+
+        RelationEvents {
+            events,
+            relation: PhantomData,
+        }
+    }
+
+    /// Delivers relation event to readers registered with [`World::relation_events`].
+    pub(crate) fn emit_relation_event<R>(&mut self, event: RelationEvent)
+    where
+        R: Relation,
+    {
+        self.relation_readers.emit::<R>(event);
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::vec::Vec;
+
+    use crate::{relation::Relation, world::World};
+
+    #[test]
+    fn relation_events() {
+        use crate::world::RelationEvent::{Added, Removed};
+
+        #[derive(Clone, Copy)]
+        struct Targets;
+
+        impl Relation for Targets {
+            const EXCLUSIVE: bool = true;
+        }
+
+        #[derive(Clone, Copy)]
+        struct Knows;
+
+        impl Relation for Knows {}
+
+        let mut world = World::new();
+        let targets = world.relation_events::<Targets>();
+        let knows = world.relation_events::<Knows>();
+
+        let a = world.spawn(());
+        let b = world.spawn(());
+        let c = world.spawn(());
+
+        world.add_relation(a, Targets, b).unwrap();
+        world.add_relation(a, Targets, c).unwrap();
+        world.add_relation(a, Targets, c).unwrap();
+        assert_eq!(
+            targets.drain().collect::<Vec<_>>(),
+            [
+                Added {
+                    origin: a,
+                    target: b
+                },
+                Removed {
+                    origin: a,
+                    target: b
+                },
+                Added {
+                    origin: a,
+                    target: c
+                },
+            ]
+        );
+
+        world.despawn(c).unwrap();
+        assert_eq!(
+            targets.drain().collect::<Vec<_>>(),
+            [Removed {
+                origin: a,
+                target: c
+            }]
+        );
+
+        world.add_relation(a, Knows, b).unwrap();
+        world.add_relation(b, Knows, a).unwrap();
+        world.remove_relation::<Knows>(a, b).unwrap();
+        world.despawn(a).unwrap();
+        assert_eq!(
+            knows.drain().collect::<Vec<_>>(),
+            [
+                Added {
+                    origin: a,
+                    target: b
+                },
+                Added {
+                    origin: b,
+                    target: a
+                },
+                Removed {
+                    origin: a,
+                    target: b
+                },
+                Removed {
+                    origin: b,
+                    target: a
+                },
+            ]
+        );
+        assert!(targets.is_empty());
+    }
+}
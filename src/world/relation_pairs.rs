@@ -0,0 +1,299 @@
+//! Iteration over pairs of entities connected by relations.
+
+use core::{any::TypeId, fmt, marker::PhantomData};
+
+use crate::{
+    archetype::{chunk_idx, Archetype, CHUNK_LEN_USIZE},
+    query::{Access, DefaultQuery, Fetch, IntoQuery, Query, QueryItem},
+    relation::{OriginComponent, Relation},
+};
+
+use super::World;
+
+/// Joins queries of origins and targets of relation `R`.
+///
+/// Visits every relation once, with items of origin and target queries
+/// fetched from both ends of the relation.
+/// Relations are skipped if either end does not satisfy its query.
+/// Relation of an entity to itself is skipped if origin and target queries
+/// access the same component and at least one of them mutably.
+///
+/// Created with [`World::relation_pairs`] and [`World::relation_pairs_with`].
+/// Holds world borrowed mutably, so no borrow locks are needed.
+pub struct RelationPairs<'a, R, O: IntoQuery, T: IntoQuery> {
+    world: &'a mut World,
+    origin: O::Query,
+    target: T::Query,
+    relation: PhantomData<fn() -> R>,
+}
+
+impl<R, O, T> fmt::Debug for RelationPairs<'_, R, O, T>
+where
+    O: IntoQuery,
+    T: IntoQuery,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RelationPairs")
+            .field("relation", &core::any::type_name::<R>())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns `true` if queries can't fetch items from the same entity at once.
+fn conflicts<O, T>(origin: &O, target: &T, archetype: &Archetype) -> bool
+where
+    O: Query,
+    T: Query,
+{
+    archetype
+        .ids()
+        .any(|id| match (origin.access(id), target.access(id)) {
+            (Some(Access::Read), Some(Access::Read)) => false,
+            (Some(_), Some(_)) => true,
+            _ => false,
+        })
+}
+
+impl<'a, R, O, T> RelationPairs<'a, R, O, T>
+where
+    R: Relation,
+    O: IntoQuery,
+    T: IntoQuery,
+{
+    fn new(world: &'a mut World, origin: O::Query, target: T::Query) -> Self {
+        let id = TypeId::of::<OriginComponent<R>>();
+        assert!(
+            origin.access(id) != Some(Access::Write) && target.access(id) != Some(Access::Write),
+            "Relation pairs can't be queried with mutable access to `OriginComponent<{}>`",
+            core::any::type_name::<R>()
+        );
+
+        world.maintenance();
+
+        RelationPairs {
+            world,
+            origin,
+            target,
+            relation: PhantomData,
+        }
+    }
+
+    /// Calls a closure for each relation
+    /// with origin query item, relation and target query item.
+    pub fn for_each<Fun>(&mut self, mut f: Fun)
+    where
+        Fun: for<'b> FnMut(QueryItem<'b, O>, &'b R, QueryItem<'b, T>),
+    {
+        let epoch = self.world.epoch.next_mut();
+        let entities = &self.world.entities;
+        let archetypes = &self.world.archetypes;
+        let origin_id = TypeId::of::<OriginComponent<R>>();
+
+        for archetype in archetypes.iter() {
+            if archetype.is_empty() || !self.origin.visit_archetype(archetype) {
+                continue;
+            }
+
+            let Some(component) = archetype.component(origin_id) else {
+                continue;
+            };
+
+            // Safety: world is borrowed mutably.
+            let origins = unsafe { component.data().ptr.cast::<OriginComponent<R>>() };
+            let self_conflict = conflicts(&self.origin, &self.target, archetype);
+
+            // Fetch only to check which origins satisfy the query.
+            let mut fetch = unsafe { self.origin.fetch(archetype, epoch) };
+
+            let len = archetype.len();
+            let mut chunk_start = 0;
+            while chunk_start < len {
+                let chunk = chunk_idx(chunk_start);
+                let chunk_end = len.min(chunk_start + CHUNK_LEN_USIZE);
+                let indices = chunk_start..chunk_end;
+                chunk_start = chunk_end;
+
+                if !unsafe { fetch.visit_chunk(chunk) } {
+                    continue;
+                }
+
+                for idx in indices {
+                    if !unsafe { fetch.visit_item(idx) } {
+                        continue;
+                    }
+
+                    let id = archetype.entities()[idx];
+
+                    // Safety: `idx` is in bounds and `OriginComponent<R>` is not borrowed mutably.
+                    let origin_component = unsafe { &*origins.as_ptr().add(idx) };
+
+                    for origin in origin_component.origins() {
+                        let target = origin.target();
+                        if target == id && self_conflict {
+                            continue;
+                        }
+
+                        let Some((target_archetype, target_idx)) = entities.get_location(target)
+                        else {
+                            continue;
+                        };
+                        if target_archetype == u32::MAX {
+                            continue;
+                        }
+
+                        let target_archetype = &archetypes[target_archetype as usize];
+                        let target_idx = target_idx as usize;
+                        let target_chunk = chunk_idx(target_idx);
+
+                        if !self.target.visit_archetype(target_archetype) {
+                            continue;
+                        }
+
+                        // Entities may be fetched for several relations.
+                        // Each fetch is a separate modification with its own epoch.
+                        let epoch = self.world.epoch.next_mut();
+
+                        let mut target_fetch =
+                            unsafe { self.target.fetch(target_archetype, epoch) };
+                        if !unsafe { target_fetch.visit_chunk(target_chunk) }
+                            || !unsafe { target_fetch.visit_item(target_idx) }
+                        {
+                            continue;
+                        }
+
+                        let mut origin_fetch = unsafe { self.origin.fetch(archetype, epoch) };
+                        if !unsafe { origin_fetch.visit_chunk(chunk) }
+                            || !unsafe { origin_fetch.visit_item(idx) }
+                        {
+                            continue;
+                        }
+
+                        unsafe { origin_fetch.touch_chunk(chunk) };
+                        unsafe { target_fetch.touch_chunk(target_chunk) };
+
+                        let origin_item = unsafe { origin_fetch.get_item(idx) };
+                        let target_item = unsafe { target_fetch.get_item(target_idx) };
+                        f(origin_item, origin.relation(), target_item);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl World {
+    /// Joins queries of origins and targets of relation `R`.
+    ///
+    /// This is useful for systems that need components of both ends of relations,
+    /// like constraint solvers or propagation of transforms from parents to children.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either query borrows `OriginComponent<R>` mutably.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, relation::ChildOf, world::World};
+    /// #[derive(Component)]
+    /// struct Pos(f32);
+    ///
+    /// #[derive(Component)]
+    /// struct Offset(f32);
+    ///
+    /// let mut world = World::new();
+    /// let parent = world.spawn((Pos(1.0),));
+    /// let child = world.spawn((Pos(0.0), Offset(2.0)));
+    /// world.add_relation(child, ChildOf, parent).unwrap();
+    ///
+    /// world
+    ///     .relation_pairs::<ChildOf, (&mut Pos, &Offset), &Pos>()
+    ///     .for_each(|(pos, offset), _, parent_pos| pos.0 = parent_pos.0 + offset.0);
+    ///
+    /// assert_eq!(world.query_one_mut::<&Pos>(child).unwrap().0, 3.0);
+    /// ```
+    pub fn relation_pairs<R, O, T>(&mut self) -> RelationPairs<'_, R, O, T>
+    where
+        R: Relation,
+        O: DefaultQuery,
+        T: DefaultQuery,
+    {
+        RelationPairs::new(self, O::default_query(), T::default_query())
+    }
+
+    /// Joins queries of origins and targets of relation `R`.
+    ///
+    /// See [`World::relation_pairs`].
+    pub fn relation_pairs_with<R, O, T>(
+        &mut self,
+        origin: O,
+        target: T,
+    ) -> RelationPairs<'_, R, O, T>
+    where
+        R: Relation,
+        O: IntoQuery,
+        T: IntoQuery,
+    {
+        RelationPairs::new(self, origin.into_query(), target.into_query())
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::vec::Vec;
+
+    use crate::{
+        query::Entities,
+        relation::Relation,
+        test::{Str, U32},
+        world::World,
+    };
+
+    #[test]
+    fn relation_pairs() {
+        #[derive(Clone, Copy)]
+        struct Link(u32);
+
+        impl Relation for Link {}
+
+        let mut world = World::new();
+        let a = world.spawn((U32(1),));
+        let b = world.spawn((U32(10), Str("b")));
+        let c = world.spawn((Str("c"),));
+
+        world.add_relation(a, Link(100), b).unwrap();
+        world.add_relation(b, Link(200), a).unwrap();
+        world.add_relation(a, Link(300), c).unwrap();
+        world.add_relation(a, Link(400), a).unwrap();
+
+        let mut names = Vec::new();
+        world
+            .relation_pairs::<Link, &mut U32, &Str>()
+            .for_each(|origin, link, target| {
+                origin.0 += link.0;
+                names.push(target.0);
+            });
+        names.sort_unstable();
+
+        assert_eq!(names, ["b", "c"]);
+        assert_eq!(world.query_one_mut::<&U32>(a), Ok(&U32(401)));
+        assert_eq!(world.query_one_mut::<&U32>(b), Ok(&U32(10)));
+
+        // Relation to `c` is skipped as it has no `U32`.
+        // Relation of `a` to itself is skipped as queries conflict.
+        let mut count = 0;
+        world
+            .relation_pairs::<Link, &mut U32, &U32>()
+            .for_each(|_, _, _| count += 1);
+        assert_eq!(count, 2);
+
+        let mut pairs = Vec::new();
+        world
+            .relation_pairs::<Link, Entities, Entities>()
+            .for_each(|origin, link, target| pairs.push((origin, link.0, target)));
+        pairs.sort_by_key(|pair| pair.1);
+
+        assert_eq!(pairs, [(a, 100, b), (b, 200, a), (a, 300, c), (a, 400, a)]);
+    }
+}
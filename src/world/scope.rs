@@ -0,0 +1,143 @@
+//! Scopes of temporary entities.
+
+use alloc::vec::Vec;
+use core::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use crate::{bundle::DynamicComponentBundle, entity::EntityId};
+
+use super::World;
+
+/// Scope that despawns temporary entities when it ends.
+///
+/// Entities spawned with [`Scope::spawn`] are temporary
+/// and despawned at the end of the scope, unless promoted with [`Scope::promote`].
+/// Entities spawned through the [`World`] this scope dereferences to are not affected.
+///
+/// Created by [`World::scope`].
+pub struct Scope<'a> {
+    world: &'a mut World,
+    temporary: Vec<EntityId>,
+}
+
+impl fmt::Debug for Scope<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scope")
+            .field("temporary", &self.temporary)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Scope<'_> {
+    /// Spawns temporary entity with provided bundle of components.
+    pub fn spawn<B>(&mut self, bundle: B) -> EntityId
+    where
+        B: DynamicComponentBundle,
+    {
+        let id = self.world.spawn(bundle);
+        self.temporary.push(id);
+        id
+    }
+
+    /// Promotes temporary entity so it outlives the scope.
+    ///
+    /// Returns `false` if entity is not temporary entity of this scope.
+    pub fn promote(&mut self, id: EntityId) -> bool {
+        match self.temporary.iter().position(|&e| e == id) {
+            None => false,
+            Some(idx) => {
+                self.temporary.swap_remove(idx);
+                true
+            }
+        }
+    }
+
+    /// Returns temporary entities of this scope.
+    #[inline]
+    pub fn temporary(&self) -> &[EntityId] {
+        &self.temporary
+    }
+}
+
+impl Deref for Scope<'_> {
+    type Target = World;
+
+    #[inline]
+    fn deref(&self) -> &World {
+        self.world
+    }
+}
+
+impl DerefMut for Scope<'_> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut World {
+        self.world
+    }
+}
+
+impl Drop for Scope<'_> {
+    fn drop(&mut self) {
+        for id in self.temporary.drain(..) {
+            // Entity may be already despawned explicitly.
+            let _ = self.world.despawn(id);
+        }
+    }
+}
+
+impl World {
+    /// Runs closure with [`Scope`] that despawns
+    /// temporary entities when closure returns.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut world = World::new();
+    ///
+    /// let (scratch, kept) = world.scope(|scope| {
+    ///     let scratch = scope.spawn((ExampleComponent,));
+    ///     let kept = scope.spawn((ExampleComponent,));
+    ///     scope.promote(kept);
+    ///     (scratch, kept)
+    /// });
+    ///
+    /// assert!(!world.is_alive(scratch));
+    /// assert!(world.is_alive(kept));
+    /// ```
+    pub fn scope<R>(&mut self, f: impl FnOnce(&mut Scope<'_>) -> R) -> R {
+        let mut scope = Scope {
+            world: self,
+            temporary: Vec::new(),
+        };
+        f(&mut scope)
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{test::U32, world::World};
+
+    /// Tests that scope despawns only remaining temporary entities.
+    #[test]
+    fn world_scope() {
+        let mut world = World::new();
+
+        let (a, b, c) = world.scope(|scope| {
+            let a = scope.spawn((U32(0),));
+            let b = scope.spawn((U32(1),));
+            let c = scope.spawn((U32(2),));
+            scope.despawn(a).unwrap();
+            assert!(scope.promote(b));
+            assert!(!scope.promote(b));
+            assert_eq!(scope.temporary(), &[a, c]);
+            (a, b, c)
+        });
+
+        assert!(!world.is_alive(a));
+        assert!(world.is_alive(b));
+        assert!(!world.is_alive(c));
+    }
+}
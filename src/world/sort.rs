@@ -0,0 +1,198 @@
+//! Keeping archetype rows sorted by a key component.
+
+use alloc::vec::Vec;
+use core::any::TypeId;
+
+use crate::{archetype::Archetype, component::Component, epoch::EpochId};
+
+use super::World;
+
+/// Component type that keeps rows of archetypes sorted.
+#[derive(Clone)]
+struct SortKey {
+    id: TypeId,
+
+    /// Sorts archetype rows by the key.
+    /// Returns index of the first moved row.
+    sort: unsafe fn(&mut Archetype) -> Option<usize>,
+
+    /// Column epoch and length of each archetype when it was last sorted,
+    /// indexed by archetype index.
+    sorted: Vec<Option<(EpochId, usize)>>,
+}
+
+/// Sort keys registered with [`World::sort_by_key`].
+pub(super) struct SortKeys {
+    keys: Vec<SortKey>,
+}
+
+impl SortKeys {
+    pub fn new() -> Self {
+        SortKeys { keys: Vec::new() }
+    }
+
+    pub fn fork(&self) -> Self {
+        SortKeys {
+            keys: self.keys.clone(),
+        }
+    }
+}
+
+/// Sorts archetype by component `T`.
+///
+/// # Safety
+///
+/// Archetype must contain component `T`.
+unsafe fn sort_archetype<T>(archetype: &mut Archetype) -> Option<usize>
+where
+    T: Ord + 'static,
+{
+    unsafe { archetype.insertion_sort_by::<T>(Ord::cmp) }
+}
+
+impl World {
+    /// Keeps rows of all archetypes with component `T` sorted by its value.
+    ///
+    /// Rows are sorted in [`World::maintenance`],
+    /// so queries iterate over entities of each archetype in ascending key order.
+    /// Only archetypes where the key column was modified
+    /// or number of entities changed since last sorting are re-sorted.
+    /// Insertion sort is used, so sorting takes linear time
+    /// when only a few rows are out of order.
+    ///
+    /// Modifications made after last maintenance are not reflected in the order.
+    /// If archetype contains several sort keys, the first registered one is used.
+    ///
+    /// Sorting moves entities within archetypes,
+    /// calling [`Component::ON_MOVE`] hooks of their components.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Component)]
+    /// struct Priority(u32);
+    ///
+    /// let mut world = World::new();
+    /// world.sort_by_key::<Priority>();
+    ///
+    /// world.spawn((Priority(3),));
+    /// world.spawn((Priority(1),));
+    /// world.spawn((Priority(2),));
+    /// world.maintenance();
+    ///
+    /// let order = world.query::<&Priority>().iter().map(|p| p.0).collect::<Vec<_>>();
+    /// assert_eq!(order, [1, 2, 3]);
+    /// ```
+    pub fn sort_by_key<T>(&mut self)
+    where
+        T: Component + Ord,
+    {
+        let id = TypeId::of::<T>();
+        if self.sort_keys.keys.iter().any(|key| key.id == id) {
+            return;
+        }
+
+        self.sort_keys.keys.push(SortKey {
+            id,
+            sort: sort_archetype::<T>,
+            sorted: Vec::new(),
+        });
+    }
+
+    /// Sorts archetypes that were changed since last sorting.
+    pub(super) fn sort_archetypes(&mut self) {
+        if self.sort_keys.keys.is_empty() {
+            return;
+        }
+
+        for (archetype_idx, archetype) in self.archetypes.iter_mut().enumerate() {
+            if archetype.len() < 2 {
+                continue;
+            }
+
+            let Some(key) = self
+                .sort_keys
+                .keys
+                .iter_mut()
+                .find(|key| archetype.has_component(key.id))
+            else {
+                continue;
+            };
+
+            // Safety: world is borrowed mutably, no other borrows exist.
+            let epoch = unsafe { archetype.component(key.id).unwrap().data().epoch.get() };
+            let state = Some((epoch, archetype.len()));
+
+            if key.sorted.len() <= archetype_idx {
+                key.sorted.resize(archetype_idx + 1, None);
+            }
+            if key.sorted[archetype_idx] == state {
+                continue;
+            }
+
+            // Safety: archetype contains the key component
+            // and world is borrowed mutably.
+            if let Some(first) = unsafe { (key.sort)(archetype) } {
+                for (idx, &id) in archetype.entities().iter().enumerate().skip(first) {
+                    self.entities
+                        .set_location(id, archetype_idx as u32, idx as u32);
+                }
+            }
+
+            key.sorted[archetype_idx] = state;
+        }
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::vec::Vec;
+
+    use crate::{component::Component, test::U32, world::World};
+
+    #[test]
+    fn sort_by_key() {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+        struct Key(u32);
+        impl Component for Key {}
+
+        let mut world = World::new();
+        world.sort_by_key::<Key>();
+
+        let ids = [5, 3, 8, 1]
+            .into_iter()
+            .map(|k| (k, world.spawn((Key(k), U32(k)))))
+            .collect::<Vec<_>>();
+        world.maintenance();
+
+        let keys = world
+            .query::<&Key>()
+            .iter()
+            .map(|k| k.0)
+            .collect::<Vec<_>>();
+        assert_eq!(keys, [1, 3, 5, 8]);
+
+        // Locations are updated, components stay with their entities.
+        for &(k, id) in &ids {
+            assert_eq!(world.query_one_mut::<&U32>(id), Ok(&U32(k)));
+        }
+
+        world.query_mut::<&mut Key>().for_each(|k| k.0 = 10 - k.0);
+        world.despawn(ids[0].1).unwrap();
+        world.maintenance();
+
+        let keys = world
+            .query::<&Key>()
+            .iter()
+            .map(|k| k.0)
+            .collect::<Vec<_>>();
+        assert_eq!(keys, [2, 7, 9]);
+
+        for &(k, id) in &ids[1..] {
+            assert_eq!(world.query_one_mut::<&Key>(id), Ok(&Key(10 - k)));
+            assert_eq!(world.query_one_mut::<&U32>(id), Ok(&U32(k)));
+        }
+    }
+}
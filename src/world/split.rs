@@ -0,0 +1,169 @@
+//! Split borrow of the [`World`] into resources and entities.
+
+use core::{fmt, marker::PhantomData};
+
+use atomicell::{Ref, RefMut};
+
+use crate::{
+    entity::EntityId,
+    query::{DefaultQuery, IntoQuery},
+};
+
+use super::{NoSuchEntity, QueryOne, QueryRef, World};
+
+/// Resource half of the split [`World`] borrow.
+///
+/// Provides access to resources, including `!Send` and `!Sync` ones,
+/// same way as [`WorldLocal`](super::WorldLocal) does.
+/// Borrows of resources are checked at runtime.
+///
+/// Created with [`World::split`].
+pub struct ResourceView<'a> {
+    world: &'a World,
+
+    // Resources of the world may be `!Send` and `!Sync`.
+    marker: PhantomData<*mut u8>,
+}
+
+impl fmt::Debug for ResourceView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResourceView").finish_non_exhaustive()
+    }
+}
+
+impl<'a> ResourceView<'a> {
+    /// Returns some reference to a resource.
+    /// Returns none if resource is not found.
+    #[inline]
+    pub fn get_resource<T: 'static>(&self) -> Option<Ref<'a, T>> {
+        // Safety:
+        // View is created from mutable reference to the world
+        // and cannot leave the thread.
+        unsafe { self.world.res.get_local() }
+    }
+
+    /// Returns reference to a resource.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if resource is missing.
+    #[track_caller]
+    #[inline]
+    pub fn expect_resource<T: 'static>(&self) -> Ref<'a, T> {
+        self.get_resource().unwrap()
+    }
+
+    /// Returns some mutable reference to a resource.
+    /// Returns none if resource is not found.
+    #[inline]
+    pub fn get_resource_mut<T: 'static>(&self) -> Option<RefMut<'a, T>> {
+        // Safety:
+        // View is created from mutable reference to the world
+        // and cannot leave the thread.
+        unsafe { self.world.res.get_local_mut() }
+    }
+
+    /// Returns mutable reference to a resource.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if resource is missing.
+    #[track_caller]
+    #[inline]
+    pub fn expect_resource_mut<T: 'static>(&self) -> RefMut<'a, T> {
+        self.get_resource_mut().unwrap()
+    }
+}
+
+/// Entity half of the split [`World`] borrow.
+///
+/// Allows to query components of the entities.
+/// Borrows of components are checked at runtime.
+///
+/// Created with [`World::split`].
+#[derive(Clone, Copy)]
+pub struct QueryView<'a> {
+    world: &'a World,
+}
+
+impl fmt::Debug for QueryView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("QueryView").finish_non_exhaustive()
+    }
+}
+
+impl<'a> QueryView<'a> {
+    /// Queries the world to iterate over entities and components specified by the query type.
+    ///
+    /// See [`World::query`].
+    #[inline]
+    pub fn query<Q>(&self) -> QueryRef<'a, (Q,), ()>
+    where
+        Q: DefaultQuery,
+    {
+        self.world.query::<Q>()
+    }
+
+    /// Queries the world to iterate over entities and components specified by the query.
+    ///
+    /// See [`World::query_with`].
+    #[inline]
+    pub fn query_with<Q>(&self, query: Q) -> QueryRef<'a, (Q,), ()>
+    where
+        Q: IntoQuery,
+    {
+        self.world.query_with(query)
+    }
+
+    /// Queries components from specified entity.
+    ///
+    /// See [`World::query_one`].
+    #[inline]
+    pub fn query_one<Q>(&self, id: EntityId) -> Result<QueryOne<'a, Q>, NoSuchEntity>
+    where
+        Q: DefaultQuery,
+    {
+        self.world.query_one::<Q>(id)
+    }
+
+    /// Returns `true` if specified entity is alive.
+    #[inline]
+    pub fn is_alive(&self, id: EntityId) -> bool {
+        self.world.is_alive(id)
+    }
+}
+
+impl World {
+    /// Splits world borrow into resource and entity halves.
+    ///
+    /// Both halves can be used simultaneously,
+    /// so resources may be borrowed mutably while querying components.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut world = World::new();
+    /// world.insert_resource(0u32);
+    /// world.spawn((ExampleComponent,));
+    ///
+    /// let (res, entities) = world.split();
+    /// let mut counter = res.expect_resource_mut::<u32>();
+    /// for _ in entities.query::<&ExampleComponent>().iter() {
+    ///     *counter += 1;
+    /// }
+    /// assert_eq!(*counter, 1);
+    /// ```
+    pub fn split(&mut self) -> (ResourceView<'_>, QueryView<'_>) {
+        self.maintenance();
+
+        let world = &*self;
+        (
+            ResourceView {
+                world,
+                marker: PhantomData,
+            },
+            QueryView { world },
+        )
+    }
+}
@@ -0,0 +1,371 @@
+//! View of the [`World`] restricted to a subset of component types.
+
+use alloc::vec::Vec;
+use core::{
+    any::{type_name, TypeId},
+    fmt,
+};
+
+use crate::{
+    archetype::Archetype,
+    bundle::DynamicComponentBundle,
+    component::Component,
+    entity::EntityId,
+    epoch::EpochId,
+    query::{Access, DefaultQuery, ImmutableQuery, IntoQuery, Query, QueryItem, UnitFetch},
+};
+
+use super::{EntityError, NoSuchEntity, QueryOneError, QueryRef, World};
+
+/// Filter that skips archetypes with components not in the whitelist.
+///
+/// Used by [`SubWorldView`] queries.
+#[derive(Clone, Copy)]
+pub struct SubWorldFilter<'a> {
+    components: &'a [TypeId],
+}
+
+impl fmt::Debug for SubWorldFilter<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SubWorldFilter").finish_non_exhaustive()
+    }
+}
+
+impl SubWorldFilter<'_> {
+    #[inline]
+    fn allows(&self, id: TypeId) -> bool {
+        self.components.binary_search(&id).is_ok()
+    }
+
+    #[inline]
+    fn allows_archetype(&self, archetype: &Archetype) -> bool {
+        archetype.ids().all(|id| self.allows(id))
+    }
+}
+
+impl IntoQuery for SubWorldFilter<'_> {
+    type Query = Self;
+
+    #[inline]
+    fn into_query(self) -> Self {
+        self
+    }
+}
+
+unsafe impl Query for SubWorldFilter<'_> {
+    type Item<'a> = ();
+    type Fetch<'a> = UnitFetch;
+
+    #[inline]
+    fn access(&self, _ty: TypeId) -> Option<Access> {
+        None
+    }
+
+    #[inline]
+    fn visit_archetype(&self, archetype: &Archetype) -> bool {
+        self.allows_archetype(archetype)
+    }
+
+    #[inline]
+    unsafe fn access_archetype(&self, _archetype: &Archetype, _f: &dyn Fn(TypeId, Access)) {}
+
+    #[inline]
+    unsafe fn fetch<'a>(&mut self, _archetype: &'a Archetype, _epoch: EpochId) -> UnitFetch {
+        UnitFetch::new()
+    }
+
+    #[inline]
+    fn reserved_entity_item<'a>(&self, _id: EntityId) -> Option<Self::Item<'a>> {
+        // Reserved entities have no components.
+        Some(())
+    }
+}
+
+unsafe impl ImmutableQuery for SubWorldFilter<'_> {}
+
+/// View of the [`World`] that contains only entities
+/// with all components from the whitelist.
+///
+/// Entities with any other component are invisible to the view,
+/// as if they were not spawned.
+/// Queries iterate only over visible entities
+/// and structural changes through the view may use only whitelisted components.
+///
+/// Useful to run systems against a minimal world in tests
+/// while using the same query code paths as with the full world.
+///
+/// Created with [`World::sub_world`].
+pub struct SubWorldView<'a> {
+    world: &'a mut World,
+
+    /// Sorted and deduplicated.
+    components: Vec<TypeId>,
+}
+
+impl fmt::Debug for SubWorldView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SubWorldView").finish_non_exhaustive()
+    }
+}
+
+impl<'a> SubWorldView<'a> {
+    #[inline]
+    fn filter(&self) -> SubWorldFilter<'_> {
+        SubWorldFilter {
+            components: &self.components,
+        }
+    }
+
+    /// Returns `true` if component type is in the whitelist.
+    #[inline]
+    pub fn allows(&self, id: TypeId) -> bool {
+        self.filter().allows(id)
+    }
+
+    /// Returns `true` if specified entity is alive and visible in this view.
+    pub fn is_alive(&self, id: EntityId) -> bool {
+        self.check_entity(id).is_ok()
+    }
+
+    fn check_entity(&self, id: EntityId) -> Result<(), NoSuchEntity> {
+        let (archetype_idx, _idx) = self.world.entities.get_location(id).ok_or(NoSuchEntity)?;
+        if archetype_idx == u32::MAX {
+            return Ok(());
+        }
+
+        let archetype = &self.world.archetypes[archetype_idx as usize];
+        if self.filter().allows_archetype(archetype) {
+            Ok(())
+        } else {
+            Err(NoSuchEntity)
+        }
+    }
+
+    #[track_caller]
+    fn assert_allowed(&self, id: TypeId, name: &str) {
+        assert!(
+            self.allows(id),
+            "Component `{}` is not in the sub-world whitelist",
+            name
+        );
+    }
+
+    /// Returns current world epoch.
+    #[inline]
+    pub fn epoch(&self) -> EpochId {
+        self.world.epoch()
+    }
+
+    /// Spawns a new entity in the world with provided bundle of components.
+    ///
+    /// # Panics
+    ///
+    /// Panics if bundle contains component that is not in the whitelist.
+    #[track_caller]
+    pub fn spawn<B>(&mut self, bundle: B) -> EntityId
+    where
+        B: DynamicComponentBundle,
+    {
+        bundle.with_components(|infos| {
+            for info in infos {
+                self.assert_allowed(info.id(), info.name());
+            }
+        });
+        self.world.spawn(bundle)
+    }
+
+    /// Despawns visible entity.
+    pub fn despawn(&mut self, id: EntityId) -> Result<(), NoSuchEntity> {
+        self.check_entity(id)?;
+        self.world.despawn(id)
+    }
+
+    /// Inserts component to visible entity.
+    ///
+    /// # Panics
+    ///
+    /// Panics if component is not in the whitelist.
+    #[track_caller]
+    pub fn insert<T>(&mut self, id: EntityId, component: T) -> Result<(), NoSuchEntity>
+    where
+        T: Component,
+    {
+        self.assert_allowed(TypeId::of::<T>(), type_name::<T>());
+        self.check_entity(id)?;
+        self.world.insert(id, component)
+    }
+
+    /// Removes component from visible entity.
+    pub fn remove<T>(&mut self, id: EntityId) -> Result<T, EntityError>
+    where
+        T: 'static,
+    {
+        self.check_entity(id)?;
+        self.world.remove(id)
+    }
+
+    /// Queries visible entities.
+    ///
+    /// See [`World::query`].
+    #[inline]
+    pub fn query<Q>(&self) -> QueryRef<'_, (Q::Query,), SubWorldFilter<'_>>
+    where
+        Q: DefaultQuery,
+    {
+        self.query_with(Q::default_query())
+    }
+
+    /// Queries visible entities with provided query instance.
+    ///
+    /// See [`World::query_with`].
+    #[inline]
+    pub fn query_with<Q>(&self, query: Q) -> QueryRef<'_, (Q,), SubWorldFilter<'_>>
+    where
+        Q: IntoQuery,
+    {
+        QueryRef::new(&*self.world, (query.into_query(),), self.filter())
+    }
+
+    /// Queries visible entities.
+    /// Skips runtime borrow checks as view is borrowed mutably.
+    ///
+    /// See [`World::query_mut`].
+    #[inline]
+    pub fn query_mut<Q>(&mut self) -> QueryRef<'_, (Q::Query,), SubWorldFilter<'_>>
+    where
+        Q: DefaultQuery,
+    {
+        self.query_with_mut(Q::default_query())
+    }
+
+    /// Queries visible entities with provided query instance.
+    /// Skips runtime borrow checks as view is borrowed mutably.
+    ///
+    /// See [`World::query_with_mut`].
+    #[inline]
+    pub fn query_with_mut<Q>(&mut self, query: Q) -> QueryRef<'_, (Q,), SubWorldFilter<'_>>
+    where
+        Q: IntoQuery,
+    {
+        // Safety: view holds mutable borrow of the world.
+        unsafe { QueryRef::new_unchecked(&*self.world, (query.into_query(),), self.filter()) }
+    }
+
+    /// Queries components from specified visible entity.
+    ///
+    /// See [`World::query_one_mut`].
+    pub fn query_one_mut<Q>(
+        &mut self,
+        id: EntityId,
+    ) -> Result<QueryItem<'_, Q::Query>, QueryOneError>
+    where
+        Q: DefaultQuery,
+    {
+        self.check_entity(id)?;
+        self.world.query_one_mut::<Q>(id)
+    }
+}
+
+impl World {
+    /// Returns view of the world that contains only entities
+    /// composed entirely of the specified component types.
+    ///
+    /// See [`SubWorldView`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use core::any::TypeId;
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Component)]
+    /// struct Pos(f32);
+    ///
+    /// #[derive(Component)]
+    /// struct Vel(f32);
+    ///
+    /// #[derive(Component)]
+    /// struct Sprite;
+    ///
+    /// fn integrate(world: &mut edict::world::SubWorldView) {
+    ///     world
+    ///         .query_mut::<(&mut Pos, &Vel)>()
+    ///         .for_each(|(pos, vel)| pos.0 += vel.0);
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let hidden = world.spawn((Pos(0.0), Vel(1.0), Sprite));
+    ///
+    /// let mut view = world.sub_world([TypeId::of::<Pos>(), TypeId::of::<Vel>()]);
+    /// let e = view.spawn((Pos(0.0), Vel(2.0)));
+    /// assert!(!view.is_alive(hidden));
+    ///
+    /// integrate(&mut view);
+    /// assert_eq!(view.query_one_mut::<&Pos>(e).unwrap().0, 2.0);
+    ///
+    /// assert_eq!(world.query_one_mut::<&Pos>(hidden).unwrap().0, 0.0);
+    /// ```
+    pub fn sub_world(&mut self, components: impl IntoIterator<Item = TypeId>) -> SubWorldView<'_> {
+        self.maintenance();
+
+        let mut components: Vec<TypeId> = components.into_iter().collect();
+        components.sort_unstable();
+        components.dedup();
+
+        SubWorldView {
+            world: self,
+            components,
+        }
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::vec::Vec;
+
+    use crate::{
+        test::{Bool, Str, U32},
+        world::{NoSuchEntity, QueryOneError, World},
+    };
+
+    #[test]
+    fn sub_world() {
+        use core::any::TypeId;
+
+        let mut world = World::new();
+        let hidden = world.spawn((U32(1), Str("hidden")));
+        let visible = world.spawn((U32(2),));
+
+        let mut view = world.sub_world([TypeId::of::<U32>(), TypeId::of::<Bool>()]);
+        assert!(view.is_alive(visible));
+        assert!(!view.is_alive(hidden));
+
+        let spawned = view.spawn((U32(3), Bool(true)));
+        view.query_mut::<&mut U32>().for_each(|u| u.0 *= 10);
+
+        let mut values = view.query::<&U32>().iter().map(|u| u.0).collect::<Vec<_>>();
+        values.sort();
+        assert_eq!(values, [20, 30]);
+
+        assert_eq!(
+            view.query_one_mut::<&U32>(hidden),
+            Err(QueryOneError::NoSuchEntity)
+        );
+        assert_eq!(view.insert(hidden, Bool(false)), Err(NoSuchEntity));
+        assert_eq!(view.remove::<Bool>(spawned), Ok(Bool(true)));
+        assert_eq!(view.despawn(hidden), Err(NoSuchEntity));
+        view.despawn(visible).unwrap();
+
+        assert_eq!(world.query_one_mut::<&U32>(hidden), Ok(&U32(1)));
+        assert!(!world.is_alive(visible));
+        assert!(world.is_alive(spawned));
+    }
+
+    #[test]
+    #[should_panic(expected = "not in the sub-world whitelist")]
+    fn sub_world_spawn_not_allowed() {
+        let mut world = World::new();
+        let mut view = world.sub_world([core::any::TypeId::of::<U32>()]);
+        view.spawn((U32(1), Str("nope")));
+    }
+}
@@ -0,0 +1,271 @@
+//! Delivery of lifecycle and component change events of individual entities.
+
+use alloc::{
+    sync::{Arc, Weak},
+    vec::Vec,
+};
+use core::fmt;
+
+use parking_lot::Mutex;
+
+use crate::{
+    archetype::Archetype,
+    entity::{EntityId, EntitySet},
+    epoch::EpochId,
+};
+
+use super::{ChangeKind, ComponentChange, World};
+
+/// Event of the entity reported by [`EntityEvents`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EntityEvent {
+    /// Component of the entity was inserted, modified or removed.
+    Component(ComponentChange),
+
+    /// Entity was despawned.
+    /// This is the last event reported for the entity.
+    Despawned,
+}
+
+/// Receives events of a single entity.
+///
+/// Created with [`World::subscribe`].
+/// Events are collected by the world during maintenance,
+/// which happens at the start of every method that borrows world mutably,
+/// or when [`World::maintenance`] is called explicitly.
+/// Changes made between two maintenances are collapsed,
+/// so component inserted and removed in between is not reported.
+///
+/// Modification is reported when component was borrowed mutably,
+/// regardless of whether the value was actually changed.
+///
+/// Dropping the handle unregisters it from the world.
+pub struct EntityEvents {
+    entity: EntityId,
+    events: Arc<Mutex<Vec<EntityEvent>>>,
+}
+
+impl fmt::Debug for EntityEvents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EntityEvents")
+            .field("entity", &self.entity)
+            .field("events", &self.events.lock().len())
+            .finish()
+    }
+}
+
+impl EntityEvents {
+    /// Returns subscribed entity.
+    #[inline]
+    pub fn entity(&self) -> EntityId {
+        self.entity
+    }
+
+    /// Returns events collected since last drain in order of delivery.
+    pub fn drain(&self) -> alloc::vec::IntoIter<EntityEvent> {
+        core::mem::take(&mut *self.events.lock()).into_iter()
+    }
+
+    /// Returns `true` if no events were collected since last drain.
+    pub fn is_empty(&self) -> bool {
+        self.events.lock().is_empty()
+    }
+}
+
+/// World-side state of [`EntityEvents`].
+struct SubscriptionState {
+    entity: EntityId,
+
+    /// Archetype of the entity at last harvest.
+    archetype: u32,
+    after_epoch: EpochId,
+    events: Weak<Mutex<Vec<EntityEvent>>>,
+}
+
+impl SubscriptionState {
+    /// Collects events of the entity.
+    /// Returns `false` if entity is despawned.
+    fn harvest(
+        &mut self,
+        archetypes: &[Archetype],
+        entities: &EntitySet,
+        events: &mut Vec<EntityEvent>,
+        epoch: EpochId,
+    ) -> bool {
+        let Some((archetype_idx, idx)) = entities.get_location(self.entity) else {
+            events.push(EntityEvent::Despawned);
+            return false;
+        };
+
+        let old = &archetypes[self.archetype as usize];
+        let new = &archetypes[archetype_idx as usize];
+
+        if self.archetype != archetype_idx {
+            for info in old.infos() {
+                if !new.has_component(info.id()) {
+                    events.push(EntityEvent::Component(ComponentChange {
+                        id: info.id(),
+                        name: info.name(),
+                        kind: ChangeKind::Removed,
+                    }));
+                }
+            }
+        }
+
+        for info in new.infos() {
+            let kind = if !old.has_component(info.id()) {
+                ChangeKind::Inserted
+            } else {
+                let component = new.component(info.id()).unwrap();
+
+                // Safety: world is borrowed mutably, no fetches may be alive.
+                let data = unsafe { component.data() };
+                if !data.entity_epochs[idx as usize].after(self.after_epoch) {
+                    continue;
+                }
+                ChangeKind::Modified
+            };
+
+            events.push(EntityEvent::Component(ComponentChange {
+                id: info.id(),
+                name: info.name(),
+                kind,
+            }));
+        }
+
+        self.archetype = archetype_idx;
+        self.after_epoch = epoch;
+        true
+    }
+}
+
+/// Collection of entity subscriptions registered in the world.
+pub(super) struct Subscriptions {
+    subscriptions: Vec<SubscriptionState>,
+}
+
+impl Subscriptions {
+    pub fn new() -> Self {
+        Subscriptions {
+            subscriptions: Vec::new(),
+        }
+    }
+
+    /// Collects events for all live subscriptions
+    /// and drops states of subscriptions that were dropped
+    /// or whose entities were despawned.
+    pub fn harvest(&mut self, archetypes: &[Archetype], entities: &EntitySet, epoch: EpochId) {
+        self.subscriptions
+            .retain_mut(|state| match state.events.upgrade() {
+                None => false,
+                Some(events) => state.harvest(archetypes, entities, &mut events.lock(), epoch),
+            });
+    }
+}
+
+impl World {
+    /// Subscribes to events of the entity.
+    ///
+    /// Returned [`EntityEvents`] receives component insertions, modifications
+    /// and removals made after this call, and despawn of the entity.
+    /// This allows to react on changes of a single entity
+    /// without running world-wide [`Modified`] queries.
+    /// If entity is not alive, [`EntityEvent::Despawned`] is reported immediately.
+    ///
+    /// [`Modified`]: crate::query::Modified
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::{ChangeKind, EntityEvent, World}, ExampleComponent};
+    /// let mut world = World::new();
+    /// let entity = world.spawn(());
+    /// let events = world.subscribe(entity);
+    ///
+    /// world.insert(entity, ExampleComponent).unwrap();
+    /// world.maintenance();
+    ///
+    /// let event = events.drain().next().unwrap();
+    /// assert!(matches!(event, EntityEvent::Component(c) if c.kind == ChangeKind::Inserted));
+    ///
+    /// world.despawn(entity).unwrap();
+    /// world.maintenance();
+    ///
+    /// assert_eq!(events.drain().collect::<Vec<_>>(), [EntityEvent::Despawned]);
+    /// ```
+    pub fn subscribe(&mut self, entity: EntityId) -> EntityEvents {
+        self.maintenance();
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+
+        match self.entities.get_location(entity) {
+            None => events.lock().push(EntityEvent::Despawned),
+            Some((archetype, _)) => {
+                let epoch = self.epoch.current_mut();
+                self.subscriptions.subscriptions.push(SubscriptionState {
+                    entity,
+                    archetype,
+                    after_epoch: epoch,
+                    events: Arc::downgrade(&events),
+                });
+            }
+        }
+
+        EntityEvents { entity, events }
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::vec::Vec;
+
+    use crate::{
+        test::{Str, U32},
+        world::World,
+    };
+
+    /// Tests that entity subscription reports changes of the entity only.
+    #[test]
+    fn entity_subscription() {
+        use crate::world::{ChangeKind, EntityEvent};
+
+        let mut world = World::new();
+        let a = world.spawn((U32(0),));
+        let b = world.spawn((U32(0),));
+
+        let events = world.subscribe(a);
+        world.maintenance();
+        assert!(events.is_empty());
+
+        world.query_one_mut::<&mut U32>(a).unwrap().0 = 1;
+        world.query_one_mut::<&mut U32>(b).unwrap().0 = 1;
+        world.insert(a, Str("a")).unwrap();
+        world.maintenance();
+
+        let kinds = events
+            .drain()
+            .map(|event| match event {
+                EntityEvent::Component(change) => (change.name, change.kind),
+                EntityEvent::Despawned => panic!("Entity is alive"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                (core::any::type_name::<U32>(), ChangeKind::Modified),
+                (core::any::type_name::<Str>(), ChangeKind::Inserted),
+            ]
+        );
+
+        world.remove::<U32>(a).unwrap();
+        world.maintenance();
+        let event = events.drain().next().unwrap();
+        assert!(matches!(event, EntityEvent::Component(c) if c.kind == ChangeKind::Removed));
+
+        world.despawn(a).unwrap();
+        world.maintenance();
+        world.maintenance();
+        assert_eq!(events.drain().collect::<Vec<_>>(), [EntityEvent::Despawned]);
+    }
+}
@@ -0,0 +1,195 @@
+//! Bulk harvesting of component changes.
+
+use alloc::{
+    sync::{Arc, Weak},
+    vec::Vec,
+};
+use core::{any::TypeId, fmt, marker::PhantomData};
+
+use hashbrown::HashSet;
+use parking_lot::Mutex;
+
+use crate::{
+    archetype::{chunk_idx, Archetype, CHUNK_LEN_USIZE},
+    entity::EntityId,
+    epoch::EpochId,
+};
+
+type Modified = Mutex<HashSet<EntityId>>;
+
+/// Collects ids of entities whose component `T` was modified.
+///
+/// Created with [`World::track`].
+/// Changes are harvested by the world in bulk during maintenance,
+/// which happens at the start of every method that borrows world mutably,
+/// or when [`World::maintenance`] is called explicitly.
+///
+/// Dropping the tracker unregisters it from the world.
+///
+/// [`World::track`]: super::World::track
+/// [`World::maintenance`]: super::World::maintenance
+pub struct ChangeTracker<T> {
+    modified: Arc<Modified>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> fmt::Debug for ChangeTracker<T>
+where
+    T: 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ChangeTracker<{}>", core::any::type_name::<T>())
+    }
+}
+
+impl<T> ChangeTracker<T> {
+    /// Returns ids of entities with component `T` modified since last drain.
+    /// Each entity is yielded at most once.
+    ///
+    /// Entity may be yielded even if it was despawned or lost the component
+    /// after modification was harvested.
+    pub fn drain(&self) -> hashbrown::hash_set::IntoIter<EntityId> {
+        core::mem::take(&mut *self.modified.lock()).into_iter()
+    }
+
+    /// Returns `true` if no modifications were harvested since last drain.
+    pub fn is_empty(&self) -> bool {
+        self.modified.lock().is_empty()
+    }
+}
+
+/// World-side state of a [`ChangeTracker`].
+pub(super) struct TrackerState {
+    component: TypeId,
+    after_epoch: EpochId,
+    modified: Weak<Modified>,
+}
+
+impl TrackerState {
+    fn harvest(&mut self, archetypes: &[Archetype], modified: &Modified, epoch: EpochId) {
+        let mut modified = modified.lock();
+
+        for archetype in archetypes {
+            let component = match archetype.component(self.component) {
+                None => continue,
+                Some(component) => component,
+            };
+
+            // Safety: world is borrowed mutably, no fetches may be alive.
+            let data = unsafe { component.data() };
+            if !data.epoch.after(self.after_epoch) {
+                continue;
+            }
+
+            let entities = archetype.entities();
+            let mut chunk_start = 0;
+            while chunk_start < entities.len() {
+                let chunk_end = (chunk_start + CHUNK_LEN_USIZE).min(entities.len());
+
+                if data.chunk_epochs[chunk_idx(chunk_start)].after(self.after_epoch) {
+                    for idx in chunk_start..chunk_end {
+                        if data.entity_epochs[idx].after(self.after_epoch) {
+                            modified.insert(entities[idx]);
+                        }
+                    }
+                }
+
+                chunk_start = chunk_end;
+            }
+        }
+
+        self.after_epoch = epoch;
+    }
+}
+
+/// Collection of change trackers registered in the world.
+pub(super) struct Trackers {
+    trackers: Vec<TrackerState>,
+}
+
+impl Trackers {
+    pub fn new() -> Self {
+        Trackers {
+            trackers: Vec::new(),
+        }
+    }
+
+    pub fn add<T>(&mut self, epoch: EpochId) -> ChangeTracker<T>
+    where
+        T: 'static,
+    {
+        let modified = Arc::new(Mutex::new(HashSet::new()));
+
+        self.trackers.push(TrackerState {
+            component: TypeId::of::<T>(),
+            after_epoch: epoch,
+            modified: Arc::downgrade(&modified),
+        });
+
+        ChangeTracker {
+            modified,
+            marker: PhantomData,
+        }
+    }
+
+    /// Harvests modifications for all live trackers
+    /// and drops states of trackers that were dropped.
+    pub fn harvest(&mut self, archetypes: &[Archetype], epoch: EpochId) {
+        self.trackers.retain_mut(|state| {
+            if !epoch.after(state.after_epoch) {
+                return state.modified.strong_count() > 0;
+            }
+
+            match state.modified.upgrade() {
+                None => false,
+                Some(modified) => {
+                    state.harvest(archetypes, &modified, epoch);
+                    true
+                }
+            }
+        });
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use alloc::vec::Vec;
+
+    use crate::{
+        test::{Str, U32},
+        world::World,
+    };
+
+    #[test]
+    fn change_tracker() {
+        let mut world = World::new();
+
+        let a = world.spawn((U32(0),));
+        let b = world.spawn((U32(1),));
+        world.spawn((Str("untracked"),));
+
+        let tracker = world.track::<U32>();
+        world.maintenance();
+        assert!(tracker.is_empty());
+
+        world
+            .query_mut::<&mut U32>()
+            .for_each(|U32(value)| *value += 1);
+        world.maintenance();
+
+        let mut modified = tracker.drain().collect::<Vec<_>>();
+        assert_eq!(modified.len(), 2);
+        modified.retain(|e| *e != a && *e != b);
+        assert!(modified.is_empty());
+
+        world.maintenance();
+        assert!(tracker.is_empty());
+
+        world.insert(a, Str("moved")).unwrap();
+        assert_eq!(tracker.drain().count(), 0);
+
+        drop(tracker);
+        world.maintenance();
+    }
+}
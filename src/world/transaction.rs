@@ -0,0 +1,95 @@
+//! Staged structural changes applied atomically.
+
+use core::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use crate::action::{ActionBuffer, ActionEncoder};
+
+use super::World;
+
+/// Transaction over the [`World`].
+///
+/// Provides read access to the world and an [`ActionEncoder`]
+/// through which structural changes are staged.
+/// Staged changes are applied only when transaction closure succeeds.
+///
+/// Created by [`World::transaction`].
+pub struct Transaction<'a> {
+    world: &'a World,
+    encoder: ActionEncoder<'a>,
+}
+
+impl fmt::Debug for Transaction<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Transaction").finish_non_exhaustive()
+    }
+}
+
+impl<'a> Transaction<'a> {
+    pub(super) fn new(world: &'a World, buffer: &'a mut ActionBuffer) -> Self {
+        Transaction {
+            world,
+            encoder: ActionEncoder::new(buffer, world.entity_set()),
+        }
+    }
+
+    /// Returns reference to the world as it was before the transaction.
+    /// Staged changes are not visible through it.
+    #[inline]
+    pub fn world(&self) -> &'a World {
+        self.world
+    }
+}
+
+impl<'a> Deref for Transaction<'a> {
+    type Target = ActionEncoder<'a>;
+
+    #[inline]
+    fn deref(&self) -> &ActionEncoder<'a> {
+        &self.encoder
+    }
+}
+
+impl<'a> DerefMut for Transaction<'a> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut ActionEncoder<'a> {
+        &mut self.encoder
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{test::U32, world::World};
+
+    /// Tests that failed transaction leaves no trace in the world.
+    #[test]
+    fn transaction_rollback() {
+        let mut world = World::new();
+
+        let a = world.spawn((U32(1),));
+
+        let result = world.transaction(|tx| {
+            tx.despawn(a);
+            let b = tx.spawn((U32(2),));
+            Err::<(), _>(b)
+        });
+
+        let b = result.unwrap_err();
+        assert!(world.is_alive(a));
+        assert!(!world.is_alive(b));
+        assert_eq!(world.query::<&U32>().iter().count(), 1);
+
+        let b = world
+            .transaction(|tx| {
+                tx.despawn(a);
+                Ok::<_, ()>(tx.spawn((U32(2),)))
+            })
+            .unwrap();
+
+        assert!(!world.is_alive(a));
+        assert_eq!(world.get_one_copied::<&U32, U32>(b), Ok(U32(2)));
+    }
+}
@@ -0,0 +1,120 @@
+//! Access to components through typed entity handles.
+
+use core::any::{type_name, TypeId};
+
+use crate::{
+    archetype::chunk_idx,
+    bundle::{ComponentBundle, ContainsComponent},
+    entity::{Entity, EntityId},
+};
+
+use super::{NoSuchEntity, World};
+
+impl World {
+    /// Spawns a new entity with provided bundle of components
+    /// and returns typed handle to it.
+    ///
+    /// Typed handle proves statically that entity has components of the bundle,
+    /// so they can be accessed with [`World::get_typed`] and [`World::get_typed_mut`]
+    /// which fail only if entity is despawned.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Component)]
+    /// struct Pos(f32);
+    ///
+    /// #[derive(Component)]
+    /// struct Vel(f32);
+    ///
+    /// let mut world = World::new();
+    /// let e = world.spawn_typed((Pos(0.0), Vel(1.0)));
+    ///
+    /// let vel = world.get_typed::<Vel, _, _>(e).unwrap().0;
+    /// world.get_typed_mut::<Pos, _, _>(e).unwrap().0 += vel;
+    /// assert_eq!(world.get_typed::<Pos, _, _>(e).unwrap().0, 1.0);
+    ///
+    /// world.despawn(e.id()).unwrap();
+    /// assert!(world.get_typed::<Pos, _, _>(e).is_err());
+    /// ```
+    #[inline]
+    pub fn spawn_typed<B>(&mut self, bundle: B) -> Entity<B>
+    where
+        B: ComponentBundle,
+    {
+        Entity::new(self.spawn(bundle))
+    }
+
+    /// Returns reference to component of the entity spawned with [`World::spawn_typed`].
+    ///
+    /// Component is returned even if it is disabled with [`World::set_enabled`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if component was removed from the entity using its [`EntityId`].
+    pub fn get_typed<T, B, I>(&mut self, entity: Entity<B>) -> Result<&T, NoSuchEntity>
+    where
+        T: 'static,
+        B: ContainsComponent<T, I>,
+    {
+        self.maintenance();
+
+        let (archetype_idx, idx) = self.typed_location::<T>(entity.id())?;
+        let component = self.archetypes[archetype_idx as usize]
+            .component(TypeId::of::<T>())
+            .unwrap();
+
+        // Safety: world is borrowed mutably, no other borrows exist.
+        let data = unsafe { component.data() };
+        Ok(unsafe { &*data.ptr.as_ptr().cast::<T>().add(idx as usize) })
+    }
+
+    /// Returns mutable reference to component of the entity spawned with [`World::spawn_typed`].
+    ///
+    /// Component is returned even if it is disabled with [`World::set_enabled`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if component was removed from the entity using its [`EntityId`].
+    pub fn get_typed_mut<T, B, I>(&mut self, entity: Entity<B>) -> Result<&mut T, NoSuchEntity>
+    where
+        T: 'static,
+        B: ContainsComponent<T, I>,
+    {
+        self.maintenance();
+
+        let (archetype_idx, idx) = self.typed_location::<T>(entity.id())?;
+        let epoch = self.epoch.next_mut();
+        let component = self.archetypes[archetype_idx as usize]
+            .component(TypeId::of::<T>())
+            .unwrap();
+
+        // Safety: world is borrowed mutably, no other borrows exist.
+        let data = unsafe { component.data_mut() };
+        data.epoch.bump(epoch);
+        data.chunk_epochs[chunk_idx(idx as usize)].bump(epoch);
+        data.entity_epochs[idx as usize].bump(epoch);
+        Ok(unsafe { &mut *data.ptr.as_ptr().cast::<T>().add(idx as usize) })
+    }
+
+    /// Returns location of the entity that must have component `T`.
+    fn typed_location<T>(&self, id: EntityId) -> Result<(u32, u32), NoSuchEntity>
+    where
+        T: 'static,
+    {
+        let (archetype_idx, idx) = self.entities.get_location(id).ok_or(NoSuchEntity)?;
+
+        if archetype_idx == u32::MAX
+            || !self.archetypes[archetype_idx as usize].has_component(TypeId::of::<T>())
+        {
+            panic!(
+                "Component `{}` was removed from typed entity {}",
+                type_name::<T>(),
+                id
+            );
+        }
+
+        Ok((archetype_idx, idx))
+    }
+}
@@ -0,0 +1,429 @@
+//! Reversible operations log for undo/redo.
+
+use alloc::vec::Vec;
+use core::{any::TypeId, fmt, ptr::NonNull};
+
+use smallvec::SmallVec;
+
+use crate::{
+    bundle::{DynamicBundle, EntityBuilder},
+    entity::EntityId,
+};
+
+use super::World;
+
+/// Recorded change of an entity.
+struct Change {
+    id: EntityId,
+
+    /// Changed component types.
+    /// `None` if all components were changed, i.e. entity was spawned or despawned.
+    types: Option<SmallVec<[TypeId; 8]>>,
+
+    /// Cloneable components of changed types before the change.
+    /// `None` if entity was not alive.
+    before: Option<EntityBuilder>,
+
+    /// Cloneable components of changed types after the change.
+    /// `None` if entity is not alive.
+    after: Option<EntityBuilder>,
+}
+
+/// Change that is being recorded.
+pub(super) struct PendingChange {
+    id: EntityId,
+    types: Option<SmallVec<[TypeId; 8]>>,
+    before: Option<EntityBuilder>,
+}
+
+/// Log of reversible operations performed on the [`World`].
+///
+/// While recording is enabled, spawning and despawning entities,
+/// inserting and removing components and bundles are recorded,
+/// capturing values of the affected components before and after the operation.
+///
+/// Values are captured only for components registered as cloneable
+/// with [`ComponentInfoRef::cloneable`],
+/// other components are not affected by undo and redo.
+/// Modifications made through queries and entities spawned with [`World::spawn_batch`]
+/// are not recorded.
+/// [`World::clear`] discards all recorded changes.
+///
+/// [`ComponentInfoRef::cloneable`]: crate::component::ComponentInfoRef::cloneable
+pub struct UndoLog {
+    changes: Vec<Change>,
+
+    /// Number of changes that are applied.
+    /// Changes after this position can be redone.
+    applied: usize,
+
+    recording: bool,
+}
+
+impl fmt::Debug for UndoLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UndoLog")
+            .field("changes", &self.changes.len())
+            .field("applied", &self.applied)
+            .field("recording", &self.recording)
+            .finish()
+    }
+}
+
+impl UndoLog {
+    pub(super) fn new() -> Self {
+        UndoLog {
+            changes: Vec::new(),
+            applied: 0,
+            recording: false,
+        }
+    }
+
+    /// Returns `true` if operations are recorded.
+    #[inline]
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// Enables or disables recording of operations.
+    /// Recording is disabled by default.
+    #[inline]
+    pub fn set_recording(&mut self, recording: bool) {
+        self.recording = recording;
+    }
+
+    /// Returns `true` if there is an operation to undo.
+    #[inline]
+    pub fn can_undo(&self) -> bool {
+        self.applied > 0
+    }
+
+    /// Returns `true` if there is an operation to redo.
+    #[inline]
+    pub fn can_redo(&self) -> bool {
+        self.applied < self.changes.len()
+    }
+
+    /// Forgets all recorded operations.
+    pub fn clear(&mut self) {
+        self.changes.clear();
+        self.applied = 0;
+    }
+
+    fn record(&mut self, change: Change) {
+        // New operation invalidates redo history.
+        self.changes.truncate(self.applied);
+        self.changes.push(change);
+        self.applied += 1;
+    }
+}
+
+impl World {
+    /// Returns log of recorded operations.
+    #[inline]
+    pub fn undo_log(&self) -> &UndoLog {
+        &self.undo_log
+    }
+
+    /// Returns mutable reference to the log of recorded operations.
+    #[inline]
+    pub fn undo_log_mut(&mut self) -> &mut UndoLog {
+        &mut self.undo_log
+    }
+
+    /// Reverts last applied recorded operation.
+    /// Returns `false` if there is nothing to undo.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{component::Component, world::World};
+    /// #[derive(Clone, Component, Debug, PartialEq)]
+    /// struct Name(&'static str);
+    ///
+    /// let mut builder = World::builder();
+    /// builder.register_component::<Name>().cloneable();
+    /// let mut world = builder.build();
+    /// world.undo_log_mut().set_recording(true);
+    ///
+    /// let entity = world.spawn((Name("a"),));
+    /// world.insert(entity, Name("b")).unwrap();
+    ///
+    /// world.undo();
+    /// assert_eq!(world.query_one_mut::<&Name>(entity), Ok(&Name("a")));
+    ///
+    /// world.undo();
+    /// assert!(!world.is_alive(entity));
+    ///
+    /// world.redo();
+    /// world.redo();
+    /// assert_eq!(world.query_one_mut::<&Name>(entity), Ok(&Name("b")));
+    /// ```
+    pub fn undo(&mut self) -> bool {
+        if !self.undo_log.can_undo() {
+            return false;
+        }
+
+        self.undo_log.applied -= 1;
+        let idx = self.undo_log.applied;
+        self.apply_change(idx, true);
+        true
+    }
+
+    /// Reapplies last reverted recorded operation.
+    /// Returns `false` if there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        if !self.undo_log.can_redo() {
+            return false;
+        }
+
+        let idx = self.undo_log.applied;
+        self.undo_log.applied += 1;
+        self.apply_change(idx, false);
+        true
+    }
+
+    /// Brings entity to the state before or after the recorded change.
+    fn apply_change(&mut self, idx: usize, undo: bool) {
+        let change = &self.undo_log.changes[idx];
+        let id = change.id;
+        let types = change.types.clone();
+        let state = match undo {
+            true => &change.before,
+            false => &change.after,
+        };
+
+        // Safety: snapshots contain only cloneable components.
+        let state = state
+            .as_ref()
+            .map(|state| unsafe { state.clone_cloneable() });
+
+        let recording = core::mem::replace(&mut self.undo_log.recording, false);
+
+        match state {
+            None => {
+                let _ = self.despawn(id);
+            }
+            Some(state) if !self.is_alive(id) => {
+                self.spawn_external_with_id(id, state);
+            }
+            Some(state) => {
+                for &ty in types.iter().flatten() {
+                    if !state.contains_id(ty) {
+                        let _ = self.drop_erased(id, ty);
+                    }
+                }
+                let _ = self.insert_external_bundle(id, state);
+            }
+        }
+
+        self.undo_log.recording = recording;
+    }
+
+    /// Returns clones of cloneable components of the entity.
+    /// Only specified component types are cloned if `types` is `Some`.
+    ///
+    /// Returns `None` if entity is not alive.
+    fn snapshot(&self, id: EntityId, types: Option<&[TypeId]>) -> Option<EntityBuilder> {
+        let (archetype, idx) = self.entities.get_location(id)?;
+
+        let mut builder = EntityBuilder::new();
+        if archetype == u32::MAX {
+            return Some(builder);
+        }
+
+        let archetype = &self.archetypes[archetype as usize];
+        for ty in archetype.ids() {
+            if types.is_some_and(|types| !types.contains(&ty)) {
+                continue;
+            }
+
+            let component = archetype.component(ty).unwrap();
+            if !component.is_cloneable() {
+                continue;
+            }
+
+            // Safety: world is borrowed immutably, no mutable borrows exist.
+            // Index is in bounds and component is cloneable.
+            unsafe {
+                let ptr = component.data().ptr.as_ptr();
+                let ptr = NonNull::new_unchecked(ptr.add(idx as usize * component.layout().size()));
+                builder.add_cloned(component, ptr);
+            }
+        }
+
+        Some(builder)
+    }
+
+    /// Starts recording change of the entity.
+    /// All components are changed if `types` is `None`.
+    ///
+    /// Returns `None` if recording is disabled or change affects no cloneable components.
+    pub(super) fn begin_change(
+        &self,
+        id: EntityId,
+        types: Option<&[TypeId]>,
+    ) -> Option<PendingChange> {
+        if !self.undo_log.recording {
+            return None;
+        }
+
+        let types = match types {
+            None => None,
+            Some(types) => {
+                let types = types
+                    .iter()
+                    .copied()
+                    .filter(|&ty| {
+                        self.registry
+                            .get_info(ty)
+                            .is_some_and(|info| info.is_cloneable())
+                    })
+                    .collect::<SmallVec<[TypeId; 8]>>();
+
+                if types.is_empty() {
+                    return None;
+                }
+                Some(types)
+            }
+        };
+
+        Some(PendingChange {
+            id,
+            before: self.snapshot(id, types.as_deref()),
+            types,
+        })
+    }
+
+    /// Finishes recording change of the entity.
+    pub(super) fn end_change(&mut self, pending: Option<PendingChange>) {
+        let Some(pending) = pending else {
+            return;
+        };
+
+        let after = self.snapshot(pending.id, pending.types.as_deref());
+        if pending.before.is_none() && after.is_none() {
+            return;
+        }
+
+        self.undo_log.record(Change {
+            id: pending.id,
+            types: pending.types,
+            before: pending.before,
+            after,
+        });
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{component::Component, world::World};
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Pos(i32);
+    impl Component for Pos {}
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Name(&'static str);
+    impl Component for Name {}
+
+    #[derive(Debug, PartialEq)]
+    struct Opaque;
+    impl Component for Opaque {}
+
+    fn world() -> World {
+        let mut builder = World::builder();
+        builder.register_component::<Pos>().cloneable();
+        builder.register_component::<Name>().cloneable();
+        let mut world = builder.build();
+        world.undo_log_mut().set_recording(true);
+        world
+    }
+
+    #[test]
+    fn undo_insert_remove() {
+        let mut world = world();
+        let e = world.spawn((Pos(0),));
+        world.insert(e, Pos(1)).unwrap();
+        world.insert(e, Name("a")).unwrap();
+        assert_eq!(world.remove::<Pos>(e), Ok(Pos(1)));
+
+        assert!(world.undo());
+        assert_eq!(world.query_one_mut::<&Pos>(e), Ok(&Pos(1)));
+
+        assert!(world.undo());
+        assert_eq!(world.has_component::<Name>(e), Ok(false));
+
+        assert!(world.undo());
+        assert_eq!(world.query_one_mut::<&Pos>(e), Ok(&Pos(0)));
+
+        assert!(world.redo());
+        assert!(world.redo());
+        assert!(world.redo());
+        assert_eq!(world.has_component::<Pos>(e), Ok(false));
+        assert_eq!(world.query_one_mut::<&Name>(e), Ok(&Name("a")));
+        assert!(!world.redo());
+    }
+
+    #[test]
+    fn undo_despawn() {
+        let mut world = world();
+        let e = world.spawn((Pos(1), Name("a"), Opaque));
+        world.despawn(e).unwrap();
+
+        assert!(world.undo());
+        assert_eq!(
+            world.query_one_mut::<(&Pos, &Name)>(e),
+            Ok((&Pos(1), &Name("a")))
+        );
+        // Values of non-cloneable components can't be restored.
+        assert_eq!(world.has_component::<Opaque>(e), Ok(false));
+
+        assert!(world.redo());
+        assert!(!world.is_alive(e));
+    }
+
+    #[test]
+    fn undo_apply_bundles() {
+        let mut world = world();
+        let a = world.spawn((Pos(0),));
+        let b = world.spawn(());
+        world
+            .apply_bundles([(a, (Pos(1),)), (b, (Pos(2),)), (a, (Pos(3),))])
+            .unwrap();
+
+        assert!(world.undo());
+        assert_eq!(world.query_one_mut::<&Pos>(a), Ok(&Pos(1)));
+        assert!(world.undo());
+        assert_eq!(world.has_component::<Pos>(b), Ok(false));
+        assert!(world.undo());
+        assert_eq!(world.query_one_mut::<&Pos>(a), Ok(&Pos(0)));
+    }
+
+    #[test]
+    fn new_change_drops_redo() {
+        let mut world = world();
+        let e = world.spawn((Pos(0),));
+        world.insert(e, Pos(1)).unwrap();
+
+        assert!(world.undo());
+        world.insert(e, Pos(2)).unwrap();
+        assert!(!world.undo_log().can_redo());
+
+        assert!(world.undo());
+        assert_eq!(world.query_one_mut::<&Pos>(e), Ok(&Pos(0)));
+    }
+
+    #[test]
+    fn clear_discards_changes() {
+        let mut world = world();
+        let e = world.spawn((Pos(0),));
+        world.insert(e, Pos(1)).unwrap();
+        world.clear();
+
+        assert!(!world.undo_log().can_undo());
+        assert!(!world.undo());
+        assert!(!world.is_alive(e));
+    }
+}
@@ -0,0 +1,152 @@
+//! View of the [`World`] with multiple disjoint queries.
+
+use alloc::vec::Vec;
+use core::{any::TypeId, cell::RefCell, fmt, marker::PhantomData};
+
+use crate::{
+    archetype::Archetype,
+    query::{Access, DefaultQuery, Query},
+};
+
+use super::{QueryRef, World};
+
+/// Set of queries that can be viewed simultaneously through [`WorldView`].
+///
+/// Implemented for tuples of queries.
+pub trait ViewQueries {
+    /// Tuple of [`QueryRef`] for each query in the set.
+    type Refs<'a>;
+
+    /// Checks that queries do not conflict with each other in any of the archetypes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if two queries access same component in the same archetype
+    /// and at least one of them performs [`Access::Write`].
+    fn check_disjoint(archetypes: &[Archetype]);
+
+    /// Returns query references for all queries in the set.
+    ///
+    /// # Safety
+    ///
+    /// `check_disjoint` must succeed for the world archetypes
+    /// and no other query may access the world while returned references are alive.
+    unsafe fn refs(world: &World) -> Self::Refs<'_>;
+}
+
+/// Collects component accesses the query performs in the archetype.
+fn archetype_access(query: &impl Query, archetype: &Archetype) -> Vec<(TypeId, Access)> {
+    let accesses = RefCell::new(Vec::new());
+    if query.visit_archetype(archetype) {
+        unsafe {
+            query.access_archetype(archetype, &|id, access| {
+                accesses.borrow_mut().push((id, access));
+            });
+        }
+    }
+    accesses.into_inner()
+}
+
+/// Panics if any two access lists conflict.
+fn assert_disjoint(accesses: &[Vec<(TypeId, Access)>]) {
+    for (idx, lhs) in accesses.iter().enumerate() {
+        for rhs in &accesses[idx + 1..] {
+            for &(lhs_id, lhs_access) in lhs {
+                for &(rhs_id, rhs_access) in rhs {
+                    if lhs_id == rhs_id
+                        && (matches!(lhs_access, Access::Write)
+                            || matches!(rhs_access, Access::Write))
+                    {
+                        panic!("Queries in `WorldView` conflict on {:?}", lhs_id);
+                    }
+                }
+            }
+        }
+    }
+}
+
+macro_rules! impl_view_queries {
+    () => {};
+    ($($a:ident)+) => {
+        impl<$($a),+> ViewQueries for ($($a,)+)
+        where
+            $($a: DefaultQuery,)+
+        {
+            type Refs<'a> = ($(QueryRef<'a, ($a,), ()>,)+);
+
+            fn check_disjoint(archetypes: &[Archetype]) {
+                for archetype in archetypes {
+                    assert_disjoint(&[$(archetype_access(&$a::default_query(), archetype),)+]);
+                }
+            }
+
+            #[inline]
+            unsafe fn refs(world: &World) -> Self::Refs<'_> {
+                ($(unsafe { QueryRef::new_unchecked(world, ($a::default_query(),), ()) },)+)
+            }
+        }
+    };
+}
+
+for_tuple!(impl_view_queries);
+
+/// View of the [`World`] through a set of disjoint queries.
+///
+/// Queries are checked for conflicts once, when view is created.
+/// After that all of them can be iterated mutably at the same time
+/// without any runtime borrow checks.
+///
+/// Created with [`World::view`].
+pub struct WorldView<'a, T> {
+    world: &'a mut World,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> fmt::Debug for WorldView<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WorldView").finish_non_exhaustive()
+    }
+}
+
+impl<'a, T> WorldView<'a, T>
+where
+    T: ViewQueries,
+{
+    pub(super) fn new(world: &'a mut World) -> Self {
+        T::check_disjoint(world.archetypes());
+        WorldView {
+            world,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns tuple of [`QueryRef`]s, one for each query of the view.
+    ///
+    /// Returned queries do not lock archetypes.
+    #[inline]
+    pub fn queries(&mut self) -> T::Refs<'_> {
+        // Safety: queries are checked to be disjoint for all archetypes.
+        // World is borrowed mutably, so no new archetypes can be added,
+        // and no other queries may exist.
+        unsafe { T::refs(self.world) }
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{
+        test::{Str, U32},
+        world::World,
+    };
+
+    /// Tests that conflicting queries cannot be viewed together.
+    #[test]
+    #[should_panic]
+    fn world_view_conflict() {
+        let mut world = World::new();
+        world.spawn((U32(1), Str("a")));
+
+        let _view = world.view::<(&mut U32, (&Str, &U32))>();
+    }
+}
@@ -0,0 +1,339 @@
+//! Inspection of component changes of individual entities.
+
+use alloc::vec::Vec;
+use core::{any::TypeId, ptr::NonNull};
+
+use crate::{component::ComponentInfo, entity::EntityId, epoch::EpochId, query::Access};
+
+use super::World;
+
+/// Kind of change of watched component.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    /// Component was inserted into the entity.
+    Inserted,
+
+    /// Component was modified.
+    ///
+    /// [`WatchHandle`] reports modification only
+    /// if component value is not equal to the previous one.
+    Modified,
+
+    /// Component was removed from the entity or entity was despawned.
+    Removed,
+}
+
+/// Change of watched component reported by [`WatchHandle`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ComponentChange {
+    /// Id of the component type.
+    pub id: TypeId,
+
+    /// Name of the component type.
+    pub name: &'static str,
+
+    /// Kind of change.
+    pub kind: ChangeKind,
+}
+
+/// Snapshot of one component value.
+struct Snapshot {
+    info: ComponentInfo,
+    value: NonNull<u8>,
+
+    /// Set if `value` is initialized.
+    present: bool,
+
+    /// Set if component was found during current update.
+    seen: bool,
+}
+
+impl Snapshot {
+    fn new(info: &ComponentInfo) -> Self {
+        let layout = info.layout();
+
+        let value = if layout.size() == 0 {
+            // Safety: alignment is never zero.
+            unsafe { NonNull::new_unchecked(layout.align() as *mut u8) }
+        } else {
+            // Safety: layout size is non-zero.
+            match NonNull::new(unsafe { alloc::alloc::alloc(layout) }) {
+                None => alloc::alloc::handle_alloc_error(layout),
+                Some(ptr) => ptr,
+            }
+        };
+
+        Snapshot {
+            info: info.clone(),
+            value,
+            present: false,
+            seen: false,
+        }
+    }
+
+    /// Replaces snapshot with the clone of the component.
+    ///
+    /// # Safety
+    ///
+    /// `src` must point to initialized component of snapshot type.
+    unsafe fn store(&mut self, src: NonNull<u8>) {
+        self.clear();
+        unsafe {
+            self.info.clone_one(src, self.value);
+        }
+        self.present = true;
+    }
+
+    fn clear(&mut self) {
+        if self.present {
+            self.present = false;
+            unsafe {
+                self.info.final_drop(self.value, 1);
+            }
+        }
+    }
+}
+
+impl Drop for Snapshot {
+    fn drop(&mut self) {
+        self.clear();
+
+        let layout = self.info.layout();
+        if layout.size() != 0 {
+            unsafe {
+                alloc::alloc::dealloc(self.value.as_ptr(), layout);
+            }
+        }
+    }
+}
+
+/// Handle that reports changes of entity's components since last update.
+///
+/// Only components registered with both [`ComponentInfoRef::comparable`]
+/// and [`ComponentInfoRef::cloneable`] are watched.
+/// Values of watched components are cloned into snapshots
+/// that are reused between updates, so steady state updates do not allocate.
+/// Components not modified since last update are not compared.
+///
+/// Created with [`World::watch`].
+///
+/// [`ComponentInfoRef::comparable`]: crate::component::ComponentInfoRef::comparable
+/// [`ComponentInfoRef::cloneable`]: crate::component::ComponentInfoRef::cloneable
+///
+/// # Example
+///
+/// ```
+/// # use edict::{component::Component, world::{ChangeKind, World}};
+/// #[derive(Clone, PartialEq, Component)]
+/// struct Pos(i32);
+///
+/// let mut builder = World::builder();
+/// builder.register_component::<Pos>().comparable().cloneable();
+/// let mut world = builder.build();
+///
+/// let e = world.spawn((Pos(0),));
+/// let mut watch = world.watch(e);
+///
+/// // Written but not changed.
+/// world.query_one_mut::<&mut Pos>(e).unwrap().0 = 0;
+/// watch.update(&world);
+/// assert!(watch.changes().is_empty());
+///
+/// world.query_one_mut::<&mut Pos>(e).unwrap().0 = 1;
+/// watch.update(&world);
+/// assert_eq!(watch.changes()[0].kind, ChangeKind::Modified);
+/// assert_eq!(watch.snapshot::<Pos>().unwrap().0, 1);
+/// ```
+pub struct WatchHandle {
+    entity: EntityId,
+    epoch: EpochId,
+    snapshots: Vec<Snapshot>,
+    changes: Vec<ComponentChange>,
+}
+
+impl WatchHandle {
+    /// Returns watched entity.
+    #[inline]
+    pub fn entity(&self) -> EntityId {
+        self.entity
+    }
+
+    /// Returns changes found by last update.
+    #[inline]
+    pub fn changes(&self) -> &[ComponentChange] {
+        &self.changes
+    }
+
+    /// Returns value of the component at last update.
+    /// Returns `None` if entity did not have the component
+    /// or component is not watched.
+    pub fn snapshot<T>(&self) -> Option<&T>
+    where
+        T: 'static,
+    {
+        let snapshot = self
+            .snapshots
+            .iter()
+            .find(|s| s.info.id() == TypeId::of::<T>())?;
+
+        if !snapshot.present {
+            return None;
+        }
+
+        // Safety: snapshot is initialized value of type `T`.
+        Some(unsafe { snapshot.value.cast::<T>().as_ref() })
+    }
+
+    /// Compares components of the entity with snapshots taken at last update
+    /// and replaces snapshots of changed components.
+    ///
+    /// # Panics
+    ///
+    /// Panics if watched component of the entity is borrowed mutably.
+    pub fn update(&mut self, world: &World) {
+        self.changes.clear();
+
+        for snapshot in &mut self.snapshots {
+            snapshot.seen = false;
+        }
+
+        if let Some((archetype_idx, idx)) = world.entities.get_location(self.entity) {
+            if archetype_idx != u32::MAX {
+                let archetype = &world.archetypes[archetype_idx as usize];
+
+                for id in archetype.ids() {
+                    let component = archetype.component(id).unwrap();
+                    if !component.is_watchable() {
+                        continue;
+                    }
+
+                    let snapshot = match self.snapshots.iter().position(|s| s.info.id() == id) {
+                        Some(pos) => &mut self.snapshots[pos],
+                        None => {
+                            self.snapshots.push(Snapshot::new(component));
+                            self.snapshots.last_mut().unwrap()
+                        }
+                    };
+                    snapshot.seen = true;
+
+                    unsafe {
+                        if !component.borrow(Access::Read) {
+                            panic!("Component `{}` is borrowed mutably", component.name());
+                        }
+
+                        let data = component.data();
+                        let ptr = NonNull::new_unchecked(
+                            data.ptr
+                                .as_ptr()
+                                .add(idx as usize * component.layout().size()),
+                        );
+
+                        let kind = if !snapshot.present {
+                            Some(ChangeKind::Inserted)
+                        } else if data.entity_epochs[idx as usize].after(self.epoch)
+                            && !component.eq_one(ptr, snapshot.value)
+                        {
+                            Some(ChangeKind::Modified)
+                        } else {
+                            None
+                        };
+
+                        if let Some(kind) = kind {
+                            snapshot.store(ptr);
+                            self.changes.push(ComponentChange {
+                                id,
+                                name: component.name(),
+                                kind,
+                            });
+                        }
+
+                        component.release(Access::Read);
+                    }
+                }
+            }
+        }
+
+        for snapshot in &mut self.snapshots {
+            if snapshot.present && !snapshot.seen {
+                snapshot.clear();
+                self.changes.push(ComponentChange {
+                    id: snapshot.info.id(),
+                    name: snapshot.info.name(),
+                    kind: ChangeKind::Removed,
+                });
+            }
+        }
+
+        self.epoch = world.epoch.current();
+    }
+}
+
+impl World {
+    /// Returns [`WatchHandle`] that reports changes of entity's components.
+    ///
+    /// Components are snapshotted immediately,
+    /// so first [`WatchHandle::update`] reports only changes made after this call.
+    /// If entity is not alive the handle never reports any changes.
+    pub fn watch(&self, entity: EntityId) -> WatchHandle {
+        let mut watch = WatchHandle {
+            entity,
+            epoch: EpochId::start(),
+            snapshots: Vec::new(),
+            changes: Vec::new(),
+        };
+        watch.update(self);
+        watch.changes.clear();
+        watch
+    }
+}
+
+mod test {
+    #![cfg(test)]
+
+    use crate::{component::Component, test::U32, world::World};
+
+    /// Tests that watch handle reports inserted, modified and removed components.
+    #[test]
+    fn watch_changes() {
+        use crate::world::{ChangeKind, ComponentChange};
+        use core::any::TypeId;
+
+        #[derive(Clone, Debug, PartialEq)]
+        struct Name(alloc::string::String);
+        impl Component for Name {}
+
+        let mut builder = World::builder();
+        builder
+            .register_component::<Name>()
+            .comparable()
+            .cloneable();
+        let mut world = builder.build();
+
+        let e = world.spawn((U32(1),));
+        let mut watch = world.watch(e);
+
+        let change = |kind| ComponentChange {
+            id: TypeId::of::<Name>(),
+            name: core::any::type_name::<Name>(),
+            kind,
+        };
+
+        world.insert(e, Name("a".into())).unwrap();
+        world.insert(e, U32(2)).unwrap();
+        watch.update(&world);
+        assert_eq!(watch.changes(), [change(ChangeKind::Inserted)]);
+
+        world.insert(e, Name("b".into())).unwrap();
+        watch.update(&world);
+        assert_eq!(watch.changes(), [change(ChangeKind::Modified)]);
+        assert_eq!(watch.snapshot::<Name>(), Some(&Name("b".into())));
+
+        watch.update(&world);
+        assert!(watch.changes().is_empty());
+
+        world.despawn(e).unwrap();
+        watch.update(&world);
+        assert_eq!(watch.changes(), [change(ChangeKind::Removed)]);
+        assert_eq!(watch.snapshot::<Name>(), None);
+    }
+}
@@ -0,0 +1,106 @@
+//! Weak references to entities.
+
+use core::ptr::NonNull;
+
+use crate::{
+    archetype::{chunk_idx, Archetype},
+    entity::{EntityId, EntitySet, WeakEntity},
+};
+
+use super::{NoSuchEntity, World};
+
+impl World {
+    /// Returns weak reference to the entity.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use edict::{world::World, ExampleComponent};
+    /// let mut world = World::new();
+    /// let e = world.spawn((ExampleComponent,));
+    ///
+    /// let weak = world.downgrade(e).unwrap();
+    /// assert_eq!(world.upgrade(weak), Some(e));
+    ///
+    /// world.despawn(e).unwrap();
+    /// assert_eq!(world.upgrade(weak), None);
+    /// ```
+    pub fn downgrade(&mut self, id: EntityId) -> Result<WeakEntity, NoSuchEntity> {
+        self.maintenance();
+
+        let (archetype_idx, idx) = self.entities.get_location(id).ok_or(NoSuchEntity)?;
+        let spawned = self.archetypes[archetype_idx as usize].spawn_epochs()[idx as usize];
+        Ok(WeakEntity::new(id, spawned))
+    }
+
+    /// Returns id of the entity referenced by weak reference.
+    /// Returns `None` if reference is null or entity was despawned,
+    /// even if its id was allocated again.
+    #[inline]
+    pub fn upgrade(&self, weak: WeakEntity) -> Option<EntityId> {
+        upgrade(&self.entities, &self.archetypes, weak)
+    }
+
+    /// Nulls weak references to despawned entities
+    /// in components with [`Component::VISIT_WEAK`] hook.
+    ///
+    /// [`Component::VISIT_WEAK`]: crate::component::Component::VISIT_WEAK
+    pub(super) fn null_weak_entities(&mut self) {
+        // Despawned flag is taken even if there is nothing to visit.
+        if !self.entities.take_despawned() || !self.registry.has_weak() {
+            return;
+        }
+
+        let mut epoch = None;
+
+        for archetype in self.archetypes.iter() {
+            for info in archetype.infos() {
+                if !info.has_weak() {
+                    continue;
+                }
+
+                let component = archetype.component(info.id()).unwrap();
+
+                // Safety: world is borrowed mutably, no other borrows exist.
+                let data = unsafe { component.data_mut() };
+
+                for idx in 0..archetype.len() {
+                    let mut nulled = false;
+
+                    unsafe {
+                        let ptr = data.ptr.as_ptr().add(idx * info.layout().size());
+                        info.visit_weak(NonNull::new_unchecked(ptr), &mut |weak| {
+                            if !weak.is_null()
+                                && upgrade(&self.entities, &self.archetypes, *weak).is_none()
+                            {
+                                *weak = WeakEntity::null();
+                                nulled = true;
+                            }
+                        });
+                    }
+
+                    if nulled {
+                        let epoch = *epoch.get_or_insert_with(|| self.epoch.next());
+                        data.epoch.bump(epoch);
+                        data.chunk_epochs[chunk_idx(idx)].bump(epoch);
+                        data.entity_epochs[idx].bump(epoch);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn upgrade(entities: &EntitySet, archetypes: &[Archetype], weak: WeakEntity) -> Option<EntityId> {
+    let id = weak.id()?;
+    let (archetype_idx, idx) = entities.get_location(id)?;
+    if archetype_idx == u32::MAX {
+        return None;
+    }
+
+    let spawned = archetypes[archetype_idx as usize].spawn_epochs()[idx as usize];
+    if spawned != weak.spawned() {
+        return None;
+    }
+    Some(id)
+}